@@ -0,0 +1,8 @@
+-- Revert 001_initial: drop the initial schema (triggers and indexes go with their tables)
+DROP TABLE IF EXISTS app_settings;
+DROP TABLE IF EXISTS api_key_audit;
+DROP TABLE IF EXISTS billing_summaries;
+DROP TABLE IF EXISTS tool_invocations;
+DROP TABLE IF EXISTS usage_records;
+DROP TABLE IF EXISTS messages;
+DROP TABLE IF EXISTS conversations;
@@ -0,0 +1,3 @@
+-- Revert 002_tool_permissions
+DROP INDEX IF EXISTS idx_tool_invocations_decision;
+ALTER TABLE tool_invocations DROP COLUMN decision;
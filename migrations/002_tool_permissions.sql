@@ -0,0 +1,4 @@
+-- Record the permission decision taken for each tool invocation
+ALTER TABLE tool_invocations ADD COLUMN decision TEXT; -- policy_allow, allow_once, allow_always, policy_deny, user_deny
+
+CREATE INDEX idx_tool_invocations_decision ON tool_invocations(decision);
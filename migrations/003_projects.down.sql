@@ -0,0 +1,59 @@
+-- Revert 003_projects
+DROP INDEX IF EXISTS idx_usage_records_project;
+ALTER TABLE usage_records DROP COLUMN project;
+
+-- conversations.project is a foreign key, which SQLite cannot drop in place, so the table is rebuilt.
+-- Triggers on messages refer to conversations and would block the rename, so they are recreated too.
+DROP INDEX IF EXISTS idx_conversations_project;
+DROP TRIGGER IF EXISTS increment_message_count;
+DROP TRIGGER IF EXISTS decrement_message_count;
+CREATE TABLE conversations_new (
+    id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (unixepoch()),
+    updated_at INTEGER NOT NULL DEFAULT (unixepoch()),
+    model_provider TEXT,
+    model_name TEXT,
+    system_prompt TEXT,
+    total_cost TEXT,
+    message_count INTEGER DEFAULT 0,
+    status TEXT DEFAULT 'active' CHECK (status IN ('active', 'archived', 'deleted')),
+    settings TEXT
+);
+INSERT INTO conversations_new (id, title, created_at, updated_at, model_provider, model_name, system_prompt,
+                               total_cost, message_count, status, settings)
+    SELECT id, title, created_at, updated_at, model_provider, model_name, system_prompt,
+           total_cost, message_count, status, settings
+    FROM conversations;
+DROP TABLE conversations;
+ALTER TABLE conversations_new RENAME TO conversations;
+
+CREATE INDEX idx_conversations_updated_at ON conversations(updated_at);
+CREATE INDEX idx_conversations_provider ON conversations(model_provider);
+CREATE INDEX idx_conversations_status ON conversations(status);
+
+CREATE TRIGGER update_conversations_timestamp
+    AFTER UPDATE ON conversations
+    BEGIN
+        UPDATE conversations SET updated_at = unixepoch() WHERE id = NEW.id;
+    END;
+
+CREATE TRIGGER increment_message_count
+    AFTER INSERT ON messages
+    BEGIN
+        UPDATE conversations 
+        SET message_count = message_count + 1,
+            updated_at = unixepoch()
+        WHERE id = NEW.conversation_id;
+    END;
+
+CREATE TRIGGER decrement_message_count
+    AFTER DELETE ON messages
+    BEGIN
+        UPDATE conversations 
+        SET message_count = message_count - 1,
+            updated_at = unixepoch()
+        WHERE id = OLD.conversation_id;
+    END;
+
+DROP TABLE IF EXISTS projects;
@@ -0,0 +1,14 @@
+-- Projects group conversations for budgeting and cost attribution
+CREATE TABLE projects (
+    name TEXT PRIMARY KEY,
+    description TEXT,
+    created_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+ALTER TABLE conversations ADD COLUMN project TEXT REFERENCES projects(name) ON DELETE SET NULL;
+
+-- Project of the conversation at the time of the request, so charge-back survives reassignment
+ALTER TABLE usage_records ADD COLUMN project TEXT;
+
+CREATE INDEX idx_conversations_project ON conversations(project);
+CREATE INDEX idx_usage_records_project ON usage_records(project);
@@ -0,0 +1,2 @@
+-- Revert 004_cost_alerts
+DROP TABLE IF EXISTS cost_alerts;
@@ -0,0 +1,15 @@
+-- Cost alerts delivered by the alert dispatcher, kept so acknowledgements survive restarts
+CREATE TABLE cost_alerts (
+    id TEXT PRIMARY KEY,
+    alert_type TEXT NOT NULL, -- JSON encoded CostAlertType
+    severity TEXT NOT NULL,
+    message TEXT NOT NULL,
+    suggested_action TEXT,
+    context TEXT NOT NULL, -- JSON encoded AlertContext
+    timestamp INTEGER NOT NULL,
+    acknowledged BOOLEAN NOT NULL DEFAULT FALSE,
+    acknowledged_at INTEGER
+);
+
+CREATE INDEX idx_cost_alerts_timestamp ON cost_alerts(timestamp);
+CREATE INDEX idx_cost_alerts_acknowledged ON cost_alerts(acknowledged);
@@ -0,0 +1,4 @@
+-- Revert 005_api_key_sources
+-- Reading the table first makes a pooled connection reload a schema changed by another connection
+SELECT COUNT(*) FROM api_key_audit;
+ALTER TABLE api_key_audit DROP COLUMN source;
@@ -0,0 +1,2 @@
+-- Where an accessed API key came from: an environment variable, api_key_command or secure storage
+ALTER TABLE api_key_audit ADD COLUMN source TEXT;
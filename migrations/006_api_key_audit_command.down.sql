@@ -0,0 +1,4 @@
+-- Revert 006_api_key_audit_command
+-- Reading the table first makes a pooled connection reload a schema changed by another connection
+SELECT COUNT(*) FROM api_key_audit;
+ALTER TABLE api_key_audit DROP COLUMN command;
@@ -0,0 +1,2 @@
+-- ValeChat command that read or changed the key, e.g. `chat` or `api-key`, to spot unexpected access
+ALTER TABLE api_key_audit ADD COLUMN command TEXT;
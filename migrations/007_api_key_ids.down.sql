@@ -0,0 +1,7 @@
+-- Revert 007_api_key_ids
+DROP TABLE IF EXISTS api_key_selections;
+
+DROP INDEX IF EXISTS idx_usage_records_key_id;
+-- Reading the table first makes a pooled connection reload a schema changed by another connection
+SELECT COUNT(*) FROM usage_records;
+ALTER TABLE usage_records DROP COLUMN key_id;
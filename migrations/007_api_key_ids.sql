@@ -0,0 +1,16 @@
+-- API key a request was sent with, e.g. `openai` or the named key `openai/team-a`
+ALTER TABLE usage_records ADD COLUMN key_id TEXT;
+-- Until now every request used its provider's only key
+UPDATE usage_records SET key_id = provider;
+CREATE INDEX idx_usage_records_key_id ON usage_records(key_id);
+
+-- Key chosen for a provider in a conversation or project; a conversation choice overrides its project's,
+-- and without either the provider's default key is used
+CREATE TABLE api_key_selections (
+    scope TEXT NOT NULL CHECK (scope IN ('conversation', 'project')),
+    scope_id TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    key_id TEXT NOT NULL,
+    updated_at INTEGER NOT NULL DEFAULT (unixepoch()),
+    PRIMARY KEY (scope, scope_id, provider)
+);
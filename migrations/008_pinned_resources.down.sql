@@ -0,0 +1,2 @@
+-- Revert 008_pinned_resources
+DROP TABLE IF EXISTS pinned_resources;
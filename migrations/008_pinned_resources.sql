@@ -0,0 +1,8 @@
+-- MCP resources pinned to a conversation; their current contents are sent with every message
+CREATE TABLE pinned_resources (
+    conversation_id TEXT NOT NULL,
+    uri TEXT NOT NULL,
+    pinned_at INTEGER NOT NULL DEFAULT (unixepoch()),
+    PRIMARY KEY (conversation_id, uri),
+    FOREIGN KEY (conversation_id) REFERENCES conversations(id)
+);
@@ -1,12 +1,14 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tokio::fs;
+use std::collections::{BTreeMap, HashMap};
 use tracing::info;
 use rust_decimal::Decimal;
 
 use crate::error::{Error, Result};
-use crate::platform::AppPaths;
+use crate::app::layers::{save_user_config, ConfigSources, LayeredConfig};
+use crate::platform::{AppPaths, SecretsConfig};
+use crate::billing::currency::{normalize_code, CurrencyFormatter, ExchangeRates, BASE_CURRENCY};
 use crate::models::{QualityPriority, TaskType};
+use crate::storage::{AlertSeverity, BackupConfig, BillingPeriod, EncryptionConfig, RetentionConfig, CostAlertConfig, SpendingLimit, SpendingLimitType};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -17,6 +19,18 @@ pub struct AppConfig {
     pub fallback: FallbackConfig,
     pub rate_limiting: RateLimitingConfig,
     pub capability_detection: CapabilityDetectionConfig,
+    #[serde(default)]
+    pub tool_permissions: ToolPermissionConfig,
+    #[serde(default)]
+    pub alerts: AlertDeliveryConfig,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default)]
+    pub secrets: SecretsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +39,11 @@ pub struct ModelConfig {
     pub default_model: String,
     pub enabled: bool,
     pub api_endpoint: Option<String>,
+    /// Shell command printing the API key, e.g. `pass show openai`; used when no environment variable is set
+    ///
+    /// Only taken from the system or user file; see `LayeredConfig`.
+    #[serde(default)]
+    pub api_key_command: Option<String>,
     pub timeout_seconds: Option<u64>,
     pub max_retries: Option<u32>,
     pub rate_limits: Option<ProviderRateLimits>,
@@ -67,11 +86,55 @@ pub enum TransportType {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BillingConfig {
-    pub daily_limit_usd: Option<f64>,
-    pub monthly_limit_usd: Option<f64>,
+    /// Daily spending limit, in the `currency.limits` currency
+    #[serde(alias = "daily_limit_usd")]
+    pub daily_limit: Option<f64>,
+    /// Monthly spending limit, in the `currency.limits` currency
+    #[serde(alias = "monthly_limit_usd")]
+    pub monthly_limit: Option<f64>,
     pub per_model_limits: HashMap<String, f64>,
     pub alert_threshold_percent: f64,
     pub track_usage: bool,
+    /// Requests estimated above this cost need confirmation before they are sent; 0 disables
+    #[serde(default = "default_confirm_request_cost", alias = "confirm_request_cost_usd")]
+    pub confirm_request_cost: f64,
+    #[serde(default)]
+    pub currency: CurrencyConfig,
+    /// Pause all requests when a runaway conversation or an extreme request cost is detected
+    #[serde(default)]
+    pub emergency_stop_on_anomaly: bool,
+}
+
+fn default_confirm_request_cost() -> f64 {
+    0.25
+}
+
+/// Display currency and the exchange rates used to convert from USD
+///
+/// Costs are stored in USD regardless of these settings. Rates are never fetched;
+/// they are edited here or imported with `valechat currency import`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CurrencyConfig {
+    /// Currency costs are shown in
+    pub display: String,
+    /// Currency the billing amounts and provider cost limits are written in
+    pub limits: String,
+    /// Units of each currency per US dollar
+    pub rates: BTreeMap<String, Decimal>,
+    /// When the rate table was last changed
+    pub rates_updated: Option<String>,
+}
+
+impl Default for CurrencyConfig {
+    fn default() -> Self {
+        Self {
+            display: BASE_CURRENCY.to_string(),
+            limits: BASE_CURRENCY.to_string(),
+            rates: BTreeMap::new(),
+            rates_updated: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -119,6 +182,124 @@ pub struct CapabilityDetectionConfig {
     pub model_preferences: HashMap<TaskType, Vec<String>>, // Preferred models for each task type
 }
 
+/// Permission rules applied before any MCP tool is executed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolPermissionConfig {
+    /// Action taken when no rule matches
+    #[serde(default)]
+    pub default_action: PermissionAction,
+    /// Rules are evaluated in order; the first match wins
+    #[serde(default)]
+    pub rules: Vec<ToolPermissionRule>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionAction {
+    Allow,
+    Deny,
+    #[default]
+    Ask,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolPermissionRule {
+    /// Glob pattern for the server name (`*` and `?` wildcards)
+    #[serde(default = "match_all_pattern")]
+    pub server: String,
+    /// Glob pattern for the tool name (`*` and `?` wildcards)
+    #[serde(default = "match_all_pattern")]
+    pub tool: String,
+    pub action: PermissionAction,
+    /// All constraints must hold for the rule to match
+    #[serde(default)]
+    pub arguments: Vec<ArgumentConstraint>,
+}
+
+/// Constraint on a single tool argument
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArgumentConstraint {
+    pub argument: String,
+    /// The argument must be a path inside this directory
+    pub path_prefix: Option<String>,
+    /// The argument must match this glob pattern
+    pub pattern: Option<String>,
+    /// The argument must equal one of these values
+    pub one_of: Option<Vec<String>>,
+}
+
+fn match_all_pattern() -> String {
+    "*".to_string()
+}
+
+/// Where cost alerts are delivered, per severity
+///
+/// Severities left out of the config file keep their default routes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertDeliveryConfig {
+    pub info: Vec<AlertSinkKind>,
+    pub warning: Vec<AlertSinkKind>,
+    pub critical: Vec<AlertSinkKind>,
+    pub emergency: Vec<AlertSinkKind>,
+    /// Endpoint receiving a JSON POST for alerts routed to `webhook`; without it the route is skipped
+    pub webhook_url: Option<String>,
+    /// Extra headers sent with webhook requests (e.g. authorization)
+    pub webhook_headers: HashMap<String, String>,
+    /// Alert log file; defaults to `alerts.log` in the logs directory
+    pub log_file: Option<String>,
+}
+
+impl Default for AlertDeliveryConfig {
+    fn default() -> Self {
+        use AlertSinkKind::*;
+        Self {
+            info: vec![Tui, Log],
+            warning: vec![Tui, Log],
+            critical: vec![Tui, Log, Desktop],
+            emergency: vec![Tui, Log, Desktop, Webhook],
+            webhook_url: None,
+            webhook_headers: HashMap::new(),
+            log_file: None,
+        }
+    }
+}
+
+impl AlertDeliveryConfig {
+    pub fn sinks_for(&self, severity: &AlertSeverity) -> &[AlertSinkKind] {
+        match severity {
+            AlertSeverity::Info => &self.info,
+            AlertSeverity::Warning => &self.warning,
+            AlertSeverity::Critical => &self.critical,
+            AlertSeverity::Emergency => &self.emergency,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertSinkKind {
+    /// Banner in the terminal UI
+    Tui,
+    /// Desktop notification (freedesktop notification spec)
+    Desktop,
+    /// JSON POST to `webhook_url`
+    Webhook,
+    /// JSON line appended to the alert log file
+    Log,
+}
+
+impl AlertSinkKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertSinkKind::Tui => "tui",
+            AlertSinkKind::Desktop => "desktop",
+            AlertSinkKind::Webhook => "webhook",
+            AlertSinkKind::Log => "log",
+        }
+    }
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         let mut default_models = HashMap::new();
@@ -129,6 +310,7 @@ impl Default for AppConfig {
             default_model: "gpt-4".to_string(),
             enabled: false, // Disabled by default until API key is configured
             api_endpoint: None, // Uses default OpenAI endpoint
+            api_key_command: None,
             timeout_seconds: Some(60),
             max_retries: Some(3),
             rate_limits: Some(ProviderRateLimits {
@@ -147,6 +329,7 @@ impl Default for AppConfig {
             default_model: "claude-3-sonnet-20240229".to_string(),
             enabled: false, // Disabled by default until API key is configured
             api_endpoint: None, // Uses default Anthropic endpoint
+            api_key_command: None,
             timeout_seconds: Some(60),
             max_retries: Some(3),
             rate_limits: Some(ProviderRateLimits {
@@ -165,6 +348,7 @@ impl Default for AppConfig {
             default_model: "gemini-1.5-flash".to_string(),
             enabled: false, // Disabled by default until API key is configured
             api_endpoint: None, // Uses default Gemini endpoint
+            api_key_command: None,
             timeout_seconds: Some(60),
             max_retries: Some(3),
             rate_limits: Some(ProviderRateLimits {
@@ -199,11 +383,14 @@ impl Default for AppConfig {
             models: default_models,
             mcp_servers: HashMap::new(),
             billing: BillingConfig {
-                daily_limit_usd: Some(10.0),
-                monthly_limit_usd: Some(100.0),
+                daily_limit: Some(10.0),
+                monthly_limit: Some(100.0),
                 per_model_limits: HashMap::new(),
                 alert_threshold_percent: 80.0,
                 track_usage: true,
+                confirm_request_cost: default_confirm_request_cost(),
+                currency: CurrencyConfig::default(),
+                emergency_stop_on_anomaly: false,
             },
             ui: UIConfig {
                 theme: "system".to_string(), // system, light, dark
@@ -242,46 +429,28 @@ impl Default for AppConfig {
                 performance_tracking: true,
                 model_preferences,
             },
+            tool_permissions: ToolPermissionConfig::default(),
+            alerts: AlertDeliveryConfig::default(),
+            backup: BackupConfig::default(),
+            encryption: EncryptionConfig::default(),
+            retention: RetentionConfig::default(),
+            secrets: SecretsConfig::default(),
         }
     }
 }
 
 impl AppConfig {
+    /// Load the configuration layered from every source this process sees; see [`LayeredConfig`]
     pub async fn load(paths: &AppPaths) -> Result<Self> {
-        let config_file = paths.config_file();
-        
-        if !config_file.exists() {
-            info!("Config file not found, creating default configuration");
-            let default_config = Self::default();
-            default_config.save(paths).await?;
-            return Ok(default_config);
-        }
-
-        info!("Loading configuration from: {:?}", config_file);
-        
-        let config_content = fs::read_to_string(&config_file).await?;
-        let config: AppConfig = toml::from_str(&config_content)
-            .map_err(|e| Error::Config(config::ConfigError::Message(e.to_string())))?;
-
-        // Validate configuration
-        config.validate()?;
-
+        let layered = LayeredConfig::load(&ConfigSources::discover(paths, None, Vec::new()))?;
+        layered.config.validate()?;
         info!("Configuration loaded successfully");
-        Ok(config)
+        Ok(layered.config)
     }
 
-    pub async fn save(&self, paths: &AppPaths) -> Result<()> {
-        let config_file = paths.config_file();
-        
-        info!("Saving configuration to: {:?}", config_file);
-        
-        let config_content = toml::to_string_pretty(self)
-            .map_err(|e| Error::Config(config::ConfigError::Message(e.to_string())))?;
-        
-        fs::write(&config_file, config_content).await?;
-        
-        info!("Configuration saved successfully");
-        Ok(())
+    /// Save as the user file of `sources`, keeping only what differs from the defaults and system file
+    pub fn save(&self, sources: &ConfigSources) -> Result<()> {
+        save_user_config(sources, self)
     }
 
     pub fn validate(&self) -> Result<()> {
@@ -302,10 +471,39 @@ impl AppConfig {
             }
         }
 
+        // Validate tool permission rules
+        for rule in &self.tool_permissions.rules {
+            if rule.server.is_empty() || rule.tool.is_empty() {
+                return Err(Error::validation("Tool permission rules need non-empty server and tool patterns"));
+            }
+            if rule.arguments.iter().any(|c| c.argument.is_empty()) {
+                return Err(Error::validation(format!(
+                    "Tool permission rule for {}/{} has an argument constraint without an argument name",
+                    rule.server, rule.tool
+                )));
+            }
+        }
+
+        // Validate alert delivery
+        if let Some(url) = &self.alerts.webhook_url {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(Error::validation(format!("Alert webhook URL must be http(s): {}", url)));
+            }
+        }
+
         // Validate billing configuration
         if self.billing.alert_threshold_percent < 0.0 || self.billing.alert_threshold_percent > 100.0 {
             return Err(Error::validation("Alert threshold must be between 0 and 100"));
         }
+        if self.billing.confirm_request_cost < 0.0 {
+            return Err(Error::validation("Request confirmation cost cannot be negative"));
+        }
+        let rates = ExchangeRates::new(self.billing.currency.rates.iter().map(|(code, rate)| (code, *rate)))?;
+        for code in [&self.billing.currency.display, &self.billing.currency.limits] {
+            if rates.rate(&normalize_code(code)?).is_none() {
+                return Err(Error::validation(format!("No exchange rate configured for {}", code)));
+            }
+        }
 
         // Validate UI configuration
         if self.ui.font_size < 8 || self.ui.font_size > 32 {
@@ -363,6 +561,107 @@ impl AppConfig {
                 }?;
                 limit_str.parse().ok()
             })
+            .and_then(|amount| self.limit_to_usd(amount))
+    }
+
+    /// Exchange rate table from the currency settings; invalid entries are rejected by `validate`
+    pub fn exchange_rates(&self) -> ExchangeRates {
+        ExchangeRates::new(self.billing.currency.rates.iter().map(|(code, rate)| (code, *rate))).unwrap_or_default()
+    }
+
+    /// Formatter for the configured display currency, USD if it has no rate
+    pub fn currency_formatter(&self) -> CurrencyFormatter {
+        CurrencyFormatter::new(&self.billing.currency.display, &self.exchange_rates()).unwrap_or_default()
+    }
+
+    /// Convert a configured limit amount from the limit currency into USD
+    pub fn limit_to_usd(&self, amount: Decimal) -> Option<Decimal> {
+        self.exchange_rates().to_usd(amount, &self.billing.currency.limits).ok()
+    }
+
+    /// Convert a USD amount into the limit currency, for showing or editing limits
+    pub fn limit_from_usd(&self, amount: Decimal) -> Option<Decimal> {
+        self.exchange_rates().from_usd(amount, &self.billing.currency.limits).ok()
+    }
+
+    fn billing_limit(&self, amount: Option<f64>) -> Option<Decimal> {
+        amount
+            .and_then(|amount| Decimal::try_from(amount).ok())
+            .and_then(|amount| self.limit_to_usd(amount))
+    }
+
+    /// Spending limits defined in the billing section and per-provider cost limits
+    ///
+    /// `per_model_limits` are monthly; invalid or non-positive amounts are skipped.
+    /// Amounts are converted from the limit currency into USD.
+    pub fn spending_limits(&self) -> Vec<SpendingLimit> {
+        let threshold = Some((self.billing.alert_threshold_percent / 100.0) as f32);
+        let mut limits = Vec::new();
+        let mut push = |limit_type: SpendingLimitType, amount: Option<Decimal>, period: BillingPeriod| {
+            if let Some(amount) = amount.filter(|amount| *amount > Decimal::ZERO) {
+                limits.push(SpendingLimit::new(limit_type, amount, period, threshold));
+            }
+        };
+
+        push(SpendingLimitType::Global, self.billing_limit(self.billing.daily_limit), BillingPeriod::Daily);
+        push(SpendingLimitType::Global, self.billing_limit(self.billing.monthly_limit), BillingPeriod::Monthly);
+
+        for (model, amount) in &self.billing.per_model_limits {
+            push(SpendingLimitType::PerModel(model.clone()), self.billing_limit(Some(*amount)), BillingPeriod::Monthly);
+        }
+
+        for provider in self.models.keys() {
+            let scope = || SpendingLimitType::PerProvider(provider.clone());
+            push(scope(), self.get_cost_limit_as_decimal(provider, "request"), BillingPeriod::PerRequest);
+            push(scope(), self.get_cost_limit_as_decimal(provider, "daily"), BillingPeriod::Daily);
+            push(scope(), self.get_cost_limit_as_decimal(provider, "monthly"), BillingPeriod::Monthly);
+        }
+
+        limits
+    }
+
+    /// Estimated request cost above which the user has to confirm a request to `provider`
+    ///
+    /// The lower of the configured confirmation cost and the provider's `max_cost_per_request`.
+    pub fn request_confirmation_threshold(&self, provider: &str) -> Option<Decimal> {
+        let configured = self.billing_limit(Some(self.billing.confirm_request_cost))
+            .filter(|amount| *amount > Decimal::ZERO);
+        let per_request = self.get_cost_limit_as_decimal(provider, "request")
+            .filter(|amount| *amount > Decimal::ZERO);
+
+        match (configured, per_request) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Alert thresholds derived from the billing limits
+    ///
+    /// Warnings start at the alert threshold, critical alerts at the limit itself.
+    /// Provider thresholds use the monthly provider cost limit.
+    pub fn cost_alert_config(&self) -> CostAlertConfig {
+        let threshold = Decimal::try_from(self.billing.alert_threshold_percent / 100.0).unwrap_or(Decimal::ONE);
+        let limit = |value: Option<f64>| self.billing_limit(value).filter(|v| *v > Decimal::ZERO);
+        let daily = limit(self.billing.daily_limit);
+        let monthly = limit(self.billing.monthly_limit);
+
+        let provider_limits = self.models.keys()
+            .filter_map(|provider| {
+                self.get_cost_limit_as_decimal(provider, "monthly").map(|amount| (provider.clone(), amount))
+            })
+            .collect();
+
+        CostAlertConfig {
+            enabled: self.billing.track_usage,
+            daily_warning_limit: daily.map(|d| d * threshold),
+            daily_critical_limit: daily,
+            monthly_warning_limit: monthly.map(|m| m * threshold),
+            monthly_critical_limit: monthly,
+            provider_limits,
+            emergency_stop_on_anomaly: self.billing.emergency_stop_on_anomaly,
+            money: self.currency_formatter(),
+            ..CostAlertConfig::default()
+        }
     }
 
     pub fn to_rate_limits(&self, provider: &str) -> crate::models::provider::RateLimits {
@@ -422,13 +721,109 @@ mod tests {
         assert!(config.models.contains_key("openai"));
         assert!(config.models.contains_key("anthropic"));
         assert!(config.models.contains_key("gemini"));
-        assert_eq!(config.billing.daily_limit_usd, Some(10.0));
+        assert_eq!(config.billing.daily_limit, Some(10.0));
         assert_eq!(config.ui.theme, "system");
         assert!(config.fallback.enabled);
         assert!(config.rate_limiting.enabled);
         assert!(config.capability_detection.enabled);
     }
 
+    #[test]
+    fn test_tool_permissions_without_default_action() {
+        let permissions: ToolPermissionConfig = toml::from_str("[[rules]]\nserver = \"fs\"\ntool = \"*\"\naction = \"allow\"\n").unwrap();
+        assert_eq!(permissions.default_action, PermissionAction::Ask);
+        assert_eq!(permissions.rules.len(), 1);
+    }
+
+    #[test]
+    fn test_spending_limits_from_config() {
+        let mut config = AppConfig::default();
+        config.billing.per_model_limits.insert("gpt-4".to_string(), 25.0);
+        config.models.get_mut("openai").unwrap().cost_limits = Some(CostLimits {
+            max_cost_per_request: Some("0.50".to_string()),
+            daily_cost_limit: None,
+            monthly_cost_limit: Some("not a number".to_string()),
+        });
+
+        let limits = config.spending_limits();
+        assert_eq!(limits.len(), 4);
+        assert!(limits.iter().any(|l| l.limit_type == SpendingLimitType::Global && l.period == BillingPeriod::Daily));
+        assert!(limits.iter().any(|l| l.limit_type == SpendingLimitType::PerModel("gpt-4".to_string())
+            && l.period == BillingPeriod::Monthly && l.amount == Decimal::from(25)));
+        assert!(limits.iter().any(|l| l.limit_type == SpendingLimitType::PerProvider("openai".to_string())
+            && l.period == BillingPeriod::PerRequest && l.amount == Decimal::new(50, 2)));
+        assert!(limits.iter().all(|l| l.alert_threshold == Some(0.8)));
+
+        assert_eq!(config.request_confirmation_threshold("openai"), Some(Decimal::new(25, 2)));
+        config.billing.confirm_request_cost = 0.0;
+        assert_eq!(config.request_confirmation_threshold("openai"), Some(Decimal::new(50, 2)));
+        assert_eq!(config.request_confirmation_threshold("anthropic"), None);
+    }
+
+    #[test]
+    fn test_limits_in_local_currency() {
+        let mut config = AppConfig::default();
+        config.billing.currency.rates.insert("EUR".to_string(), Decimal::new(80, 2));
+        config.billing.currency.display = "EUR".to_string();
+        config.billing.currency.limits = "EUR".to_string();
+        config.billing.monthly_limit = Some(80.0);
+        config.models.get_mut("openai").unwrap().cost_limits = Some(CostLimits {
+            max_cost_per_request: Some("0.40".to_string()),
+            daily_cost_limit: None,
+            monthly_cost_limit: None,
+        });
+        assert!(config.validate().is_ok());
+
+        let limits = config.spending_limits();
+        assert!(limits.iter().any(|l| l.limit_type == SpendingLimitType::Global
+            && l.period == BillingPeriod::Monthly && l.amount == Decimal::from(100)));
+        assert_eq!(config.get_cost_limit_as_decimal("openai", "request"), Some(Decimal::new(50, 2)));
+        assert_eq!(config.limit_from_usd(Decimal::from(100)), Some(Decimal::from(80)));
+        assert_eq!(config.currency_formatter().format(Decimal::from(10)), "€8.00");
+        assert_eq!(config.cost_alert_config().money, config.currency_formatter());
+
+        config.billing.currency.display = "GBP".to_string();
+        assert!(config.validate().is_err());
+
+        let currency: CurrencyConfig = toml::from_str("display = \"EUR\"\n[rates]\nEUR = 0.92\nGBP = \"0.79\"\n").unwrap();
+        assert_eq!(currency.limits, "USD");
+        assert_eq!(currency.rates.get("EUR"), Some(&Decimal::new(92, 2)));
+        assert_eq!(currency.rates.get("GBP"), Some(&Decimal::new(79, 2)));
+
+        // Limits saved under their former `_usd` names still load
+        let billing: BillingConfig = toml::from_str(
+            "daily_limit_usd = 5.0\nper_model_limits = {}\nalert_threshold_percent = 80.0\ntrack_usage = true\n"
+        ).unwrap();
+        assert_eq!(billing.daily_limit, Some(5.0));
+    }
+
+    #[test]
+    fn test_alert_thresholds_and_routes() {
+        let mut config = AppConfig::default();
+        config.billing.monthly_limit = None;
+        config.models.get_mut("anthropic").unwrap().cost_limits = Some(CostLimits {
+            max_cost_per_request: None,
+            daily_cost_limit: None,
+            monthly_cost_limit: Some("40".to_string()),
+        });
+
+        let thresholds = config.cost_alert_config();
+        assert_eq!(thresholds.daily_warning_limit, Some(Decimal::from(8)));
+        assert_eq!(thresholds.daily_critical_limit, Some(Decimal::from(10)));
+        assert_eq!(thresholds.monthly_warning_limit, None);
+        assert_eq!(thresholds.provider_limits.get("anthropic"), Some(&Decimal::from(40)));
+
+        let routes = "info = []\ncritical = [\"desktop\", \"webhook\"]\nwebhook_url = \"https://example.com/hook\"\n";
+        config.alerts = toml::from_str(routes).unwrap();
+        assert!(config.alerts.sinks_for(&AlertSeverity::Info).is_empty());
+        assert_eq!(config.alerts.sinks_for(&AlertSeverity::Warning), &[AlertSinkKind::Tui, AlertSinkKind::Log]);
+        assert_eq!(config.alerts.sinks_for(&AlertSeverity::Critical), &[AlertSinkKind::Desktop, AlertSinkKind::Webhook]);
+        assert!(config.validate().is_ok());
+
+        config.alerts.webhook_url = Some("ftp://example.com".to_string());
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_priority_sorting() {
         let config = AppConfig::default();
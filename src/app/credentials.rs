@@ -0,0 +1,256 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+use crate::error::{Error, Result};
+
+/// Longest an `api_key_command` may run, e.g. while a password manager asks to be unlocked
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where an API key was found, in the order they are checked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialSource {
+    /// The provider's standard variable, e.g. `OPENAI_API_KEY`
+    Environment(String),
+    /// The provider's `api_key_command`
+    Command,
+    /// The system keychain or encrypted secrets file
+    SecureStorage,
+}
+
+impl CredentialSource {
+    /// Short form recorded in the key audit log
+    pub fn as_str(&self) -> String {
+        match self {
+            CredentialSource::Environment(name) => format!("env:{}", name),
+            CredentialSource::Command => "command".to_string(),
+            CredentialSource::SecureStorage => "storage".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CredentialSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialSource::Environment(name) => write!(f, "environment variable {}", name),
+            CredentialSource::Command => write!(f, "api_key_command"),
+            CredentialSource::SecureStorage => write!(f, "secure storage"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ResolvedApiKey {
+    pub value: String,
+    pub source: CredentialSource,
+}
+
+impl fmt::Debug for ResolvedApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResolvedApiKey").field("source", &self.source).finish_non_exhaustive()
+    }
+}
+
+/// Id of a provider's key: the provider name for its default key, `provider/name` for a named one
+pub fn key_id(provider: &str, name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("{}/{}", provider, name),
+        None => provider.to_string(),
+    }
+}
+
+/// Provider and key name of a key id such as `openai/team-a`
+pub fn split_key_id(key_id: &str) -> (&str, Option<&str>) {
+    match key_id.split_once('/') {
+        Some((provider, name)) => (provider, Some(name)),
+        None => (key_id, None),
+    }
+}
+
+/// Check that a key id is a provider name optionally followed by `/name`
+pub fn validate_key_id(key_id: &str) -> Result<()> {
+    let valid = |part: &str| {
+        part.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && part.ends_with(|c: char| c.is_ascii_alphanumeric())
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+    };
+    let (provider, name) = split_key_id(key_id);
+    // The first `__` of a variable name must be the one separating provider and key name
+    if !valid(provider) || env_name_part(provider).contains("__") || !name.is_none_or(valid) {
+        return Err(Error::validation(format!(
+            "Invalid API key id `{}`: use a provider name such as `openai`, or `openai/team-a` for a named key",
+            key_id
+        )));
+    }
+    Ok(())
+}
+
+/// A key in secure storage, without its value
+#[derive(Debug, Clone)]
+pub struct StoredApiKey {
+    pub id: String,
+    /// When the value was last set; unknown for keys stored before rotation was tracked
+    pub rotated_at: Option<DateTime<Utc>>,
+    /// Rotation schedule of the key, or the default from `[secrets]`
+    pub rotate_every_days: Option<u32>,
+}
+
+impl StoredApiKey {
+    /// When the key should be replaced, if it has a schedule
+    pub fn rotation_due(&self) -> Option<DateTime<Utc>> {
+        let days = self.rotate_every_days.filter(|days| *days > 0)?;
+        Some(self.rotated_at? + ChronoDuration::days(days as i64))
+    }
+
+    pub fn is_rotation_due(&self, now: DateTime<Utc>) -> bool {
+        self.rotation_due().is_some_and(|due| due <= now)
+    }
+}
+
+/// Standard variable holding the key for `key_id`, e.g. `OPENAI_API_KEY`, `OPENAI_ADMIN_API_KEY` or `OPENAI__TEAM_A_API_KEY` for `openai/team-a`
+///
+/// The double underscore keeps named keys apart from providers such as `openai-admin`.
+pub fn env_var_name(key_id: &str) -> String {
+    match split_key_id(key_id) {
+        (provider, Some(name)) => format!("{}__{}_API_KEY", env_name_part(provider), env_name_part(name)),
+        (provider, None) => format!("{}_API_KEY", env_name_part(provider)),
+    }
+}
+
+fn env_name_part(part: &str) -> String {
+    part.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect()
+}
+
+/// Key for `provider` from its environment variable, if set
+pub fn key_from_env(provider: &str) -> Option<ResolvedApiKey> {
+    let name = env_var_name(provider);
+    let value = std::env::var(&name).ok().filter(|value| !value.trim().is_empty())?;
+    Some(ResolvedApiKey { value: value.trim().to_string(), source: CredentialSource::Environment(name) })
+}
+
+/// Run the `api_key_command` of `provider` through the shell and take the first line it prints as the key
+pub async fn run_api_key_command(provider: &str, command: &str) -> Result<String> {
+    debug!("Running api_key_command for {}", provider);
+    let mut process = if cfg!(windows) {
+        let mut process = tokio::process::Command::new("cmd");
+        process.arg("/C").arg(command);
+        process
+    } else {
+        let mut process = tokio::process::Command::new("sh");
+        process.arg("-c").arg(command);
+        process
+    };
+    process.stdin(std::process::Stdio::null()).kill_on_drop(true);
+
+    let output = tokio::time::timeout(COMMAND_TIMEOUT, process.output())
+        .await
+        .map_err(|_| Error::platform(format!(
+            "api_key_command for {} did not finish within {}s", provider, COMMAND_TIMEOUT.as_secs()
+        )))?
+        .map_err(|e| Error::platform(format!("Failed to run api_key_command for {}: {}", provider, e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr.trim();
+        return Err(Error::platform(format!(
+            "api_key_command for {} failed ({}){}{}", provider, output.status,
+            if detail.is_empty() { "" } else { ": " }, detail
+        )));
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| Error::platform(format!("api_key_command for {} printed no key", provider)))
+}
+
+/// Resolved keys kept in memory for a limited time, so rotated keys and changed variables are picked up
+#[derive(Default)]
+pub struct ApiKeyCache {
+    entries: RwLock<HashMap<String, (ResolvedApiKey, Instant)>>,
+}
+
+impl ApiKeyCache {
+    pub fn get(&self, provider: &str, ttl: Duration) -> Option<ResolvedApiKey> {
+        self.entries.read()
+            .get(provider)
+            .filter(|(_, fetched)| fetched.elapsed() < ttl)
+            .map(|(key, _)| key.clone())
+    }
+
+    pub fn insert(&self, provider: &str, key: ResolvedApiKey) {
+        self.entries.write().insert(provider.to_string(), (key, Instant::now()));
+    }
+
+    pub fn remove(&self, provider: &str) {
+        self.entries.write().remove(provider);
+    }
+
+    pub fn clear(&self) {
+        self.entries.write().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_var_name() {
+        assert_eq!(env_var_name("openai"), "OPENAI_API_KEY");
+        assert_eq!(env_var_name("gemini"), "GEMINI_API_KEY");
+        assert_eq!(env_var_name("openai-admin"), "OPENAI_ADMIN_API_KEY");
+        assert_eq!(env_var_name("openai/team-a"), "OPENAI__TEAM_A_API_KEY");
+
+        // The admin key used for billing reconciliation is not a named key of `openai`
+        assert_ne!(env_var_name("openai/admin"), env_var_name("openai-admin"));
+        assert!(validate_key_id("openai__admin").is_err());
+        assert!(validate_key_id("openai-_admin").is_err());
+        assert!(validate_key_id("openai_/admin").is_err());
+        assert!(validate_key_id("openai/_admin").is_err());
+    }
+
+    #[test]
+    fn test_key_ids() {
+        assert_eq!(split_key_id("openai/team-a"), ("openai", Some("team-a")));
+        assert_eq!(split_key_id("openai"), ("openai", None));
+        assert_eq!(key_id("openai", Some("team-a")), "openai/team-a");
+        assert!(validate_key_id("openai/team-a").is_ok());
+        assert!(validate_key_id("openai-admin").is_ok());
+        for invalid in ["", "openai/", "/team-a", "openai/team/a", "open ai"] {
+            assert!(validate_key_id(invalid).is_err(), "{} should be rejected", invalid);
+        }
+
+        let now = Utc::now();
+        let key = StoredApiKey { id: "openai/team-a".into(), rotated_at: Some(now - ChronoDuration::days(91)), rotate_every_days: Some(90) };
+        assert!(key.is_rotation_due(now));
+        assert!(!StoredApiKey { rotate_every_days: Some(0), ..key.clone() }.is_rotation_due(now));
+        assert!(!StoredApiKey { rotated_at: None, ..key }.is_rotation_due(now));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_api_key_command() {
+        assert_eq!(run_api_key_command("openai", "printf 'sk-test\\nsecond line\\n'").await.unwrap(), "sk-test");
+        let error = run_api_key_command("openai", "echo locked >&2; exit 2").await.unwrap_err().to_string();
+        assert!(error.contains("openai") && error.contains("locked"));
+        assert!(run_api_key_command("openai", "true").await.is_err());
+    }
+
+    #[test]
+    fn test_cache_expires() {
+        let cache = ApiKeyCache::default();
+        cache.insert("openai", ResolvedApiKey { value: "sk".into(), source: CredentialSource::Command });
+        assert_eq!(cache.get("openai", Duration::from_secs(60)).unwrap().source, CredentialSource::Command);
+        assert!(cache.get("openai", Duration::ZERO).is_none());
+        cache.remove("openai");
+        assert!(cache.get("openai", Duration::from_secs(60)).is_none());
+    }
+}
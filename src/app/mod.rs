@@ -1,8 +1,12 @@
 pub mod config;
+pub mod credentials;
+pub mod layers;
 pub mod state;
 
 pub use config::{AppConfig, ModelConfig, MCPServerConfig, BillingConfig, UIConfig};
-pub use state::AppState;
+pub use credentials::{CredentialSource, ResolvedApiKey, StoredApiKey};
+pub use layers::{apply_changes, ConfigOrigin, ConfigSources, LayeredConfig, SettingChange};
+pub use state::{AppState, ConfigApplied, RequestCostContext};
 
 // Imports will be added back when Tauri integration is restored
 
@@ -1,26 +1,71 @@
 use std::sync::Arc;
+use std::time::Duration;
 use std::collections::HashMap;
 use parking_lot::RwLock;
-use tracing::{info, debug};
+use tracing::{info, debug, warn};
 
-use crate::app::config::AppConfig;
+use crate::app::config::{AppConfig, MCPServerConfig};
+use crate::app::credentials::{self, ApiKeyCache, CredentialSource, ResolvedApiKey, StoredApiKey};
+use crate::app::layers::{ConfigFingerprint, ConfigSources, LayeredConfig};
 use crate::error::Result;
 use crate::platform::{AppPaths, SecureStorageManager};
-use crate::storage::{Database, ConversationRepository, UsageRepository};
-use crate::chat::types::{MessageContent, ChatMessage, MessageRole as ChatMessageRole};
+use crate::storage::{
+    Database, ConversationRepository, UsageRepository, ProjectRepository, BillingSystem,
+    SpendingEnforcement, EnforcementAction, EnforcementConfig, AlertContext, AlertSeverity, AlertStore, CostAlert, CostAlertSystem, CostAlertType,
+    SpendForecast, SpendForecaster, BackupSystem, RetentionManager, ApiKeyAuditLog, KeyOperation, KeyScope,
+    KeySelectionRepository,
+};
+use crate::storage::encryption;
+use crate::billing::{AlertDispatcher, TuiAlertSink};
+use crate::app::config::AlertSinkKind;
+use crate::storage::usage::UsageParams;
+use crate::chat::types::{MessageContent, ChatMessage, MessageRole as ChatMessageRole, SessionSettings, ToolInvocation};
 use crate::models::provider::ModelProvider;
-use crate::mcp::{MCPClient, MCPClientConfig, MCPServerManager};
+use crate::mcp::{
+    MCPClient, MCPClientConfig, MCPServerManager, ResourceManager, ResourceConfig, Content,
+    PromptTemplateManager, PromptTemplateConfig, TemplateContext, AttachmentLimits,
+};
+use crate::mcp::resources::ResourceUpdateEvent;
+use crate::mcp::permissions::{
+    allow_always_rule, ApprovalResponse, PermissionDecision, ToolApprovalRequest, ToolDecision, ToolPermissionPolicy,
+};
+
+/// Overrides the backup passphrase kept in secure storage
+pub const BACKUP_PASSPHRASE_ENV: &str = "VALECHAT_BACKUP_PASSPHRASE";
+const BACKUP_PASSPHRASE_SECRET: &str = "backup_passphrase";
 
 pub struct AppState {
     config: Arc<RwLock<AppConfig>>,
+    /// Files, environment and overrides the configuration was layered from; unset keeps changes in memory
+    config_sources: Option<ConfigSources>,
+    /// File state the running configuration reflects, so saving it is not taken for an edit
+    config_fingerprint: Arc<RwLock<ConfigFingerprint>>,
     paths: AppPaths,
     secure_storage: SecureStorageManager,
-    database: Database,
+    database: Arc<Database>,
+    /// Replaced when the backup settings change
+    backups: RwLock<Arc<BackupSystem>>,
+    /// Scheduled backups, once started; restarted under new backup settings
+    backup_schedule: parking_lot::Mutex<Option<tokio::task::JoinHandle<()>>>,
     conversation_repo: ConversationRepository,
     usage_repo: UsageRepository,
-    api_key_cache: Arc<RwLock<HashMap<String, String>>>,
+    project_repo: ProjectRepository,
+    key_selections: KeySelectionRepository,
+    billing: Arc<BillingSystem>,
+    enforcement: Arc<SpendingEnforcement>,
+    cost_alerts: Arc<tokio::sync::Mutex<CostAlertSystem>>,
+    alert_store: AlertStore,
+    tui_alerts: TuiAlertSink,
+    api_key_cache: Arc<ApiKeyCache>,
+    key_audit: ApiKeyAuditLog,
+    /// Command this process runs, recorded with every key operation
+    audit_command: Option<String>,
     mcp_client: Option<Arc<tokio::sync::Mutex<MCPClient>>>,
     mcp_server_manager: Arc<tokio::sync::Mutex<MCPServerManager>>,
+    resource_client: Option<Arc<MCPClient>>,
+    resource_manager: Arc<ResourceManager>,
+    prompt_manager: Arc<PromptTemplateManager>,
+    tool_approver: Arc<RwLock<Option<tokio::sync::mpsc::UnboundedSender<ToolApprovalRequest>>>>,
 }
 
 impl AppState {
@@ -32,12 +77,41 @@ impl AppState {
         info!("Initializing application state");
 
         // Initialize database
-        let database = Database::new(&paths).await?;
+        let database = Arc::new(Database::new(&paths).await?);
         let pool = database.get_pool();
+        let backups = Arc::new(BackupSystem::new(database.clone(), Arc::new(paths.clone()), config.backup.clone()));
+        if let Ok(passphrase) = std::env::var(BACKUP_PASSPHRASE_ENV) {
+            backups.set_passphrase(Some(passphrase));
+        }
         
+        // Conversation content is encrypted at rest when enabled
+        let cipher = encryption::open_field_cipher(&pool, &secure_storage, &config.encryption).await?;
+
         // Initialize repositories
-        let conversation_repo = ConversationRepository::new(pool.clone());
+        let conversation_repo = ConversationRepository::new(pool.clone()).with_cipher(cipher);
         let usage_repo = UsageRepository::new(pool.clone());
+        let project_repo = ProjectRepository::new(pool.clone());
+        let key_selections = KeySelectionRepository::new(pool.clone());
+        let billing = Arc::new(BillingSystem::new(pool.clone()));
+        let enforcement = Arc::new(SpendingEnforcement::new(billing.clone()));
+
+        // Configured limits live in the same store as limits set at runtime
+        if let Err(e) = billing.sync_config_limits(config.spending_limits()).await {
+            warn!("Failed to import spending limits from configuration: {}", e);
+        }
+
+        // Alerts raised by the cost alert system are fanned out by the dispatcher
+        let alert_store = AlertStore::new(pool.clone());
+        let tui_alerts = TuiAlertSink::new();
+        let (cost_alerts, alert_receiver) = CostAlertSystem::new(config.cost_alert_config());
+        let mut cost_alerts = cost_alerts.with_store(alert_store.clone());
+        if let Err(e) = cost_alerts.load_baselines(&pool).await {
+            warn!("Failed to load spend baselines: {}", e);
+        }
+        AlertDispatcher::from_config(config.alerts.clone(), &paths)?
+            .with_sink(AlertSinkKind::Tui, tui_alerts.clone())
+            .with_store(alert_store.clone())
+            .spawn(alert_receiver);
 
         // Initialize MCP server manager
         let mcp_server_manager = Arc::new(tokio::sync::Mutex::new(MCPServerManager::new()));
@@ -49,25 +123,42 @@ impl AppState {
 
         let mut app_state = Self {
             config: Arc::new(RwLock::new(config.clone())),
+            config_sources: None,
+            config_fingerprint: Arc::new(RwLock::new(Vec::new())),
             paths,
             secure_storage,
             database,
+            backups: RwLock::new(backups),
+            backup_schedule: parking_lot::Mutex::new(None),
             conversation_repo,
             usage_repo,
-            api_key_cache: Arc::new(RwLock::new(HashMap::new())),
+            project_repo,
+            key_selections,
+            billing,
+            enforcement,
+            cost_alerts: Arc::new(tokio::sync::Mutex::new(cost_alerts)),
+            alert_store,
+            tui_alerts,
+            api_key_cache: Arc::new(ApiKeyCache::default()),
+            key_audit: ApiKeyAuditLog::new(pool.clone()),
+            audit_command: None,
             mcp_client,
             mcp_server_manager,
+            resource_client: None,
+            resource_manager: Arc::new(ResourceManager::new(ResourceConfig::default())),
+            prompt_manager: Arc::new(PromptTemplateManager::new(PromptTemplateConfig::default())),
+            tool_approver: Arc::new(RwLock::new(None)),
         };
         
-        // Now initialize MCP client if there are MCP servers configured
-        if !config.mcp_servers.is_empty() {
+        // MCP clients exist even without servers, so servers added to the configuration later can be used
+        {
             let mcp_config = MCPClientConfig::default();
             let server_manager_clone = {
                 let guard = app_state.mcp_server_manager.lock().await;
                 (*guard).clone()
             };
             
-            match MCPClient::new(server_manager_clone, mcp_config) {
+            match MCPClient::new(server_manager_clone.clone(), mcp_config.clone()) {
                 Ok(client) => {
                     app_state.mcp_client = Some(Arc::new(tokio::sync::Mutex::new(client)));
                     info!("MCP client initialized successfully");
@@ -76,6 +167,24 @@ impl AppState {
                     tracing::warn!("Failed to initialize MCP client: {}", e);
                 }
             }
+            
+            // Resource and prompt managers share their own client so they don't contend with tool calls
+            match MCPClient::new(server_manager_clone.clone(), mcp_config) {
+                Ok(client) => app_state.resource_client = Some(Arc::new(client)),
+                Err(e) => tracing::warn!("Failed to initialize MCP resource client: {}", e),
+            }
+            
+            // Forward server notifications (resource updates, list changes) to the resource manager
+            let mut notifications = server_manager_clone.subscribe_notifications().await;
+            server_manager_clone.start_notification_polling(std::time::Duration::from_secs(2));
+            let resource_manager = app_state.resource_manager.clone();
+            tokio::spawn(async move {
+                while let Some((server_name, notification)) = notifications.recv().await {
+                    if let Err(e) = resource_manager.handle_notification(&server_name, notification).await {
+                        tracing::warn!("Failed to handle notification from MCP server {}: {}", server_name, e);
+                    }
+                }
+            });
         }
 
 
@@ -92,6 +201,12 @@ impl AppState {
 
         // Initialize MCP servers
         app_state.initialize_mcp_servers().await?;
+        
+        for (server_name, (state, _)) in app_state.get_mcp_server_status().await {
+            if state == crate::mcp::ServerState::Ready {
+                app_state.register_mcp_server_content(&server_name).await;
+            }
+        }
 
         Ok(app_state)
     }
@@ -100,157 +215,316 @@ impl AppState {
         self.config.read().clone()
     }
 
+    /// Read the configuration from `sources` again when they change, and save changes to their user file
+    pub fn with_config_sources(mut self, sources: ConfigSources) -> Self {
+        self.config_fingerprint = Arc::new(RwLock::new(sources.fingerprint()));
+        self.config_sources = Some(sources);
+        self
+    }
+
+    /// Name the command this process runs in the key audit log, e.g. `chat`
+    pub fn with_audit_command(mut self, command: impl Into<String>) -> Self {
+        self.audit_command = Some(command.into());
+        self
+    }
+
     pub async fn update_config<F>(&self, updater: F) -> Result<()>
     where
         F: FnOnce(&mut AppConfig),
+    {
+        self.try_update_config(|config| {
+            updater(config);
+            Ok(())
+        }).await?;
+        Ok(())
+    }
+
+    /// Like [`update_config`](Self::update_config), for changes that can fail; nothing is saved when they do
+    pub async fn try_update_config<F>(&self, updater: F) -> Result<ConfigApplied>
+    where
+        F: FnOnce(&mut AppConfig) -> Result<()>,
     {
         debug!("Updating application configuration");
-        
-        {
-            let mut config = self.config.write();
-            updater(&mut config);
+
+        let Some(sources) = &self.config_sources else {
+            // Validate a copy so a rejected update leaves the running configuration untouched
+            let mut config = self.config.read().clone();
+            updater(&mut config)?;
             config.validate()?;
-        }
+            return self.apply_config(config).await;
+        };
+
+        // Changes go to the user file; project files, environment and flags still take precedence
+        let mut user = LayeredConfig::user_level(sources)?;
+        updater(&mut user)?;
+        let config = LayeredConfig::load_with_user(sources, &user)?.config;
+        config.validate()?;
 
-        // Save the updated configuration
-        let config = self.config.read().clone();
-        config.save(&self.paths).await?;
+        user.save(sources)?;
+        *self.config_fingerprint.write() = sources.fingerprint();
+        let applied = self.apply_config(config).await?;
 
         info!("Configuration updated and saved");
-        Ok(())
+        Ok(applied)
     }
 
-    pub async fn get_available_models(&self) -> Result<Vec<String>> {
-        let config = self.config.read();
-        let enabled_models = config.get_enabled_models();
-        Ok(enabled_models.into_iter().map(|s| s.to_string()).collect())
+    /// Read the configuration sources again; an invalid configuration leaves the running one in place
+    pub async fn reload_config(&self) -> Result<ConfigApplied> {
+        let Some(sources) = &self.config_sources else {
+            return Ok(ConfigApplied::default());
+        };
+        *self.config_fingerprint.write() = sources.fingerprint();
+        let config = LayeredConfig::load(sources)?.config;
+        config.validate()?;
+        let applied = self.apply_config(config).await?;
+
+        info!("Configuration reloaded");
+        Ok(applied)
     }
 
-    pub async fn get_api_key(&self, provider: &str) -> Result<Option<String>> {
-        // Check cache first
-        {
-            let cache = self.api_key_cache.read();
-            if let Some(api_key) = cache.get(provider) {
-                return Ok(Some(api_key.clone()));
-            }
-        }
+    /// Whether a configuration file changed since the configuration was last read or saved
+    pub fn config_files_changed(&self) -> bool {
+        self.config_sources.as_ref()
+            .is_some_and(|sources| sources.fingerprint() != *self.config_fingerprint.read())
+    }
 
-        // If not in cache, retrieve from secure storage
-        match self.secure_storage.retrieve_api_key(provider).await? {
-            Some(api_key) => {
-                // Cache the retrieved key
-                {
-                    let mut cache = self.api_key_cache.write();
-                    cache.insert(provider.to_string(), api_key.clone());
-                }
-                Ok(Some(api_key))
+    /// Take a validated configuration into use and bring the parts built from it up to date
+    ///
+    /// Providers and retention rules read the configuration whenever they are used. Encryption,
+    /// alert delivery and the secrets backend are set up once, so changes to them need a restart.
+    async fn apply_config(&self, config: AppConfig) -> Result<ConfigApplied> {
+        let previous = std::mem::replace(&mut *self.config.write(), config.clone());
+        // Key commands or the cache lifetime may have changed
+        self.api_key_cache.clear();
+        self.billing.sync_config_limits(config.spending_limits()).await?;
+        self.cost_alerts.lock().await.update_config(config.cost_alert_config());
+
+        if section_changed(&previous.backup, &config.backup) {
+            *self.backups.write() = Arc::new(self.get_backups().with_config(config.backup.clone()));
+            if self.backup_schedule.lock().is_some() {
+                self.start_backup_schedule().await;
             }
-            None => Ok(None),
         }
+
+        let mcp_servers = self.sync_mcp_servers(&previous.mcp_servers, &config.mcp_servers).await;
+        let restart_required = [
+            ("encryption", section_changed(&previous.encryption, &config.encryption)),
+            ("alerts", section_changed(&previous.alerts, &config.alerts)),
+            ("secrets", section_changed(&previous.secrets, &config.secrets)),
+        ]
+        .into_iter()
+        .filter_map(|(section, changed)| changed.then_some(section))
+        .collect();
+
+        Ok(ConfigApplied { mcp_servers, restart_required })
     }
 
-    /// Batch retrieve API keys for multiple providers to reduce keychain prompts
-    pub async fn get_api_keys_batch(&self, providers: &[&str]) -> Result<std::collections::HashMap<String, String>> {
-        let mut result = std::collections::HashMap::new();
-        let mut uncached_providers = Vec::new();
+    /// Start, restart or stop the MCP servers whose configuration changed
+    ///
+    /// Enabled servers are started when they are new, start automatically or were running.
+    /// Returns what happened to each server, e.g. `started fs`.
+    async fn sync_mcp_servers(
+        &self,
+        previous: &HashMap<String, MCPServerConfig>,
+        current: &HashMap<String, MCPServerConfig>,
+    ) -> Vec<String> {
+        let mut names: Vec<&String> = previous.keys().chain(current.keys()).collect();
+        names.sort();
+        names.dedup();
 
-        // First, check cache for all providers
-        {
-            let cache = self.api_key_cache.read();
-            for &provider in providers {
-                if let Some(api_key) = cache.get(provider) {
-                    result.insert(provider.to_string(), api_key.clone());
-                } else {
-                    uncached_providers.push(provider);
-                }
+        let mut changes = Vec::new();
+        for name in names {
+            let config = current.get(name);
+            if !section_changed(&previous.get(name), &config) {
+                continue;
             }
-        }
 
-        // Batch retrieve uncached keys to minimize keychain access
-        if !uncached_providers.is_empty() {
-            let mut cache_updates = std::collections::HashMap::new();
-            
-            for &provider in &uncached_providers {
-                if let Some(api_key) = self.secure_storage.retrieve_api_key(provider).await? {
-                    result.insert(provider.to_string(), api_key.clone());
-                    cache_updates.insert(provider.to_string(), api_key);
+            let mut manager = self.mcp_server_manager.lock().await;
+            let state = manager.get_server(name).await;
+            let was_added = state.is_some();
+            let was_running = matches!(
+                state,
+                Some(crate::mcp::ServerState::Starting | crate::mcp::ServerState::Initializing | crate::mcp::ServerState::Ready)
+            );
+            if was_added {
+                if let Err(e) = manager.remove_server(name).await {
+                    warn!("Failed to stop MCP server {}: {}", name, e);
                 }
             }
 
-            // Update cache with all retrieved keys at once
-            if !cache_updates.is_empty() {
-                let mut cache = self.api_key_cache.write();
-                for (provider, api_key) in cache_updates {
-                    cache.insert(provider, api_key);
+            let is_new = !previous.contains_key(name);
+            let Some(config) = config.filter(|config| config.enabled && (config.auto_start || was_running || is_new)) else {
+                drop(manager);
+                if was_added {
+                    let _ = self.resource_manager.unregister_client(name).await;
+                    let _ = self.prompt_manager.unregister_client(name).await;
+                    if was_running {
+                        changes.push(format!("stopped {}", name));
+                    }
+                }
+                continue;
+            };
+
+            let started = match manager.add_server(name.clone(), config.clone()).await {
+                Ok(()) => manager.start_server(name).await,
+                Err(e) => Err(e),
+            };
+            drop(manager);
+            match started {
+                Ok(()) => {
+                    self.register_mcp_server_content(name).await;
+                    changes.push(format!("{} {}", if was_running { "restarted" } else { "started" }, name));
+                }
+                Err(e) => {
+                    warn!("Failed to start MCP server {}: {}", name, e);
+                    changes.push(format!("failed to start {}", name));
                 }
             }
         }
+        changes
+    }
 
-        Ok(result)
+    pub async fn get_available_models(&self) -> Result<Vec<String>> {
+        let config = self.config.read();
+        let enabled_models = config.get_enabled_models();
+        Ok(enabled_models.into_iter().map(|s| s.to_string()).collect())
+    }
+
+    pub async fn get_api_key(&self, provider: &str) -> Result<Option<String>> {
+        Ok(self.resolve_api_key(provider).await?.map(|key| key.value))
     }
 
-    /// Batch retrieve API keys (single keychain prompt)
-    pub async fn get_api_keys_batch_consolidated(&self, providers: &[&str]) -> Result<std::collections::HashMap<String, String>> {
-        let mut result = std::collections::HashMap::new();
-        let mut uncached_providers = Vec::new();
+    /// Find the key for `provider`, or a named key such as `openai/team-a`, in its environment variable,
+    /// its `api_key_command` or secure storage, in that order, and record the access in the key audit log
+    pub async fn resolve_api_key(&self, provider: &str) -> Result<Option<ResolvedApiKey>> {
+        if let Some(key) = self.api_key_cache.get(provider, self.api_key_ttl()) {
+            self.audit_key_access(provider, &Ok(Some(key.clone()))).await;
+            return Ok(Some(key));
+        }
 
-        // First, check cache for all providers
-        {
-            let cache = self.api_key_cache.read();
-            for &provider in providers {
-                if let Some(api_key) = cache.get(provider) {
-                    result.insert(provider.to_string(), api_key.clone());
-                } else {
-                    uncached_providers.push(provider);
-                }
-            }
+        let result = match self.external_api_key(provider).await {
+            Ok(None) => self.secure_storage.retrieve_api_key(provider).await
+                .map(|key| key.map(|value| ResolvedApiKey { value, source: CredentialSource::SecureStorage })),
+            other => other,
+        };
+        if let Ok(Some(key)) = &result {
+            self.api_key_cache.insert(provider, key.clone());
         }
+        self.audit_key_access(provider, &result).await;
+        result
+    }
 
-        // If we have uncached providers, retrieve the entire bundle at once (single keychain prompt)
-        if !uncached_providers.is_empty() {
-            debug!("Retrieving consolidated API key bundle for {} uncached providers", uncached_providers.len());
-            
-            match self.secure_storage.retrieve_api_key_bundle().await {
-                Ok(bundle) => {
-                    let mut cache_updates = std::collections::HashMap::new();
-                    
-                    // Extract keys for requested providers from the bundle
-                    for &provider in &uncached_providers {
-                        if let Some(api_key) = bundle.get_key(provider) {
-                            result.insert(provider.to_string(), api_key.clone());
-                            cache_updates.insert(provider.to_string(), api_key.clone());
-                        }
-                    }
+    /// Batch retrieve API keys to reduce keychain prompts
+    pub async fn get_api_keys_batch(&self, providers: &[&str]) -> Result<HashMap<String, String>> {
+        self.get_api_keys_batch_consolidated(providers).await
+    }
 
-                    // Update cache with all retrieved keys at once
-                    if !cache_updates.is_empty() {
-                        let mut cache = self.api_key_cache.write();
-                        for (provider, api_key) in cache_updates {
-                            cache.insert(provider, api_key);
-                        }
-                        debug!("Updated cache with {} API keys", result.len());
-                    }
+    /// Batch retrieve API keys, reading secure storage once for every provider without another source
+    pub async fn get_api_keys_batch_consolidated(&self, providers: &[&str]) -> Result<HashMap<String, String>> {
+        let mut resolved = HashMap::new();
+        let mut from_storage = Vec::new();
+
+        let ttl = self.api_key_ttl();
+        for &provider in providers {
+            let key = match self.api_key_cache.get(provider, ttl) {
+                Some(key) => Ok(Some(key)),
+                None => self.external_api_key(provider).await,
+            };
+            match key {
+                Ok(Some(key)) => {
+                    resolved.insert(provider.to_string(), key);
                 }
+                Ok(None) => from_storage.push(provider),
                 Err(e) => {
-                    tracing::warn!("Failed to retrieve consolidated API key bundle: {}", e);
-                    return Err(e);
+                    warn!("Failed to resolve API key for {}: {}", provider, e);
+                    self.audit_key_access(provider, &Err(e)).await;
+                }
+            }
+        }
+
+        // The whole bundle is read at once (single keychain prompt)
+        if !from_storage.is_empty() {
+            debug!("Retrieving consolidated API key bundle for {} providers", from_storage.len());
+            let bundle = self.secure_storage.retrieve_api_key_bundle().await?;
+            for provider in from_storage {
+                match bundle.get_key(provider) {
+                    Some(value) => {
+                        let key = ResolvedApiKey { value: value.clone(), source: CredentialSource::SecureStorage };
+                        resolved.insert(provider.to_string(), key);
+                    }
+                    None => self.audit_key_access(provider, &Ok(None)).await,
                 }
             }
         }
 
+        let mut result = HashMap::new();
+        for (provider, key) in resolved {
+            self.api_key_cache.insert(&provider, key.clone());
+            self.audit_key_access(&provider, &Ok(Some(key.clone()))).await;
+            result.insert(provider, key.value);
+        }
         Ok(result)
     }
 
+    /// Key from the environment or the provider's `api_key_command`, which take precedence over secure storage
+    ///
+    /// Only a provider's default key has an `api_key_command`; named keys come from their variable or storage.
+    async fn external_api_key(&self, provider: &str) -> Result<Option<ResolvedApiKey>> {
+        if let Some(key) = credentials::key_from_env(provider) {
+            return Ok(Some(key));
+        }
+        let command = self.config.read().models.get(provider).and_then(|model| model.api_key_command.clone());
+        match command {
+            Some(command) => {
+                let value = credentials::run_api_key_command(provider, &command).await?;
+                Ok(Some(ResolvedApiKey { value, source: CredentialSource::Command }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn api_key_ttl(&self) -> Duration {
+        Duration::from_secs(self.config.read().secrets.cache_ttl_seconds)
+    }
+
+    async fn audit_key_access(&self, provider: &str, result: &Result<Option<ResolvedApiKey>>) {
+        let (source, error) = match result {
+            Ok(Some(key)) => (Some(key.source.as_str()), None),
+            Ok(None) => (None, Some("No API key found".to_string())),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        self.audit_key_operation(provider, KeyOperation::Accessed, source.as_deref(), error.as_deref()).await;
+    }
+
+    async fn audit_key_operation(&self, provider: &str, operation: KeyOperation, source: Option<&str>, error: Option<&str>) {
+        let command = self.audit_command.as_deref();
+        if let Err(e) = self.key_audit.record(provider, operation, source, command, error).await {
+            warn!("Failed to record API key {} for {}: {}", operation.as_str(), provider, e);
+        }
+    }
+
+    pub fn get_key_audit(&self) -> &ApiKeyAuditLog {
+        &self.key_audit
+    }
+
+    /// Store the key for `provider`, or a named key such as `openai/team-a`, and enable its provider
     pub async fn set_api_key(&self, provider: &str, api_key: &str) -> Result<()> {
-        self.secure_storage.store_api_key(provider, api_key).await?;
+        credentials::validate_key_id(provider)?;
+        let existing = self.secure_storage.retrieve_api_key(provider).await;
+        let operation = if matches!(existing, Ok(Some(_))) { KeyOperation::Updated } else { KeyOperation::Created };
+        let result = match existing {
+            Ok(_) => self.secure_storage.store_api_key(provider, api_key).await,
+            Err(e) => Err(e),
+        };
+        let error = result.as_ref().err().map(ToString::to_string);
+        self.audit_key_operation(provider, operation, Some("storage"), error.as_deref()).await;
+        result?;
         
-        // Update cache
-        {
-            let mut cache = self.api_key_cache.write();
-            cache.insert(provider.to_string(), api_key.to_string());
-        }
+        // The next lookup goes through the chain again, since an environment variable may still take precedence
+        self.api_key_cache.remove(provider);
         
         // Enable the model provider if API key is successfully stored
+        let (provider, _) = credentials::split_key_id(provider);
         self.update_config(|config| {
             if let Some(model_config) = config.models.get_mut(provider) {
                 model_config.enabled = true;
@@ -262,12 +536,17 @@ impl AppState {
     }
 
     pub async fn remove_api_key(&self, provider: &str) -> Result<()> {
-        self.secure_storage.delete_api_key(provider).await?;
+        let result = self.secure_storage.delete_api_key(provider).await;
+        let error = result.as_ref().err().map(ToString::to_string);
+        self.audit_key_operation(provider, KeyOperation::Deleted, Some("storage"), error.as_deref()).await;
+        result?;
         
         // Remove from cache
-        {
-            let mut cache = self.api_key_cache.write();
-            cache.remove(provider);
+        self.api_key_cache.remove(provider);
+
+        // Named keys can go without taking their provider's default key along
+        if credentials::split_key_id(provider).1.is_some() {
+            return Ok(());
         }
         
         // Disable the model provider when API key is removed
@@ -281,6 +560,51 @@ impl AppState {
         Ok(())
     }
 
+    /// Keys in secure storage with their rotation schedule, by id
+    pub async fn list_stored_api_keys(&self) -> Result<Vec<StoredApiKey>> {
+        let bundle = self.secure_storage.retrieve_api_key_bundle().await?;
+        let default_days = self.config.read().secrets.rotate_every_days;
+        let mut keys: Vec<StoredApiKey> = bundle.keys.keys()
+            .map(|id| {
+                let metadata = bundle.metadata.get(id);
+                StoredApiKey {
+                    id: id.clone(),
+                    rotated_at: metadata.map(|metadata| metadata.rotated_at),
+                    rotate_every_days: metadata.and_then(|metadata| metadata.rotate_every_days).or(default_days),
+                }
+            })
+            .collect();
+        keys.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(keys)
+    }
+
+    /// Stored keys whose rotation date has passed
+    pub async fn keys_due_for_rotation(&self) -> Result<Vec<StoredApiKey>> {
+        let now = chrono::Utc::now();
+        Ok(self.list_stored_api_keys().await?.into_iter().filter(|key| key.is_rotation_due(now)).collect())
+    }
+
+    /// Remind to rotate a stored key every `days` days; `None` falls back to the default from `[secrets]`
+    pub async fn set_api_key_rotation(&self, key_id: &str, days: Option<u32>) -> Result<()> {
+        self.secure_storage.set_api_key_rotation(key_id, days).await
+    }
+
+    pub fn get_key_selections(&self) -> &KeySelectionRepository {
+        &self.key_selections
+    }
+
+    /// Use `key_id` for its provider in a conversation or project; the key must be available
+    pub async fn select_api_key(&self, scope: KeyScope<'_>, key_id: &str) -> Result<()> {
+        credentials::validate_key_id(key_id)?;
+        if self.resolve_api_key(key_id).await?.is_none() {
+            return Err(crate::error::Error::validation(format!(
+                "No API key `{}`; set it with `valechat api-key {} --set <key>` or in {}",
+                key_id, key_id, credentials::env_var_name(key_id)
+            )));
+        }
+        self.key_selections.select(scope, key_id).await
+    }
+
     pub fn get_paths(&self) -> &AppPaths {
         &self.paths
     }
@@ -297,14 +621,107 @@ impl AppState {
         &self.usage_repo
     }
 
+    pub fn get_project_repo(&self) -> &ProjectRepository {
+        &self.project_repo
+    }
+
+    pub fn get_billing(&self) -> Arc<BillingSystem> {
+        self.billing.clone()
+    }
+
+    pub fn get_enforcement(&self) -> Arc<SpendingEnforcement> {
+        self.enforcement.clone()
+    }
+
+    pub fn get_alert_store(&self) -> &AlertStore {
+        &self.alert_store
+    }
+
+    /// Forecast this month's spend and project every spending limit
+    pub async fn forecast_spending(&self) -> Result<SpendForecast> {
+        let limits = self.billing.list_spending_limits().await?;
+        SpendForecaster::new(self.database.get_pool()).forecast(&limits).await
+    }
+
+    /// Register the channel the terminal UI receives cost alerts on
+    pub fn set_alert_listener(&self, sender: tokio::sync::mpsc::UnboundedSender<CostAlert>) {
+        self.tui_alerts.attach(sender);
+    }
+
+    /// Acknowledge a cost alert, returning false if it is unknown or already acknowledged
+    pub async fn acknowledge_alert(&self, alert_id: &str) -> Result<bool> {
+        self.cost_alerts.lock().await.acknowledge_alert(alert_id).await
+    }
+
     pub fn get_database(&self) -> &Database {
         &self.database
     }
 
-    pub async fn validate_provider_credentials(&self, provider: &str) -> Result<bool> {
-        match self.get_api_key(provider).await? {
+    /// Retention rules from the current configuration
+    pub fn get_retention(&self) -> RetentionManager {
+        RetentionManager::new(self.database.get_pool(), self.config.read().retention.clone())
+    }
+
+    pub fn get_backups(&self) -> Arc<BackupSystem> {
+        self.backups.read().clone()
+    }
+
+    /// Run scheduled backups in the background, restarting them if they already run
+    ///
+    /// Changes to the backup settings restart the schedule once it has been started.
+    pub async fn start_backup_schedule(&self) {
+        let backups = self.get_backups();
+        let enabled = backups.config().enabled && backups.config().auto_backup_interval_hours > 0;
+        if enabled {
+            if let Err(e) = self.unlock_backups().await {
+                debug!("Backup passphrase unavailable: {}", e);
+            }
+        }
+
+        let task = tokio::spawn(async move {
+            if !enabled {
+                return;
+            }
+            if let Err(e) = backups.initialize().await {
+                warn!("Scheduled backups disabled: {}", e);
+                return;
+            }
+            if let Err(e) = backups.start_auto_backup().await {
+                warn!("Scheduled backups stopped: {}", e);
+            }
+        });
+        if let Some(previous) = self.backup_schedule.lock().replace(task) {
+            previous.abort();
+        }
+    }
+
+    /// Load the backup passphrase from secure storage if it is not already set
+    ///
+    /// Returns whether encrypted backups can now be written and read.
+    pub async fn unlock_backups(&self) -> Result<bool> {
+        let backups = self.get_backups();
+        if !backups.has_passphrase() {
+            let passphrase = self.secure_storage.retrieve_secret(BACKUP_PASSPHRASE_SECRET).await?;
+            backups.set_passphrase(passphrase);
+        }
+        Ok(backups.has_passphrase())
+    }
+
+    pub async fn set_backup_passphrase(&self, passphrase: Option<&str>) -> Result<()> {
+        match passphrase {
+            Some(passphrase) => self.secure_storage.store_secret(BACKUP_PASSPHRASE_SECRET, passphrase).await?,
+            None => self.secure_storage.delete_secret(BACKUP_PASSPHRASE_SECRET).await?,
+        }
+        self.get_backups().set_passphrase(passphrase.map(str::to_string));
+        Ok(())
+    }
+
+    /// Check the key for `provider`, or a named key such as `openai/team-a`, with a provider health check
+    pub async fn validate_provider_credentials(&self, key_id: &str) -> Result<bool> {
+        let (provider, _) = credentials::split_key_id(key_id);
+        match self.get_api_key(key_id).await? {
             Some(api_key) if !api_key.is_empty() => {
-                debug!("API key found for provider: {}, performing health check", provider);
+                debug!("API key {} found, performing health check", key_id);
                 
                 // Create a provider instance and test the credentials
                 let provider_result = match provider {
@@ -346,12 +763,12 @@ impl AppState {
                     match provider_instance.health_check().await {
                         Ok(health_status) => {
                             if health_status.is_healthy {
-                                debug!("Credentials valid for provider: {} (response time: {:?}ms)", 
-                                       provider, health_status.response_time_ms);
+                                debug!("Credentials valid for {} (response time: {:?}ms)", 
+                                       key_id, health_status.response_time_ms);
                                 Ok(true)
                             } else {
-                                debug!("Credentials invalid for provider: {} (error: {:?})", 
-                                       provider, health_status.error_message);
+                                debug!("Credentials invalid for {} (error: {:?})", 
+                                       key_id, health_status.error_message);
                                 Ok(false)
                             }
                         }
@@ -366,7 +783,7 @@ impl AppState {
                 }
             }
             _ => {
-                debug!("No API key found for provider: {}", provider);
+                debug!("No API key {} found", key_id);
                 Ok(false)
             }
         }
@@ -420,69 +837,43 @@ impl AppState {
         let conversation = self.conversation_repo.get_conversation(conversation_id).await?
             .ok_or_else(|| crate::error::Error::chat("Conversation not found"))?;
 
-        let config = self.get_config();
-        let (provider_name, model_name) = if let Some(preferred) = preferred_provider {
-            // Use the preferred provider if specified and enabled
-            if let Some(provider_config) = config.models.get(preferred) {
-                if provider_config.enabled {
-                    (preferred, provider_config.default_model.as_str())
-                } else {
-                    return Err(crate::error::Error::chat(format!("Provider {} is not enabled", preferred)));
-                }
-            } else {
-                return Err(crate::error::Error::chat(format!("Provider {} is not configured", preferred)));
-            }
-        } else {
-            // Use conversation's preferred provider or find the first enabled provider
-            let mut provider_name = conversation.model_provider.as_str();
-            let mut model_name = conversation.model_name.as_str();
-
-            // Check if conversation's provider is still enabled
-            if let Some(provider_config) = config.models.get(provider_name) {
-                if !provider_config.enabled {
-                    // Fall back to first enabled provider
-                    if let Some((name, provider_config)) = config.models.iter().find(|(_, config)| config.enabled) {
-                        provider_name = name;
-                        model_name = &provider_config.default_model;
-                    } else {
-                        return Err(crate::error::Error::chat("No enabled providers found"));
-                    }
-                }
-            } else {
-                // Provider no longer exists, fall back to first enabled
-                if let Some((name, provider_config)) = config.models.iter().find(|(_, config)| config.enabled) {
-                    provider_name = name;
-                    model_name = &provider_config.default_model;
-                } else {
-                    return Err(crate::error::Error::chat("No enabled providers found"));
-                }
-            }
-            
-            (provider_name, model_name)
-        };
+        let (provider_name, model_name) = self.resolve_provider(
+            &conversation.model_provider,
+            &conversation.model_name,
+            preferred_provider,
+        )?;
+        let (provider_name, model_name) = (provider_name.as_str(), model_name.as_str());
 
-        // Get API key for the provider
-        let api_key = self.get_api_key(provider_name).await?
-            .ok_or_else(|| crate::error::Error::chat(format!("No API key for provider: {}", provider_name)))?;
+        // The key chosen for the conversation or its project, else the provider's default key
+        let key_id = self.key_selections.active_key_id(conversation_id, provider_name).await?;
+        let api_key = self.get_api_key(&key_id).await?
+            .ok_or_else(|| crate::error::Error::chat(format!("No API key for provider: {}", key_id)))?;
+
+        // System prompt plus the current contents of any pinned MCP resources
+        let system_text = self.system_text(conversation_id, conversation.system_prompt.as_deref()).await;
+
+        let messages = self.conversation_repo.get_messages(conversation_id).await?;
+        let prompt_chars = content.len()
+            + system_text.as_ref().map_or(0, |text| text.len())
+            + messages.iter().filter_map(|msg| msg.content.get_text()).map(str::len).sum::<usize>();
+        let project = self.project_repo.get_conversation_project(conversation_id).await?;
+        self.enforce_spending_limits(provider_name, model_name, project.as_deref(), prompt_chars).await?;
 
         // Create a simple provider instance and send the message
-        let response = match provider_name {
+        let (response, pricing) = match provider_name {
             "openai" => {
                 use crate::models::OpenAIProvider;
                 let provider = OpenAIProvider::new(api_key)?;
                 
                 // Build conversation history
-                let messages = self.conversation_repo.get_messages(conversation_id).await?;
                 let mut provider_messages = Vec::new();
                 
                 // Add system message if exists
-                if let Some(ref system_prompt) = conversation.system_prompt {
-                    if !system_prompt.is_empty() {
-                        provider_messages.push(crate::models::provider::Message::new(
-                            crate::models::provider::MessageRole::System,
-                            system_prompt.clone(),
-                        ));
-                    }
+                if let Some(ref system_text) = system_text {
+                    provider_messages.push(crate::models::provider::Message::new(
+                        crate::models::provider::MessageRole::System,
+                        system_text.clone(),
+                    ));
                 }
                 
                 // Add conversation history
@@ -504,17 +895,24 @@ impl AppState {
                 ));
                 
                 let request = crate::models::provider::ChatRequest::new(provider_messages, model_name.to_string());
-                let response = provider.send_message(request).await?;
-                response.content
+                let pricing = provider.get_pricing();
+                (provider.send_message(request).await?, pricing)
             }
             "anthropic" => {
                 use crate::models::AnthropicProvider;
                 let provider = AnthropicProvider::new(api_key)?;
                 
                 // Similar message building for Anthropic
-                let messages = self.conversation_repo.get_messages(conversation_id).await?;
                 let mut provider_messages = Vec::new();
                 
+                // Add system message if exists
+                if let Some(ref system_text) = system_text {
+                    provider_messages.push(crate::models::provider::Message::new(
+                        crate::models::provider::MessageRole::System,
+                        system_text.clone(),
+                    ));
+                }
+                
                 for msg in messages {
                     let role = if msg.role == ChatMessageRole::User {
                         crate::models::provider::MessageRole::User
@@ -532,25 +930,22 @@ impl AppState {
                 ));
                 
                 let request = crate::models::provider::ChatRequest::new(provider_messages, model_name.to_string());
-                let response = provider.send_message(request).await?;
-                response.content
+                let pricing = provider.get_pricing();
+                (provider.send_message(request).await?, pricing)
             }
             "gemini" => {
                 use crate::models::GeminiProvider;
                 let provider = GeminiProvider::new(api_key)?;
                 
                 // Build conversation history for Gemini
-                let messages = self.conversation_repo.get_messages(conversation_id).await?;
                 let mut provider_messages = Vec::new();
                 
                 // Add system message if exists
-                if let Some(ref system_prompt) = conversation.system_prompt {
-                    if !system_prompt.is_empty() {
-                        provider_messages.push(crate::models::provider::Message::new(
-                            crate::models::provider::MessageRole::System,
-                            system_prompt.clone(),
-                        ));
-                    }
+                if let Some(ref system_text) = system_text {
+                    provider_messages.push(crate::models::provider::Message::new(
+                        crate::models::provider::MessageRole::System,
+                        system_text.clone(),
+                    ));
                 }
                 
                 // Add conversation history
@@ -572,8 +967,8 @@ impl AppState {
                 ));
                 
                 let request = crate::models::provider::ChatRequest::new(provider_messages, model_name.to_string());
-                let response = provider.send_message(request).await?;
-                response.content
+                let pricing = provider.get_pricing();
+                (provider.send_message(request).await?, pricing)
             }
             _ => return Err(crate::error::Error::chat(format!("Unsupported provider: {}", provider_name))),
         };
@@ -588,13 +983,192 @@ impl AppState {
         let assistant_msg = ChatMessage::new(
             conversation_id.to_string(),
             ChatMessageRole::Assistant,
-            MessageContent::text(response.clone()),
+            MessageContent::text(response.content.clone()),
         );
         
         self.conversation_repo.create_message(&user_msg).await?;
         self.conversation_repo.create_message(&assistant_msg).await?;
+
+        if let Some(usage) = &response.usage {
+            let cost = pricing.map_or(rust_decimal::Decimal::ZERO, |pricing| pricing.calculate_cost(usage));
+            let params = UsageParams::new(provider_name, model_name, usage.input_tokens, usage.output_tokens, cost)
+                .with_conversation_id(conversation_id)
+                .with_message_id(&assistant_msg.id)
+                .with_key_id(&key_id);
+            if let Err(e) = self.usage_repo.record_usage(params).await {
+                warn!("Failed to record usage for {}/{}: {}", provider_name, model_name, e);
+            }
+            self.enforcement.record_successful_request(provider_name, model_name, cost).await?;
+            self.check_cost_alerts(provider_name, model_name, conversation_id, cost).await;
+        }
         
-        Ok(response)
+        Ok(response.content)
+    }
+
+    /// Provider and model a message would be sent with
+    ///
+    /// An explicitly preferred provider must be enabled; otherwise the conversation's provider is
+    /// used, falling back to the first enabled provider when it has been disabled or removed.
+    fn resolve_provider(
+        &self,
+        conversation_provider: &str,
+        conversation_model: &str,
+        preferred_provider: Option<&str>,
+    ) -> Result<(String, String)> {
+        let config = self.get_config();
+
+        if let Some(preferred) = preferred_provider {
+            return match config.models.get(preferred) {
+                Some(provider_config) if provider_config.enabled => {
+                    Ok((preferred.to_string(), provider_config.default_model.clone()))
+                }
+                Some(_) => Err(crate::error::Error::chat(format!("Provider {} is not enabled", preferred))),
+                None => Err(crate::error::Error::chat(format!("Provider {} is not configured", preferred))),
+            };
+        }
+
+        match config.models.get(conversation_provider) {
+            Some(provider_config) if provider_config.enabled => {
+                Ok((conversation_provider.to_string(), conversation_model.to_string()))
+            }
+            _ => config.models.iter()
+                .find(|(_, config)| config.enabled)
+                .map(|(name, provider_config)| (name.clone(), provider_config.default_model.clone()))
+                .ok_or_else(|| crate::error::Error::chat("No enabled providers found")),
+        }
+    }
+
+    /// System prompt plus the current contents of any pinned MCP resources
+    async fn system_text(&self, conversation_id: &str, system_prompt: Option<&str>) -> Option<String> {
+        let mut parts: Vec<String> = system_prompt.iter()
+            .filter(|prompt| !prompt.is_empty())
+            .map(|prompt| prompt.to_string())
+            .collect();
+        parts.extend(self.get_pinned_context(conversation_id).await);
+        if parts.is_empty() { None } else { Some(parts.join("\n\n")) }
+    }
+
+    /// Everything needed to estimate the cost of the next message in a conversation
+    ///
+    /// Loaded once per conversation/provider change so the estimate can follow keystrokes cheaply.
+    pub async fn request_cost_context(
+        &self,
+        conversation_id: &str,
+        preferred_provider: Option<&str>,
+    ) -> Result<RequestCostContext> {
+        let conversation = self.conversation_repo.get_conversation(conversation_id).await?
+            .ok_or_else(|| crate::error::Error::chat("Conversation not found"))?;
+        let (provider, model) = self.resolve_provider(
+            &conversation.model_provider,
+            &conversation.model_name,
+            preferred_provider,
+        )?;
+
+        let system_text = self.system_text(conversation_id, conversation.system_prompt.as_deref()).await;
+        let messages = self.conversation_repo.get_messages(conversation_id).await?;
+        let context_chars = system_text.as_ref().map_or(0, |text| text.len())
+            + messages.iter().filter_map(|msg| msg.content.get_text()).map(str::len).sum::<usize>();
+
+        let confirm_above = self.get_config().request_confirmation_threshold(&provider);
+        Ok(RequestCostContext { provider, model, context_chars, confirm_above })
+    }
+
+    /// Estimated cost of sending a draft of `draft_chars` characters in the given context
+    pub fn estimate_request_cost(&self, context: &RequestCostContext, draft_chars: usize) -> rust_decimal::Decimal {
+        self.estimate_prompt_cost(&context.provider, &context.model, context.context_chars + draft_chars)
+    }
+
+    fn estimate_prompt_cost(&self, provider: &str, model: &str, prompt_chars: usize) -> rust_decimal::Decimal {
+        // Roughly four characters per token; assume a medium-length reply
+        let input_tokens = (prompt_chars / 4) as u32;
+        self.enforcement.estimate_cost(provider, model, input_tokens, 500)
+    }
+
+    /// Refuse to send a request whose estimated cost would break a spending limit
+    async fn enforce_spending_limits(
+        &self,
+        provider: &str,
+        model: &str,
+        project: Option<&str>,
+        prompt_chars: usize,
+    ) -> Result<()> {
+        let estimated_cost = self.estimate_prompt_cost(provider, model, prompt_chars);
+        let enforcement_config = EnforcementConfig::default();
+
+        let result = self.enforcement
+            .check_project_request(provider, model, project, estimated_cost, &enforcement_config)
+            .await?;
+
+        if matches!(result.action_taken, EnforcementAction::Warning) {
+            if let Some(info) = &result.limit_info {
+                warn!(
+                    "Spending at {:.0}% of {} {} limit",
+                    info.percentage_used, info.limit_type, info.period
+                );
+            }
+        }
+
+        if !result.allowed {
+            let reason = result.reason.unwrap_or_else(|| "spending limit reached".to_string());
+            return Err(crate::error::Error::chat(format!("Request blocked: {}", reason)));
+        }
+
+        Ok(())
+    }
+
+    /// Compare spending after a request against the alert thresholds
+    ///
+    /// Raised alerts go through the dispatcher; failures only affect alerting, never the request.
+    async fn check_cost_alerts(&self, provider: &str, model: &str, conversation_id: &str, cost: rust_decimal::Decimal) {
+        let billing_period = chrono::Utc::now().format("%Y-%m").to_string();
+        let daily = self.usage_repo.get_daily_statistics().await;
+        let by_provider = self.usage_repo.get_provider_period_costs(&billing_period).await;
+        let ((daily_cost, _), provider_spending) = match (daily, by_provider) {
+            (Ok(daily), Ok(by_provider)) => (daily, by_provider),
+            (Err(e), _) | (_, Err(e)) => {
+                warn!("Failed to load spending for cost alerts: {}", e);
+                return;
+            }
+        };
+        let monthly_cost = provider_spending.values().sum();
+
+        let context = AlertContext {
+            provider: Some(provider.to_string()),
+            model: Some(model.to_string()),
+            conversation_id: Some(conversation_id.to_string()),
+            billing_period,
+        };
+
+        let forecast = self.forecast_spending().await;
+
+        let mut cost_alerts = self.cost_alerts.lock().await;
+        match cost_alerts
+            .check_spending_alerts(daily_cost, monthly_cost, &provider_spending, cost, context.clone())
+            .await
+        {
+            Ok(alerts) => {
+                // Runaway spend is stopped rather than just reported when configured to
+                let runaway = alerts.iter().any(|alert| {
+                    alert.severity == AlertSeverity::Emergency
+                        && matches!(alert.alert_type, CostAlertType::SpendingSpike { .. } | CostAlertType::HighCostRequest { .. })
+                });
+                if runaway {
+                    warn!("Anomalous spend in conversation {}; engaging emergency stop", conversation_id);
+                    if let Err(e) = self.enforcement.set_emergency_stop(true).await {
+                        warn!("Failed to engage emergency stop: {}", e);
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to check cost alerts: {}", e),
+        }
+
+        // Warn ahead of time about budgets the forecast expects to run out
+        match forecast {
+            Ok(forecast) => {
+                cost_alerts.check_budget_forecast(&forecast, context);
+            }
+            Err(e) => warn!("Failed to forecast spending: {}", e),
+        }
     }
 
     /// Initialize MCP servers based on configuration
@@ -654,19 +1228,153 @@ impl AppState {
         }
     }
 
-    /// Execute an MCP tool
+    /// Register the channel used to ask the user about tool calls with an "ask" policy
+    ///
+    /// Without an approver, tool calls that need approval are denied.
+    pub fn set_tool_approver(&self, sender: tokio::sync::mpsc::UnboundedSender<ToolApprovalRequest>) {
+        *self.tool_approver.write() = Some(sender);
+    }
+
+    /// Execute an MCP tool in a conversation after checking the tool permission policy
+    ///
+    /// The permission decision and the outcome are recorded in `tool_invocations`.
     pub async fn execute_mcp_tool(
         &self,
+        conversation_id: &str,
         server_name: &str,
         tool_name: &str,
         arguments: serde_json::Value,
     ) -> Result<crate::mcp::ToolResult> {
-        if let Some(client) = &self.mcp_client {
-            let client = client.lock().await;
-            client.call_tool(server_name, tool_name, arguments, None).await
-        } else {
-            Err(crate::error::Error::mcp("MCP client not initialized".to_string()))
+        let client = self.mcp_client.clone()
+            .ok_or_else(|| crate::error::Error::mcp("MCP client not initialized".to_string()))?;
+
+        let settings = self.conversation_repo.get_conversation(conversation_id).await?
+            .map(|session| session.settings)
+            .unwrap_or_default();
+
+        let mut invocation = ToolInvocation::new(tool_name.to_string(), server_name.to_string(), arguments.clone());
+        let started = std::time::Instant::now();
+
+        // Invalid arguments are rejected before the policy is applied, so nobody is asked to approve them
+        let rejection = client.lock().await.check_tool_arguments(server_name, tool_name, &arguments).await;
+        let outcome = match rejection {
+            Some(rejection) => Ok(rejection),
+            None => {
+                let (decision, denial) = self.authorize_tool_call(server_name, tool_name, &arguments, &settings).await;
+                info!("Tool {}/{} permission decision: {}", server_name, tool_name, decision);
+                invocation = invocation.with_decision(decision);
+
+                match denial {
+                    Some(reason) => Err(crate::error::Error::mcp(format!(
+                        "Tool {}/{} was not run: {}", server_name, tool_name, reason
+                    ))),
+                    None => {
+                        let client = client.lock().await;
+                        client.call_tool(server_name, tool_name, arguments, Some(conversation_id)).await
+                    }
+                }
+            }
+        };
+
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let (invocation, content) = match &outcome {
+            Ok(result) => {
+                let value = serde_json::to_value(result)?;
+                let is_error = result.is_error.unwrap_or(false);
+                let content = if is_error {
+                    MessageContent::tool_error(invocation.id.clone(), value.clone())
+                } else {
+                    MessageContent::tool_result(invocation.id.clone(), value.clone())
+                };
+                let mut invocation = invocation.with_result(value, duration_ms);
+                if is_error {
+                    // Keep the server's error text so failed calls are easy to find later
+                    let text = result.content.iter().flatten()
+                        .find_map(|c| match c {
+                            Content::Text { text } => Some(text.clone()),
+                            _ => None,
+                        })
+                        .unwrap_or_else(|| "tool reported an error".to_string());
+                    invocation.error = Some(text);
+                }
+                (invocation, content)
+            }
+            Err(e) => {
+                let content = MessageContent::tool_error(invocation.id.clone(), serde_json::json!({ "error": e.to_string() }));
+                (invocation.with_error(e.to_string(), duration_ms), content)
+            }
+        };
+
+        let mut message = ChatMessage::new(conversation_id.to_string(), ChatMessageRole::Tool, content);
+        message.add_tool_invocation(invocation);
+        if let Err(e) = self.conversation_repo.create_message(&message).await {
+            warn!("Failed to record tool invocation {}/{}: {}", server_name, tool_name, e);
+        }
+
+        outcome
+    }
+
+    /// Apply session settings and permission rules to a tool call, asking the user if required
+    async fn authorize_tool_call(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        arguments: &serde_json::Value,
+        settings: &SessionSettings,
+    ) -> (ToolDecision, Option<String>) {
+        if !settings.enable_tools {
+            return (ToolDecision::DeniedByPolicy, Some("tools are disabled in this conversation".to_string()));
+        }
+
+        let decision = {
+            let config = self.config.read();
+            ToolPermissionPolicy::new(&config.tool_permissions)
+                .evaluate(server_name, tool_name, arguments, &settings.allowed_servers)
+        };
+
+        match decision {
+            PermissionDecision::Allow => (ToolDecision::AllowedByPolicy, None),
+            PermissionDecision::Deny(reason) => (ToolDecision::DeniedByPolicy, Some(reason)),
+            PermissionDecision::Ask => match self.request_tool_approval(server_name, tool_name, arguments).await {
+                Some(ApprovalResponse::AllowOnce) => (ToolDecision::AllowedOnce, None),
+                Some(ApprovalResponse::AllowAlways) => {
+                    let rule = allow_always_rule(server_name, tool_name);
+                    if let Err(e) = self.update_config(|config| config.tool_permissions.rules.insert(0, rule)).await {
+                        warn!("Failed to persist tool permission rule for {}/{}: {}", server_name, tool_name, e);
+                    }
+                    (ToolDecision::AllowedAlways, None)
+                }
+                Some(ApprovalResponse::Deny) => (ToolDecision::DeniedByUser, Some("denied by user".to_string())),
+                None => (
+                    ToolDecision::DeniedByPolicy,
+                    Some("approval required but no interactive approver is available".to_string()),
+                ),
+            },
+        }
+    }
+
+    async fn request_tool_approval(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        arguments: &serde_json::Value,
+    ) -> Option<ApprovalResponse> {
+        let sender = self.tool_approver.read().clone()?;
+        let (responder, response) = tokio::sync::oneshot::channel();
+
+        let request = ToolApprovalRequest {
+            server_name: server_name.to_string(),
+            tool_name: tool_name.to_string(),
+            arguments: arguments.clone(),
+            responder,
+        };
+
+        if sender.send(request).is_err() {
+            warn!("Tool approver is no longer listening");
+            return None;
         }
+
+        response.await.ok()
     }
 
     /// Check if a specific MCP server is running
@@ -688,7 +1396,11 @@ impl AppState {
             }
             
             // Start the server
-            server_manager.start_server(server_name).await
+            server_manager.start_server(server_name).await?;
+            drop(server_manager);
+            
+            self.register_mcp_server_content(server_name).await;
+            Ok(())
         } else {
             Err(crate::error::Error::mcp(format!("MCP server {} not found in configuration", server_name)))
         }
@@ -696,6 +1408,8 @@ impl AppState {
 
     /// Stop an MCP server
     pub async fn stop_mcp_server(&self, server_name: &str) -> Result<()> {
+        self.resource_manager.unregister_client(server_name).await?;
+        self.prompt_manager.unregister_client(server_name).await?;
         let mut server_manager = self.mcp_server_manager.lock().await;
         server_manager.stop_server(server_name).await
     }
@@ -705,6 +1419,240 @@ impl AppState {
         let server_manager = self.mcp_server_manager.lock().await;
         server_manager.get_server_status().await
     }
+
+    /// Make a running server's resources and prompts available to the managers
+    async fn register_mcp_server_content(&self, server_name: &str) {
+        if let Some(client) = &self.resource_client {
+            if let Err(e) = self.resource_manager.register_client(server_name.to_string(), client.clone()).await {
+                tracing::warn!("Failed to load resources from MCP server {}: {}", server_name, e);
+            }
+            if let Err(e) = self.prompt_manager.register_client(server_name.to_string(), client.clone()).await {
+                tracing::warn!("Failed to load prompts from MCP server {}: {}", server_name, e);
+            }
+        }
+    }
+
+    /// List resources from all running MCP servers
+    pub async fn list_mcp_resources(&self) -> Result<HashMap<String, Vec<crate::mcp::Resource>>> {
+        if let Some(client) = &self.resource_client {
+            client.list_resources().await
+        } else {
+            Ok(HashMap::new())
+        }
+    }
+
+    /// Get the MCP resource manager
+    pub fn get_resource_manager(&self) -> Arc<ResourceManager> {
+        Arc::clone(&self.resource_manager)
+    }
+
+    /// Pin an MCP resource into a conversation's context; `on_update` fires when it changes
+    pub async fn pin_resource(
+        &self,
+        conversation_id: &str,
+        uri: &str,
+        on_update: Arc<dyn Fn(ResourceUpdateEvent) + Send + Sync>,
+    ) -> Result<()> {
+        if self.get_pinned_resources(conversation_id).await?.iter().any(|pinned| pinned == uri) {
+            return Ok(());
+        }
+
+        self.load_resource_contents(uri, false).await?;
+        self.conversation_repo.pin_resource(conversation_id, uri).await?;
+
+        // An existing subscription is reused by the server; the conversation's callback is added to it
+        if let Err(e) = self.resource_manager.subscribe_to_resource(uri, conversation_id, on_update).await {
+            if let Err(undo) = self.conversation_repo.unpin_resource(conversation_id, uri).await {
+                warn!("Failed to remove the pin of {} after its subscription failed: {}", uri, undo);
+            }
+            return Err(e);
+        }
+        info!("Pinned resource {} to conversation {}", uri, conversation_id);
+        Ok(())
+    }
+
+    /// Subscribe to the resources pinned in earlier sessions; returns the URIs that could not be subscribed
+    pub async fn restore_pinned_resources(&self, on_update: Arc<dyn Fn(ResourceUpdateEvent) + Send + Sync>) -> Result<Vec<String>> {
+        let mut failed: Vec<String> = Vec::new();
+        for (conversation_id, uri) in self.conversation_repo.list_all_pins().await? {
+            if failed.contains(&uri) {
+                continue;
+            }
+            let subscribed = match self.load_resource_contents(&uri, false).await {
+                Ok(_) => self.resource_manager.subscribe_to_resource(&uri, &conversation_id, on_update.clone()).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = subscribed {
+                warn!("Pinned resource {} is unavailable: {}", uri, e);
+                failed.push(uri);
+            }
+        }
+        Ok(failed)
+    }
+
+    /// Read a resource, refreshing the resource lists once if the URI is unknown
+    async fn load_resource_contents(&self, uri: &str, force_refresh: bool) -> Result<Vec<Content>> {
+        if let Some(contents) = self.resource_manager.get_resource(uri, force_refresh).await? {
+            return Ok(contents);
+        }
+
+        self.resource_manager.refresh_all_resources().await?;
+        self.resource_manager.get_resource(uri, true).await?
+            .ok_or_else(|| crate::error::Error::mcp(format!("Resource not found: {}", uri)))
+    }
+
+    /// Insert a resource's current contents into a conversation as messages
+    pub async fn attach_resource(&self, conversation_id: &str, uri: &str) -> Result<Vec<ChatMessage>> {
+        let contents = self.load_resource_contents(uri, true).await?;
+        let messages = crate::mcp::content_to_messages(conversation_id, &contents, uri, &AttachmentLimits::default())?;
+        self.insert_mcp_messages(conversation_id, messages, uri).await
+    }
+
+    /// Execute a prompt template and insert the result into a conversation as messages
+    pub async fn attach_prompt(
+        &self,
+        conversation_id: &str,
+        server_name: &str,
+        prompt_name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<Vec<ChatMessage>> {
+        let result = self.prompt_manager.execute_template(TemplateContext {
+            variables: arguments,
+            server_name: server_name.to_string(),
+            prompt_name: prompt_name.to_string(),
+        }).await?;
+
+        let source = format!("{}/{}", server_name, prompt_name);
+        let messages = crate::mcp::prompt_to_messages(conversation_id, &result.messages, &source, &AttachmentLimits::default())?;
+        self.insert_mcp_messages(conversation_id, messages, &source).await
+    }
+
+    async fn insert_mcp_messages(&self, conversation_id: &str, messages: Vec<ChatMessage>, source: &str) -> Result<Vec<ChatMessage>> {
+        self.conversation_repo.get_conversation(conversation_id).await?
+            .ok_or_else(|| crate::error::Error::chat("Conversation not found"))?;
+
+        for message in &messages {
+            self.conversation_repo.create_message(message).await?;
+        }
+
+        info!("Inserted {} messages from {} into conversation {}", messages.len(), source, conversation_id);
+        Ok(messages)
+    }
+
+    /// Known resource URIs, used for command completion
+    pub async fn list_known_resource_uris(&self) -> Vec<String> {
+        let mut uris: Vec<String> = match self.resource_manager.list_resources(None).await {
+            Ok(result) => result.resources.into_iter().map(|info| info.resource.uri).collect(),
+            Err(_) => Vec::new(),
+        };
+        uris.sort();
+        uris
+    }
+
+    /// Known prompts as `server/name` with their argument names, used for command completion
+    pub async fn list_known_prompts(&self) -> Vec<(String, Vec<String>)> {
+        let mut prompts: Vec<(String, Vec<String>)> = match self.prompt_manager.list_prompts(None).await {
+            Ok(result) => result.prompts.into_iter()
+                .map(|info| {
+                    let arguments = info.prompt.arguments.unwrap_or_default()
+                        .into_iter()
+                        .map(|arg| arg.name)
+                        .collect();
+                    (format!("{}/{}", info.server_name, info.prompt.name), arguments)
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        prompts.sort();
+        prompts
+    }
+
+    /// Remove a pinned resource from a conversation, unsubscribing once nothing else pins it
+    pub async fn unpin_resource(&self, conversation_id: &str, uri: &str) -> Result<bool> {
+        let removed = self.conversation_repo.unpin_resource(conversation_id, uri).await?;
+        if removed {
+            // The server subscription ends with the last conversation pinning the resource
+            self.resource_manager.unsubscribe_from_resource(uri, conversation_id).await?;
+        }
+        Ok(removed)
+    }
+
+    /// Get the resource URIs pinned to a conversation
+    pub async fn get_pinned_resources(&self, conversation_id: &str) -> Result<Vec<String>> {
+        self.conversation_repo.list_pinned_resources(conversation_id).await
+    }
+
+    /// Render pinned resources as context text for the model
+    async fn get_pinned_context(&self, conversation_id: &str) -> Option<String> {
+        let uris = match self.get_pinned_resources(conversation_id).await {
+            Ok(uris) => uris,
+            Err(e) => {
+                warn!("Failed to load pinned resources: {}", e);
+                return None;
+            }
+        };
+        if uris.is_empty() {
+            return None;
+        }
+
+        let mut sections = Vec::new();
+        for uri in uris {
+            let body = match self.resource_manager.get_resource(&uri, false).await {
+                Ok(Some(contents)) => contents.iter()
+                    .map(|content| match content {
+                        Content::Text { text } => text.clone(),
+                        Content::Image { mime_type, .. } => format!("[{} image]", mime_type),
+                        Content::Resource { resource } => format!("[binary resource {}]", resource.uri),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Ok(None) => "[resource unavailable]".to_string(),
+                Err(e) => format!("[failed to read resource: {}]", e),
+            };
+            sections.push(format!("Pinned resource {}:\n{}", uri, body));
+        }
+
+        Some(sections.join("\n\n"))
+    }
+}
+
+/// Provider, model and history size used to estimate the cost of a draft message
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestCostContext {
+    pub provider: String,
+    pub model: String,
+    /// Characters of system prompt, pinned context and history sent with every message
+    pub context_chars: usize,
+    /// Estimates above this need explicit confirmation before sending
+    pub confirm_above: Option<rust_decimal::Decimal>,
+}
+
+/// What taking a new configuration into use changed beyond the settings themselves
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigApplied {
+    /// MCP servers started, restarted or stopped, e.g. `started fs`
+    pub mcp_servers: Vec<String>,
+    /// Changed sections that only take effect after a restart
+    pub restart_required: Vec<&'static str>,
+}
+
+impl ConfigApplied {
+    /// One-line summary for a status message, empty when there is nothing to add
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.mcp_servers.is_empty() {
+            parts.push(format!("MCP: {}", self.mcp_servers.join(", ")));
+        }
+        if !self.restart_required.is_empty() {
+            parts.push(format!("restart to apply [{}]", self.restart_required.join(", ")));
+        }
+        parts.join("; ")
+    }
+}
+
+/// Whether two versions of a configuration section differ
+fn section_changed<T: serde::Serialize>(previous: &T, current: &T) -> bool {
+    serde_json::to_value(previous).ok() != serde_json::to_value(current).ok()
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -728,4 +1676,20 @@ mod tests {
         let status = ProviderStatus::MissingCredentials;
         assert_eq!(status, ProviderStatus::MissingCredentials);
     }
+
+    #[test]
+    fn test_config_applied_summary() {
+        let previous = AppConfig::default();
+        let mut current = previous.clone();
+        assert!(!section_changed(&previous.backup, &current.backup));
+        current.backup.auto_backup_interval_hours = 6;
+        assert!(section_changed(&previous.backup, &current.backup));
+
+        assert_eq!(ConfigApplied::default().summary(), "");
+        let applied = ConfigApplied {
+            mcp_servers: vec!["started fs".to_string(), "stopped git".to_string()],
+            restart_required: vec!["encryption"],
+        };
+        assert_eq!(applied.summary(), "MCP: started fs, stopped git; restart to apply [encryption]");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,354 @@
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use reqwest::Client;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::app::config::{AlertDeliveryConfig, AlertSinkKind};
+use crate::error::{Error, Result};
+use crate::platform::{send_desktop_notification, AppPaths, NotificationUrgency};
+use crate::storage::{AlertSeverity, AlertStore, CostAlert};
+
+/// Destination for cost alerts
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    async fn deliver(&self, alert: &CostAlert) -> Result<()>;
+}
+
+/// Forwards alerts to the terminal UI once it has attached a listener
+#[derive(Clone, Default)]
+pub struct TuiAlertSink {
+    listener: Arc<RwLock<Option<mpsc::UnboundedSender<CostAlert>>>>,
+}
+
+impl TuiAlertSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route alerts to `sender`, replacing any previous listener
+    pub fn attach(&self, sender: mpsc::UnboundedSender<CostAlert>) {
+        *self.listener.write() = Some(sender);
+    }
+}
+
+#[async_trait]
+impl AlertSink for TuiAlertSink {
+    async fn deliver(&self, alert: &CostAlert) -> Result<()> {
+        match self.listener.read().as_ref() {
+            Some(sender) => sender
+                .send(alert.clone())
+                .map_err(|_| Error::unknown("Terminal UI alert listener is closed")),
+            None => {
+                debug!("No terminal UI attached; alert {} only kept in history", alert.id);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Desktop notification through the freedesktop notification service
+pub struct DesktopAlertSink;
+
+#[async_trait]
+impl AlertSink for DesktopAlertSink {
+    async fn deliver(&self, alert: &CostAlert) -> Result<()> {
+        let urgency = match alert.severity {
+            AlertSeverity::Info => NotificationUrgency::Low,
+            AlertSeverity::Warning => NotificationUrgency::Normal,
+            AlertSeverity::Critical | AlertSeverity::Emergency => NotificationUrgency::Critical,
+        };
+        let body = match &alert.suggested_action {
+            Some(action) => format!("{}\n{}", alert.message, action),
+            None => alert.message.clone(),
+        };
+
+        send_desktop_notification("ValeChat cost alert", &body, urgency).await?;
+        Ok(())
+    }
+}
+
+/// Posts alerts as JSON to a webhook
+///
+/// The payload carries a `text` field so chat webhooks (Slack, Mattermost) can show it as is.
+pub struct WebhookAlertSink {
+    client: Client,
+    url: String,
+    headers: HashMap<String, String>,
+}
+
+impl WebhookAlertSink {
+    pub fn new(url: impl Into<String>, headers: HashMap<String, String>) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| Error::platform(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self { client, url: url.into(), headers })
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookAlertSink {
+    async fn deliver(&self, alert: &CostAlert) -> Result<()> {
+        let payload = json!({
+            "text": format!("[{}] {}", alert.severity.as_str(), alert.message),
+            "alert": alert,
+        });
+
+        let mut request = self.client.post(&self.url).json(&payload);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(Error::platform(format!("Alert webhook returned {}", response.status())));
+        }
+        Ok(())
+    }
+}
+
+/// Appends alerts as JSON lines to a log file
+pub struct LogFileAlertSink {
+    path: PathBuf,
+}
+
+impl LogFileAlertSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl AlertSink for LogFileAlertSink {
+    async fn deliver(&self, alert: &CostAlert) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut line = serde_json::to_string(alert)?;
+        line.push('\n');
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+/// Drains the cost alert channel and fans alerts out to the sinks routed for their severity
+pub struct AlertDispatcher {
+    config: AlertDeliveryConfig,
+    sinks: HashMap<AlertSinkKind, Box<dyn AlertSink>>,
+    store: Option<AlertStore>,
+}
+
+impl AlertDispatcher {
+    /// Dispatcher without any sinks installed
+    pub fn new(config: AlertDeliveryConfig) -> Self {
+        Self {
+            config,
+            sinks: HashMap::new(),
+            store: None,
+        }
+    }
+
+    /// Install the desktop, log file and (if a URL is set) webhook sinks
+    ///
+    /// The terminal UI sink is installed separately because it needs the UI's channel.
+    pub fn from_config(config: AlertDeliveryConfig, paths: &AppPaths) -> Result<Self> {
+        let log_file = config.log_file.as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| paths.logs_dir().join("alerts.log"));
+        let webhook = match &config.webhook_url {
+            Some(url) => Some(WebhookAlertSink::new(url.clone(), config.webhook_headers.clone())?),
+            None => None,
+        };
+
+        let mut dispatcher = Self::new(config)
+            .with_sink(AlertSinkKind::Desktop, DesktopAlertSink)
+            .with_sink(AlertSinkKind::Log, LogFileAlertSink::new(log_file));
+        if let Some(webhook) = webhook {
+            dispatcher = dispatcher.with_sink(AlertSinkKind::Webhook, webhook);
+        }
+        Ok(dispatcher)
+    }
+
+    pub fn with_sink(mut self, kind: AlertSinkKind, sink: impl AlertSink + 'static) -> Self {
+        self.sinks.insert(kind, Box::new(sink));
+        self
+    }
+
+    /// Record every dispatched alert in `store` so it can be acknowledged later
+    pub fn with_store(mut self, store: AlertStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Deliver an alert to its routed sinks, returning how many accepted it
+    ///
+    /// A failing sink is logged and does not stop delivery to the others.
+    pub async fn dispatch(&self, alert: &CostAlert) -> usize {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.save(alert).await {
+                warn!("Failed to store cost alert {}: {}", alert.id, e);
+            }
+        }
+
+        let mut delivered = 0;
+        for kind in self.config.sinks_for(&alert.severity) {
+            let Some(sink) = self.sinks.get(kind) else {
+                debug!("No {} alert sink configured; skipping", kind.as_str());
+                continue;
+            };
+
+            match sink.deliver(alert).await {
+                Ok(()) => delivered += 1,
+                Err(e) => warn!("Failed to deliver cost alert {} to {}: {}", alert.id, kind.as_str(), e),
+            }
+        }
+        delivered
+    }
+
+    /// Dispatch alerts until every sender of `receiver` is dropped
+    pub fn spawn(self, mut receiver: mpsc::UnboundedReceiver<CostAlert>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(alert) = receiver.recv().await {
+                self.dispatch(&alert).await;
+            }
+            debug!("Cost alert channel closed; dispatcher stopping");
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{AlertContext, CostAlertConfig, CostAlertSystem, CostAlertType, Database};
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use tempfile::TempDir;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn alert(severity: AlertSeverity) -> CostAlert {
+        CostAlert {
+            id: uuid::Uuid::new_v4().to_string(),
+            alert_type: CostAlertType::DailyLimit {
+                current: Decimal::new(120, 0),
+                limit: Decimal::new(100, 0),
+            },
+            severity,
+            message: "Daily spending $120.00 exceeded $100.00 limit".to_string(),
+            timestamp: Utc::now(),
+            acknowledged: false,
+            suggested_action: None,
+            context: AlertContext {
+                provider: None,
+                model: None,
+                conversation_id: None,
+                billing_period: "2026-10".to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_routes_by_severity() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_file = temp_dir.path().join("alerts.log");
+        let tui = TuiAlertSink::new();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        tui.attach(sender);
+
+        let config = AlertDeliveryConfig {
+            info: vec![AlertSinkKind::Log],
+            warning: vec![AlertSinkKind::Tui, AlertSinkKind::Log],
+            ..AlertDeliveryConfig::default()
+        };
+        let dispatcher = AlertDispatcher::new(config)
+            .with_sink(AlertSinkKind::Tui, tui)
+            .with_sink(AlertSinkKind::Log, LogFileAlertSink::new(&log_file));
+
+        assert_eq!(dispatcher.dispatch(&alert(AlertSeverity::Info)).await, 1);
+        assert!(receiver.try_recv().is_err());
+
+        let warning = alert(AlertSeverity::Warning);
+        assert_eq!(dispatcher.dispatch(&warning).await, 2);
+        assert_eq!(receiver.try_recv().unwrap().id, warning.id);
+
+        let lines: Vec<CostAlert> = std::fs::read_to_string(&log_file).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].id, warning.id);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink_posts_json() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hooks/alerts"))
+            .and(header("x-token", "secret"))
+            .and(body_partial_json(json!({ "alert": { "severity": "Critical" } })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let headers = HashMap::from([("x-token".to_string(), "secret".to_string())]);
+        let sink = WebhookAlertSink::new(format!("{}/hooks/alerts", server.uri()), headers).unwrap();
+        sink.deliver(&alert(AlertSeverity::Critical)).await.unwrap();
+
+        let failing = WebhookAlertSink::new(format!("{}/missing", server.uri()), HashMap::new()).unwrap();
+        assert!(failing.deliver(&alert(AlertSeverity::Critical)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_acknowledgement_persists() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = AppPaths::with_data_dir(temp_dir.path()).unwrap();
+        let db = Database::new(&paths).await.unwrap();
+        let store = AlertStore::new(db.get_pool());
+
+        let (mut system, receiver) = CostAlertSystem::new(CostAlertConfig::default());
+        let handle = AlertDispatcher::new(AlertDeliveryConfig::default())
+            .with_store(store.clone())
+            .spawn(receiver);
+
+        let context = alert(AlertSeverity::Info).context;
+        let raised = system
+            .check_spending_alerts(Decimal::new(120, 0), Decimal::new(120, 0), &HashMap::new(), Decimal::ZERO, context)
+            .await
+            .unwrap();
+        assert_eq!(raised.len(), 1);
+        drop(system);
+        handle.await.unwrap();
+
+        let outstanding = store.list(true, 10).await.unwrap();
+        assert_eq!(outstanding.len(), 1);
+        assert_eq!(outstanding[0].id, raised[0].id);
+        assert_eq!(outstanding[0].severity, AlertSeverity::Critical);
+
+        // A fresh alert system (e.g. after a restart) can still acknowledge it
+        let (system, _receiver) = CostAlertSystem::new(CostAlertConfig::default());
+        let mut system = system.with_store(store.clone());
+        assert!(system.acknowledge_alert(&raised[0].id).await.unwrap());
+        assert!(!system.acknowledge_alert(&raised[0].id).await.unwrap());
+
+        assert!(store.list(true, 10).await.unwrap().is_empty());
+        assert!(store.list(false, 10).await.unwrap()[0].acknowledged);
+    }
+}
@@ -0,0 +1,257 @@
+// Display currencies and the offline exchange-rate table
+//
+// Costs are always stored in USD. Rates are only ever changed by the user, either by
+// editing the configuration or importing a local file; nothing is fetched.
+
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+use crate::error::{Error, Result};
+
+/// Currency all costs are stored in
+pub const BASE_CURRENCY: &str = "USD";
+
+/// Units of each currency per US dollar
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExchangeRates {
+    rates: BTreeMap<String, Decimal>,
+}
+
+impl ExchangeRates {
+    /// Build a rate table, rejecting malformed currency codes and non-positive rates
+    pub fn new<I, S>(rates: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (S, Decimal)>,
+        S: AsRef<str>,
+    {
+        let mut table = BTreeMap::new();
+        for (code, rate) in rates {
+            let code = normalize_code(code.as_ref())?;
+            if rate <= Decimal::ZERO {
+                return Err(Error::validation(format!("Exchange rate for {} must be positive", code)));
+            }
+            if code != BASE_CURRENCY {
+                table.insert(code, rate);
+            }
+        }
+        Ok(Self { rates: table })
+    }
+
+    /// Parse a rate file: a JSON object (optionally under a `rates` key) or `CODE RATE` lines
+    ///
+    /// Lines may separate code and rate with whitespace, `,` or `=`; `#` starts a comment.
+    pub fn parse(content: &str) -> Result<Self> {
+        if content.trim_start().starts_with('{') {
+            let value: serde_json::Value = serde_json::from_str(content)?;
+            let object = value.get("rates").unwrap_or(&value).as_object()
+                .ok_or_else(|| Error::validation("Rate file must contain an object of currency rates"))?;
+
+            let mut rates = Vec::with_capacity(object.len());
+            for (code, rate) in object {
+                let rate = match rate {
+                    serde_json::Value::String(s) => s.trim().parse::<Decimal>().ok(),
+                    serde_json::Value::Number(n) => n.to_string().parse::<Decimal>().ok(),
+                    _ => None,
+                }
+                .ok_or_else(|| Error::validation(format!("Invalid exchange rate for {}", code)))?;
+                rates.push((code.clone(), rate));
+            }
+            return Self::new(rates);
+        }
+
+        let mut rates = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() || line.starts_with('[') {
+                continue;
+            }
+            let mut parts = line
+                .split(|c: char| c.is_whitespace() || c == ',' || c == '=')
+                .filter(|part| !part.is_empty());
+            let (Some(code), Some(rate), None) = (parts.next(), parts.next(), parts.next()) else {
+                return Err(Error::validation(format!("Line {}: expected `CODE RATE`", number + 1)));
+            };
+            let rate = rate.trim_matches('"').parse::<Decimal>()
+                .map_err(|_| Error::validation(format!("Line {}: invalid exchange rate {}", number + 1, rate)))?;
+            rates.push((code.trim_matches('"').to_string(), rate));
+        }
+        Self::new(rates)
+    }
+
+    /// Units of `code` per US dollar
+    pub fn rate(&self, code: &str) -> Option<Decimal> {
+        let code = code.to_ascii_uppercase();
+        if code == BASE_CURRENCY {
+            Some(Decimal::ONE)
+        } else {
+            self.rates.get(&code).copied()
+        }
+    }
+
+    /// Convert a USD amount into `code`
+    pub fn from_usd(&self, amount: Decimal, code: &str) -> Result<Decimal> {
+        Ok(amount * self.required_rate(code)?)
+    }
+
+    /// Convert an amount in `code` into USD
+    pub fn to_usd(&self, amount: Decimal, code: &str) -> Result<Decimal> {
+        Ok(amount / self.required_rate(code)?)
+    }
+
+    pub fn rates(&self) -> &BTreeMap<String, Decimal> {
+        &self.rates
+    }
+
+    pub fn into_rates(self) -> BTreeMap<String, Decimal> {
+        self.rates
+    }
+
+    fn required_rate(&self, code: &str) -> Result<Decimal> {
+        self.rate(code)
+            .ok_or_else(|| Error::validation(format!("No exchange rate configured for {}", code)))
+    }
+}
+
+/// Formats USD amounts in the display currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrencyFormatter {
+    code: String,
+    rate: Decimal,
+}
+
+impl Default for CurrencyFormatter {
+    fn default() -> Self {
+        Self::usd()
+    }
+}
+
+impl CurrencyFormatter {
+    pub fn usd() -> Self {
+        Self { code: BASE_CURRENCY.to_string(), rate: Decimal::ONE }
+    }
+
+    pub fn new(code: &str, rates: &ExchangeRates) -> Result<Self> {
+        let code = normalize_code(code)?;
+        let rate = rates.required_rate(&code)?;
+        Ok(Self { code, rate })
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn is_usd(&self) -> bool {
+        self.code == BASE_CURRENCY
+    }
+
+    /// Convert a USD amount into the display currency
+    pub fn convert(&self, usd: Decimal) -> Decimal {
+        usd * self.rate
+    }
+
+    /// Convert an amount in the display currency back into USD
+    pub fn to_usd(&self, amount: Decimal) -> Decimal {
+        amount / self.rate
+    }
+
+    /// Format a USD amount with two decimal places, e.g. `$1.50` or `€1.38`
+    pub fn format(&self, usd: Decimal) -> String {
+        self.format_dp(usd, 2)
+    }
+
+    /// Format a USD amount with four decimal places for per-request costs
+    pub fn format_precise(&self, usd: Decimal) -> String {
+        self.format_dp(usd, 4)
+    }
+
+    /// Format a USD amount given as a float, as kept by some TUI components
+    pub fn format_f64(&self, usd: f64, dp: u32) -> String {
+        self.format_dp(Decimal::try_from(usd).unwrap_or_default(), dp)
+    }
+
+    /// Format a USD amount rounded to `dp` decimal places
+    pub fn format_dp(&self, usd: Decimal, dp: u32) -> String {
+        let amount = self.convert(usd).round_dp(dp);
+        let sign = if amount.is_sign_negative() && !amount.is_zero() { "-" } else { "" };
+        let digits = format!("{:.*}", dp as usize, amount.abs());
+        match symbol(&self.code) {
+            Some(symbol) => format!("{}{}{}", sign, symbol, digits),
+            None => format!("{}{} {}", sign, digits, self.code),
+        }
+    }
+
+    /// Currency symbol, or the code when there is no common symbol
+    pub fn symbol(&self) -> &str {
+        symbol(&self.code).unwrap_or(&self.code)
+    }
+}
+
+fn symbol(code: &str) -> Option<&'static str> {
+    match code {
+        "USD" => Some("$"),
+        "EUR" => Some("€"),
+        "GBP" => Some("£"),
+        "JPY" | "CNY" => Some("¥"),
+        "INR" => Some("₹"),
+        "KRW" => Some("₩"),
+        _ => None,
+    }
+}
+
+/// Validate an ISO 4217 style code and return it upper-cased
+pub fn normalize_code(code: &str) -> Result<String> {
+    let code = code.trim().to_ascii_uppercase();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(Error::validation(format!("Invalid currency code: {}", code)));
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rates() -> ExchangeRates {
+        ExchangeRates::new([("eur", Decimal::new(92, 2)), ("CHF", Decimal::new(88, 2))]).unwrap()
+    }
+
+    #[test]
+    fn test_conversion_round_trip() {
+        let rates = rates();
+        let eur = rates.from_usd(Decimal::new(100, 0), "EUR").unwrap();
+        assert_eq!(eur, Decimal::new(92, 0));
+        assert_eq!(rates.to_usd(eur, "eur").unwrap(), Decimal::new(100, 0));
+        assert_eq!(rates.to_usd(Decimal::ONE, "USD").unwrap(), Decimal::ONE);
+        assert!(rates.to_usd(Decimal::ONE, "SEK").is_err());
+
+        assert!(ExchangeRates::new([("EUR", Decimal::ZERO)]).is_err());
+        assert!(ExchangeRates::new([("EURO", Decimal::ONE)]).is_err());
+    }
+
+    #[test]
+    fn test_formatting() {
+        let rates = rates();
+        assert_eq!(CurrencyFormatter::usd().format(Decimal::new(150, 2)), "$1.50");
+        assert_eq!(CurrencyFormatter::usd().format_precise(Decimal::new(-5, 3)), "-$0.0050");
+
+        let eur = CurrencyFormatter::new("eur", &rates).unwrap();
+        assert_eq!(eur.format(Decimal::new(150, 2)), "€1.38");
+        let chf = CurrencyFormatter::new("CHF", &rates).unwrap();
+        assert_eq!(chf.format(Decimal::new(10, 0)), "8.80 CHF");
+        assert!(CurrencyFormatter::new("SEK", &rates).is_err());
+    }
+
+    #[test]
+    fn test_parse_rate_files() {
+        let json = ExchangeRates::parse(r#"{"base": "USD", "rates": {"EUR": 0.92, "GBP": "0.79"}}"#).unwrap();
+        assert_eq!(json.rate("EUR"), Some(Decimal::new(92, 2)));
+        assert_eq!(json.rate("GBP"), Some(Decimal::new(79, 2)));
+        assert!(ExchangeRates::parse(r#"{"EUR": true}"#).is_err());
+
+        let lines = ExchangeRates::parse("# rates per USD\n[rates]\nEUR = \"0.92\"\nGBP,0.79\nJPY 151.2\n").unwrap();
+        assert_eq!(lines.rates().len(), 3);
+        assert_eq!(lines.rate("jpy"), Some(Decimal::new(1512, 1)));
+
+        assert!(ExchangeRates::parse("EUR\n").is_err());
+    }
+}
@@ -1,4 +1,14 @@
-// Billing module - to be implemented in Phase 4
+// Provider billing integration
+
+pub mod alerts;
+pub mod currency;
+pub mod providers;
+pub mod reconciliation;
+
+pub use alerts::{AlertDispatcher, AlertSink, DesktopAlertSink, LogFileAlertSink, TuiAlertSink, WebhookAlertSink};
+pub use currency::{CurrencyFormatter, ExchangeRates, BASE_CURRENCY};
+pub use providers::{adapter_for, AnthropicBillingAdapter, BillingAdapter, OpenAIBillingAdapter, ProviderUsageEntry};
+pub use reconciliation::{period_bounds, ProviderReconciliation, ReconciliationJob, ReconciliationReport};
 
 pub struct UsageTracker;
-pub struct BillingLimits;
\ No newline at end of file
+pub struct BillingLimits;
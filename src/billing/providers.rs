@@ -0,0 +1,394 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::error::{Error, Result};
+
+/// Maximum number of pages fetched from a provider before giving up
+const MAX_PAGES: usize = 50;
+
+/// Cost reported by a provider for a request or a time bucket
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderUsageEntry {
+    /// Provider-side request id when the API reports individual requests
+    pub request_id: Option<String>,
+    /// Model the cost belongs to; `None` applies to every model of the provider
+    pub model: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    /// Cost in USD
+    pub cost: Decimal,
+}
+
+/// Fetches billed usage from a provider's organization API
+#[async_trait]
+pub trait BillingAdapter: Send + Sync {
+    /// Provider id as used in usage records
+    fn provider(&self) -> &str;
+
+    /// Fetch provider-side costs for `[start, end)`
+    async fn fetch_usage(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<ProviderUsageEntry>>;
+}
+
+/// Create the billing adapter for a provider, if one exists
+pub fn adapter_for(provider: &str, api_key: String) -> Result<Option<Box<dyn BillingAdapter>>> {
+    let adapter: Box<dyn BillingAdapter> = match provider {
+        "openai" => Box::new(OpenAIBillingAdapter::new(api_key)?),
+        "anthropic" => Box::new(AnthropicBillingAdapter::new(api_key)?),
+        _ => return Ok(None),
+    };
+    Ok(Some(adapter))
+}
+
+fn build_client() -> Result<Client> {
+    Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| Error::model_provider(format!("Failed to create HTTP client: {}", e)))
+}
+
+async fn read_json<T: for<'de> Deserialize<'de>>(response: reqwest::Response, provider: &str) -> Result<T> {
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(Error::model_provider(format!(
+            "{} billing API error {}: {}",
+            provider, status, error_text
+        )));
+    }
+
+    response
+        .json::<T>()
+        .await
+        .map_err(|e| Error::model_provider(format!("Failed to parse {} billing response: {}", provider, e)))
+}
+
+/// Adapter for the OpenAI organization costs API (`/organization/costs`)
+///
+/// Requires an admin API key.
+pub struct OpenAIBillingAdapter {
+    client: Client,
+    api_key: String,
+    base_url: String,
+}
+
+impl OpenAIBillingAdapter {
+    pub fn new(api_key: String) -> Result<Self> {
+        Ok(Self {
+            client: build_client()?,
+            api_key,
+            base_url: "https://api.openai.com/v1".to_string(),
+        })
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAICostsPage {
+    data: Vec<OpenAICostBucket>,
+    #[serde(default)]
+    has_more: bool,
+    next_page: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAICostBucket {
+    start_time: i64,
+    end_time: i64,
+    #[serde(default)]
+    results: Vec<OpenAICostResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAICostResult {
+    amount: OpenAIAmount,
+    line_item: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIAmount {
+    value: serde_json::Number,
+}
+
+#[async_trait]
+impl BillingAdapter for OpenAIBillingAdapter {
+    fn provider(&self) -> &str {
+        "openai"
+    }
+
+    async fn fetch_usage(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<ProviderUsageEntry>> {
+        let url = format!("{}/organization/costs", self.base_url);
+        let mut entries = Vec::new();
+        let mut page: Option<String> = None;
+
+        for _ in 0..MAX_PAGES {
+            let mut query = vec![
+                ("start_time", start.timestamp().to_string()),
+                ("end_time", end.timestamp().to_string()),
+                ("bucket_width", "1d".to_string()),
+                ("group_by", "line_item".to_string()),
+                ("limit", "31".to_string()),
+            ];
+            if let Some(page) = &page {
+                query.push(("page", page.clone()));
+            }
+
+            let response = self.client
+                .get(&url)
+                .bearer_auth(&self.api_key)
+                .query(&query)
+                .send()
+                .await
+                .map_err(|e| Error::model_provider(format!("Request failed: {}", e)))?;
+            let body: OpenAICostsPage = read_json(response, "OpenAI").await?;
+
+            for bucket in body.data {
+                let (Some(bucket_start), Some(bucket_end)) = (
+                    DateTime::from_timestamp(bucket.start_time, 0),
+                    DateTime::from_timestamp(bucket.end_time, 0),
+                ) else {
+                    continue;
+                };
+
+                for result in bucket.results {
+                    entries.push(ProviderUsageEntry {
+                        request_id: None,
+                        model: result.line_item.as_deref().map(openai_line_item_model),
+                        start_time: bucket_start,
+                        end_time: bucket_end,
+                        cost: parse_decimal(&result.amount.value.to_string())?,
+                    });
+                }
+            }
+
+            match body.next_page {
+                Some(next) if body.has_more => page = Some(next),
+                _ => break,
+            }
+        }
+
+        debug!("Fetched {} OpenAI cost entries", entries.len());
+        Ok(entries)
+    }
+}
+
+/// Line items look like "gpt-4o-2024-08-06, input"; keep the model part
+fn openai_line_item_model(line_item: &str) -> String {
+    line_item.split(',').next().unwrap_or(line_item).trim().to_string()
+}
+
+/// Adapter for the Anthropic Admin API cost report (`/organizations/cost_report`)
+///
+/// Requires an admin API key. Amounts are reported in cents.
+pub struct AnthropicBillingAdapter {
+    client: Client,
+    api_key: String,
+    base_url: String,
+}
+
+impl AnthropicBillingAdapter {
+    pub fn new(api_key: String) -> Result<Self> {
+        Ok(Self {
+            client: build_client()?,
+            api_key,
+            base_url: "https://api.anthropic.com/v1".to_string(),
+        })
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicCostPage {
+    data: Vec<AnthropicCostBucket>,
+    #[serde(default)]
+    has_more: bool,
+    next_page: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicCostBucket {
+    starting_at: DateTime<Utc>,
+    ending_at: DateTime<Utc>,
+    #[serde(default)]
+    results: Vec<AnthropicCostResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicCostResult {
+    amount: String,
+    model: Option<String>,
+}
+
+#[async_trait]
+impl BillingAdapter for AnthropicBillingAdapter {
+    fn provider(&self) -> &str {
+        "anthropic"
+    }
+
+    async fn fetch_usage(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<ProviderUsageEntry>> {
+        let url = format!("{}/organizations/cost_report", self.base_url);
+        let mut entries = Vec::new();
+        let mut page: Option<String> = None;
+
+        for _ in 0..MAX_PAGES {
+            let mut query = vec![
+                ("starting_at", start.to_rfc3339()),
+                ("ending_at", end.to_rfc3339()),
+                ("group_by[]", "description".to_string()),
+                ("limit", "31".to_string()),
+            ];
+            if let Some(page) = &page {
+                query.push(("page", page.clone()));
+            }
+
+            let response = self.client
+                .get(&url)
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", "2023-06-01")
+                .query(&query)
+                .send()
+                .await
+                .map_err(|e| Error::model_provider(format!("Request failed: {}", e)))?;
+            let body: AnthropicCostPage = read_json(response, "Anthropic").await?;
+
+            for bucket in body.data {
+                for result in bucket.results {
+                    entries.push(ProviderUsageEntry {
+                        request_id: None,
+                        model: result.model,
+                        start_time: bucket.starting_at,
+                        end_time: bucket.ending_at,
+                        cost: parse_decimal(&result.amount)? / Decimal::ONE_HUNDRED,
+                    });
+                }
+            }
+
+            match body.next_page {
+                Some(next) if body.has_more => page = Some(next),
+                _ => break,
+            }
+        }
+
+        debug!("Fetched {} Anthropic cost entries", entries.len());
+        Ok(entries)
+    }
+}
+
+fn parse_decimal(value: &str) -> Result<Decimal> {
+    Decimal::from_str(value)
+        .or_else(|_| Decimal::from_scientific(value))
+        .map_err(|e| Error::model_provider(format!("Invalid cost amount '{}': {}", value, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_openai_costs_paginated() {
+        let server = MockServer::start().await;
+        let day = Utc.with_ymd_and_hms(2026, 9, 1, 0, 0, 0).unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/organization/costs"))
+            .and(header("authorization", "Bearer admin-key"))
+            .and(query_param("page", "page_2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{
+                    "start_time": day.timestamp() + 86400,
+                    "end_time": day.timestamp() + 2 * 86400,
+                    "results": [{"amount": {"value": 0.5, "currency": "usd"}, "line_item": "gpt-4o, output"}]
+                }],
+                "has_more": false,
+                "next_page": null
+            })))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/organization/costs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{
+                    "start_time": day.timestamp(),
+                    "end_time": day.timestamp() + 86400,
+                    "results": [{"amount": {"value": 1.25, "currency": "usd"}, "line_item": "gpt-4o-2024-08-06, input"}]
+                }],
+                "has_more": true,
+                "next_page": "page_2"
+            })))
+            .mount(&server)
+            .await;
+
+        let adapter = OpenAIBillingAdapter::new("admin-key".to_string()).unwrap()
+            .with_base_url(server.uri());
+        let entries = adapter.fetch_usage(day, day + chrono::Duration::days(2)).await.unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].model.as_deref(), Some("gpt-4o-2024-08-06"));
+        assert_eq!(entries[0].cost, Decimal::new(125, 2));
+        assert_eq!(entries[1].start_time, day + chrono::Duration::days(1));
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_cost_report_in_cents() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/cost_report"))
+            .and(header("x-api-key", "admin-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{
+                    "starting_at": "2026-09-01T00:00:00Z",
+                    "ending_at": "2026-09-02T00:00:00Z",
+                    "results": [{"currency": "USD", "amount": "123.5", "model": "claude-3-haiku-20240307"}]
+                }],
+                "has_more": false,
+                "next_page": null
+            })))
+            .mount(&server)
+            .await;
+
+        let adapter = AnthropicBillingAdapter::new("admin-key".to_string()).unwrap()
+            .with_base_url(server.uri());
+        let start = Utc.with_ymd_and_hms(2026, 9, 1, 0, 0, 0).unwrap();
+        let entries = adapter.fetch_usage(start, start + chrono::Duration::days(1)).await.unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].cost, Decimal::new(1235, 3));
+        assert_eq!(entries[0].model.as_deref(), Some("claude-3-haiku-20240307"));
+    }
+
+    #[tokio::test]
+    async fn test_api_error_is_reported() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("invalid admin key"))
+            .mount(&server)
+            .await;
+
+        let adapter = OpenAIBillingAdapter::new("bad".to_string()).unwrap()
+            .with_base_url(server.uri());
+        let start = Utc.with_ymd_and_hms(2026, 9, 1, 0, 0, 0).unwrap();
+        let err = adapter.fetch_usage(start, start + chrono::Duration::days(1)).await.unwrap_err();
+
+        assert!(err.to_string().contains("401"));
+    }
+}
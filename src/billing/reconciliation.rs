@@ -0,0 +1,429 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use tracing::{info, warn};
+
+use crate::billing::providers::{BillingAdapter, ProviderUsageEntry};
+use crate::error::{Error, Result};
+use crate::storage::billing::{BillingSystem, VerificationResult};
+use crate::storage::cost_alerts::{CostAlert, CostAlertSystem};
+use crate::storage::usage::{UsageRecord, UsageRepository};
+
+/// Reconciliation outcome for one provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderReconciliation {
+    pub provider: String,
+    /// Cost tracked locally for the period
+    pub local_cost: Decimal,
+    /// Cost billed by the provider for the period
+    pub provider_cost: Decimal,
+    pub records_checked: usize,
+    pub verified: Vec<VerificationResult>,
+    /// Unverified local records with no provider counterpart
+    pub unmatched_records: usize,
+    /// Provider cost that could not be attributed to any local record
+    pub unmatched_provider_cost: Decimal,
+    /// Set when fetching or verifying failed for this provider
+    pub error: Option<String>,
+}
+
+/// Result of reconciling a billing period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    pub billing_period: String,
+    pub providers: Vec<ProviderReconciliation>,
+    pub alerts: Vec<CostAlert>,
+}
+
+/// Matches local usage records against provider billing data and verifies them
+pub struct ReconciliationJob {
+    billing: BillingSystem,
+    usage_repo: UsageRepository,
+    alerts: CostAlertSystem,
+    adapters: Vec<Box<dyn BillingAdapter>>,
+}
+
+impl ReconciliationJob {
+    pub fn new(pool: SqlitePool, alerts: CostAlertSystem) -> Self {
+        Self {
+            billing: BillingSystem::new(pool.clone()),
+            usage_repo: UsageRepository::new(pool),
+            alerts,
+            adapters: Vec::new(),
+        }
+    }
+
+    pub fn add_adapter(&mut self, adapter: Box<dyn BillingAdapter>) {
+        self.adapters.push(adapter);
+    }
+
+    /// Reconcile every configured provider for a `YYYY-MM` billing period
+    ///
+    /// A failing provider is reported in its entry and does not stop the others.
+    pub async fn reconcile_period(&mut self, billing_period: &str) -> Result<ReconciliationReport> {
+        let (start, end) = period_bounds(billing_period)?;
+        let mut report = ReconciliationReport {
+            billing_period: billing_period.to_string(),
+            providers: Vec::new(),
+            alerts: Vec::new(),
+        };
+
+        for index in 0..self.adapters.len() {
+            let provider = self.adapters[index].provider().to_string();
+            let result = match self.reconcile_provider(index, start, end).await {
+                Ok(result) => result,
+                Err(e) => {
+                    warn!("Reconciliation failed for {}: {}", provider, e);
+                    report.providers.push(ProviderReconciliation {
+                        provider,
+                        local_cost: Decimal::ZERO,
+                        provider_cost: Decimal::ZERO,
+                        records_checked: 0,
+                        verified: Vec::new(),
+                        unmatched_records: 0,
+                        unmatched_provider_cost: Decimal::ZERO,
+                        error: Some(e.to_string()),
+                    });
+                    continue;
+                }
+            };
+
+            if let Some(alert) = self.alerts.check_cost_discrepancy(
+                &provider,
+                result.local_cost,
+                result.provider_cost,
+                billing_period,
+            ) {
+                report.alerts.push(alert);
+            }
+
+            info!(
+                "Reconciled {} for {}: {} verified, local ${}, provider ${}",
+                provider, billing_period, result.verified.len(), result.local_cost, result.provider_cost
+            );
+            report.providers.push(result);
+        }
+
+        Ok(report)
+    }
+
+    async fn reconcile_provider(
+        &self,
+        index: usize,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<ProviderReconciliation> {
+        let adapter = &self.adapters[index];
+        let provider = adapter.provider().to_string();
+
+        // get_usage_records uses an inclusive upper bound
+        let records = self.usage_repo
+            .get_usage_records(Some(start), Some(end - chrono::Duration::seconds(1)), Some(&provider), None, None)
+            .await?;
+        let entries = adapter.fetch_usage(start, end).await?;
+
+        let outcome = match_entries(&records, &entries);
+        let verified = self.billing.verify_costs_batch(outcome.verifications).await?;
+
+        Ok(ProviderReconciliation {
+            provider,
+            local_cost: records.iter().map(|r| r.cost).sum(),
+            provider_cost: entries.iter().map(|e| e.cost).sum(),
+            records_checked: records.len(),
+            verified,
+            unmatched_records: outcome.unmatched_records,
+            unmatched_provider_cost: outcome.unmatched_provider_cost,
+            error: None,
+        })
+    }
+}
+
+/// Parse a `YYYY-MM` billing period into its `[start, end)` range
+pub fn period_bounds(billing_period: &str) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    let first = NaiveDate::parse_from_str(&format!("{}-01", billing_period), "%Y-%m-%d")
+        .map_err(|_| Error::validation(format!("Invalid billing period '{}', expected YYYY-MM", billing_period)))?;
+    let next = if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    }
+    .ok_or_else(|| Error::validation(format!("Invalid billing period '{}'", billing_period)))?;
+
+    let to_utc = |date: NaiveDate| date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc());
+    match (to_utc(first), to_utc(next)) {
+        (Some(start), Some(end)) => Ok((start, end)),
+        _ => Err(Error::validation(format!("Invalid billing period '{}'", billing_period))),
+    }
+}
+
+#[derive(Debug, Default)]
+struct MatchOutcome {
+    verifications: Vec<(String, Decimal)>,
+    unmatched_records: usize,
+    unmatched_provider_cost: Decimal,
+}
+
+/// Provider cost for one time bucket and model
+struct Bucket<'a> {
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    model: Option<&'a str>,
+    cost: Decimal,
+    records: Vec<&'a UsageRecord>,
+}
+
+/// Match provider entries to local records
+///
+/// Entries carrying a request id verify that record directly. The rest are summed
+/// per time bucket and model, and the bucket cost is split across the local records
+/// in the bucket proportionally to their recorded cost.
+fn match_entries(records: &[UsageRecord], entries: &[ProviderUsageEntry]) -> MatchOutcome {
+    let mut outcome = MatchOutcome::default();
+    let by_request_id: HashMap<&str, &UsageRecord> = records.iter()
+        .map(|r| (r.request_id.as_str(), r))
+        .collect();
+    let mut matched: HashSet<&str> = HashSet::new();
+    let mut buckets: Vec<Bucket> = Vec::new();
+
+    for entry in entries {
+        if let Some(request_id) = &entry.request_id {
+            match by_request_id.get(request_id.as_str()) {
+                Some(record) => {
+                    matched.insert(record.request_id.as_str());
+                    if !record.verified {
+                        outcome.verifications.push((record.request_id.clone(), entry.cost));
+                    }
+                }
+                None => outcome.unmatched_provider_cost += entry.cost,
+            }
+            continue;
+        }
+
+        let existing = buckets.iter_mut().find(|b| {
+            b.start_time == entry.start_time && b.end_time == entry.end_time && b.model == entry.model.as_deref()
+        });
+        match existing {
+            Some(bucket) => bucket.cost += entry.cost,
+            None => buckets.push(Bucket {
+                start_time: entry.start_time,
+                end_time: entry.end_time,
+                model: entry.model.as_deref(),
+                cost: entry.cost,
+                records: Vec::new(),
+            }),
+        }
+    }
+
+    for record in records.iter().filter(|r| !matched.contains(r.request_id.as_str())) {
+        let best = buckets.iter()
+            .enumerate()
+            .filter(|(_, b)| b.start_time <= record.timestamp && record.timestamp < b.end_time)
+            .filter_map(|(i, b)| model_match_score(b.model, &record.model).map(|score| (score, i)))
+            .max_by_key(|(score, i)| (*score, std::cmp::Reverse(*i)));
+
+        match best {
+            Some((_, index)) => buckets[index].records.push(record),
+            None if !record.verified => outcome.unmatched_records += 1,
+            None => {}
+        }
+    }
+
+    for bucket in buckets {
+        if bucket.records.is_empty() {
+            outcome.unmatched_provider_cost += bucket.cost;
+            continue;
+        }
+
+        let local_total: Decimal = bucket.records.iter().map(|r| r.cost).sum();
+        let count = Decimal::from(bucket.records.len());
+        for record in bucket.records.iter().filter(|r| !r.verified) {
+            let share = if local_total.is_zero() {
+                bucket.cost / count
+            } else {
+                bucket.cost * record.cost / local_total
+            };
+            outcome.verifications.push((record.request_id.clone(), share.round_dp(6)));
+        }
+    }
+
+    outcome
+}
+
+/// Score how well a provider-reported model name matches a local model id
+///
+/// Exact matches win; dated snapshots ("claude-3-haiku-20240307") match their alias
+/// in either direction. Entries without a model match anything with the lowest score.
+fn model_match_score(provider_model: Option<&str>, local_model: &str) -> Option<usize> {
+    let Some(provider_model) = provider_model else {
+        return Some(0);
+    };
+    if provider_model == local_model {
+        return Some(usize::MAX);
+    }
+
+    let is_snapshot_of = |full: &str, alias: &str| {
+        full.strip_prefix(alias)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+    };
+
+    if is_snapshot_of(provider_model, local_model) {
+        Some(local_model.len())
+    } else if is_snapshot_of(local_model, provider_model) {
+        Some(provider_model.len())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::billing::providers::OpenAIBillingAdapter;
+    use crate::storage::cost_alerts::{CostAlertConfig, CostAlertType};
+    use crate::storage::usage::UsageParams;
+    use crate::platform::AppPaths;
+    use crate::storage::Database;
+    use chrono::TimeZone;
+    use serde_json::json;
+    use tempfile::TempDir;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn record(request_id: &str, model: &str, timestamp: DateTime<Utc>, cost: Decimal) -> UsageRecord {
+        UsageRecord {
+            id: 0,
+            timestamp,
+            provider: "openai".to_string(),
+            model: model.to_string(),
+            input_tokens: 0,
+            output_tokens: 0,
+            cost,
+            conversation_id: None,
+            message_id: None,
+            request_id: request_id.to_string(),
+            billing_period: "2026-09".to_string(),
+            verified: false,
+            verification_timestamp: None,
+            project: None,
+            key_id: None,
+        }
+    }
+
+    fn bucket_entry(model: &str, start: DateTime<Utc>, cost: Decimal) -> ProviderUsageEntry {
+        ProviderUsageEntry {
+            request_id: None,
+            model: Some(model.to_string()),
+            start_time: start,
+            end_time: start + chrono::Duration::days(1),
+            cost,
+        }
+    }
+
+    #[test]
+    fn test_period_bounds() {
+        let (start, end) = period_bounds("2026-12").unwrap();
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 12, 1, 0, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap());
+        assert!(period_bounds("2026-13").is_err());
+        assert!(period_bounds("september").is_err());
+    }
+
+    #[test]
+    fn test_model_match_score() {
+        assert_eq!(model_match_score(Some("gpt-4o"), "gpt-4o"), Some(usize::MAX));
+        assert!(model_match_score(Some("gpt-4o-2024-08-06"), "gpt-4o").is_some());
+        assert!(model_match_score(Some("claude-3-haiku"), "claude-3-haiku-20240307").is_some());
+        assert!(model_match_score(Some("gpt-4o-mini-2024-07-18"), "gpt-4o").is_none());
+        assert_eq!(model_match_score(None, "gpt-4o"), Some(0));
+    }
+
+    #[test]
+    fn test_match_by_request_id_and_bucket() {
+        let day = Utc.with_ymd_and_hms(2026, 9, 3, 0, 0, 0).unwrap();
+        let records = vec![
+            record("a", "gpt-4o", day + chrono::Duration::hours(1), Decimal::new(1, 0)),
+            record("b", "gpt-4o", day + chrono::Duration::hours(2), Decimal::new(3, 0)),
+            record("c", "gpt-4o-mini", day + chrono::Duration::hours(3), Decimal::new(2, 0)),
+            record("d", "gpt-4o", day + chrono::Duration::days(5), Decimal::new(1, 0)),
+        ];
+        let entries = vec![
+            ProviderUsageEntry {
+                request_id: Some("c".to_string()),
+                model: None,
+                start_time: day,
+                end_time: day,
+                cost: Decimal::new(25, 1),
+            },
+            bucket_entry("gpt-4o-2024-08-06", day, Decimal::new(5, 0)),
+            bucket_entry("gpt-4o-2024-08-06", day, Decimal::new(3, 0)),
+            bucket_entry("o1", day, Decimal::new(7, 0)),
+        ];
+
+        let outcome = match_entries(&records, &entries);
+        let verified: HashMap<String, Decimal> = outcome.verifications.into_iter().collect();
+
+        assert_eq!(verified["c"], Decimal::new(25, 1));
+        assert_eq!(verified["a"], Decimal::new(2, 0));
+        assert_eq!(verified["b"], Decimal::new(6, 0));
+        assert!(!verified.contains_key("d"));
+        assert_eq!(outcome.unmatched_records, 1);
+        assert_eq!(outcome.unmatched_provider_cost, Decimal::new(7, 0));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_period_verifies_and_alerts() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = AppPaths::with_data_dir(temp_dir.path()).unwrap();
+        let database = Database::new(&paths).await.unwrap();
+        let pool = database.get_pool();
+        let usage_repo = UsageRepository::new(pool.clone());
+        let request_id = usage_repo
+            .record_usage(UsageParams::new("openai", "gpt-4o", 1000, 500, Decimal::new(100, 2)))
+            .await
+            .unwrap();
+
+        let now = Utc::now();
+        let period = now.format("%Y-%m").to_string();
+        let (start, _) = period_bounds(&period).unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/organization/costs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{
+                    "start_time": start.timestamp(),
+                    "end_time": (now + chrono::Duration::days(1)).timestamp(),
+                    "results": [{"amount": {"value": 1.5, "currency": "usd"}, "line_item": "gpt-4o-2024-08-06, input"}]
+                }],
+                "has_more": false,
+                "next_page": null
+            })))
+            .mount(&server)
+            .await;
+
+        let (alert_system, mut alert_receiver) = CostAlertSystem::new(CostAlertConfig::default());
+        let mut job = ReconciliationJob::new(pool, alert_system);
+        job.add_adapter(Box::new(
+            OpenAIBillingAdapter::new("admin-key".to_string()).unwrap().with_base_url(server.uri()),
+        ));
+
+        let report = job.reconcile_period(&period).await.unwrap();
+
+        assert_eq!(report.providers.len(), 1);
+        let openai = &report.providers[0];
+        assert!(openai.error.is_none());
+        assert_eq!(openai.verified.len(), 1);
+        assert_eq!(openai.verified[0].request_id, request_id);
+        assert_eq!(openai.verified[0].verified_cost, Decimal::new(15, 1));
+
+        assert_eq!(report.alerts.len(), 1);
+        assert!(matches!(report.alerts[0].alert_type, CostAlertType::CostDiscrepancy { .. }));
+        assert!(alert_receiver.try_recv().is_ok());
+
+        let unverified = usage_repo.get_unverified_records(None).await.unwrap();
+        assert!(unverified.is_empty());
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error::Error;
+use crate::mcp::permissions::ToolDecision;
 
 /// Represents a single chat message
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,6 +142,8 @@ pub struct ToolInvocation {
     pub error: Option<String>,
     pub duration_ms: Option<u64>,
     pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub decision: Option<ToolDecision>,
 }
 
 impl ToolInvocation {
@@ -154,9 +157,15 @@ impl ToolInvocation {
             error: None,
             duration_ms: None,
             timestamp: Utc::now(),
+            decision: None,
         }
     }
 
+    pub fn with_decision(mut self, decision: ToolDecision) -> Self {
+        self.decision = Some(decision);
+        self
+    }
+
     pub fn with_result(mut self, result: serde_json::Value, duration_ms: u64) -> Self {
         self.result = Some(result);
         self.duration_ms = Some(duration_ms);
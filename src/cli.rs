@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(name = "valechat")]
@@ -12,6 +12,10 @@ pub struct Cli {
     #[arg(short, long)]
     pub config: Option<String>,
 
+    /// Override a setting, e.g. `--set ui.theme=light`; may be repeated
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    pub overrides: Vec<String>,
+
     /// Enable debug logging
     #[arg(short, long)]
     pub debug: bool,
@@ -40,20 +44,12 @@ pub enum Commands {
     
     /// Manage API keys
     ApiKey {
-        /// Provider name (openai, anthropic, etc.)
-        provider: String,
-        
-        /// Set API key for provider
-        #[arg(short, long)]
-        set: Option<String>,
-        
-        /// Remove API key for provider
-        #[arg(short, long)]
-        remove: bool,
-        
-        /// Show current API key status
-        #[arg(long)]
-        status: bool,
+        /// Provider name (openai, anthropic, etc.), or a named key such as openai/team-a
+        #[arg(required_unless_present = "list")]
+        provider: Option<String>,
+
+        #[command(flatten)]
+        options: ApiKeyOptions,
     },
     
     /// List available models and providers
@@ -72,6 +68,22 @@ pub enum Commands {
         /// Show usage for specific provider
         #[arg(long)]
         provider: Option<String>,
+
+        /// Show usage charged to a project
+        #[arg(long)]
+        project: Option<String>,
+
+        /// List usage for every project, with unassigned usage shown separately
+        #[arg(long, conflicts_with = "project")]
+        by_project: bool,
+
+        /// Show usage per API key for a billing period (--period YYYY-MM, defaults to this month)
+        #[arg(long, conflicts_with_all = ["project", "by_project", "forecast"])]
+        by_key: bool,
+
+        /// Forecast month-end spend and when each budget runs out
+        #[arg(long)]
+        forecast: bool,
     },
     
     /// Export conversation data
@@ -88,6 +100,315 @@ pub enum Commands {
         #[arg(short, long)]
         conversation: Option<String>,
     },
+
+    /// Billing verification against provider APIs
+    Billing {
+        #[command(subcommand)]
+        action: BillingCommands,
+    },
+
+    /// Display currency and the offline exchange-rate table
+    Currency {
+        #[command(subcommand)]
+        action: CurrencyCommands,
+    },
+
+    /// Database schema migrations
+    Db {
+        #[command(subcommand)]
+        action: DbCommands,
+    },
+
+    /// Delete or archive old conversations according to the retention rules
+    Purge {
+        /// Show what would be purged without changing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Ignore the configured rules and purge conversations inactive for this many days
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        older_than: Option<u32>,
+
+        /// With --older-than, only conversations with this provider
+        #[arg(long, requires = "older_than")]
+        provider: Option<String>,
+
+        /// With --older-than, only conversations in this project
+        #[arg(long, requires = "older_than")]
+        project: Option<String>,
+
+        /// With --older-than, archive instead of deleting
+        #[arg(long, requires = "older_than")]
+        archive: bool,
+    },
+
+    /// Create, verify and restore database backups
+    Backup {
+        #[command(subcommand)]
+        action: BackupCommands,
+    },
+
+    /// Inspect the layered configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommands,
+    },
+
+    /// Inspect where API keys and other secrets are stored
+    Secrets {
+        #[command(subcommand)]
+        action: SecretsCommands,
+    },
+
+    /// Review the security audit log
+    Audit {
+        #[command(subcommand)]
+        action: AuditCommands,
+    },
+}
+
+impl Commands {
+    /// Name of the command as typed, recorded in the key audit log
+    pub fn name(&self) -> &'static str {
+        match self {
+            Commands::Chat { .. } => "chat",
+            Commands::ApiKey { .. } => "api-key",
+            Commands::Models { .. } => "models",
+            Commands::Usage { .. } => "usage",
+            Commands::Export { .. } => "export",
+            Commands::Billing { .. } => "billing",
+            Commands::Currency { .. } => "currency",
+            Commands::Db { .. } => "db",
+            Commands::Purge { .. } => "purge",
+            Commands::Backup { .. } => "backup",
+            Commands::Config { .. } => "config",
+            Commands::Secrets { .. } => "secrets",
+            Commands::Audit { .. } => "audit",
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct ApiKeyOptions {
+    /// Set API key for provider
+    #[arg(short, long)]
+    pub set: Option<String>,
+
+    /// Remove API key for provider
+    #[arg(short, long)]
+    pub remove: bool,
+
+    /// Show current API key status
+    #[arg(long)]
+    pub status: bool,
+
+    /// Check the key with a request to the provider
+    #[arg(long)]
+    pub validate: bool,
+
+    /// Remind to rotate the stored key every this many days; 0 turns the reminder off
+    #[arg(long, value_name = "DAYS")]
+    pub rotate_every: Option<u32>,
+
+    /// Use the key for the conversations of a project
+    #[arg(long, value_name = "PROJECT")]
+    pub use_for_project: Option<String>,
+
+    /// List stored keys with their rotation dates
+    #[arg(long, conflicts_with_all = ["set", "remove", "status", "validate", "rotate_every", "use_for_project"])]
+    pub list: bool,
+}
+
+#[derive(Subcommand)]
+pub enum AuditCommands {
+    /// API key reads and changes, with suspicious patterns flagged
+    Keys {
+        /// Only this provider and its named keys, or one key such as openai/team-a
+        #[arg(long)]
+        provider: Option<String>,
+
+        /// How many days back to look
+        #[arg(long, default_value_t = valechat::storage::DEFAULT_REVIEW_DAYS)]
+        days: u32,
+
+        /// Most entries to list
+        #[arg(long, default_value_t = 50)]
+        limit: u32,
+
+        /// Only failed operations
+        #[arg(long)]
+        failures: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SecretsCommands {
+    /// Show the secret store in use and whether it is unlocked
+    Status,
+
+    /// List the names of stored API keys and secrets, never their values
+    List,
+
+    /// Create a random key file to unlock the encrypted secrets file without a passphrase
+    GenerateKeyFile {
+        /// Where to write the key file
+        path: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print the effective configuration
+    Show {
+        /// Only this setting or section, e.g. `billing.currency`
+        key: Option<String>,
+
+        /// List every setting with the file, variable or flag it came from
+        #[arg(long)]
+        origin: bool,
+    },
+
+    /// List the configuration files in the order they are applied
+    Files,
+}
+
+#[derive(Subcommand)]
+pub enum BackupCommands {
+    /// Back up the database now
+    Create {
+        /// Take a full backup even when incremental backups are enabled
+        #[arg(long)]
+        full: bool,
+    },
+
+    /// List backups, newest first
+    List,
+
+    /// Replace the database with the contents of a backup
+    Restore {
+        /// Backup ID as shown by `backup list`
+        id: String,
+
+        /// Write the restored database to this file instead of replacing the current one
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Skip the backup of the current database normally taken before restoring
+        #[arg(long)]
+        no_safety_backup: bool,
+
+        /// Restore without asking
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Check that backups can be read and rebuild an intact database
+    Verify {
+        /// Backup ID; all backups when omitted
+        id: Option<String>,
+    },
+
+    /// Delete backups older than the retention period
+    Prune {
+        /// Number of newest backup chains to keep regardless of age
+        #[arg(long, default_value_t = 1)]
+        keep: usize,
+
+        /// Show what would be deleted without deleting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Set the passphrase encrypted backups use, stored in the system keychain
+    Passphrase {
+        /// Remove the stored passphrase instead
+        #[arg(long)]
+        clear: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DbCommands {
+    /// Apply pending schema migrations
+    Migrate,
+
+    /// Show applied and pending migrations
+    Status,
+
+    /// Revert applied migrations, e.g. before downgrading to an older release
+    Rollback {
+        /// Revert every migration newer than this version
+        #[arg(long, conflicts_with = "steps")]
+        to: Option<i64>,
+
+        /// Number of migrations to revert
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+
+        /// Revert without asking; reverted migrations drop the data they added
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Encrypt message content and tool arguments/results in place
+    Encrypt,
+
+    /// Store conversation content in plain text again
+    Decrypt {
+        /// Decrypt without asking
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CurrencyCommands {
+    /// Show the display currency, limit currency and exchange rates
+    Show,
+
+    /// Set the currency costs are displayed in (e.g. EUR)
+    Display {
+        code: String,
+    },
+
+    /// Set the currency billing and provider limits are written in
+    Limits {
+        code: String,
+    },
+
+    /// Set an exchange rate by hand, in units of the currency per US dollar
+    SetRate {
+        code: String,
+        rate: String,
+    },
+
+    /// Remove a currency from the rate table
+    RemoveRate {
+        code: String,
+    },
+
+    /// Import exchange rates from a local JSON or `CODE RATE` file
+    Import {
+        file: String,
+
+        /// Replace the whole table instead of merging into it
+        #[arg(long)]
+        replace: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BillingCommands {
+    /// Reconcile local usage with provider billing data
+    Reconcile {
+        /// Billing period to reconcile (YYYY-MM, defaults to the current month)
+        #[arg(short, long)]
+        period: Option<String>,
+
+        /// Reconcile only this provider (openai, anthropic)
+        #[arg(long)]
+        provider: Option<String>,
+    },
 }
 
 impl Default for Cli {
@@ -99,6 +420,7 @@ impl Default for Cli {
                 model: None,
             }),
             config: None,
+            overrides: Vec::new(),
             debug: false,
             no_color: false,
         }
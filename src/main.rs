@@ -1,5 +1,5 @@
 use anyhow::Result;
-use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
 use clap::Parser;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
@@ -9,6 +9,7 @@ use crossterm::{
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::{
     io,
+    path::PathBuf,
     sync::Arc,
     time::Duration,
 };
@@ -18,11 +19,13 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 mod cli;
 mod tui;
 
-use cli::{Cli, Commands};
+use cli::{ApiKeyOptions, AuditCommands, BackupCommands, BillingCommands, Cli, Commands, ConfigCommands, CurrencyCommands, DbCommands, SecretsCommands};
 use tui::{App, EventHandler};
 use valechat::{
-    app::{AppConfig, AppState},
-    platform::{AppPaths, SecureStorageManager},
+    app::{AppState, ConfigSources, LayeredConfig, StoredApiKey},
+    billing::{self, AlertDispatcher, CurrencyFormatter, ExchangeRates, ReconciliationJob},
+    storage::{encryption, BackupInfo, KeyAuditRules, KeyScope, RetentionAction, RetentionManager, RetentionRule, BackupType, CostAlertSystem, Database, DatabaseEncryption, EncryptionConfig, MigrationState, RecoveryOptions, UsageStatistics},
+    platform::{file_storage::EncryptedFileStorage, AppPaths, SecretsBackend, SecretsConfig, SecureStorageManager},
 };
 
 #[tokio::main]
@@ -33,26 +36,73 @@ async fn main() -> Result<()> {
     // Initialize logging
     init_logging(cli.debug)?;
 
+    let command = cli.command.unwrap_or_default();
+
+    let paths = AppPaths::new()?;
+    paths.ensure_dirs_exist()?;
+    let config_sources = config_sources(&paths, cli.config.as_deref(), cli.overrides)?;
+
+    // Schema maintenance must work on databases the normal startup would refuse or migrate
+    if let Commands::Db { action } = command {
+        return handle_db_command(&paths, &config_sources, action).await;
+    }
+    match command {
+        Commands::Config { action } => return handle_config_command(&config_sources, action),
+        Commands::Secrets { action } => return handle_secrets_command(&paths, &config_sources, action).await,
+        _ => {}
+    }
+
     // Initialize application state
-    let app_state = init_app_state(cli.config.as_deref()).await?;
+    let app_state = init_app_state(paths, config_sources, command.name()).await?;
 
     // Handle different commands
-    match cli.command.unwrap_or_default() {
+    match command {
         Commands::Chat { conversation, provider, model } => {
             run_chat_interface(app_state, conversation, provider, model).await?;
         }
-        Commands::ApiKey { provider, set, remove, status } => {
-            handle_api_key_command(app_state, &provider, set, remove, status).await?;
+        Commands::ApiKey { options: ApiKeyOptions { list: true, .. }, .. } => {
+            handle_api_key_list(app_state).await?;
+        }
+        Commands::ApiKey { provider, options } => {
+            handle_api_key_command(app_state, &provider.unwrap_or_default(), options).await?;
         }
         Commands::Models { enabled } => {
             handle_models_command(app_state, enabled).await?;
         }
-        Commands::Usage { period, provider } => {
-            handle_usage_command(app_state, period, provider).await?;
+        Commands::Usage { forecast: true, provider, .. } => {
+            handle_forecast_command(app_state, provider).await?;
+        }
+        Commands::Usage { by_key: true, period, provider, .. } => {
+            handle_key_usage_command(app_state, period, provider).await?;
+        }
+        Commands::Usage { period, provider, project, by_project, .. } => {
+            handle_usage_command(app_state, period, provider, project, by_project).await?;
         }
         Commands::Export { format, output, conversation } => {
             handle_export_command(app_state, &format, output, conversation).await?;
         }
+        Commands::Billing { action } => {
+            handle_billing_command(app_state, action).await?;
+        }
+        Commands::Currency { action } => {
+            handle_currency_command(app_state, action).await?;
+        }
+        Commands::Purge { dry_run, older_than, provider, project, archive } => {
+            let rule = older_than.map(|older_than_days| RetentionRule {
+                older_than_days,
+                action: if archive { RetentionAction::Archive } else { RetentionAction::Delete },
+                provider,
+                project,
+            });
+            handle_purge_command(app_state, dry_run, rule).await?;
+        }
+        Commands::Backup { action } => {
+            handle_backup_command(app_state, action).await?;
+        }
+        Commands::Audit { action: AuditCommands::Keys { provider, days, limit, failures } } => {
+            handle_key_audit_command(app_state, provider, days, limit, failures).await?;
+        }
+        Commands::Db { .. } | Commands::Config { .. } | Commands::Secrets { .. } => unreachable!("handled before startup"),
     }
 
     Ok(())
@@ -88,43 +138,154 @@ fn init_logging(debug: bool) -> Result<()> {
     Ok(())
 }
 
-async fn init_app_state(config_path: Option<&str>) -> Result<Arc<AppState>> {
+fn config_sources(paths: &AppPaths, config_path: Option<&str>, overrides: Vec<String>) -> Result<ConfigSources> {
+    // A file named explicitly has to exist; the default one is optional
+    if let Some(path) = config_path {
+        if !std::path::Path::new(path).is_file() {
+            anyhow::bail!("Configuration file {} not found", path);
+        }
+    }
+    Ok(ConfigSources::discover(paths, config_path.map(PathBuf::from), overrides))
+}
+
+async fn init_app_state(paths: AppPaths, config_sources: ConfigSources, command: &str) -> Result<Arc<AppState>> {
     info!("Initializing application state...");
 
-    // Initialize platform-specific paths
-    let paths = AppPaths::new()?;
-    paths.ensure_dirs_exist()?;
+    // An unreadable or invalid configuration stops startup rather than being replaced by defaults
+    let config = LayeredConfig::load(&config_sources)?.config;
+    config.validate()?;
+
+    // Initialize secure storage
+    let secure_storage = open_secure_storage(&paths, &config.secrets)?;
+
+    // Create app state
+    let app_state = AppState::new(config, paths, secure_storage).await?
+        .with_config_sources(config_sources)
+        .with_audit_command(command);
+
+    info!("Application state initialized successfully");
+    Ok(Arc::new(app_state))
+}
+
+/// Open the configured secret store, asking for the passphrase of the encrypted file on a terminal
+fn open_secure_storage(paths: &AppPaths, config: &SecretsConfig) -> Result<SecureStorageManager> {
+    use std::io::IsTerminal;
 
-    // Load configuration
-    let config = if let Some(custom_path) = config_path {
-        // Load from custom path
-        match tokio::fs::read_to_string(custom_path).await {
-            Ok(content) => {
-                match toml::from_str(&content) {
-                    Ok(config) => config,
-                    Err(e) => {
-                        eprintln!("Error parsing config file {}: {}", custom_path, e);
-                        AppConfig::default()
+    let ask = |prompt: &str| read_secret(prompt).map_err(|e| valechat::Error::platform(e.to_string()));
+    let storage = SecureStorageManager::from_config(config, paths, |path, new| {
+        if !io::stdin().is_terminal() {
+            return Ok(None);
+        }
+        if !new {
+            return ask(&format!("Passphrase for {}: ", path.display())).map(Some);
+        }
+
+        eprintln!("No system keychain is available; secrets will be kept in {}.", path.display());
+        let passphrase = ask("Choose a passphrase for it: ")?;
+        if passphrase.is_empty() {
+            return Ok(None);
+        }
+        if ask("Repeat the passphrase: ")? != passphrase {
+            return Err(valechat::Error::validation("Passphrases do not match"));
+        }
+        Ok(Some(passphrase))
+    })?;
+    info!("Using {} for secrets", storage.backend_name());
+    Ok(storage)
+}
+
+/// Secret settings for maintenance commands, which have to work even when the rest of the configuration does not
+fn secrets_config(sources: &ConfigSources) -> SecretsConfig {
+    match LayeredConfig::load(sources) {
+        Ok(layered) => layered.config.secrets,
+        Err(e) => {
+            eprintln!("Warning: {}; using the default secret store", e);
+            SecretsConfig::default()
+        }
+    }
+}
+
+async fn handle_secrets_command(paths: &AppPaths, sources: &ConfigSources, action: SecretsCommands) -> Result<()> {
+    let config = secrets_config(sources);
+    match action {
+        SecretsCommands::Status => {
+            let backend = config.resolve_backend(paths);
+            println!("Configured backend: {:?}", config.backend);
+            match backend {
+                SecretsBackend::File => {
+                    let path = SecretsConfig::file_path(paths);
+                    let state = if path.exists() { "" } else { " (not created yet)" };
+                    println!("Secrets are kept in {}{}", path.display(), state);
+                    match &config.key_file {
+                        Some(key_file) => println!("Unlocked with key file {}", key_file.display()),
+                        None => println!("Unlocked with a passphrase (prompted, or {})",
+                            valechat::platform::secure_storage::SECRETS_PASSPHRASE_ENV),
                     }
                 }
+                _ => println!("Secrets are kept in the system keychain"),
+            }
+        }
+        SecretsCommands::List => {
+            let storage = open_secure_storage(paths, &config)?;
+            let mut providers = storage.list_providers().await?;
+            providers.sort();
+            let secrets = storage.list_secrets().await?;
+            println!("Secret store: {}", storage.backend_name());
+            if providers.is_empty() && secrets.is_empty() {
+                println!("No secrets stored");
             }
-            Err(e) => {
-                eprintln!("Error reading config file {}: {}", custom_path, e);
-                AppConfig::default()
+            for provider in providers {
+                println!("  api key   {}", provider);
+            }
+            for secret in secrets {
+                println!("  secret    {}", secret);
             }
         }
-    } else {
-        AppConfig::load(&paths).await.unwrap_or_default()
-    };
-
-    // Initialize secure storage
-    let secure_storage = SecureStorageManager::new()?;
+        SecretsCommands::GenerateKeyFile { path } => {
+            let path = PathBuf::from(path);
+            EncryptedFileStorage::generate_key_file(&path)?;
+            println!("Wrote a new key to {}", path.display());
+            println!("Set `key_file = \"{}\"` under [secrets] to unlock the secret store with it.", path.display());
+            println!("A secrets file created with a passphrase cannot be opened with a key file.");
+        }
+    }
+    Ok(())
+}
 
-    // Create app state
-    let app_state = AppState::new(config, paths, secure_storage).await?;
+fn handle_config_command(sources: &ConfigSources, action: ConfigCommands) -> Result<()> {
+    match action {
+        ConfigCommands::Show { key, origin } => {
+            let layered = LayeredConfig::load(sources)?;
+            if let Err(e) = layered.config.validate() {
+                eprintln!("Warning: {}\n", e);
+            }
 
-    info!("Application state initialized successfully");
-    Ok(Arc::new(app_state))
+            let entries = layered.entries(key.as_deref());
+            if entries.is_empty() {
+                anyhow::bail!("No setting named {}", key.unwrap_or_default());
+            }
+            if !origin && key.is_none() {
+                print!("{}", layered.to_toml()?);
+                return Ok(());
+            }
+            let width = entries.iter().map(|(key, _, _)| key.len()).max().unwrap_or(0);
+            for (key, value, source) in entries {
+                if origin {
+                    println!("{:<width$} = {:<24} # {}", key, value, source, width = width);
+                } else {
+                    println!("{:<width$} = {}", key, value, width = width);
+                }
+            }
+        }
+        ConfigCommands::Files => {
+            println!("built-in defaults");
+            for (origin, path) in sources.files() {
+                let state = if path.is_file() { "" } else { " (not present)" };
+                println!("{}{}", origin, state);
+            }
+        }
+    }
+    Ok(())
 }
 
 async fn run_chat_interface(
@@ -193,28 +354,133 @@ async fn run_event_loop(
     Ok(())
 }
 
-async fn handle_api_key_command(
-    app_state: Arc<AppState>,
-    provider: &str,
-    set: Option<String>,
-    remove: bool,
-    status: bool,
-) -> Result<()> {
+async fn handle_api_key_command(app_state: Arc<AppState>, provider: &str, options: ApiKeyOptions) -> Result<()> {
+    let ApiKeyOptions { set, remove, status, validate, rotate_every, use_for_project, .. } = options;
+    if set.is_none() && !remove && !status && !validate && rotate_every.is_none() && use_for_project.is_none() {
+        println!("Please specify --set, --remove, --status, --validate, --rotate-every or --use-for-project");
+        return Ok(());
+    }
+
     if let Some(api_key) = set {
         app_state.set_api_key(provider, &api_key).await?;
         println!("API key set for provider: {}", provider);
     } else if remove {
         app_state.remove_api_key(provider).await?;
         println!("API key removed for provider: {}", provider);
-    } else if status {
-        match app_state.get_api_key(provider).await? {
-            Some(_) => println!("API key configured for provider: {}", provider),
+    }
+
+    if let Some(days) = rotate_every {
+        app_state.set_api_key_rotation(provider, Some(days)).await?;
+        match days {
+            0 => println!("Rotation reminders turned off for {}", provider),
+            days => println!("{} will be due for rotation every {} day(s)", provider, days),
+        }
+    }
+
+    if let Some(project) = use_for_project {
+        app_state.select_api_key(KeyScope::Project(&project), provider).await?;
+        println!("Project {} now uses {}", project, provider);
+    }
+
+    if status {
+        match app_state.resolve_api_key(provider).await? {
+            Some(key) => println!("API key configured for provider: {} (from {})", provider, key.source),
             None => println!("No API key configured for provider: {}", provider),
         }
+        let stored = app_state.list_stored_api_keys().await?.into_iter().find(|key| key.id == provider);
+        if let Some(key) = stored {
+            print_rotation(&key);
+        }
+    }
+
+    if validate {
+        if app_state.validate_provider_credentials(provider).await? {
+            println!("✅ {} was accepted by the provider", provider);
+        } else {
+            println!("❌ {} was rejected by the provider or could not be checked", provider);
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_api_key_list(app_state: Arc<AppState>) -> Result<()> {
+    let keys = app_state.list_stored_api_keys().await?;
+    if keys.is_empty() {
+        println!("No API keys in secure storage");
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now();
+    println!("{:<28} {:<12} {:<12} Status", "Key", "Rotated", "Due");
+    for key in &keys {
+        let date = |date: Option<chrono::DateTime<chrono::Utc>>| {
+            date.map_or("-".to_string(), |date| date.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+        };
+        let status = if key.is_rotation_due(now) { "⚠ rotation due" } else { "ok" };
+        println!("{:<28} {:<12} {:<12} {}", key.id, date(key.rotated_at), date(key.rotation_due()), status);
+    }
+    Ok(())
+}
+
+fn print_rotation(key: &StoredApiKey) {
+    let Some(rotated_at) = key.rotated_at else {
+        return;
+    };
+    println!("  Last rotated: {}", rotated_at.with_timezone(&chrono::Local).format("%Y-%m-%d"));
+    if let Some(due) = key.rotation_due() {
+        let overdue = if due <= chrono::Utc::now() { " ⚠ rotation due" } else { "" };
+        println!("  Rotation due: {}{}", due.with_timezone(&chrono::Local).format("%Y-%m-%d"), overdue);
+    }
+}
+
+async fn handle_key_audit_command(
+    app_state: Arc<AppState>,
+    provider: Option<String>,
+    days: u32,
+    limit: u32,
+    failures_only: bool,
+) -> Result<()> {
+    // Anomalies are judged on the whole period, not only the entries listed
+    let rules = KeyAuditRules::default();
+    let (entries, anomalies) = app_state.get_key_audit().review(provider, days, &rules).await?;
+
+    let listed: Vec<_> = entries.iter()
+        .filter(|entry| !failures_only || !entry.success)
+        .take(limit as usize)
+        .collect();
+    if listed.is_empty() {
+        println!("No API key operations in the last {} day(s)", days);
     } else {
-        println!("Please specify --set, --remove, or --status");
+        println!("{:<17} {:<12} {:<9} {:<7} {:<24} {:<9} Error", "Time", "Provider", "Operation", "Result", "Source", "Command");
+        for entry in &listed {
+            let flag = if rules.is_unexpected(entry) { " ⚠ unexpected command" } else { "" };
+            println!(
+                "{:<17} {:<12} {:<9} {:<7} {:<24} {:<9} {}{}",
+                entry.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                entry.provider,
+                entry.operation,
+                if entry.success { "ok" } else { "FAILED" },
+                entry.source.as_deref().unwrap_or("-"),
+                entry.command.as_deref().unwrap_or("-"),
+                entry.error_message.as_deref().unwrap_or(""),
+                flag,
+            );
+        }
+        if entries.len() > listed.len() && !failures_only {
+            println!("... {} older entries; use --limit to see more", entries.len() - listed.len());
+        }
     }
 
+    println!();
+    if anomalies.is_empty() {
+        println!("No anomalies in the last {} day(s)", days);
+    } else {
+        println!("⚠ Anomalies:");
+        for anomaly in &anomalies {
+            println!("  - {}", anomaly);
+        }
+    }
     Ok(())
 }
 
@@ -252,18 +518,44 @@ async fn handle_usage_command(
     app_state: Arc<AppState>,
     period: Option<String>,
     provider: Option<String>,
+    project: Option<String>,
+    by_project: bool,
 ) -> Result<()> {
     let usage_repo = app_state.get_usage_repo();
+    let money = app_state.get_config().currency_formatter();
+
+    let stats = match project.as_deref() {
+        Some(name) => usage_repo.get_project_usage_statistics(name).await,
+        None => usage_repo.get_usage_statistics().await,
+    };
     
-    match usage_repo.get_usage_statistics().await {
+    match stats {
         Ok(stats) => {
-            println!("Usage Statistics:");
+            if by_project {
+                print_project_chargeback(&stats, &money);
+                return Ok(());
+            }
+            match project.as_deref() {
+                Some(name) => println!("Usage Statistics for project '{}':", name),
+                None => println!("Usage Statistics:"),
+            }
             println!("  Total Requests: {}", stats.total_requests);
-            println!("  Total Cost: ${:.4}", stats.total_cost.to_f64().unwrap_or(0.0));
+            println!("  Total Cost: {}", money.format_precise(stats.total_cost));
             println!("  Input Tokens: {}", stats.total_input_tokens);
             println!("  Output Tokens: {}", stats.total_output_tokens);
-            println!("  Current Month Cost: ${:.4}", stats.current_month_cost.to_f64().unwrap_or(0.0));
-            println!("  Previous Month Cost: ${:.4}", stats.previous_month_cost.to_f64().unwrap_or(0.0));
+            println!("  Current Month Cost: {}", money.format_precise(stats.current_month_cost));
+            println!("  Previous Month Cost: {}", money.format_precise(stats.previous_month_cost));
+
+            if project.is_some() {
+                let mut providers: Vec<_> = stats.by_provider.iter().collect();
+                providers.sort_by(|a, b| b.1.cost.cmp(&a.1.cost));
+                println!("\n  By provider:");
+                for (provider, usage) in providers {
+                    println!("    {:<12} {:>6} requests  {}", provider, usage.requests, money.format_precise(usage.cost));
+                }
+            } else if !stats.by_project.is_empty() {
+                println!("\n  {} project(s) with attributed usage; see `valechat usage --by-project`", stats.by_project.len());
+            }
             
             // Show period-specific data if requested
             if let Some(period_str) = period {
@@ -284,6 +576,572 @@ async fn handle_usage_command(
     Ok(())
 }
 
+async fn handle_forecast_command(app_state: Arc<AppState>, provider: Option<String>) -> Result<()> {
+    let forecast = app_state.forecast_spending().await?;
+    let money = app_state.get_config().currency_formatter();
+
+    println!("Spend forecast for {}:", forecast.billing_period);
+    println!("  Month to date:       {}", money.format_precise(forecast.month_to_date));
+    println!("  Projected month end: {}", money.format_precise(forecast.projected_month_end));
+    println!("  Trend:               {:?}", forecast.trend);
+
+    let mut providers: Vec<_> = forecast.by_provider.iter()
+        .filter(|(name, _)| provider.as_ref().is_none_or(|p| p == *name))
+        .collect();
+    providers.sort_by(|a, b| b.1.projected_month_end.cmp(&a.1.projected_month_end));
+    if !providers.is_empty() {
+        println!("\n  By provider:");
+        for (name, provider_forecast) in providers {
+            println!(
+                "    {:<12} {:>11} so far  {:>11} projected",
+                name,
+                money.format_precise(provider_forecast.month_to_date),
+                money.format_precise(provider_forecast.projected_month_end)
+            );
+        }
+    }
+
+    if forecast.budgets.is_empty() {
+        println!("\n  No daily, weekly or monthly global or provider limits to project");
+        return Ok(());
+    }
+
+    println!("\n  Budgets:");
+    for budget in &forecast.budgets {
+        let outlook = match (budget.exhaustion_date, budget.days_left) {
+            (Some(_), Some(0)) => "runs out today".to_string(),
+            (Some(date), Some(days)) => format!("runs out {} (in {} days)", date, days),
+            _ => "within budget".to_string(),
+        };
+        println!(
+            "    {:<32} {} of {} used, {} projected - {}",
+            budget.description,
+            money.format(budget.spent),
+            money.format(budget.limit),
+            money.format(budget.projected),
+            outlook
+        );
+    }
+
+    Ok(())
+}
+
+/// Per-project totals for charge-back, with unassigned usage listed separately
+async fn handle_key_usage_command(app_state: Arc<AppState>, period: Option<String>, provider: Option<String>) -> Result<()> {
+    let billing_period = match period.as_deref() {
+        None | Some("month") => chrono::Utc::now().format("%Y-%m").to_string(),
+        Some(period) if chrono::NaiveDate::parse_from_str(&format!("{}-01", period), "%Y-%m-%d").is_ok() => period.to_string(),
+        Some(period) => anyhow::bail!("--by-key takes a billing period as YYYY-MM, not '{}'", period),
+    };
+    let money = app_state.get_config().currency_formatter();
+
+    let usage: Vec<_> = app_state.get_usage_repo().get_key_usage(&billing_period).await?
+        .into_iter()
+        .filter(|usage| provider.as_ref().is_none_or(|p| *p == usage.provider))
+        .collect();
+    if usage.is_empty() {
+        println!("No usage in {}", billing_period);
+        return Ok(());
+    }
+
+    println!("Usage by API key for {}:", billing_period);
+    let mut total = Decimal::ZERO;
+    for key in &usage {
+        total += key.cost;
+        println!(
+            "  {:<28} {:>6} requests  {:>10} tokens  {}",
+            key.key_id, key.requests, key.input_tokens + key.output_tokens, money.format_precise(key.cost)
+        );
+    }
+    println!("  Total: {}", money.format_precise(total));
+    Ok(())
+}
+
+fn print_project_chargeback(stats: &UsageStatistics, money: &CurrencyFormatter) {
+    println!("Usage by project:");
+    let mut projects: Vec<_> = stats.by_project.iter().collect();
+    projects.sort_by(|a, b| b.1.cost.cmp(&a.1.cost).then_with(|| a.0.cmp(b.0)));
+
+    let mut attributed = Decimal::ZERO;
+    let mut attributed_requests = 0;
+    for (name, usage) in projects {
+        attributed += usage.cost;
+        attributed_requests += usage.requests;
+        println!(
+            "  {:<24} {:>6} requests  {:>10} tokens  {}",
+            name, usage.requests, usage.input_tokens + usage.output_tokens, money.format_precise(usage.cost)
+        );
+    }
+
+    println!(
+        "  {:<24} {:>6} requests  {:>10}         {}",
+        "(unassigned)",
+        stats.total_requests.saturating_sub(attributed_requests),
+        "",
+        money.format_precise(stats.total_cost - attributed)
+    );
+    println!("  Total: {}", money.format_precise(stats.total_cost));
+}
+
+async fn handle_billing_command(app_state: Arc<AppState>, action: BillingCommands) -> Result<()> {
+    match action {
+        BillingCommands::Reconcile { period, provider } => {
+            let period = period.unwrap_or_else(|| chrono::Utc::now().format("%Y-%m").to_string());
+            let providers = match provider {
+                Some(provider) => vec![provider],
+                None => vec!["openai".to_string(), "anthropic".to_string()],
+            };
+
+            // Discrepancy alerts go to the configured sinks like alerts raised in the TUI
+            let config = app_state.get_config();
+            let (alert_system, alerts) = CostAlertSystem::new(config.cost_alert_config());
+            let dispatcher = AlertDispatcher::from_config(config.alerts.clone(), app_state.get_paths())?
+                .with_store(app_state.get_alert_store().clone())
+                .spawn(alerts);
+            let mut job = ReconciliationJob::new(app_state.get_database().get_pool(), alert_system);
+            let mut configured = 0;
+
+            for provider in &providers {
+                // Usage and cost APIs need an admin key; fall back to the regular key
+                let api_key = match app_state.get_api_key(&format!("{}-admin", provider)).await? {
+                    Some(key) => Some(key),
+                    None => app_state.get_api_key(provider).await?,
+                };
+                let Some(api_key) = api_key else {
+                    println!("Skipping {}: no API key configured (set one with `valechat api-key {}-admin --set ...`)", provider, provider);
+                    continue;
+                };
+
+                match billing::adapter_for(provider, api_key)? {
+                    Some(adapter) => {
+                        job.add_adapter(adapter);
+                        configured += 1;
+                    }
+                    None => println!("Skipping {}: provider has no billing API", provider),
+                }
+            }
+
+            if configured == 0 {
+                println!("No providers to reconcile");
+                return Ok(());
+            }
+
+            let report = job.reconcile_period(&period).await?;
+            drop(job);
+            dispatcher.await?;
+            // Provider invoices are in USD, so reconciliation output stays in USD
+            println!("Billing reconciliation for {}:", report.billing_period);
+            for result in &report.providers {
+                println!("\n  {}", result.provider);
+                if let Some(error) = &result.error {
+                    println!("    Failed: {}", error);
+                    continue;
+                }
+                println!("    Local cost:      ${:.4}", result.local_cost.to_f64().unwrap_or(0.0));
+                println!("    Provider cost:   ${:.4}", result.provider_cost.to_f64().unwrap_or(0.0));
+                println!("    Records checked: {}", result.records_checked);
+                println!("    Verified:        {}", result.verified.len());
+                println!("    Unmatched:       {}", result.unmatched_records);
+                if !result.unmatched_provider_cost.is_zero() {
+                    println!(
+                        "    Unattributed provider cost: ${:.4}",
+                        result.unmatched_provider_cost.to_f64().unwrap_or(0.0)
+                    );
+                }
+            }
+
+            if !report.alerts.is_empty() {
+                println!("\nAlerts:");
+                for alert in &report.alerts {
+                    println!("  [{:?}] {}", alert.severity, alert.message);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_currency_command(app_state: Arc<AppState>, action: CurrencyCommands) -> Result<()> {
+    let updated = || Some(chrono::Utc::now().to_rfc3339());
+
+    match action {
+        CurrencyCommands::Show => {
+            let config = app_state.get_config();
+            let currency = &config.billing.currency;
+            println!("Display currency: {}", currency.display);
+            println!("Limit currency:   {}", currency.limits);
+            if currency.rates.is_empty() {
+                println!("\nNo exchange rates configured; costs are shown in USD");
+                return Ok(());
+            }
+            println!(
+                "\nExchange rates per 1 USD (updated {}):",
+                currency.rates_updated.as_deref().unwrap_or("unknown")
+            );
+            for (code, rate) in &currency.rates {
+                println!("  {:<4} {}", code, rate);
+            }
+        }
+        CurrencyCommands::Display { code } => {
+            let code = billing::currency::normalize_code(&code)?;
+            app_state.update_config(|config| config.billing.currency.display = code.clone()).await?;
+            println!("Costs are now displayed in {}", code);
+        }
+        CurrencyCommands::Limits { code } => {
+            let code = billing::currency::normalize_code(&code)?;
+            app_state.update_config(|config| config.billing.currency.limits = code.clone()).await?;
+            println!("Billing and provider limit amounts are now read as {}", code);
+        }
+        CurrencyCommands::SetRate { code, rate } => {
+            let rate: Decimal = rate.parse()
+                .map_err(|_| anyhow::anyhow!("Invalid exchange rate: {}", rate))?;
+            let rates = ExchangeRates::new([(code.as_str(), rate)])?;
+            app_state.update_config(|config| {
+                config.billing.currency.rates.extend(rates.into_rates());
+                config.billing.currency.rates_updated = updated();
+            }).await?;
+            println!("1 USD = {} {}", rate, code.to_uppercase());
+        }
+        CurrencyCommands::RemoveRate { code } => {
+            let code = billing::currency::normalize_code(&code)?;
+            app_state.update_config(|config| {
+                config.billing.currency.rates.remove(&code);
+                config.billing.currency.rates_updated = updated();
+            }).await?;
+            println!("Removed the {} exchange rate", code);
+        }
+        CurrencyCommands::Import { file, replace } => {
+            let content = tokio::fs::read_to_string(&file).await?;
+            let rates = ExchangeRates::parse(&content)?.into_rates();
+            let count = rates.len();
+            app_state.update_config(|config| {
+                if replace {
+                    config.billing.currency.rates = rates;
+                } else {
+                    config.billing.currency.rates.extend(rates);
+                }
+                config.billing.currency.rates_updated = updated();
+            }).await?;
+            println!("Imported {} exchange rate(s) from {}", count, file);
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_db_command(paths: &AppPaths, sources: &ConfigSources, action: DbCommands) -> Result<()> {
+    let database = Database::open(paths).await?;
+    let migrator = database.migrator();
+
+    match action {
+        DbCommands::Migrate => {
+            let applied = migrator.run().await?;
+            if applied.is_empty() {
+                println!("Database schema is up to date (version {})", migrator.current_version().await?);
+            } else {
+                for version in &applied {
+                    println!("Applied migration {:03}", version);
+                }
+                println!("Database schema is now at version {}", migrator.current_version().await?);
+            }
+        }
+        DbCommands::Status => {
+            println!(
+                "Schema version {} (this build knows up to {})\n",
+                migrator.current_version().await?,
+                migrator.latest_version()
+            );
+            for status in migrator.status().await? {
+                let state = match status.state {
+                    MigrationState::Pending => "pending".to_string(),
+                    MigrationState::Applied { applied_at } => {
+                        format!("applied {}", applied_at.format("%Y-%m-%d %H:%M"))
+                    }
+                    MigrationState::Modified { applied_at } => {
+                        format!("MODIFIED since it was applied {}", applied_at.format("%Y-%m-%d %H:%M"))
+                    }
+                    MigrationState::Unknown { applied_at } => {
+                        format!("applied {} by a newer release", applied_at.format("%Y-%m-%d %H:%M"))
+                    }
+                };
+                println!("  {:03}  {:<28} {}", status.version, status.name, state);
+            }
+
+            if migrator.current_version().await? > 0 {
+                let encryption = DatabaseEncryption::new(database.get_pool()).status().await?;
+                println!(
+                    "\nConversation content: {} ({} encrypted, {} plain-text values)",
+                    if encryption.enabled { "encrypted" } else { "plain text" },
+                    encryption.encrypted_values,
+                    encryption.plaintext_values
+                );
+            }
+        }
+        DbCommands::Rollback { to, steps, yes } => {
+            let current = migrator.current_version().await?;
+            let applied: Vec<i64> = migrator.status().await?
+                .into_iter()
+                .filter(|status| status.state != MigrationState::Pending)
+                .map(|status| status.version)
+                .collect();
+            let target = to.unwrap_or_else(|| {
+                applied.iter().rev().nth(steps).copied().unwrap_or(0)
+            });
+
+            let reverting: Vec<&i64> = applied.iter().rev().filter(|version| **version > target).collect();
+            if reverting.is_empty() {
+                println!("Nothing to roll back; the schema is at version {}", current);
+                return Ok(());
+            }
+            if !yes {
+                println!("This reverts migration(s) {} and drops the data they added.",
+                    reverting.iter().map(|v| format!("{:03}", v)).collect::<Vec<_>>().join(", "));
+                println!("Re-run with --yes to continue. Starting this release again re-applies them.");
+                return Ok(());
+            }
+
+            for version in migrator.rollback(target).await? {
+                println!("Reverted migration {:03}", version);
+            }
+            println!("Database schema is now at version {}", migrator.current_version().await?);
+        }
+        DbCommands::Encrypt => {
+            migrator.run().await?;
+            let secure_storage = open_secure_storage(paths, &secrets_config(sources))?;
+            let config = EncryptionConfig { enabled: true };
+            encryption::open_field_cipher(&database.get_pool(), &secure_storage, &config).await?;
+            println!("Conversation content is encrypted; the key is kept in the {}.", secure_storage.backend_name());
+            println!("Set `enabled = true` under [encryption] in the configuration to keep encrypting new content.");
+        }
+        DbCommands::Decrypt { yes } => {
+            let secure_storage = open_secure_storage(paths, &secrets_config(sources))?;
+            let Some(cipher) = encryption::load_field_cipher(&secure_storage).await? else {
+                anyhow::bail!("No database encryption key found in the {}", secure_storage.backend_name());
+            };
+            if !yes {
+                println!("This stores all conversation content in plain text. Re-run with --yes to continue.");
+                return Ok(());
+            }
+            let count = DatabaseEncryption::new(database.get_pool()).decrypt_existing(&cipher).await?;
+            println!("Decrypted {} stored values", count);
+            println!("Set `enabled = false` under [encryption] in the configuration, or the next start encrypts them again.");
+        }
+    }
+
+    database.close().await;
+    Ok(())
+}
+
+async fn handle_purge_command(app_state: Arc<AppState>, dry_run: bool, rule: Option<RetentionRule>) -> Result<()> {
+    let mut config = app_state.get_config().retention;
+    if let Some(rule) = rule {
+        config.rules = vec![rule];
+        config.usage_records_days = None;
+    } else if config.rules.is_empty() && config.usage_records_days.is_none() {
+        println!("No retention rules are configured; only conversations deleted earlier are purged.");
+        println!("Add [[retention.rules]] to the configuration or pass --older-than <days>.");
+    }
+
+    let report = RetentionManager::new(app_state.get_database().get_pool(), config).purge(dry_run).await?;
+    if report.is_empty() {
+        println!("Nothing to purge");
+        return Ok(());
+    }
+
+    for conversation in &report.conversations {
+        println!(
+            "  {:<8} {}  {:<10} {:>4} msgs  {}",
+            conversation.action.as_str(),
+            conversation.last_activity.format("%Y-%m-%d"),
+            conversation.provider,
+            conversation.message_count,
+            conversation.title
+        );
+    }
+    let (deleted, archived) = (report.count(RetentionAction::Delete), report.count(RetentionAction::Archive));
+    if dry_run {
+        println!(
+            "\nWould delete {} and archive {} conversation(s), and delete {} usage record(s). Billing summaries are kept.",
+            deleted, archived, report.usage_records_deleted
+        );
+    } else {
+        println!(
+            "\nDeleted {} and archived {} conversation(s), and deleted {} usage record(s).{}",
+            deleted,
+            archived,
+            report.usage_records_deleted,
+            if report.vacuumed { " The database file was compacted." } else { "" }
+        );
+        if deleted > 0 {
+            println!("Older backups still contain purged conversations; prune them with `valechat backup prune`.");
+        }
+    }
+    Ok(())
+}
+
+async fn handle_backup_command(app_state: Arc<AppState>, action: BackupCommands) -> Result<()> {
+    let backups = app_state.get_backups();
+    if let BackupCommands::Passphrase { clear } = action {
+        if clear {
+            app_state.set_backup_passphrase(None).await?;
+            println!("Backup passphrase removed; existing encrypted backups need it to be restored");
+        } else {
+            let passphrase = read_secret("New backup passphrase: ")?;
+            if passphrase.is_empty() {
+                anyhow::bail!("The passphrase cannot be empty");
+            }
+            if read_secret("Repeat passphrase: ")? != passphrase {
+                anyhow::bail!("Passphrases do not match");
+            }
+            app_state.set_backup_passphrase(Some(&passphrase)).await?;
+            println!("Backup passphrase stored in the system keychain");
+        }
+        return Ok(());
+    }
+
+    // Encrypted backups need the passphrase to be written or read
+    if let Err(e) = app_state.unlock_backups().await {
+        tracing::warn!("Could not read the backup passphrase: {}", e);
+    }
+    backups.initialize().await?;
+
+    match action {
+        BackupCommands::Create { full } => {
+            let backup_type = if full || !backups.config().incremental { BackupType::Full } else { BackupType::Incremental };
+            let backup = backups.create_backup(backup_type).await?;
+            print_backup(&backup);
+            if !backup.verified && backups.config().verify_backups {
+                anyhow::bail!("Backup {} was written but failed verification", backup.id);
+            }
+        }
+        BackupCommands::List => {
+            let list = backups.list_backups().await?;
+            if list.is_empty() {
+                println!("No backups in {}", backups.backup_dir().display());
+            }
+            for backup in &list {
+                print_backup(backup);
+            }
+        }
+        BackupCommands::Restore { id, to, no_safety_backup, yes } => {
+            let backup = backups.find_backup(&id).await?;
+            if to.is_none() && !yes {
+                println!(
+                    "This replaces the current database with backup {} from {}.",
+                    backup.id,
+                    backup.created_at.format("%Y-%m-%d %H:%M")
+                );
+                println!("Close any running ValeChat sessions and re-run with --yes to continue.");
+                return Ok(());
+            }
+            let result = backups.restore_backup(RecoveryOptions {
+                backup_id: id,
+                target_path: to.clone().map(Into::into),
+                verify_before_restore: true,
+                create_backup_before_restore: !no_safety_backup,
+                tables_to_restore: None,
+            }).await?;
+            match to {
+                Some(path) => println!("Backup {} written to {}", backup.id, path),
+                None => println!("Restored backup {} ({} records)", backup.id, result.records_restored),
+            }
+            if let Some(pre_restore) = result.pre_restore_backup_id {
+                println!("The previous database was saved as backup {}", pre_restore);
+            }
+            for warning in result.warnings {
+                println!("Warning: {}", warning);
+            }
+        }
+        BackupCommands::Verify { id } => {
+            let ids = match id {
+                Some(id) => vec![id],
+                None => backups.list_backups().await?.into_iter().map(|b| b.id).collect(),
+            };
+            let mut failed = 0;
+            for id in ids {
+                let verified = backups.verify(&id).await?;
+                println!("  {:<30} {}", id, if verified { "ok" } else { "FAILED" });
+                failed += usize::from(!verified);
+            }
+            if failed > 0 {
+                anyhow::bail!("{} backup(s) failed verification", failed);
+            }
+        }
+        BackupCommands::Prune { keep, dry_run } => {
+            let pruned = backups.prune_backups(keep, dry_run).await?;
+            let verb = if dry_run { "Would delete" } else { "Deleted" };
+            for backup in &pruned {
+                println!("{} {}", verb, backup.id);
+            }
+            println!(
+                "{} {} backup(s) older than {} days",
+                verb,
+                pruned.len(),
+                backups.config().retention_days
+            );
+        }
+        BackupCommands::Passphrase { .. } => unreachable!("handled above"),
+    }
+
+    Ok(())
+}
+
+fn print_backup(backup: &BackupInfo) {
+    let mut flags = vec![backup.backup_type.as_str()];
+    if backup.encrypted {
+        flags.push("encrypted");
+    }
+    if !backup.verified {
+        flags.push("unverified");
+    }
+    println!(
+        "  {:<30} {}  {:>10}  {}{}",
+        backup.id,
+        backup.created_at.format("%Y-%m-%d %H:%M"),
+        format!("{} KB", backup.size_bytes.div_ceil(1024)),
+        flags.join(", "),
+        backup.parent_id.as_deref().map(|p| format!(" (on {})", p)).unwrap_or_default(),
+    );
+}
+
+/// Read a line from the terminal without echoing it
+fn read_secret(prompt: &str) -> Result<String> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use std::io::{IsTerminal, Write};
+
+    eprint!("{}", prompt);
+    io::stderr().flush()?;
+    if !io::stdin().is_terminal() {
+        let mut line = String::new();
+        io::stdin().read_line(&mut line)?;
+        return Ok(line.trim_end_matches(['\r', '\n']).to_string());
+    }
+
+    enable_raw_mode()?;
+    let mut secret = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(anyhow::anyhow!("Cancelled"));
+                }
+                KeyCode::Char(c) => secret.push(c),
+                KeyCode::Backspace => {
+                    secret.pop();
+                }
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+    disable_raw_mode()?;
+    eprintln!();
+    result.map(|_| secret)
+}
+
 async fn handle_export_command(
     app_state: Arc<AppState>,
     format: &str,
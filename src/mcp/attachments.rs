@@ -0,0 +1,190 @@
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::chat::types::{AttachmentData, ChatMessage, MessageAttachment, MessageContent, MessageRole};
+use crate::error::{Error, Result};
+use crate::mcp::types::{Content, MessageRole as PromptRole, PromptMessage};
+
+/// Limits applied when inserting MCP content into a conversation
+#[derive(Debug, Clone)]
+pub struct AttachmentLimits {
+    pub max_text_bytes: usize,
+    pub max_image_bytes: usize,
+    pub max_items: usize,
+}
+
+impl Default for AttachmentLimits {
+    fn default() -> Self {
+        Self {
+            max_text_bytes: 64 * 1024,       // 64KB per text item
+            max_image_bytes: 5 * 1024 * 1024, // 5MB decoded
+            max_items: 32,
+        }
+    }
+}
+
+/// Convert MCP content items into conversation messages from the user
+///
+/// `source` identifies where the content came from (a resource URI or `server/prompt`)
+/// and is recorded in each message's metadata.
+pub fn content_to_messages(
+    session_id: &str,
+    contents: &[Content],
+    source: &str,
+    limits: &AttachmentLimits,
+) -> Result<Vec<ChatMessage>> {
+    let items: Vec<_> = contents.iter().map(|content| (MessageRole::User, content)).collect();
+    to_messages(session_id, &items, source, limits)
+}
+
+/// Convert the messages of a rendered MCP prompt into conversation messages, keeping their roles
+pub fn prompt_to_messages(
+    session_id: &str,
+    messages: &[PromptMessage],
+    source: &str,
+    limits: &AttachmentLimits,
+) -> Result<Vec<ChatMessage>> {
+    let items: Vec<_> = messages.iter()
+        .map(|message| {
+            let role = match message.role {
+                PromptRole::User => MessageRole::User,
+                PromptRole::Assistant => MessageRole::Assistant,
+                PromptRole::System => MessageRole::System,
+            };
+            (role, &message.content)
+        })
+        .collect();
+    to_messages(session_id, &items, source, limits)
+}
+
+fn to_messages(
+    session_id: &str,
+    items: &[(MessageRole, &Content)],
+    source: &str,
+    limits: &AttachmentLimits,
+) -> Result<Vec<ChatMessage>> {
+    if items.is_empty() {
+        return Err(Error::validation(format!("{} returned no content", source)));
+    }
+
+    if items.len() > limits.max_items {
+        return Err(Error::validation(format!(
+            "{} returned {} content items, more than the limit of {}",
+            source, items.len(), limits.max_items
+        )));
+    }
+
+    let messages = items.iter()
+        .map(|(role, content)| {
+            let message_content = match content {
+                Content::Text { text } => MessageContent::text(truncate_text(text, limits.max_text_bytes)),
+                Content::Image { data, mime_type } => image_content(data, mime_type, source, limits),
+                Content::Resource { resource } => {
+                    MessageContent::text(format!("[Binary resource {} was not inlined]", resource.uri))
+                }
+            };
+
+            ChatMessage::new(session_id.to_string(), role.clone(), message_content)
+                .with_metadata("mcp_source".to_string(), serde_json::Value::String(source.to_string()))
+        })
+        .collect::<Vec<_>>();
+
+    debug!("Converted {} content items from {} into messages", messages.len(), source);
+    Ok(messages)
+}
+
+fn truncate_text(text: &str, max_bytes: usize) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+
+    let mut cut = max_bytes;
+    while !text.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    format!("{}\n[... truncated {} bytes]", &text[..cut], text.len() - cut)
+}
+
+fn image_content(data: &str, mime_type: &str, source: &str, limits: &AttachmentLimits) -> MessageContent {
+    // Base64 encodes 3 bytes in 4 characters
+    let size = data.len() / 4 * 3;
+
+    if size > limits.max_image_bytes {
+        return MessageContent::text(format!(
+            "[Image from {} ({}, {} KB) exceeds the {} KB attachment limit and was not attached]",
+            source, mime_type, size / 1024, limits.max_image_bytes / 1024
+        ));
+    }
+
+    let filename = source.rsplit('/').next().unwrap_or(source).to_string();
+    MessageContent::MultiModal {
+        text: Some(format!("[Image attached from {} ({}, {} KB)]", source, mime_type, size / 1024)),
+        attachments: vec![MessageAttachment {
+            id: Uuid::new_v4().to_string(),
+            filename,
+            content_type: mime_type.to_string(),
+            size: size as u64,
+            data: AttachmentData::Base64(data.to_string()),
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::types::ResourceReference;
+
+    #[test]
+    fn test_text_is_truncated_at_limit() {
+        let limits = AttachmentLimits { max_text_bytes: 8, ..AttachmentLimits::default() };
+        let contents = vec![Content::Text { text: "héllo world".to_string() }];
+
+        let messages = content_to_messages("conv", &contents, "file:///a.txt", &limits).unwrap();
+        let text = messages[0].content.get_text().unwrap();
+        assert!(text.starts_with("héllo w"));
+        assert!(text.contains("truncated"));
+        assert_eq!(messages[0].metadata["mcp_source"], "file:///a.txt");
+    }
+
+    #[test]
+    fn test_images_and_blobs() {
+        let limits = AttachmentLimits { max_image_bytes: 6, ..AttachmentLimits::default() };
+        let contents = vec![
+            Content::Image { data: "aGVsbG8=".to_string(), mime_type: "image/png".to_string() },
+            Content::Image { data: "aGVsbG8gd29ybGQh".to_string(), mime_type: "image/png".to_string() },
+            Content::Resource { resource: ResourceReference { uri: "file:///data.bin".to_string() } },
+        ];
+
+        let messages = content_to_messages("conv", &contents, "srv/prompt", &limits).unwrap();
+        assert!(matches!(&messages[0].content, MessageContent::MultiModal { attachments, .. } if attachments.len() == 1));
+        assert!(messages[1].content.get_text().unwrap().contains("exceeds"));
+        assert!(messages[2].content.get_text().unwrap().contains("file:///data.bin"));
+    }
+
+    #[test]
+    fn test_prompt_roles_are_kept() {
+        let prompt = vec![
+            PromptMessage { role: PromptRole::User, content: Content::Text { text: "Review this".to_string() } },
+            PromptMessage { role: PromptRole::Assistant, content: Content::Text { text: "Which file?".to_string() } },
+            PromptMessage { role: PromptRole::User, content: Content::Text { text: "main.rs".to_string() } },
+        ];
+
+        let messages = prompt_to_messages("conv", &prompt, "srv/review", &AttachmentLimits::default()).unwrap();
+        let roles: Vec<_> = messages.iter().map(|message| message.role.clone()).collect();
+        assert_eq!(roles, vec![MessageRole::User, MessageRole::Assistant, MessageRole::User]);
+        assert_eq!(messages[1].content.get_text(), Some("Which file?"));
+        assert_eq!(messages[1].metadata["mcp_source"], "srv/review");
+    }
+
+    #[test]
+    fn test_empty_and_oversized_content_rejected() {
+        let limits = AttachmentLimits { max_items: 1, ..AttachmentLimits::default() };
+        assert!(content_to_messages("conv", &[], "file:///a.txt", &limits).is_err());
+
+        let contents = vec![
+            Content::Text { text: "a".to_string() },
+            Content::Text { text: "b".to_string() },
+        ];
+        assert!(content_to_messages("conv", &contents, "file:///a.txt", &limits).is_err());
+    }
+}
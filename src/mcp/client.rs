@@ -14,8 +14,8 @@ struct PendingRequest {
     timestamp: Instant,
 }
 use crate::mcp::server_manager::{MCPServerManager, ServerState};
-use crate::mcp::types::{Tool, ToolResult, Resource, Prompt, Content, ToolCall};
-use crate::mcp::validation::{InputValidator, ValidationConfig};
+use crate::mcp::types::{Tool, ToolResult, Resource, ResourceReference, Prompt, PromptMessage, Content, ToolCall};
+use crate::mcp::validation::{format_violations, InputValidator, ValidationConfig};
 
 /// MCP client for communicating with servers and executing tools
 pub struct MCPClient {
@@ -24,6 +24,7 @@ pub struct MCPClient {
     request_timeout: Duration,
     pending_requests: Arc<RwLock<HashMap<String, PendingRequest>>>,
     validator: Arc<Mutex<InputValidator>>,
+    tool_schemas: Arc<RwLock<HashMap<String, Option<serde_json::Value>>>>, // "server/tool" -> input schema
 }
 
 
@@ -56,6 +57,7 @@ impl MCPClient {
             request_timeout: config.request_timeout,
             pending_requests: Arc::new(RwLock::new(HashMap::new())),
             validator: Arc::new(Mutex::new(validator)),
+            tool_schemas: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
@@ -102,6 +104,13 @@ impl MCPClient {
                     .map_err(|e| Error::mcp(format!("Failed to parse tools from server {}: {}", server_name, e)))?;
                 
                 debug!("Successfully retrieved {} tools from server: {}", tools.len(), server_name);
+                
+                let mut schemas = self.tool_schemas.write().await;
+                schemas.retain(|key, _| !key.starts_with(&format!("{}/", server_name)));
+                for tool in &tools {
+                    schemas.insert(format!("{}/{}", server_name, tool.name), tool.input_schema.clone());
+                }
+                
                 Ok(tools)
             } else {
                 Ok(Vec::new())
@@ -129,6 +138,10 @@ impl MCPClient {
             validator.validate_tool_input(tool_name, &arguments, client_id, None)?;
         }
         
+        if let Some(rejection) = self.check_tool_arguments(server_name, tool_name, &arguments).await {
+            return Ok(rejection);
+        }
+        
         let tool_call = ToolCall {
             name: tool_name.to_string(),
             arguments: Some(arguments),
@@ -154,6 +167,47 @@ impl MCPClient {
         }
     }
 
+    /// Check arguments against the tool's declared schema
+    ///
+    /// Arguments that don't match never reach the server; the violations are returned as a
+    /// tool error so the model can correct the call.
+    pub async fn check_tool_arguments(&self, server_name: &str, tool_name: &str, arguments: &serde_json::Value) -> Option<ToolResult> {
+        let schema = self.get_tool_schema(server_name, tool_name).await?;
+        let violations = self.validator.lock().await.validate_schema(arguments, &schema);
+        match violations {
+            Ok(violations) if !violations.is_empty() => {
+                warn!("Rejected call to tool '{}' on server '{}': {} schema violation(s)",
+                      tool_name, server_name, violations.len());
+                Some(ToolResult {
+                    content: Some(vec![Content::Text {
+                        text: format!("Invalid arguments for tool '{}': {}", tool_name, format_violations(&violations)),
+                    }]),
+                    is_error: Some(true),
+                })
+            }
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Skipping schema validation for tool '{}' on server '{}': {}", tool_name, server_name, e);
+                None
+            }
+        }
+    }
+
+    /// Get a tool's input schema, listing the server's tools if it hasn't been seen yet
+    async fn get_tool_schema(&self, server_name: &str, tool_name: &str) -> Option<serde_json::Value> {
+        let key = format!("{}/{}", server_name, tool_name);
+        if let Some(schema) = self.tool_schemas.read().await.get(&key) {
+            return schema.clone();
+        }
+
+        if let Err(e) = self.list_tools_from_server(server_name).await {
+            warn!("Failed to list tools from server {} for schema validation: {}", server_name, e);
+            return None;
+        }
+
+        self.tool_schemas.read().await.get(&key).cloned().flatten()
+    }
+
     /// List all available resources from all servers
     pub async fn list_resources(&self) -> Result<HashMap<String, Vec<Resource>>> {
         debug!("Listing resources from all servers");
@@ -229,8 +283,7 @@ impl MCPClient {
         
         if let Some(result) = response.result {
             if let Some(contents_array) = result.get("contents") {
-                let contents: Vec<Content> = serde_json::from_value(contents_array.clone())
-                    .map_err(|e| Error::mcp(format!("Failed to parse resource contents: {}", e)))?;
+                let contents = parse_resource_contents(contents_array)?;
                 
                 debug!("Successfully read resource '{}' from server: {}", resource_uri, server_name);
                 Ok(contents)
@@ -244,6 +297,46 @@ impl MCPClient {
         }
     }
 
+    /// Check whether a server advertised support for resource subscriptions
+    pub async fn supports_resource_subscriptions(&self, server_name: &str) -> bool {
+        let server_manager = self.server_manager.lock().await;
+        server_manager.get_server_capabilities(server_name).await
+            .and_then(|capabilities| capabilities.resources)
+            .map(|resources| resources.subscribe)
+            .unwrap_or(false)
+    }
+
+    /// Subscribe to update notifications for a resource
+    pub async fn subscribe_resource(&self, server_name: &str, resource_uri: &str) -> Result<()> {
+        self.send_resource_subscription(server_name, resource_uri, "resources/subscribe").await
+    }
+
+    /// Cancel a resource subscription
+    pub async fn unsubscribe_resource(&self, server_name: &str, resource_uri: &str) -> Result<()> {
+        self.send_resource_subscription(server_name, resource_uri, "resources/unsubscribe").await
+    }
+
+    async fn send_resource_subscription(&self, server_name: &str, resource_uri: &str, method: &str) -> Result<()> {
+        debug!("Sending {} for '{}' to server: {}", method, resource_uri, server_name);
+
+        if !self.supports_resource_subscriptions(server_name).await {
+            return Err(Error::mcp(format!("Server {} does not support resource subscriptions", server_name)));
+        }
+
+        let request = self.protocol_handler.create_request(
+            method.to_string(),
+            Some(serde_json::json!({ "uri": resource_uri })),
+        );
+
+        let response = self.send_request_to_server(server_name, request).await?;
+
+        if let Some(error) = response.error {
+            Err(Error::mcp(format!("{} failed for {}: {}", method, resource_uri, error.message)))
+        } else {
+            Ok(())
+        }
+    }
+
     /// List all available prompts from all servers
     pub async fn list_prompts(&self) -> Result<HashMap<String, Vec<Prompt>>> {
         debug!("Listing prompts from all servers");
@@ -304,7 +397,7 @@ impl MCPClient {
         server_name: &str,
         prompt_name: &str,
         arguments: Option<serde_json::Value>,
-    ) -> Result<Vec<Content>> {
+    ) -> Result<Vec<PromptMessage>> {
         debug!("Getting prompt '{}' from server: {}", prompt_name, server_name);
         
         let mut params = serde_json::json!({
@@ -324,7 +417,7 @@ impl MCPClient {
         
         if let Some(result) = response.result {
             if let Some(messages_array) = result.get("messages") {
-                let messages: Vec<Content> = serde_json::from_value(messages_array.clone())
+                let messages: Vec<PromptMessage> = serde_json::from_value(messages_array.clone())
                     .map_err(|e| Error::mcp(format!("Failed to parse prompt messages: {}", e)))?;
                 
                 debug!("Successfully retrieved prompt '{}' from server: {}", prompt_name, server_name);
@@ -470,6 +563,36 @@ impl MCPClient {
     }
 }
 
+/// Convert `resources/read` contents (text or blob entries) into content items
+fn parse_resource_contents(contents: &serde_json::Value) -> Result<Vec<Content>> {
+    let entries = contents.as_array()
+        .ok_or_else(|| Error::mcp("Resource contents must be an array".to_string()))?;
+
+    entries.iter().map(|entry| {
+        if entry.get("type").is_some() {
+            return serde_json::from_value(entry.clone())
+                .map_err(|e| Error::mcp(format!("Failed to parse resource contents: {}", e)));
+        }
+
+        let uri = entry.get("uri").and_then(|v| v.as_str()).unwrap_or_default();
+        let mime_type = entry.get("mimeType").and_then(|v| v.as_str());
+
+        if let Some(text) = entry.get("text").and_then(|v| v.as_str()) {
+            Ok(Content::Text { text: text.to_string() })
+        } else if let Some(blob) = entry.get("blob").and_then(|v| v.as_str()) {
+            match mime_type {
+                Some(mime) if mime.starts_with("image/") => Ok(Content::Image {
+                    data: blob.to_string(),
+                    mime_type: mime.to_string(),
+                }),
+                _ => Ok(Content::Resource { resource: ResourceReference { uri: uri.to_string() } }),
+            }
+        } else {
+            Err(Error::mcp(format!("Resource entry for '{}' has neither text nor blob", uri)))
+        }
+    }).collect()
+}
+
 /// Statistics about the MCP client
 #[derive(Debug, Clone)]
 pub struct MCPClientStatistics {
@@ -525,6 +648,50 @@ mod tests {
         assert_eq!(config.max_concurrent_requests, 100);
     }
 
+    #[test]
+    fn test_parse_resource_contents() {
+        let contents = serde_json::json!([
+            { "uri": "file:///notes.md", "mimeType": "text/markdown", "text": "# Notes" },
+            { "uri": "file:///logo.png", "mimeType": "image/png", "blob": "aGVsbG8=" },
+        ]);
+
+        let parsed = parse_resource_contents(&contents).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert!(matches!(&parsed[0], Content::Text { text } if text == "# Notes"));
+        assert!(matches!(&parsed[1], Content::Image { mime_type, .. } if mime_type == "image/png"));
+    }
+
+    #[tokio::test]
+    async fn test_subscription_requires_capability() {
+        let client = MCPClient::new(create_test_server_manager(), MCPClientConfig::default()).unwrap();
+
+        assert!(!client.supports_resource_subscriptions("missing").await);
+        assert!(client.subscribe_resource("missing", "file:///a.txt").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_schema_violations_returned_as_tool_error() {
+        let client = MCPClient::new(create_test_server_manager(), MCPClientConfig::default()).unwrap();
+        client.tool_schemas.write().await.insert(
+            "fs/read_file".to_string(),
+            Some(serde_json::json!({
+                "type": "object",
+                "properties": { "path": { "type": "string" } },
+                "required": ["path"]
+            })),
+        );
+
+        // The server doesn't exist, so only a rejected call can succeed here
+        let result = client.call_tool("fs", "read_file", serde_json::json!({"path": 42}), None).await.unwrap();
+        assert_eq!(result.is_error, Some(true));
+        match &result.content.unwrap()[0] {
+            Content::Text { text } => assert!(text.contains("/path")),
+            other => panic!("Unexpected content: {:?}", other),
+        }
+
+        assert!(client.call_tool("fs", "read_file", serde_json::json!({"path": "/tmp/a"}), None).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_cleanup_old_requests() {
         let server_manager = create_test_server_manager();
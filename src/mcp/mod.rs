@@ -1,5 +1,7 @@
+pub mod attachments;
 pub mod client;
 pub mod error_recovery;
+pub mod permissions;
 pub mod protocol;
 pub mod prompts;
 pub mod resources;
@@ -24,5 +26,7 @@ pub use types::{
     MCPMessage, MCPRequest, MCPResponse, MCPNotification, ProtocolVersion,
     Content, ToolCall, InitializeParams, InitializeResult, Implementation
 };
-pub use validation::{ValidationConfig, ValidationError, InputValidator, InputSanitizer, SanitizerConfig};
-pub use client::{MCPClient, MCPClientConfig, MCPClientStatistics};
\ No newline at end of file
+pub use validation::{ValidationConfig, ValidationError, InputValidator, InputSanitizer, SanitizerConfig, SchemaViolation};
+pub use client::{MCPClient, MCPClientConfig, MCPClientStatistics};
+pub use attachments::{AttachmentLimits, content_to_messages, prompt_to_messages};
+pub use permissions::{PermissionDecision, ToolDecision, ToolPermissionPolicy, ToolApprovalRequest, ApprovalResponse};
@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::path::{Component, Path};
+use tokio::sync::oneshot;
+use tracing::debug;
+
+use crate::app::config::{ArgumentConstraint, PermissionAction, ToolPermissionConfig, ToolPermissionRule};
+
+/// Result of evaluating the permission policy for a tool call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermissionDecision {
+    Allow,
+    Deny(String),
+    Ask,
+}
+
+/// Final decision recorded for a tool invocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ToolDecision {
+    #[serde(rename = "policy_allow")]
+    AllowedByPolicy,
+    #[serde(rename = "allow_once")]
+    AllowedOnce,
+    #[serde(rename = "allow_always")]
+    AllowedAlways,
+    #[serde(rename = "policy_deny")]
+    DeniedByPolicy,
+    #[serde(rename = "user_deny")]
+    DeniedByUser,
+}
+
+impl ToolDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToolDecision::AllowedByPolicy => "policy_allow",
+            ToolDecision::AllowedOnce => "allow_once",
+            ToolDecision::AllowedAlways => "allow_always",
+            ToolDecision::DeniedByPolicy => "policy_deny",
+            ToolDecision::DeniedByUser => "user_deny",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "policy_allow" => Some(ToolDecision::AllowedByPolicy),
+            "allow_once" => Some(ToolDecision::AllowedOnce),
+            "allow_always" => Some(ToolDecision::AllowedAlways),
+            "policy_deny" => Some(ToolDecision::DeniedByPolicy),
+            "user_deny" => Some(ToolDecision::DeniedByUser),
+            _ => None,
+        }
+    }
+
+    pub fn is_allowed(&self) -> bool {
+        matches!(
+            self,
+            ToolDecision::AllowedByPolicy | ToolDecision::AllowedOnce | ToolDecision::AllowedAlways
+        )
+    }
+}
+
+impl fmt::Display for ToolDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// User answer to an approval prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalResponse {
+    AllowOnce,
+    AllowAlways,
+    Deny,
+}
+
+/// Request sent to the UI when a tool call needs interactive approval
+#[derive(Debug)]
+pub struct ToolApprovalRequest {
+    pub server_name: String,
+    pub tool_name: String,
+    pub arguments: Value,
+    pub responder: oneshot::Sender<ApprovalResponse>,
+}
+
+/// Evaluates tool permission rules from the configuration
+pub struct ToolPermissionPolicy<'a> {
+    config: &'a ToolPermissionConfig,
+}
+
+impl<'a> ToolPermissionPolicy<'a> {
+    pub fn new(config: &'a ToolPermissionConfig) -> Self {
+        Self { config }
+    }
+
+    /// Decide whether a tool call may run
+    ///
+    /// `allowed_servers` comes from the session settings; an empty list allows every server.
+    pub fn evaluate(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        arguments: &Value,
+        allowed_servers: &[String],
+    ) -> PermissionDecision {
+        if !allowed_servers.is_empty() && !allowed_servers.iter().any(|s| s == server_name) {
+            return PermissionDecision::Deny(format!(
+                "Server '{}' is not allowed in this conversation",
+                server_name
+            ));
+        }
+
+        let matched = self.config.rules.iter()
+            .position(|rule| rule_matches(rule, server_name, tool_name, arguments));
+
+        let action = match matched {
+            Some(index) => {
+                debug!("Tool {}/{} matched permission rule #{}", server_name, tool_name, index);
+                self.config.rules[index].action
+            }
+            None => self.config.default_action,
+        };
+
+        match action {
+            PermissionAction::Allow => PermissionDecision::Allow,
+            PermissionAction::Ask => PermissionDecision::Ask,
+            PermissionAction::Deny => PermissionDecision::Deny(match matched {
+                Some(index) => format!("Denied by tool permission rule #{}", index + 1),
+                None => "Denied by the default tool permission policy".to_string(),
+            }),
+        }
+    }
+}
+
+/// Rule that always allows one specific tool, used for "allow always" answers
+pub fn allow_always_rule(server_name: &str, tool_name: &str) -> ToolPermissionRule {
+    ToolPermissionRule {
+        server: server_name.to_string(),
+        tool: tool_name.to_string(),
+        action: PermissionAction::Allow,
+        arguments: Vec::new(),
+    }
+}
+
+fn rule_matches(rule: &ToolPermissionRule, server_name: &str, tool_name: &str, arguments: &Value) -> bool {
+    glob_match(&rule.server, server_name)
+        && glob_match(&rule.tool, tool_name)
+        && rule.arguments.iter().all(|constraint| constraint_matches(constraint, arguments))
+}
+
+fn constraint_matches(constraint: &ArgumentConstraint, arguments: &Value) -> bool {
+    let value = match arguments.get(&constraint.argument) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        Some(Value::Bool(b)) => b.to_string(),
+        _ => return false,
+    };
+
+    if let Some(prefix) = &constraint.path_prefix {
+        if !path_within(&value, prefix) {
+            return false;
+        }
+    }
+
+    if let Some(pattern) = &constraint.pattern {
+        if !glob_match(pattern, &value) {
+            return false;
+        }
+    }
+
+    if let Some(allowed) = &constraint.one_of {
+        if !allowed.contains(&value) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Check that `path` lies inside `prefix` without escaping through `..`
+fn path_within(path: &str, prefix: &str) -> bool {
+    let path = Path::new(path);
+    if path.components().any(|c| matches!(c, Component::ParentDir)) {
+        return false;
+    }
+    path.starts_with(Path::new(prefix))
+}
+
+/// Match `text` against a glob pattern supporting `*` and `?`
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(server: &str, tool: &str, action: PermissionAction) -> ToolPermissionRule {
+        ToolPermissionRule {
+            server: server.to_string(),
+            tool: tool.to_string(),
+            action,
+            arguments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("read_*", "read_file"));
+        assert!(glob_match("file?", "files"));
+        assert!(glob_match("*_file", "write_file"));
+        assert!(!glob_match("read_*", "write_file"));
+        assert!(!glob_match("file?", "file"));
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let config = ToolPermissionConfig {
+            default_action: PermissionAction::Ask,
+            rules: vec![
+                rule("fs", "delete_*", PermissionAction::Deny),
+                rule("fs", "*", PermissionAction::Allow),
+            ],
+        };
+        let policy = ToolPermissionPolicy::new(&config);
+
+        assert!(matches!(policy.evaluate("fs", "delete_file", &json!({}), &[]), PermissionDecision::Deny(_)));
+        assert_eq!(policy.evaluate("fs", "read_file", &json!({}), &[]), PermissionDecision::Allow);
+        assert_eq!(policy.evaluate("web", "fetch", &json!({}), &[]), PermissionDecision::Ask);
+    }
+
+    #[test]
+    fn test_allowed_servers_enforced() {
+        let config = ToolPermissionConfig {
+            default_action: PermissionAction::Allow,
+            rules: Vec::new(),
+        };
+        let policy = ToolPermissionPolicy::new(&config);
+        let allowed = vec!["fs".to_string()];
+
+        assert_eq!(policy.evaluate("fs", "read_file", &json!({}), &allowed), PermissionDecision::Allow);
+        assert!(matches!(policy.evaluate("web", "fetch", &json!({}), &allowed), PermissionDecision::Deny(_)));
+    }
+
+    #[test]
+    fn test_path_prefix_constraint() {
+        let mut allow_home = rule("fs", "*", PermissionAction::Allow);
+        allow_home.arguments.push(ArgumentConstraint {
+            argument: "path".to_string(),
+            path_prefix: Some("/home/user/project".to_string()),
+            ..ArgumentConstraint::default()
+        });
+        let config = ToolPermissionConfig {
+            default_action: PermissionAction::Deny,
+            rules: vec![allow_home],
+        };
+        let policy = ToolPermissionPolicy::new(&config);
+
+        let inside = json!({"path": "/home/user/project/src/main.rs"});
+        let escape = json!({"path": "/home/user/project/../.ssh/id_rsa"});
+        let sibling = json!({"path": "/home/user/project-other/file"});
+
+        assert_eq!(policy.evaluate("fs", "read_file", &inside, &[]), PermissionDecision::Allow);
+        assert!(matches!(policy.evaluate("fs", "read_file", &escape, &[]), PermissionDecision::Deny(_)));
+        assert!(matches!(policy.evaluate("fs", "read_file", &sibling, &[]), PermissionDecision::Deny(_)));
+        assert!(matches!(policy.evaluate("fs", "read_file", &json!({}), &[]), PermissionDecision::Deny(_)));
+    }
+
+    #[test]
+    fn test_decision_round_trip() {
+        for decision in [
+            ToolDecision::AllowedByPolicy,
+            ToolDecision::AllowedOnce,
+            ToolDecision::AllowedAlways,
+            ToolDecision::DeniedByPolicy,
+            ToolDecision::DeniedByUser,
+        ] {
+            assert_eq!(ToolDecision::parse(decision.as_str()), Some(decision));
+        }
+    }
+}
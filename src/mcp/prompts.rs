@@ -6,7 +6,7 @@ use serde::{Serialize, Deserialize};
 use regex::Regex;
 
 use crate::error::{Error, Result};
-use crate::mcp::types::{Prompt, Content};
+use crate::mcp::types::{Prompt, PromptMessage, Content};
 use crate::mcp::client::MCPClient;
 
 /// Prompt template manager for handling MCP prompts
@@ -44,7 +44,7 @@ impl Default for PromptTemplateConfig {
 #[derive(Debug, Clone)]
 struct CachedPrompt {
     prompt: Prompt,
-    template_content: Option<Vec<Content>>,
+    template_content: Option<Vec<PromptMessage>>,
     last_updated: std::time::Instant,
     access_count: u64,
     server_name: String,
@@ -77,7 +77,7 @@ pub struct PromptInfo {
     pub access_count: u64,
     pub cache_status: CacheStatus,
     pub variables: Vec<String>,
-    pub content: Option<Vec<Content>>,
+    pub content: Option<Vec<PromptMessage>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,7 +99,8 @@ pub struct TemplateContext {
 /// Template execution result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemplateResult {
-    pub content: Vec<Content>,
+    /// Rendered messages, each keeping the role the server gave it
+    pub messages: Vec<PromptMessage>,
     pub variables_used: Vec<String>,
     pub execution_time_ms: u64,
 }
@@ -224,7 +225,7 @@ impl PromptTemplateManager {
         server_name: &str, 
         prompt_name: &str, 
         force_refresh: bool
-    ) -> Result<Option<Vec<Content>>> {
+    ) -> Result<Option<Vec<PromptMessage>>> {
         debug!("Getting prompt template: {} from {} (force_refresh: {})", prompt_name, server_name, force_refresh);
         
         let cache_key = format!("{}:{}", server_name, prompt_name);
@@ -245,7 +246,7 @@ impl PromptTemplateManager {
         }
         
         // Fetch from server
-        self.fetch_prompt_content(server_name, prompt_name).await
+        self.fetch_prompt_content(server_name, prompt_name, None).await
     }
 
     /// Execute a prompt template with given variables
@@ -258,51 +259,50 @@ impl PromptTemplateManager {
         info!("Executing template: {} from {} with {} variables", 
               context.prompt_name, context.server_name, context.variables.len());
         
-        // Get template content
-        let template_content = self.get_prompt_template(
-            &context.server_name, 
-            &context.prompt_name, 
-            false
-        ).await?;
+        self.check_required_arguments(&context).await?;
+        
+        // Get template content; servers render arguments themselves, so skip the cache when any are given
+        let template_content = if context.variables.is_empty() {
+            self.get_prompt_template(&context.server_name, &context.prompt_name, false).await?
+        } else {
+            self.fetch_prompt_content(&context.server_name, &context.prompt_name, Some(&context.variables)).await?
+        };
         
         let template_content = template_content.ok_or_else(|| {
             Error::mcp(format!("Template not found: {} from {}", context.prompt_name, context.server_name))
         })?;
         
         // Process template variables
-        let mut processed_content = Vec::new();
+        let mut processed_messages = Vec::new();
         let mut variables_used = Vec::new();
         
-        for content in template_content {
-            match content {
+        for PromptMessage { role, content } in template_content {
+            let content = match content {
                 Content::Text { text } => {
                     let (processed_text, used_vars) = self.substitute_variables(&text, &context.variables).await?;
                     variables_used.extend(used_vars);
-                    processed_content.push(Content::Text { text: processed_text });
-                }
-                Content::Image { data, mime_type } => {
-                    // Images don't typically have variables, pass through
-                    processed_content.push(Content::Image { data, mime_type });
-                }
-                Content::Resource { resource } => {
-                    // Resources might have variables in URI, but for now pass through
-                    processed_content.push(Content::Resource { resource });
+                    Content::Text { text: processed_text }
                 }
-            }
+                // Images don't typically have variables, pass through
+                Content::Image { data, mime_type } => Content::Image { data, mime_type },
+                // Resources might have variables in URI, but for now pass through
+                Content::Resource { resource } => Content::Resource { resource },
+            };
+            processed_messages.push(PromptMessage { role, content });
         }
         
         let execution_time = start_time.elapsed().as_millis() as u64;
         
         // Validate template if enabled
         if self.config.enable_template_validation {
-            self.validate_template_result(&processed_content, &context).await?;
+            self.validate_template_result(processed_messages.iter().map(|message| &message.content), &context).await?;
         }
         
         info!("Template executed successfully in {}ms with {} variables", 
               execution_time, variables_used.len());
         
         Ok(TemplateResult {
-            content: processed_content,
+            messages: processed_messages,
             variables_used,
             execution_time_ms: execution_time,
         })
@@ -326,7 +326,7 @@ impl PromptTemplateManager {
             Error::mcp(format!("Invalid variable pattern: {}", e))
         })?;
         
-        for (index, content) in template_content.iter().enumerate() {
+        for (index, content) in template_content.iter().map(|message| &message.content).enumerate() {
             match content {
                 Content::Text { text } => {
                     // Check for template syntax errors
@@ -431,7 +431,8 @@ impl PromptTemplateManager {
         &self,
         server_name: &str,
         prompt_name: &str,
-    ) -> Result<Option<Vec<Content>>> {
+        arguments: Option<&HashMap<String, String>>,
+    ) -> Result<Option<Vec<PromptMessage>>> {
         debug!("Fetching prompt content: {} from {}", prompt_name, server_name);
         
         let client = {
@@ -440,11 +441,13 @@ impl PromptTemplateManager {
         };
         
         if let Some(client) = client {
-            match client.get_prompt(server_name, prompt_name, None).await {
+            let rendered_with_arguments = arguments.is_some();
+            let arguments = arguments.map(serde_json::to_value).transpose()?;
+            match client.get_prompt(server_name, prompt_name, arguments).await {
                 Ok(content) => {
                     // Check size limit
                     let content_size = content.iter()
-                        .map(|c| match c {
+                        .map(|message| match &message.content {
                             Content::Text { text } => text.len(),
                             Content::Image { data, .. } => data.len(),
                             Content::Resource { .. } => 0, // Size not counted for references
@@ -460,12 +463,12 @@ impl PromptTemplateManager {
                     }
                     
                     // Extract variables from content
-                    let variables = self.extract_variables_from_content(&content).await?;
+                    let variables = self.extract_variables_from_content(content.iter().map(|message| &message.content)).await?;
                     
-                    // Update cache
+                    // Update cache, unless the content was rendered for specific arguments
                     let cache_key = format!("{}:{}", server_name, prompt_name);
                     let mut templates = self.templates.write().await;
-                    if let Some(cached) = templates.get_mut(&cache_key) {
+                    if let Some(cached) = templates.get_mut(&cache_key).filter(|_| !rendered_with_arguments) {
                         cached.template_content = Some(content.clone());
                         cached.last_updated = std::time::Instant::now();
                         cached.access_count += 1;
@@ -484,6 +487,29 @@ impl PromptTemplateManager {
         }
     }
 
+    /// Reject execution when a required prompt argument is missing
+    async fn check_required_arguments(&self, context: &TemplateContext) -> Result<()> {
+        let cache_key = format!("{}:{}", context.server_name, context.prompt_name);
+        let templates = self.templates.read().await;
+        
+        let missing: Vec<&str> = templates.get(&cache_key)
+            .and_then(|cached| cached.prompt.arguments.as_ref())
+            .map(|arguments| arguments.iter()
+                .filter(|arg| arg.required && !context.variables.contains_key(&arg.name))
+                .map(|arg| arg.name.as_str())
+                .collect())
+            .unwrap_or_default();
+        
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::validation(format!(
+                "Prompt {} is missing required arguments: {}",
+                context.prompt_name, missing.join(", ")
+            )))
+        }
+    }
+
     /// Substitute variables in text content
     async fn substitute_variables(
         &self,
@@ -513,7 +539,7 @@ impl PromptTemplateManager {
     }
 
     /// Extract variables from content
-    async fn extract_variables_from_content(&self, content: &[Content]) -> Result<Vec<String>> {
+    async fn extract_variables_from_content<'a>(&self, content: impl IntoIterator<Item = &'a Content>) -> Result<Vec<String>> {
         let variable_regex = Regex::new(&self.config.variable_pattern).map_err(|e| {
             Error::mcp(format!("Invalid variable pattern: {}", e))
         })?;
@@ -623,9 +649,9 @@ impl PromptTemplateManager {
     }
 
     /// Validate template execution result
-    async fn validate_template_result(
+    async fn validate_template_result<'a>(
         &self,
-        content: &[Content],
+        content: impl IntoIterator<Item = &'a Content>,
         context: &TemplateContext,
     ) -> Result<()> {
         // Check for remaining unsubstituted variables
@@ -833,6 +859,35 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_missing_required_arguments_rejected() {
+        let manager = PromptTemplateManager::new(PromptTemplateConfig::default());
+        manager.templates.write().await.insert("srv:review".to_string(), CachedPrompt {
+            prompt: Prompt {
+                name: "review".to_string(),
+                description: "Review code".to_string(),
+                arguments: Some(vec![crate::mcp::types::PromptArgument {
+                    name: "language".to_string(),
+                    description: "Programming language".to_string(),
+                    required: true,
+                }]),
+            },
+            template_content: None,
+            last_updated: std::time::Instant::now(),
+            access_count: 0,
+            server_name: "srv".to_string(),
+            variables: Vec::new(),
+        });
+
+        let context = TemplateContext {
+            variables: HashMap::new(),
+            server_name: "srv".to_string(),
+            prompt_name: "review".to_string(),
+        };
+        let error = manager.execute_template(context).await.unwrap_err();
+        assert!(error.to_string().contains("language"));
+    }
+
     #[tokio::test]
     async fn test_template_context() {
         let mut variables = HashMap::new();
@@ -5,13 +5,14 @@ use tracing::{debug, info, warn, error};
 use serde::{Serialize, Deserialize};
 
 use crate::error::{Error, Result};
-use crate::mcp::types::{Resource, ResourceContents, Content};
+use crate::mcp::types::{Resource, ResourceContents, Content, MCPNotification};
 use crate::mcp::client::MCPClient;
 
 /// Resource manager for handling MCP resources
 pub struct ResourceManager {
     resources: Arc<RwLock<HashMap<String, CachedResource>>>,
     clients: Arc<RwLock<HashMap<String, Arc<MCPClient>>>>,
+    subscriptions: Arc<RwLock<HashMap<String, Vec<ResourceSubscription>>>>,
     config: ResourceConfig,
 }
 
@@ -46,6 +47,7 @@ struct CachedResource {
     access_count: u64,
     server_name: String,
     subscription_active: bool,
+    server_subscribed: bool, // resources/subscribe acknowledged by the server
 }
 
 /// Resource query parameters
@@ -89,6 +91,8 @@ pub enum CacheStatus {
 pub struct ResourceSubscription {
     pub uri: String,
     pub server_name: String,
+    /// Who registered the callback, e.g. a conversation ID; each has at most one per resource
+    pub subscriber: String,
     pub callback: Arc<dyn Fn(ResourceUpdateEvent) + Send + Sync>,
 }
 
@@ -107,6 +111,7 @@ impl ResourceManager {
         Self {
             resources: Arc::new(RwLock::new(HashMap::new())),
             clients: Arc::new(RwLock::new(HashMap::new())),
+            subscriptions: Arc::new(RwLock::new(HashMap::new())),
             config,
         }
     }
@@ -238,8 +243,16 @@ impl ResourceManager {
         }
     }
 
-    /// Subscribe to resource changes
-    pub async fn subscribe_to_resource(&self, uri: &str, _callback: Arc<dyn Fn(ResourceUpdateEvent) + Send + Sync>) -> Result<()> {
+    /// Subscribe to resource changes, replacing an earlier callback of `subscriber`
+    ///
+    /// The `resources/subscribe` request is only sent when the owning server advertises
+    /// subscription support; otherwise the callback fires on list changes and explicit refreshes.
+    pub async fn subscribe_to_resource(
+        &self,
+        uri: &str,
+        subscriber: &str,
+        callback: Arc<dyn Fn(ResourceUpdateEvent) + Send + Sync>,
+    ) -> Result<()> {
         if !self.config.enable_subscriptions {
             return Err(Error::mcp("Resource subscriptions are disabled".to_string()));
         }
@@ -247,50 +260,146 @@ impl ResourceManager {
         info!("Subscribing to resource updates: {}", uri);
         
         // Find the server that has this resource
-        let server_name = {
+        let (server_name, already_subscribed) = {
             let resources = self.resources.read().await;
-            resources.get(uri).map(|c| c.server_name.clone())
+            resources.get(uri)
+                .map(|c| (c.server_name.clone(), c.server_subscribed))
+                .ok_or_else(|| Error::mcp(format!("Resource not found: {}", uri)))?
         };
         
-        if let Some(server_name) = server_name {
-            // Subscribe through the MCP client
+        let client = {
             let clients = self.clients.read().await;
-            if let Some(_client) = clients.get(&server_name) {
-                // Create subscription request through the MCP client
-                // Note: Resource subscriptions are optional in MCP protocol
-                // Many servers may not support this feature
+            clients.get(&server_name).cloned()
+                .ok_or_else(|| Error::mcp(format!("Server not found for resource: {}", uri)))?
+        };
+        
+        let server_subscribed = if already_subscribed {
+            true
+        } else if client.supports_resource_subscriptions(&server_name).await {
+            client.subscribe_resource(&server_name, uri).await?;
+            info!("Subscribed to resource {} on server {}", uri, server_name);
+            true
+        } else {
+            debug!("Server {} does not support resource subscriptions, tracking {} locally", server_name, uri);
+            false
+        };
+        
+        {
+            let mut subscriptions = self.subscriptions.write().await;
+            let subscribers = subscriptions.entry(uri.to_string()).or_default();
+            subscribers.retain(|sub| sub.subscriber != subscriber);
+            subscribers.push(ResourceSubscription {
+                uri: uri.to_string(),
+                server_name: server_name.clone(),
+                subscriber: subscriber.to_string(),
+                callback,
+            });
+        }
+        
+        self.mark_resource_subscribed(uri, server_subscribed).await;
+        Ok(())
+    }
+
+    /// Remove the callback of `subscriber`, and the server subscription once no callbacks remain
+    pub async fn unsubscribe_from_resource(&self, uri: &str, subscriber: &str) -> Result<()> {
+        {
+            let mut subscriptions = self.subscriptions.write().await;
+            if let Some(subscribers) = subscriptions.get_mut(uri) {
+                subscribers.retain(|sub| sub.subscriber != subscriber);
+                if !subscribers.is_empty() {
+                    debug!("Resource {} still has {} subscriber(s)", uri, subscribers.len());
+                    return Ok(());
+                }
+            }
+            subscriptions.remove(uri);
+        }
+
+        info!("Unsubscribing from resource: {}", uri);
+        
+        let server_subscription = {
+            let mut resources = self.resources.write().await;
+            resources.get_mut(uri).and_then(|cached| {
+                cached.subscription_active = false;
+                let was_subscribed = std::mem::replace(&mut cached.server_subscribed, false);
+                was_subscribed.then(|| cached.server_name.clone())
+            })
+        };
+        
+        if let Some(server_name) = server_subscription {
+            let client = self.clients.read().await.get(&server_name).cloned();
+            if let Some(client) = client {
+                client.unsubscribe_resource(&server_name, uri).await?;
+            }
+        }
+        
+        Ok(())
+    }
+
+    /// Check whether a resource has active subscribers
+    pub async fn is_subscribed(&self, uri: &str) -> bool {
+        let subscriptions = self.subscriptions.read().await;
+        subscriptions.get(uri).map(|subs| !subs.is_empty()).unwrap_or(false)
+    }
+
+    /// Handle a notification sent by one of the registered servers
+    pub async fn handle_notification(&self, server_name: &str, notification: MCPNotification) -> Result<()> {
+        match notification {
+            MCPNotification::ResourceUpdated { uri } => {
+                debug!("Resource {} updated on server {}", uri, server_name);
                 
-                // For now, just mark as subscribed locally
-                // In a full implementation with servers that support subscriptions,
-                // we would send a resources/subscribe request here
-                drop(clients);
-                self.mark_resource_subscribed(uri).await;
-                info!("Marked resource {} as subscribed (server: {})", uri, server_name);
+                if !self.is_subscribed(&uri).await {
+                    return Ok(());
+                }
                 
-                // Note: Real subscription would involve:
-                // 1. Sending resources/subscribe request
-                // 2. Handling subscription confirmation
-                // 3. Setting up notification handlers for resource changes
+                let event = match self.fetch_resource_contents(&uri, server_name).await {
+                    Ok(_) => {
+                        let resources = self.resources.read().await;
+                        match resources.get(&uri) {
+                            Some(cached) => ResourceUpdateEvent::Updated(cached.resource.clone()),
+                            None => ResourceUpdateEvent::Deleted(uri.clone()),
+                        }
+                    }
+                    Err(e) => ResourceUpdateEvent::Error(format!("Failed to refresh {}: {}", uri, e)),
+                };
+                self.notify_subscribers(&uri, event).await;
+                Ok(())
+            }
+            MCPNotification::ResourcesListChanged => {
+                debug!("Resource list changed on server {}", server_name);
+                self.refresh_server_resources(server_name).await?;
                 
+                // Report subscribed resources that disappeared from the server
+                let removed: Vec<String> = {
+                    let subscriptions = self.subscriptions.read().await;
+                    let resources = self.resources.read().await;
+                    subscriptions.iter()
+                        .filter(|(uri, subs)| {
+                            subs.iter().any(|sub| sub.server_name == server_name) && !resources.contains_key(*uri)
+                        })
+                        .map(|(uri, _)| uri.clone())
+                        .collect()
+                };
+                for uri in removed {
+                    self.notify_subscribers(&uri, ResourceUpdateEvent::Deleted(uri.clone())).await;
+                }
                 Ok(())
-            } else {
-                Err(Error::mcp(format!("Server not found for resource: {}", uri)))
             }
-        } else {
-            Err(Error::mcp(format!("Resource not found: {}", uri)))
+            _ => Ok(()),
         }
     }
 
-    /// Unsubscribe from resource changes
-    pub async fn unsubscribe_from_resource(&self, uri: &str) -> Result<()> {
-        info!("Unsubscribing from resource: {}", uri);
+    /// Invoke all callbacks registered for a resource
+    async fn notify_subscribers(&self, uri: &str, event: ResourceUpdateEvent) {
+        let callbacks: Vec<Arc<dyn Fn(ResourceUpdateEvent) + Send + Sync>> = {
+            let subscriptions = self.subscriptions.read().await;
+            subscriptions.get(uri)
+                .map(|subs| subs.iter().map(|sub| sub.callback.clone()).collect())
+                .unwrap_or_default()
+        };
         
-        let mut resources = self.resources.write().await;
-        if let Some(cached) = resources.get_mut(uri) {
-            cached.subscription_active = false;
+        for callback in callbacks {
+            callback(event.clone());
         }
-        
-        Ok(())
     }
 
     /// Refresh resources from all servers
@@ -327,14 +436,20 @@ impl ResourceManager {
                     let resource_count = resources.len();
                     let mut cache = self.resources.write().await;
                     
+                    // Drop entries the server no longer lists
+                    let listed: std::collections::HashSet<&str> = resources.iter().map(|r| r.uri.as_str()).collect();
+                    cache.retain(|uri, cached| cached.server_name != server_name || listed.contains(uri.as_str()));
+                    
                     for resource in resources {
+                        let previous = cache.get(&resource.uri);
                         let cached_resource = CachedResource {
                             resource: resource.clone(),
-                            contents: None, // Contents loaded on demand
+                            contents: previous.and_then(|c| c.contents.clone()),
                             last_updated: std::time::Instant::now(),
-                            access_count: cache.get(&resource.uri).map(|c| c.access_count).unwrap_or(0),
+                            access_count: previous.map(|c| c.access_count).unwrap_or(0),
                             server_name: server_name.to_string(),
-                            subscription_active: false,
+                            subscription_active: previous.map(|c| c.subscription_active).unwrap_or(false),
+                            server_subscribed: previous.map(|c| c.server_subscribed).unwrap_or(false),
                         };
                         
                         cache.insert(resource.uri.clone(), cached_resource);
@@ -383,17 +498,15 @@ impl ResourceManager {
                         )));
                     }
                     
-                    // Create ResourceContents for cache
-                    let resource_contents = ResourceContents {
-                        uri: uri.to_string(),
-                        mime_type: "application/json".to_string(), // Default mime type
-                        content: contents.clone(),
-                    };
-                    
                     // Update cache
                     let mut resources = self.resources.write().await;
                     if let Some(cached) = resources.get_mut(uri) {
-                        cached.contents = Some(resource_contents);
+                        cached.contents = Some(ResourceContents {
+                            uri: uri.to_string(),
+                            mime_type: cached.resource.mime_type.clone()
+                                .unwrap_or_else(|| "application/json".to_string()),
+                            content: contents.clone(),
+                        });
                         cached.last_updated = std::time::Instant::now();
                         cached.access_count += 1;
                     }
@@ -419,10 +532,11 @@ impl ResourceManager {
     }
 
     /// Mark resource as subscribed
-    async fn mark_resource_subscribed(&self, uri: &str) {
+    async fn mark_resource_subscribed(&self, uri: &str, server_subscribed: bool) {
         let mut resources = self.resources.write().await;
         if let Some(cached) = resources.get_mut(uri) {
             cached.subscription_active = true;
+            cached.server_subscribed = server_subscribed;
         }
     }
 
@@ -531,6 +645,46 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_subscribe_unknown_resource_fails() {
+        let manager = ResourceManager::new(ResourceConfig::default());
+        let result = manager.subscribe_to_resource("file:///missing.txt", "c1", Arc::new(|_| {})).await;
+        assert!(result.is_err());
+        assert!(!manager.is_subscribed("file:///missing.txt").await);
+    }
+
+    #[tokio::test]
+    async fn test_update_notification_reaches_subscribers() {
+        let manager = ResourceManager::new(ResourceConfig::default());
+        let uri = "file:///notes.md".to_string();
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let subscription = |subscriber: &str| {
+            let sink = events.clone();
+            ResourceSubscription {
+                uri: uri.clone(),
+                server_name: "files".to_string(),
+                subscriber: subscriber.to_string(),
+                callback: Arc::new(move |event| sink.lock().unwrap().push(event)),
+            }
+        };
+        manager.subscriptions.write().await.insert(uri.clone(), vec![subscription("c1"), subscription("c2")]);
+
+        // No client is registered, so the refresh fails and subscribers get an error event
+        manager.handle_notification("files", MCPNotification::ResourceUpdated { uri: uri.clone() }).await.unwrap();
+        assert_eq!(events.lock().unwrap().len(), 2);
+        assert!(matches!(events.lock().unwrap()[0], ResourceUpdateEvent::Error(_)));
+
+        // Unsubscribing removes only that subscriber's callback
+        manager.unsubscribe_from_resource(&uri, "c1").await.unwrap();
+        assert!(manager.is_subscribed(&uri).await);
+        manager.handle_notification("files", MCPNotification::ResourceUpdated { uri: uri.clone() }).await.unwrap();
+        assert_eq!(events.lock().unwrap().len(), 3);
+
+        manager.unsubscribe_from_resource(&uri, "c2").await.unwrap();
+        assert!(!manager.is_subscribed(&uri).await);
+    }
+
     #[tokio::test]
     async fn test_resource_config_defaults() {
         let config = ResourceConfig::default();
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio::time::interval;
 use tracing::{debug, error, info, warn};
 
@@ -9,9 +9,10 @@ use crate::error::{Error, Result};
 use crate::mcp::transport::{Transport, StdioTransport, TransportStatus};
 use crate::mcp::types::{
     ServerCapabilities, Tool, Resource, Prompt, InitializeParams, InitializeResult,
-    ClientCapabilities, Implementation, ProtocolVersion, SamplingCapability
+    ClientCapabilities, Implementation, ProtocolVersion, SamplingCapability,
+    MCPMessage, MCPNotification
 };
-use crate::mcp::protocol::{ProtocolHandler, JsonRpcError};
+use crate::mcp::protocol::{ProtocolHandler, ProtocolMessage, JsonRpcError, JsonRpcRequest};
 use crate::app::config::MCPServerConfig;
 
 /// Represents the lifecycle state of an MCP server
@@ -48,6 +49,9 @@ impl Default for ServerHealth {
     }
 }
 
+/// Channel end receiving server notifications, tagged with the originating server name
+pub type NotificationSender = mpsc::UnboundedSender<(String, MCPNotification)>;
+
 /// Instance of an MCP server with its transport and state
 pub struct MCPServerInstance {
     pub name: String,
@@ -60,6 +64,9 @@ pub struct MCPServerInstance {
     tools: Arc<RwLock<Vec<Tool>>>,
     resources: Arc<RwLock<Vec<Resource>>>,
     prompts: Arc<RwLock<Vec<Prompt>>>,
+    notification_sender: Arc<RwLock<Option<NotificationSender>>>,
+    // Serializes request/response exchanges so notification polling never steals a response
+    request_lock: Arc<Mutex<()>>,
 }
 
 impl MCPServerInstance {
@@ -75,6 +82,8 @@ impl MCPServerInstance {
             tools: Arc::new(RwLock::new(Vec::new())),
             resources: Arc::new(RwLock::new(Vec::new())),
             prompts: Arc::new(RwLock::new(Vec::new())),
+            notification_sender: Arc::new(RwLock::new(None)),
+            request_lock: Arc::new(Mutex::new(())),
         }
     }
 
@@ -239,6 +248,37 @@ impl MCPServerInstance {
         Ok(())
     }
 
+    /// Start a server from `config` outside any manager, list its tools and stop it again
+    pub async fn test_connection(name: &str, config: MCPServerConfig) -> Result<Vec<Tool>> {
+        let timeout = Duration::from_secs(config.timeout_seconds.max(1));
+        let mut instance = Self::new(name.to_string(), config);
+
+        let result = tokio::time::timeout(timeout, async {
+            instance.start().await?;
+            if instance.capabilities.read().await.as_ref().is_some_and(|c| c.tools.is_none()) {
+                return Ok(Vec::new());
+            }
+
+            let request = instance.protocol_handler.create_request("tools/list".to_string(), None);
+            let response = instance.send_request(request).await?;
+            if let Some(error) = response.error {
+                return Err(Error::mcp(format!("Listing tools failed: {}", error.message)));
+            }
+            match response.result.and_then(|result| result.get("tools").cloned()) {
+                Some(tools) => serde_json::from_value(tools)
+                    .map_err(|e| Error::mcp(format!("Invalid tool list: {}", e))),
+                None => Ok(Vec::new()),
+            }
+        })
+        .await
+        .unwrap_or_else(|_| Err(Error::mcp(format!("No answer from {} within {}s", name, timeout.as_secs()))));
+
+        if let Err(e) = instance.stop().await {
+            debug!("Failed to stop test instance of {}: {}", name, e);
+        }
+        result
+    }
+
     /// Restart the MCP server
     pub async fn restart(&mut self) -> Result<()> {
         info!("Restarting MCP server: {}", self.name);
@@ -305,30 +345,89 @@ impl MCPServerInstance {
     }
 
     /// Send a JSON-RPC request through the transport
-    pub async fn send_request(&self, request: crate::mcp::protocol::JsonRpcRequest) -> Result<crate::mcp::protocol::JsonRpcResponse> {
+    pub async fn send_request(&self, request: JsonRpcRequest) -> Result<crate::mcp::protocol::JsonRpcResponse> {
         if let Some(transport) = &self.transport {
+            let _exchange = self.request_lock.lock().await;
+            let request_id = request.id.clone();
+
             // Convert request to protocol message
-            let message = crate::mcp::protocol::ProtocolMessage::Request(request);
+            let message = ProtocolMessage::Request(request);
             
             // Send request through transport
             transport.send(&message).await?;
             
-            // Wait for response
-            // In a real implementation, we would match response by request ID
-            // For now, we'll wait for the next message
-            if let Some(response_msg) = transport.receive().await? {
-                match response_msg {
-                    crate::mcp::protocol::ProtocolMessage::Response(response) => Ok(response),
-                    _ => Err(Error::mcp("Unexpected message type received".to_string()))
+            // Wait for the matching response, dispatching any notifications received meanwhile
+            loop {
+                match transport.receive().await? {
+                    Some(ProtocolMessage::Response(response)) => {
+                        if request_id.as_ref().is_none_or(|id| *id == response.id) {
+                            return Ok(response);
+                        }
+                        debug!("Discarding response with unexpected id {:?} from server {}", response.id, self.name);
+                    }
+                    Some(ProtocolMessage::Request(incoming)) => {
+                        self.dispatch_incoming(incoming).await;
+                    }
+                    None => return Err(Error::mcp("No response received".to_string())),
                 }
-            } else {
-                Err(Error::mcp("No response received".to_string()))
             }
         } else {
             Err(Error::mcp("Transport not available - server not started".to_string()))
         }
     }
 
+    /// Drain notifications that arrived while no request was in flight
+    pub async fn poll_notifications(&self) -> Result<usize> {
+        let transport = match &self.transport {
+            Some(transport) => transport,
+            None => return Ok(0),
+        };
+
+        // A request in flight dispatches notifications itself
+        let _exchange = match self.request_lock.try_lock() {
+            Ok(guard) => guard,
+            Err(_) => return Ok(0),
+        };
+
+        let mut dispatched = 0;
+        while let Ok(message) = tokio::time::timeout(Duration::from_millis(10), transport.receive()).await {
+            match message? {
+                Some(ProtocolMessage::Request(incoming)) => {
+                    self.dispatch_incoming(incoming).await;
+                    dispatched += 1;
+                }
+                Some(ProtocolMessage::Response(response)) => {
+                    debug!("Discarding unsolicited response {:?} from server {}", response.id, self.name);
+                }
+                None => break,
+            }
+        }
+
+        Ok(dispatched)
+    }
+
+    /// Forward a server-initiated message to the notification channel
+    async fn dispatch_incoming(&self, incoming: JsonRpcRequest) {
+        if incoming.id.is_some() {
+            debug!("Ignoring server request '{}' from {}", incoming.method, self.name);
+            return;
+        }
+
+        match MCPMessage::from_json_rpc(incoming) {
+            Ok(MCPMessage::Notification(notification)) => {
+                debug!("Received notification from {}: {:?}", self.name, notification);
+                let sender = self.notification_sender.read().await;
+                if let Some(sender) = sender.as_ref() {
+                    if sender.send((self.name.clone(), notification)).is_err() {
+                        debug!("Notification receiver dropped for server {}", self.name);
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(e) => debug!("Ignoring unsupported notification from {}: {}", self.name, e),
+        }
+    }
+
     /// Perform health check on the server
     pub async fn health_check(&self) -> Result<()> {
         let start_time = Instant::now();
@@ -374,6 +473,7 @@ impl MCPServerInstance {
 pub struct MCPServerManager {
     servers: Arc<RwLock<HashMap<String, MCPServerInstance>>>,
     health_check_interval: Duration,
+    notification_sender: Arc<RwLock<Option<NotificationSender>>>,
 }
 
 impl MCPServerManager {
@@ -381,14 +481,24 @@ impl MCPServerManager {
         Self {
             servers: Arc::new(RwLock::new(HashMap::new())),
             health_check_interval: Duration::from_secs(30),
+            notification_sender: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// Route notifications from all servers to a new receiver, replacing any previous one
+    pub async fn subscribe_notifications(&self) -> mpsc::UnboundedReceiver<(String, MCPNotification)> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut sender = self.notification_sender.write().await;
+        *sender = Some(tx);
+        rx
+    }
+
     /// Add a new MCP server configuration
     pub async fn add_server(&mut self, name: String, config: MCPServerConfig) -> Result<()> {
         info!("Adding MCP server: {}", name);
 
-        let instance = MCPServerInstance::new(name.clone(), config);
+        let mut instance = MCPServerInstance::new(name.clone(), config);
+        instance.notification_sender = self.notification_sender.clone();
         
         let mut servers = self.servers.write().await;
         servers.insert(name.clone(), instance);
@@ -512,6 +622,47 @@ impl MCPServerManager {
         }
     }
 
+    /// Get the capabilities a server advertised during initialization
+    pub async fn get_server_capabilities(&self, name: &str) -> Option<ServerCapabilities> {
+        let servers = self.servers.read().await;
+        match servers.get(name) {
+            Some(instance) => instance.get_capabilities().await,
+            None => None,
+        }
+    }
+
+    /// Poll all ready servers for pending notifications
+    pub async fn poll_notifications(&self) -> usize {
+        let servers = self.servers.read().await;
+        let mut dispatched = 0;
+
+        for (name, instance) in servers.iter() {
+            if instance.get_state().await != ServerState::Ready {
+                continue;
+            }
+            match instance.poll_notifications().await {
+                Ok(count) => dispatched += count,
+                Err(e) => warn!("Failed to poll notifications from MCP server {}: {}", name, e),
+            }
+        }
+
+        dispatched
+    }
+
+    /// Start background polling for server notifications
+    pub fn start_notification_polling(&self, poll_interval: Duration) {
+        info!("Starting MCP notification polling every {:?}", poll_interval);
+
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut interval = interval(poll_interval);
+            loop {
+                interval.tick().await;
+                manager.poll_notifications().await;
+            }
+        });
+    }
+
     /// Start background health checking
     pub async fn start_health_monitoring(&mut self) {
         info!("Starting MCP server health monitoring");
@@ -550,7 +701,7 @@ impl MCPServerManager {
     pub async fn send_request_to_server(
         &self,
         server_name: &str,
-        request: crate::mcp::protocol::JsonRpcRequest,
+        request: JsonRpcRequest,
     ) -> Result<crate::mcp::protocol::JsonRpcResponse> {
         let servers = self.servers.read().await;
         
@@ -602,6 +753,15 @@ mod tests {
         assert_eq!(instance.config.name, "test_server");
     }
 
+    #[tokio::test]
+    async fn test_connection_reports_failure() {
+        let mut config = create_test_config();
+        config.command = "valechat-test-no-such-server".to_string();
+        config.timeout_seconds = 5;
+
+        assert!(MCPServerInstance::test_connection("missing", config).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_server_manager_add_remove() {
         let mut manager = MCPServerManager::new();
@@ -634,6 +794,25 @@ mod tests {
         assert!(!health.is_healthy);
     }
 
+    #[tokio::test]
+    async fn test_notifications_routed_to_subscriber() {
+        let mut manager = MCPServerManager::new();
+        manager.add_server("test".to_string(), create_test_config()).await.unwrap();
+        let mut receiver = manager.subscribe_notifications().await;
+
+        let notification = MCPNotification::ResourceUpdated { uri: "file:///a.txt".to_string() }
+            .to_json_rpc()
+            .unwrap();
+        {
+            let servers = manager.servers.read().await;
+            servers.get("test").unwrap().dispatch_incoming(notification).await;
+        }
+
+        let (server, notification) = receiver.recv().await.unwrap();
+        assert_eq!(server, "test");
+        assert!(matches!(notification, MCPNotification::ResourceUpdated { ref uri } if uri == "file:///a.txt"));
+    }
+
     #[test]
     fn test_server_health_default() {
         let health = ServerHealth::default();
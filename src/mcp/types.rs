@@ -72,8 +72,9 @@ pub struct SamplingCapability {}
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tool {
     pub name: String,
+    #[serde(default)]
     pub description: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "inputSchema", alias = "input_schema", skip_serializing_if = "Option::is_none")]
     pub input_schema: Option<JsonValue>, // JSON Schema for input validation
 }
 
@@ -238,6 +239,8 @@ pub enum MCPRequest {
     CallTool(ToolCall),
     ListResources,
     ReadResource(ResourceReference),
+    SubscribeResource(ResourceReference),
+    UnsubscribeResource(ResourceReference),
     ListPrompts,
     GetPrompt { name: String, arguments: Option<HashMap<String, String>> },
 }
@@ -260,6 +263,7 @@ pub enum MCPNotification {
     Initialized,
     ToolsListChanged,
     ResourcesListChanged,
+    ResourceUpdated { uri: String },
     PromptsListChanged,
     LogMessage(LogEntry),
     Progress { 
@@ -292,6 +296,16 @@ impl MCPMessage {
                 ).map_err(|e| crate::error::Error::mcp(format!("Invalid resource reference: {}", e)))?;
                 Ok(MCPMessage::Request(MCPRequest::ReadResource(resource_ref)))
             }
+            "resources/subscribe" | "resources/unsubscribe" => {
+                let resource_ref: ResourceReference = serde_json::from_value(
+                    message.params.unwrap_or(JsonValue::Null)
+                ).map_err(|e| crate::error::Error::mcp(format!("Invalid resource reference: {}", e)))?;
+                if message.method == "resources/subscribe" {
+                    Ok(MCPMessage::Request(MCPRequest::SubscribeResource(resource_ref)))
+                } else {
+                    Ok(MCPMessage::Request(MCPRequest::UnsubscribeResource(resource_ref)))
+                }
+            }
             "prompts/list" => Ok(MCPMessage::Request(MCPRequest::ListPrompts)),
             "prompts/get" => {
                 let params: serde_json::Map<String, JsonValue> = serde_json::from_value(
@@ -312,6 +326,12 @@ impl MCPMessage {
             "notifications/initialized" => Ok(MCPMessage::Notification(MCPNotification::Initialized)),
             "notifications/tools/list_changed" => Ok(MCPMessage::Notification(MCPNotification::ToolsListChanged)),
             "notifications/resources/list_changed" => Ok(MCPMessage::Notification(MCPNotification::ResourcesListChanged)),
+            "notifications/resources/updated" => {
+                let resource_ref: ResourceReference = serde_json::from_value(
+                    message.params.unwrap_or(JsonValue::Null)
+                ).map_err(|e| crate::error::Error::mcp(format!("Invalid resource update params: {}", e)))?;
+                Ok(MCPMessage::Notification(MCPNotification::ResourceUpdated { uri: resource_ref.uri }))
+            }
             "notifications/prompts/list_changed" => Ok(MCPMessage::Notification(MCPNotification::PromptsListChanged)),
             "notifications/message" => {
                 let log_entry: LogEntry = serde_json::from_value(
@@ -346,6 +366,12 @@ impl MCPRequest {
             MCPRequest::ReadResource(resource_ref) => {
                 ("resources/read", Some(serde_json::to_value(resource_ref)?))
             }
+            MCPRequest::SubscribeResource(resource_ref) => {
+                ("resources/subscribe", Some(serde_json::to_value(resource_ref)?))
+            }
+            MCPRequest::UnsubscribeResource(resource_ref) => {
+                ("resources/unsubscribe", Some(serde_json::to_value(resource_ref)?))
+            }
             MCPRequest::ListPrompts => ("prompts/list", None),
             MCPRequest::GetPrompt { name, arguments } => {
                 let mut params = serde_json::Map::new();
@@ -367,6 +393,9 @@ impl MCPNotification {
             MCPNotification::Initialized => ("notifications/initialized", None),
             MCPNotification::ToolsListChanged => ("notifications/tools/list_changed", None),
             MCPNotification::ResourcesListChanged => ("notifications/resources/list_changed", None),
+            MCPNotification::ResourceUpdated { uri } => {
+                ("notifications/resources/updated", Some(serde_json::json!({ "uri": uri })))
+            }
             MCPNotification::PromptsListChanged => ("notifications/prompts/list_changed", None),
             MCPNotification::LogMessage(log_entry) => {
                 ("notifications/message", Some(serde_json::to_value(log_entry)?))
@@ -480,4 +509,23 @@ mod tests {
         assert_eq!(json_rpc.method, "notifications/tools/list_changed");
         assert!(json_rpc.id.is_none()); // Notifications don't have IDs
     }
+
+    #[test]
+    fn test_resource_updated_notification_round_trip() {
+        let notification = MCPNotification::ResourceUpdated { uri: "file:///notes.md".to_string() };
+        let json_rpc = notification.to_json_rpc().unwrap();
+        assert_eq!(json_rpc.method, "notifications/resources/updated");
+
+        match MCPMessage::from_json_rpc(json_rpc).unwrap() {
+            MCPMessage::Notification(MCPNotification::ResourceUpdated { uri }) => {
+                assert_eq!(uri, "file:///notes.md");
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+
+        let subscribe = MCPRequest::SubscribeResource(ResourceReference { uri: "file:///notes.md".to_string() });
+        let json_rpc = subscribe.to_json_rpc().unwrap();
+        assert_eq!(json_rpc.method, "resources/subscribe");
+        assert_eq!(json_rpc.params.unwrap()["uri"], "file:///notes.md");
+    }
 }
\ No newline at end of file
@@ -279,62 +279,71 @@ impl InputValidator {
         false
     }
 
-    /// Validate against JSON schema (simplified implementation)
+    /// Check parameters against a tool's JSON Schema (draft 2020-12)
+    ///
+    /// Returns every violation found, or none when schema validation is disabled.
+    /// Fails only if the schema itself is invalid.
+    pub fn validate_schema(&self, parameters: &JsonValue, schema: &JsonValue) -> Result<Vec<SchemaViolation>> {
+        if !self.config.enable_schema_validation {
+            return Ok(Vec::new());
+        }
+
+        schema_violations(parameters, schema)
+    }
+
     fn validate_against_schema(&self, value: &JsonValue, schema: &JsonValue) -> Result<()> {
-        // This is a simplified schema validation
-        // In a production system, you'd use a proper JSON Schema library
-        
-        if let Some(schema_type) = schema.get("type") {
-            match schema_type.as_str().unwrap_or("") {
-                "object" => {
-                    if !value.is_object() {
-                        return Err(Error::validation("Expected object type".to_string()));
-                    }
-                    
-                    // Validate required properties
-                    if let Some(required) = schema.get("required") {
-                        if let Some(required_array) = required.as_array() {
-                            let obj = value.as_object().unwrap();
-                            for prop in required_array {
-                                let prop_name = prop.as_str().unwrap_or("");
-                                if !obj.contains_key(prop_name) {
-                                    return Err(Error::validation(format!(
-                                        "Required property '{}' is missing",
-                                        prop_name
-                                    )));
-                                }
-                            }
-                        }
-                    }
-                }
-                "array" => {
-                    if !value.is_array() {
-                        return Err(Error::validation("Expected array type".to_string()));
-                    }
-                }
-                "string" => {
-                    if !value.is_string() {
-                        return Err(Error::validation("Expected string type".to_string()));
-                    }
-                }
-                "number" => {
-                    if !value.is_number() {
-                        return Err(Error::validation("Expected number type".to_string()));
-                    }
-                }
-                "boolean" => {
-                    if !value.is_boolean() {
-                        return Err(Error::validation("Expected boolean type".to_string()));
-                    }
-                }
-                _ => {}
-            }
+        let violations = schema_violations(value, schema)?;
+        if violations.is_empty() {
+            return Ok(());
         }
 
-        Ok(())
+        Err(Error::validation(ValidationError::SchemaValidation {
+            message: format_violations(&violations),
+        }.to_string()))
+    }
+}
+
+/// A single JSON Schema violation
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    /// JSON pointer to the invalid value, empty for the root
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "(root): {}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
     }
 }
 
+/// Join violations into a single readable message
+pub fn format_violations(violations: &[SchemaViolation]) -> String {
+    violations.iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Check `value` against a draft 2020-12 JSON Schema and collect all violations
+pub fn schema_violations(value: &JsonValue, schema: &JsonValue) -> Result<Vec<SchemaViolation>> {
+    let validator = jsonschema::draft202012::new(schema)
+        .map_err(|e| Error::validation(format!("Invalid JSON schema: {}", e)))?;
+
+    let violations = validator.iter_errors(value)
+        .map(|e| SchemaViolation {
+            path: e.instance_path.to_string(),
+            message: e.to_string(),
+        })
+        .collect();
+
+    Ok(violations)
+}
+
 /// Input sanitizer for cleaning potentially dangerous content
 pub struct InputSanitizer {
     config: SanitizerConfig,
@@ -591,6 +600,45 @@ mod tests {
         assert!(validator.validate_tool_input("test", &invalid_input, None, Some(&schema)).is_err());
     }
 
+    #[test]
+    fn test_schema_violations_report_paths() {
+        let schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "lines": {
+                    "type": "array",
+                    "prefixItems": [{ "type": "integer" }, { "type": "integer" }]
+                }
+            },
+            "required": ["path"],
+            "additionalProperties": false
+        });
+
+        let violations = schema_violations(&json!({"lines": [1, "x"], "extra": true}), &schema).unwrap();
+        let rendered: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+
+        assert!(rendered.iter().any(|v| v.starts_with("(root)") && v.contains("path")));
+        assert!(rendered.iter().any(|v| v.starts_with("/lines/1")));
+        assert!(rendered.iter().any(|v| v.contains("extra")));
+
+        assert!(schema_violations(&json!({"path": "/tmp", "lines": [1, 2]}), &schema).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_schema_validation_can_be_disabled() {
+        let config = ValidationConfig {
+            enable_schema_validation: false,
+            ..ValidationConfig::default()
+        };
+        let validator = InputValidator::new(config).unwrap();
+        let schema = json!({"type": "object", "required": ["name"]});
+
+        assert!(validator.validate_schema(&json!({}), &schema).unwrap().is_empty());
+        assert!(schema_violations(&json!({}), &json!({"type": 5})).is_err());
+    }
+
     #[test]
     fn test_rate_limiting() {
         let config = ValidationConfig {
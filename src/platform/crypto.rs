@@ -0,0 +1,194 @@
+// Symmetric encryption for data ValeChat keeps at rest
+//
+// Keys are either random (and kept in secure storage) or derived from a passphrase with
+// PBKDF2-HMAC-SHA256. Data is sealed with ChaCha20-Poly1305 under a random nonce, or with
+// XChaCha20-Poly1305, whose 192-bit random nonces stay unique for keys used indefinitely.
+
+use std::num::NonZeroU32;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::error::{Error, Result};
+
+pub const KEY_LEN: usize = 32;
+pub const SALT_LEN: usize = 16;
+/// PBKDF2 rounds for passphrase-derived keys
+pub const PBKDF2_ITERATIONS: u32 = 600_000;
+/// XChaCha20-Poly1305 nonce length
+pub const XNONCE_LEN: usize = 24;
+
+/// Marks data sealed with a passphrase: magic, salt, iteration count, then the sealed bytes
+const PASSPHRASE_MAGIC: &[u8; 6] = b"VCENC1";
+
+/// Fill a buffer with cryptographically secure random bytes
+pub fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| Error::platform("System random number generator failed"))?;
+    Ok(bytes)
+}
+
+/// Derive a key from a passphrase
+pub fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<[u8; KEY_LEN]> {
+    let iterations = NonZeroU32::new(iterations)
+        .ok_or_else(|| Error::validation("Key derivation needs at least one iteration"))?;
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+    Ok(key)
+}
+
+/// Encrypt `plaintext`, returning the nonce followed by the ciphertext and tag
+///
+/// `aad` is authenticated but not encrypted; the same value must be passed to [`open`].
+pub fn seal(key: &[u8; KEY_LEN], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    let nonce_bytes = random_bytes::<NONCE_LEN>()?;
+    let mut sealed = Vec::with_capacity(NONCE_LEN + plaintext.len() + CHACHA20_POLY1305.tag_len());
+    sealed.extend_from_slice(&nonce_bytes);
+
+    let mut in_out = plaintext.to_vec();
+    aead_key(key)?
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce_bytes), Aad::from(aad), &mut in_out)
+        .map_err(|_| Error::platform("Encryption failed"))?;
+    sealed.extend_from_slice(&in_out);
+    Ok(sealed)
+}
+
+/// Decrypt data produced by [`seal`]
+pub fn open(key: &[u8; KEY_LEN], sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    if sealed.len() < NONCE_LEN + CHACHA20_POLY1305.tag_len() {
+        return Err(Error::validation("Encrypted data is truncated"));
+    }
+    let (nonce_bytes, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce_bytes)
+        .map_err(|_| Error::validation("Encrypted data has an invalid nonce"))?;
+
+    let mut in_out = ciphertext.to_vec();
+    let plaintext_len = aead_key(key)?
+        .open_in_place(nonce, Aad::from(aad), &mut in_out)
+        .map_err(|_| Error::validation("Decryption failed: wrong key or passphrase, or the data was modified"))?
+        .len();
+    in_out.truncate(plaintext_len);
+    Ok(in_out)
+}
+
+/// Encrypt `plaintext` with XChaCha20-Poly1305, returning the 24-byte nonce followed by the ciphertext and tag
+///
+/// For keys that seal an unbounded number of messages, where random 96-bit nonces could repeat.
+pub fn xseal(key: &[u8; KEY_LEN], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    let nonce = random_bytes::<XNONCE_LEN>()?;
+    let ciphertext = XChaCha20Poly1305::new(key.into())
+        .encrypt(XNonce::from_slice(&nonce), Payload { msg: plaintext, aad })
+        .map_err(|_| Error::platform("Encryption failed"))?;
+
+    let mut sealed = Vec::with_capacity(XNONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypt data produced by [`xseal`]
+pub fn xopen(key: &[u8; KEY_LEN], sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    if sealed.len() < XNONCE_LEN + CHACHA20_POLY1305.tag_len() {
+        return Err(Error::validation("Encrypted data is truncated"));
+    }
+    let (nonce, ciphertext) = sealed.split_at(XNONCE_LEN);
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+        .map_err(|_| Error::validation("Decryption failed: wrong key or passphrase, or the data was modified"))
+}
+
+/// Encrypt `plaintext` under a key derived from `passphrase` with a fresh salt
+pub fn seal_with_passphrase(passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let salt = random_bytes::<SALT_LEN>()?;
+    let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS)?;
+
+    let mut header = Vec::with_capacity(PASSPHRASE_MAGIC.len() + SALT_LEN + 4);
+    header.extend_from_slice(PASSPHRASE_MAGIC);
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&PBKDF2_ITERATIONS.to_le_bytes());
+
+    // The header is authenticated so the salt and iteration count cannot be swapped
+    let sealed = seal(&key, plaintext, &header)?;
+    header.extend_from_slice(&sealed);
+    Ok(header)
+}
+
+/// Decrypt data produced by [`seal_with_passphrase`]
+pub fn open_with_passphrase(passphrase: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let header_len = PASSPHRASE_MAGIC.len() + SALT_LEN + 4;
+    if !is_passphrase_sealed(data) || data.len() < header_len {
+        return Err(Error::validation("Data is not encrypted with a passphrase"));
+    }
+    let (header, sealed) = data.split_at(header_len);
+    let salt = &header[PASSPHRASE_MAGIC.len()..PASSPHRASE_MAGIC.len() + SALT_LEN];
+    let mut iterations = [0u8; 4];
+    iterations.copy_from_slice(&header[PASSPHRASE_MAGIC.len() + SALT_LEN..]);
+
+    let key = derive_key(passphrase, salt, u32::from_le_bytes(iterations))?;
+    open(&key, sealed, header)
+}
+
+/// Whether `data` starts with the passphrase encryption header
+pub fn is_passphrase_sealed(data: &[u8]) -> bool {
+    data.starts_with(PASSPHRASE_MAGIC)
+}
+
+fn aead_key(key: &[u8; KEY_LEN]) -> Result<LessSafeKey> {
+    let key = UnboundKey::new(&CHACHA20_POLY1305, key).map_err(|_| Error::platform("Invalid encryption key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open() {
+        let key = random_bytes::<KEY_LEN>().unwrap();
+        let sealed = seal(&key, b"hello", b"messages.content").unwrap();
+        assert_eq!(open(&key, &sealed, b"messages.content").unwrap(), b"hello");
+
+        // Wrong context, wrong key or tampering are all rejected
+        assert!(open(&key, &sealed, b"other").is_err());
+        assert!(open(&random_bytes::<KEY_LEN>().unwrap(), &sealed, b"messages.content").is_err());
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(open(&key, &tampered, b"messages.content").is_err());
+    }
+
+    #[test]
+    fn test_xchacha20_poly1305() {
+        let hex = |text: &str| (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap()).collect::<Vec<_>>();
+
+        // AEAD test vector from draft-irtf-cfrg-xchacha, section A.3.1
+        let key: [u8; KEY_LEN] = std::array::from_fn(|i| 0x80 + i as u8);
+        let sealed = hex(concat!(
+            "404142434445464748494a4b4c4d4e4f5051525354555657",
+            "bd6d179d3e83d43b9576579493c0e939572a1700252bfaccbed2902c21396cbb731c7f1b0b4aa6440bf3a82f4eda7e39",
+            "ae64c6708c54c216cb96b72e1213b4522f8c9ba40db5d945b11b69b982c1bb9e3f3fac2bc369488f76b2383565d3fff9",
+            "21f9664c97637da9768812f615c68b13b52ec0875924c1c7987947deafd8780acf49",
+        ));
+        let plaintext = xopen(&key, &sealed, &hex("50515253c0c1c2c3c4c5c6c7")).unwrap();
+        assert!(plaintext.starts_with(b"Ladies and Gentlemen of the class of '99"));
+
+        let key = random_bytes::<KEY_LEN>().unwrap();
+        let sealed = xseal(&key, b"hello", b"secrets").unwrap();
+        assert_eq!(sealed.len(), XNONCE_LEN + 5 + CHACHA20_POLY1305.tag_len());
+        assert_eq!(xopen(&key, &sealed, b"secrets").unwrap(), b"hello");
+        assert!(xopen(&key, &sealed, b"other").is_err());
+        assert!(open(&key, &sealed, b"secrets").is_err());
+    }
+
+    #[test]
+    fn test_passphrase_round_trip() {
+        let sealed = seal_with_passphrase("correct horse", b"backup").unwrap();
+        assert!(is_passphrase_sealed(&sealed));
+        assert_eq!(open_with_passphrase("correct horse", &sealed).unwrap(), b"backup");
+        assert!(open_with_passphrase("wrong", &sealed).is_err());
+        assert!(open_with_passphrase("correct horse", b"plain").is_err());
+    }
+}
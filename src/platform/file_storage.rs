@@ -0,0 +1,278 @@
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+use crate::error::{Error, Result};
+use crate::platform::crypto::{self, KEY_LEN, PBKDF2_ITERATIONS, SALT_LEN};
+use crate::platform::secure_storage::SecureStorage;
+
+/// Secrets file name under the configuration directory
+pub const SECRETS_FILE: &str = "secrets.enc";
+
+/// Header: magic, key kind, salt and PBKDF2 iterations; the encrypted entries follow
+const MAGIC: &[u8; 10] = b"VCSECRETS2";
+/// Files from before XChaCha20-Poly1305, sealed with 96-bit nonces; read, then rewritten on the next change
+const LEGACY_MAGIC: &[u8; 10] = b"VCSECRETS1";
+const HEADER_LEN: usize = MAGIC.len() + 1 + SALT_LEN + 4;
+const KIND_KEY_FILE: u8 = 0;
+const KIND_PASSPHRASE: u8 = 1;
+
+/// What unlocks the encrypted secrets file
+#[derive(Clone)]
+pub enum FileStoreKey {
+    Passphrase(String),
+    /// File holding a base64-encoded 32-byte key
+    KeyFile(PathBuf),
+}
+
+impl FileStoreKey {
+    fn kind(&self) -> u8 {
+        match self {
+            FileStoreKey::Passphrase(_) => KIND_PASSPHRASE,
+            FileStoreKey::KeyFile(_) => KIND_KEY_FILE,
+        }
+    }
+}
+
+impl std::fmt::Debug for FileStoreKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileStoreKey::Passphrase(_) => write!(f, "Passphrase(..)"),
+            FileStoreKey::KeyFile(path) => write!(f, "KeyFile({:?})", path),
+        }
+    }
+}
+
+type Entries = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Secrets kept in one file encrypted with XChaCha20-Poly1305, for systems without a keychain
+///
+/// The file is read again for every operation, so separate processes see each other's changes.
+/// Every write seals the file under the same long-lived key, hence the 192-bit random nonces.
+pub struct EncryptedFileStorage {
+    path: PathBuf,
+    key: Option<FileStoreKey>,
+    /// Key derived for the salt of the file, so the passphrase is stretched only once
+    derived: Mutex<Option<([u8; SALT_LEN], [u8; KEY_LEN])>>,
+    /// Serializes read-modify-write cycles within this process
+    write_lock: Mutex<()>,
+}
+
+impl EncryptedFileStorage {
+    /// Without a key the store can only report that it is locked
+    pub fn new(path: PathBuf, key: Option<FileStoreKey>) -> Self {
+        Self { path, key, derived: Mutex::new(None), write_lock: Mutex::new(()) }
+    }
+
+    /// Write a new random key file readable only by the current user
+    pub fn generate_key_file(path: &Path) -> Result<()> {
+        if path.exists() {
+            return Err(Error::validation(format!("{} already exists", path.display())));
+        }
+        let key = crypto::random_bytes::<KEY_LEN>()?;
+        write_private(path, format!("{}\n", BASE64.encode(key)).as_bytes())?;
+        info!("Generated secret store key file {:?}", path);
+        Ok(())
+    }
+
+    fn key_for(&self, kind: u8, salt: &[u8; SALT_LEN], iterations: u32) -> Result<[u8; KEY_LEN]> {
+        let key = self.key.as_ref().ok_or_else(|| {
+            Error::platform(format!(
+                "The secret store {} is locked; set VALECHAT_SECRETS_PASSPHRASE or `key_file` under [secrets]",
+                self.path.display()
+            ))
+        })?;
+        if key.kind() != kind {
+            let expected = if kind == KIND_PASSPHRASE { "a passphrase" } else { "a key file" };
+            return Err(Error::validation(format!("The secret store {} is protected by {}", self.path.display(), expected)));
+        }
+
+        match key {
+            FileStoreKey::KeyFile(path) => read_key_file(path),
+            FileStoreKey::Passphrase(passphrase) => {
+                let mut derived = self.derived.lock();
+                match *derived {
+                    Some((cached_salt, key)) if cached_salt == *salt => Ok(key),
+                    _ => {
+                        let key = crypto::derive_key(passphrase, salt, iterations)?;
+                        *derived = Some((*salt, key));
+                        Ok(key)
+                    }
+                }
+            }
+        }
+    }
+
+    fn read(&self) -> Result<Entries> {
+        let data = match std::fs::read(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Entries::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let legacy = data.starts_with(LEGACY_MAGIC);
+        if data.len() < HEADER_LEN || !(legacy || data.starts_with(MAGIC)) {
+            return Err(Error::validation(format!("{} is not a ValeChat secret store", self.path.display())));
+        }
+
+        let (header, sealed) = data.split_at(HEADER_LEN);
+        let kind = header[MAGIC.len()];
+        let salt: [u8; SALT_LEN] = header[MAGIC.len() + 1..MAGIC.len() + 1 + SALT_LEN].try_into().expect("salt length");
+        let iterations = u32::from_le_bytes(header[HEADER_LEN - 4..].try_into().expect("iteration count length"));
+
+        let key = self.key_for(kind, &salt, iterations)?;
+        let opened = if legacy { crypto::open(&key, sealed, header) } else { crypto::xopen(&key, sealed, header) };
+        let plaintext = opened
+            .map_err(|_| Error::validation(format!("Cannot unlock {}: wrong passphrase or key file", self.path.display())))?;
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| Error::platform(format!("The secret store {} is damaged: {}", self.path.display(), e)))
+    }
+
+    fn write(&self, entries: &Entries) -> Result<()> {
+        let kind = self.key.as_ref().map_or(KIND_PASSPHRASE, FileStoreKey::kind);
+        // Keep the salt of an existing file so the cached key stays valid
+        let cached_salt = self.derived.lock().map(|(salt, _)| salt);
+        let salt = match cached_salt {
+            Some(salt) => salt,
+            None => crypto::random_bytes::<SALT_LEN>()?,
+        };
+        let key = self.key_for(kind, &salt, PBKDF2_ITERATIONS)?;
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.push(kind);
+        header.extend_from_slice(&salt);
+        header.extend_from_slice(&PBKDF2_ITERATIONS.to_le_bytes());
+
+        let plaintext = serde_json::to_vec(entries)?;
+        let sealed = crypto::xseal(&key, &plaintext, &header)?;
+        header.extend_from_slice(&sealed);
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let temp = self.path.with_extension("enc.tmp");
+        write_private(&temp, &header)?;
+        std::fs::rename(&temp, &self.path)?;
+        debug!("Wrote secret store {:?}", self.path);
+        Ok(())
+    }
+
+    fn update<F>(&self, change: F) -> Result<()>
+    where
+        F: FnOnce(&mut Entries) -> bool,
+    {
+        let _guard = self.write_lock.lock();
+        let mut entries = self.read()?;
+        if change(&mut entries) {
+            self.write(&entries)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SecureStorage for EncryptedFileStorage {
+    async fn store(&self, service: &str, key: &str, value: &str) -> Result<()> {
+        self.update(|entries| {
+            entries.entry(service.to_string()).or_default().insert(key.to_string(), value.to_string());
+            true
+        })
+    }
+
+    async fn retrieve(&self, service: &str, key: &str) -> Result<Option<String>> {
+        Ok(self.read()?.get(service).and_then(|keys| keys.get(key)).cloned())
+    }
+
+    async fn delete(&self, service: &str, key: &str) -> Result<()> {
+        self.update(|entries| {
+            let Some(keys) = entries.get_mut(service) else {
+                return false;
+            };
+            let removed = keys.remove(key).is_some();
+            if keys.is_empty() {
+                entries.remove(service);
+            }
+            removed
+        })
+    }
+
+    async fn list_keys(&self, service: &str) -> Result<Vec<String>> {
+        Ok(self.read()?.get(service).map(|keys| keys.keys().cloned().collect()).unwrap_or_default())
+    }
+}
+
+fn read_key_file(path: &Path) -> Result<[u8; KEY_LEN]> {
+    let content = std::fs::read(path)
+        .map_err(|e| Error::platform(format!("Cannot read key file {}: {}", path.display(), e)))?;
+    let decoded = BASE64.decode(String::from_utf8_lossy(&content).trim()).ok();
+    decoded.as_deref()
+        .or(Some(content.as_slice()))
+        .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+        .ok_or_else(|| Error::validation(format!("{} does not hold a {}-byte key", path.display(), KEY_LEN)))
+}
+
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_passphrase_store_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(SECRETS_FILE);
+        let store = EncryptedFileStorage::new(path.clone(), Some(FileStoreKey::Passphrase("hunter2".into())));
+
+        store.store("ai.valechat.secrets", "backup_passphrase", "s3cret").await.unwrap();
+        store.store("ai.valechat.secrets", "database_key", "abc").await.unwrap();
+        assert_eq!(store.retrieve("ai.valechat.secrets", "backup_passphrase").await.unwrap().as_deref(), Some("s3cret"));
+        assert_eq!(store.list_keys("ai.valechat.secrets").await.unwrap(), vec!["backup_passphrase", "database_key"]);
+        assert!(!String::from_utf8_lossy(&std::fs::read(&path).unwrap()).contains("s3cret"));
+
+        // Another process with the same passphrase sees the entries; others cannot read them
+        let reopened = EncryptedFileStorage::new(path.clone(), Some(FileStoreKey::Passphrase("hunter2".into())));
+        reopened.delete("ai.valechat.secrets", "database_key").await.unwrap();
+        assert_eq!(store.list_keys("ai.valechat.secrets").await.unwrap(), vec!["backup_passphrase"]);
+        let wrong = EncryptedFileStorage::new(path.clone(), Some(FileStoreKey::Passphrase("wrong".into())));
+        assert!(wrong.retrieve("ai.valechat.secrets", "backup_passphrase").await.is_err());
+        let locked = EncryptedFileStorage::new(path, None);
+        assert!(locked.retrieve("ai.valechat.secrets", "backup_passphrase").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_key_file_store() {
+        let dir = TempDir::new().unwrap();
+        let key_file = dir.path().join("secrets.key");
+        EncryptedFileStorage::generate_key_file(&key_file).unwrap();
+        assert!(EncryptedFileStorage::generate_key_file(&key_file).is_err());
+
+        let path = dir.path().join(SECRETS_FILE);
+        let store = EncryptedFileStorage::new(path.clone(), Some(FileStoreKey::KeyFile(key_file)));
+        assert!(store.list_keys("ai.valechat.consolidated").await.unwrap().is_empty());
+        store.store("ai.valechat.consolidated", "api_keys", "{}").await.unwrap();
+        assert_eq!(store.list_keys("ai.valechat.consolidated").await.unwrap(), vec!["api_keys"]);
+        assert!(std::fs::read(&path).unwrap().starts_with(MAGIC));
+
+        // A passphrase does not open a store protected by a key file
+        let passphrase = EncryptedFileStorage::new(path, Some(FileStoreKey::Passphrase("key".into())));
+        assert!(passphrase.retrieve("ai.valechat.consolidated", "api_keys").await.is_err());
+    }
+}
@@ -1,10 +1,14 @@
 pub mod secure_storage;
+pub mod crypto;
+pub mod file_storage;
 
 #[cfg(target_os = "macos")]
 pub mod macos_keychain;
 pub mod paths;
 pub mod process;
+pub mod notifications;
 
-pub use secure_storage::{SecureStorage, SecureStorageManager};
+pub use secure_storage::{ApiKeyMetadata, SecretsBackend, SecretsConfig, SecureStorage, SecureStorageManager};
 pub use paths::AppPaths;
-pub use process::{ProcessManager, ProcessConfig, ResourceLimits, SandboxedProcess};
\ No newline at end of file
+pub use process::{ProcessManager, ProcessConfig, ResourceLimits, SandboxedProcess};
+pub use notifications::{send_desktop_notification, NotificationUrgency};
\ No newline at end of file
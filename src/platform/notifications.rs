@@ -0,0 +1,48 @@
+use crate::error::{Error, Result};
+
+/// Urgency levels from the freedesktop notification spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationUrgency {
+    Low = 0,
+    Normal = 1,
+    Critical = 2,
+}
+
+/// Show a desktop notification through the session bus `org.freedesktop.Notifications` service
+#[cfg(target_os = "linux")]
+pub async fn send_desktop_notification(summary: &str, body: &str, urgency: NotificationUrgency) -> Result<u32> {
+    use std::collections::HashMap;
+    use zbus::zvariant::Value;
+
+    let connection = zbus::Connection::session()
+        .await
+        .map_err(|e| Error::platform(format!("Failed to connect to the session bus: {}", e)))?;
+
+    let mut hints: HashMap<&str, Value> = HashMap::new();
+    hints.insert("urgency", Value::U8(urgency as u8));
+
+    // Critical notifications stay until dismissed; others use the server default timeout
+    let expire_timeout: i32 = if urgency == NotificationUrgency::Critical { 0 } else { -1 };
+    let actions: Vec<&str> = Vec::new();
+
+    let reply = connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &("ValeChat", 0u32, "dialog-warning", summary, body, actions, hints, expire_timeout),
+        )
+        .await
+        .map_err(|e| Error::platform(format!("Desktop notification failed: {}", e)))?;
+
+    reply
+        .body()
+        .deserialize::<u32>()
+        .map_err(|e| Error::platform(format!("Unexpected notification server reply: {}", e)))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub async fn send_desktop_notification(_summary: &str, _body: &str, _urgency: NotificationUrgency) -> Result<u32> {
+    Err(Error::platform("Desktop notifications are only supported on Linux"))
+}
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 
 use crate::error::{Error, Result};
 
+#[derive(Clone)]
 pub struct AppPaths {
     project_dirs: Option<ProjectDirs>,
     #[cfg(test)]
@@ -1,21 +1,109 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use keyring::Entry;
 use tracing::{debug, warn, info};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 use serde::{Serialize, Deserialize};
 
 use crate::error::{Error, Result};
+use crate::platform::file_storage::{EncryptedFileStorage, FileStoreKey, SECRETS_FILE};
+use crate::platform::AppPaths;
+
+/// Passphrase unlocking the encrypted secrets file without a prompt
+pub const SECRETS_PASSPHRASE_ENV: &str = "VALECHAT_SECRETS_PASSPHRASE";
+
+/// Where API keys and other secrets are kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecretsConfig {
+    pub backend: SecretsBackend,
+    /// Key file unlocking the encrypted secrets file instead of a passphrase
+    pub key_file: Option<PathBuf>,
+    /// How long a resolved API key is reused before its source is read again
+    pub cache_ttl_seconds: u64,
+    /// Remind to rotate stored API keys this many days after they were set, unless a key has its own schedule
+    pub rotate_every_days: Option<u32>,
+}
 
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            backend: SecretsBackend::Auto,
+            key_file: None,
+            cache_ttl_seconds: 300,
+            rotate_every_days: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretsBackend {
+    /// The encrypted file if it exists, else the system keychain when it is reachable
+    #[default]
+    Auto,
+    Keyring,
+    File,
+}
+
+impl SecretsConfig {
+    pub fn file_path(paths: &AppPaths) -> PathBuf {
+        paths.config_dir().join(SECRETS_FILE)
+    }
+
+    /// The backend to use, probing the system keychain when set to `auto`
+    pub fn resolve_backend(&self, paths: &AppPaths) -> SecretsBackend {
+        match self.backend {
+            SecretsBackend::Auto if Self::file_path(paths).exists() => SecretsBackend::File,
+            SecretsBackend::Auto if keyring_available() => SecretsBackend::Keyring,
+            SecretsBackend::Auto => {
+                info!("System keychain unavailable, using the encrypted secrets file");
+                SecretsBackend::File
+            }
+            SecretsBackend::Keyring if !keyring_available() => {
+                warn!("The system keychain is unavailable; stored secrets may not persist");
+                SecretsBackend::Keyring
+            }
+            backend => backend,
+        }
+    }
+
+    /// Key from the configuration or environment, if any
+    fn file_key(&self) -> Option<FileStoreKey> {
+        self.key_file.clone().map(FileStoreKey::KeyFile).or_else(|| {
+            std::env::var(SECRETS_PASSPHRASE_ENV)
+                .ok()
+                .filter(|passphrase| !passphrase.is_empty())
+                .map(FileStoreKey::Passphrase)
+        })
+    }
+}
+
+/// Stored API keys by key id: the provider name, or `provider/name` for a named key
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ApiKeyBundle {
     pub keys: HashMap<String, String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, ApiKeyMetadata>,
     pub version: u32,
 }
 
+/// Rotation details of a stored key
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiKeyMetadata {
+    /// When the value was last set
+    pub rotated_at: DateTime<Utc>,
+    /// Days after `rotated_at` that the key should be replaced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotate_every_days: Option<u32>,
+}
+
 impl ApiKeyBundle {
     pub fn new() -> Self {
         Self {
             keys: HashMap::new(),
+            metadata: HashMap::new(),
             version: 1,
         }
     }
@@ -24,14 +112,30 @@ impl ApiKeyBundle {
         self.keys.get(provider)
     }
 
+    /// Store a key value; its rotation schedule is kept and the rotation date reset
     pub fn set_key(&mut self, provider: &str, key: &str) {
         self.keys.insert(provider.to_string(), key.to_string());
+        let rotate_every_days = self.metadata.get(provider).and_then(|metadata| metadata.rotate_every_days);
+        self.metadata.insert(provider.to_string(), ApiKeyMetadata { rotated_at: Utc::now(), rotate_every_days });
     }
 
     pub fn remove_key(&mut self, provider: &str) -> bool {
+        self.metadata.remove(provider);
         self.keys.remove(provider).is_some()
     }
 
+    /// Set or clear the rotation schedule of a stored key; returns false if there is no such key
+    pub fn set_rotation(&mut self, key_id: &str, rotate_every_days: Option<u32>) -> bool {
+        if !self.keys.contains_key(key_id) {
+            return false;
+        }
+        // Keys stored before rotation was tracked count from now
+        let metadata = self.metadata.entry(key_id.to_string())
+            .or_insert_with(|| ApiKeyMetadata { rotated_at: Utc::now(), rotate_every_days: None });
+        metadata.rotate_every_days = rotate_every_days;
+        true
+    }
+
     pub fn is_empty(&self) -> bool {
         self.keys.is_empty()
     }
@@ -55,6 +159,7 @@ pub trait SecureStorage: Send + Sync {
 
 pub struct SecureStorageManager {
     backend: Box<dyn SecureStorage>,
+    backend_name: String,
 }
 
 impl SecureStorageManager {
@@ -62,9 +167,41 @@ impl SecureStorageManager {
         let backend = create_platform_storage()?;
         Ok(Self {
             backend,
+            backend_name: "system keychain".to_string(),
         })
     }
 
+    pub fn with_backend(backend: Box<dyn SecureStorage>, backend_name: impl Into<String>) -> Self {
+        Self { backend, backend_name: backend_name.into() }
+    }
+
+    /// Open the backend selected by `config`
+    ///
+    /// The encrypted file is unlocked with the configured key file or `VALECHAT_SECRETS_PASSPHRASE`;
+    /// otherwise `prompt` is asked for a passphrase with the file path and whether the file is new.
+    /// Without one the store stays locked and every access reports how to unlock it.
+    pub fn from_config<F>(config: &SecretsConfig, paths: &AppPaths, prompt: F) -> Result<Self>
+    where
+        F: FnOnce(&Path, bool) -> Result<Option<String>>,
+    {
+        match config.resolve_backend(paths) {
+            SecretsBackend::File => {
+                let path = SecretsConfig::file_path(paths);
+                let key = match config.file_key() {
+                    Some(key) => Some(key),
+                    None => prompt(&path, !path.exists())?.map(FileStoreKey::Passphrase),
+                };
+                let name = format!("encrypted file {}", path.display());
+                Ok(Self::with_backend(Box::new(EncryptedFileStorage::new(path, key)), name))
+            }
+            _ => Self::new(),
+        }
+    }
+
+    pub fn backend_name(&self) -> &str {
+        &self.backend_name
+    }
+
     pub async fn store_api_key(&self, provider: &str, key: &str) -> Result<()> {
         debug!("Storing API key for provider: {}", provider);
         
@@ -142,6 +279,15 @@ impl SecureStorageManager {
         Ok(bundle.keys.keys().cloned().collect())
     }
 
+    /// Set or clear the rotation schedule of a stored key
+    pub async fn set_api_key_rotation(&self, key_id: &str, rotate_every_days: Option<u32>) -> Result<()> {
+        let mut bundle = self.retrieve_api_key_bundle().await?;
+        if !bundle.set_rotation(key_id, rotate_every_days) {
+            return Err(Error::validation(format!("No API key `{}` in secure storage", key_id)));
+        }
+        self.store_api_key_bundle(&bundle).await
+    }
+
     /// Store all API keys in a single keychain entry
     pub async fn store_api_key_bundle(&self, bundle: &ApiKeyBundle) -> Result<()> {
         debug!("Storing API key bundle with {} keys", bundle.keys.len());
@@ -176,6 +322,26 @@ impl SecureStorageManager {
     }
 
 
+    /// Store an application secret other than a provider API key (e.g. the backup passphrase)
+    pub async fn store_secret(&self, name: &str, value: &str) -> Result<()> {
+        debug!("Storing secret: {}", name);
+        self.backend.store(SECRETS_SERVICE, name, value).await
+    }
+
+    pub async fn retrieve_secret(&self, name: &str) -> Result<Option<String>> {
+        self.backend.retrieve(SECRETS_SERVICE, name).await
+    }
+
+    pub async fn delete_secret(&self, name: &str) -> Result<()> {
+        debug!("Deleting secret: {}", name);
+        self.backend.delete(SECRETS_SERVICE, name).await
+    }
+
+    /// Names of the stored application secrets
+    pub async fn list_secrets(&self) -> Result<Vec<String>> {
+        self.backend.list_keys(SECRETS_SERVICE).await
+    }
+
     async fn log_key_access(&self, operation: &str, provider: &str, success: bool) -> Result<()> {
         // In a real implementation, this would write to a secure audit log
         // For now, we just use tracing
@@ -188,15 +354,58 @@ impl SecureStorageManager {
     }
 }
 
+/// Keychain service holding application secrets that are not provider API keys
+const SECRETS_SERVICE: &str = "ai.valechat.secrets";
+
+/// Entry listing the other entries of a service, since keychains cannot enumerate them
+const INDEX_KEY: &str = "valechat.index";
+
+/// Whether a system keychain keeps entries on disk and answers, e.g. a Secret Service daemon on Linux
+pub fn keyring_available() -> bool {
+    use keyring::credential::CredentialPersistence;
+
+    // Builds without a native store fall back to an in-memory mock that forgets every entry
+    if !matches!(keyring::default::default_credential_builder().persistence(), CredentialPersistence::UntilDelete) {
+        debug!("No persistent system keychain in this build");
+        return false;
+    }
+    match Entry::new(SECRETS_SERVICE, "availability-check").and_then(|entry| entry.get_password()) {
+        Ok(_) | Err(keyring::Error::NoEntry) => true,
+        Err(e) => {
+            debug!("System keychain unavailable: {}", e);
+            false
+        }
+    }
+}
+
 // Cross-platform storage implementation using keyring
 pub struct KeyringStorage;
 
+impl KeyringStorage {
+    fn read_index(service: &str) -> Result<BTreeSet<String>> {
+        match Entry::new(service, INDEX_KEY)?.get_password() {
+            Ok(index) => Ok(serde_json::from_str(&index).unwrap_or_default()),
+            Err(keyring::Error::NoEntry) => Ok(BTreeSet::new()),
+            Err(e) => Err(Error::SecureStorage(e)),
+        }
+    }
+
+    fn update_index(service: &str, key: &str, present: bool) -> Result<()> {
+        let mut index = Self::read_index(service)?;
+        let changed = if present { index.insert(key.to_string()) } else { index.remove(key) };
+        if changed {
+            Entry::new(service, INDEX_KEY)?.set_password(&serde_json::to_string(&index)?)?;
+        }
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl SecureStorage for KeyringStorage {
     async fn store(&self, service: &str, key: &str, value: &str) -> Result<()> {
         let entry = Entry::new(service, key)?;
         entry.set_password(value)?;
-        Ok(())
+        Self::update_index(service, key, true)
     }
 
     async fn retrieve(&self, service: &str, key: &str) -> Result<Option<String>> {
@@ -211,18 +420,16 @@ impl SecureStorage for KeyringStorage {
     async fn delete(&self, service: &str, key: &str) -> Result<()> {
         let entry = Entry::new(service, key)?;
         match entry.delete_credential() {
-            Ok(()) => Ok(()),
-            Err(keyring::Error::NoEntry) => Ok(()), // Already deleted
-            Err(e) => Err(Error::SecureStorage(e)),
+            Ok(()) => {}
+            Err(keyring::Error::NoEntry) => {} // Already deleted
+            Err(e) => return Err(Error::SecureStorage(e)),
         }
+        Self::update_index(service, key, false)
     }
 
-    async fn list_keys(&self, _service: &str) -> Result<Vec<String>> {
-        // Note: keyring crate doesn't support listing keys directly
-        // In a real implementation, we might maintain a separate index
-        // For now, return empty list
-        warn!("list_keys not fully implemented for keyring backend");
-        Ok(Vec::new())
+    /// Entries written through this backend; older entries appear once they are stored again
+    async fn list_keys(&self, service: &str) -> Result<Vec<String>> {
+        Ok(Self::read_index(service)?.into_iter().collect())
     }
 }
 
@@ -251,6 +458,24 @@ fn create_platform_storage() -> Result<Box<dyn SecureStorage>> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_bundle_rotation_metadata() {
+        // Bundles written before keys had metadata still load
+        let mut bundle = ApiKeyBundle::from_json(r#"{"keys":{"openai":"sk-old"},"version":1}"#).unwrap();
+        assert!(bundle.metadata.is_empty());
+        assert!(bundle.set_rotation("openai", Some(90)));
+        assert!(!bundle.set_rotation("openai/team-a", Some(90)));
+
+        bundle.set_key("openai/team-a", "sk-team-a");
+        bundle.set_key("openai", "sk-new");
+        let reloaded = ApiKeyBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        assert_eq!(reloaded.metadata["openai"].rotate_every_days, Some(90));
+        assert_eq!(reloaded.metadata["openai/team-a"].rotate_every_days, None);
+
+        bundle.remove_key("openai/team-a");
+        assert!(!bundle.metadata.contains_key("openai/team-a"));
+    }
+
     #[tokio::test]
     async fn test_secure_storage_roundtrip() {
         let storage = SecureStorageManager::new().unwrap();
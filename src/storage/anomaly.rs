@@ -0,0 +1,279 @@
+use std::collections::{HashMap, VecDeque};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::{Decimal, prelude::ToPrimitive};
+use sqlx::{Row, SqlitePool};
+use tracing::debug;
+
+use crate::error::Result;
+use crate::storage::decimal_helpers;
+
+/// Scales the MAD to the standard deviation of normally distributed data
+const MAD_SCALE: f64 = 1.4826;
+/// Lower bound of the spread, relative to the median, so uniform history does not flag every change
+const MIN_RELATIVE_SPREAD: f64 = 0.05;
+/// A conversation idle for this long counts as a finished session
+const SESSION_IDLE_MINUTES: i64 = 60;
+
+/// Median and median absolute deviation over the most recent values
+///
+/// Values are kept in arrival order and in sorted order, so each update is a bounded
+/// insert/remove rather than a rescan of the history.
+#[derive(Debug, Clone)]
+pub struct RollingBaseline {
+    capacity: usize,
+    values: VecDeque<Decimal>,
+    sorted: Vec<Decimal>,
+}
+
+impl RollingBaseline {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            values: VecDeque::new(),
+            sorted: Vec::new(),
+        }
+    }
+
+    /// Add a value, evicting the oldest once the window is full
+    pub fn push(&mut self, value: Decimal) {
+        if self.values.len() == self.capacity {
+            if let Some(oldest) = self.values.pop_front() {
+                if let Ok(index) = self.sorted.binary_search(&oldest) {
+                    self.sorted.remove(index);
+                }
+            }
+        }
+        self.values.push_back(value);
+        let index = self.sorted.partition_point(|v| *v < value);
+        self.sorted.insert(index, value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn median(&self) -> Option<Decimal> {
+        median_of_sorted(&self.sorted)
+    }
+
+    /// Median absolute deviation from the median
+    pub fn mad(&self) -> Option<Decimal> {
+        let median = self.median()?;
+        let mut deviations: Vec<Decimal> = self.sorted.iter().map(|v| (*v - median).abs()).collect();
+        deviations.sort();
+        median_of_sorted(&deviations)
+    }
+
+    /// Robust z-score of `value`: distance from the median in scaled MADs
+    pub fn score(&self, value: Decimal) -> Option<f64> {
+        let median = self.median()?.to_f64()?;
+        let mad = self.mad()?.to_f64()?;
+        let spread = (mad * MAD_SCALE).max(median * MIN_RELATIVE_SPREAD).max(f64::EPSILON);
+        Some((value.to_f64()? - median) / spread)
+    }
+}
+
+fn median_of_sorted(sorted: &[Decimal]) -> Option<Decimal> {
+    let n = sorted.len();
+    match n {
+        0 => None,
+        _ if n % 2 == 1 => Some(sorted[n / 2]),
+        _ => Some((sorted[n / 2 - 1] + sorted[n / 2]) / Decimal::from(2)),
+    }
+}
+
+/// Spend of one conversation that is still active
+#[derive(Debug, Clone)]
+pub struct SessionActivity {
+    pub total: Decimal,
+    pub last_request: DateTime<Utc>,
+    /// Request times and costs inside the burst window
+    recent: VecDeque<(DateTime<Utc>, Decimal)>,
+}
+
+impl SessionActivity {
+    fn new(at: DateTime<Utc>) -> Self {
+        Self { total: Decimal::ZERO, last_request: at, recent: VecDeque::new() }
+    }
+
+    /// Requests and their cost within `window` of the latest request
+    pub fn burst(&self, window: Duration) -> (usize, Decimal) {
+        let since = self.last_request - window;
+        self.recent.iter()
+            .filter(|(at, _)| *at > since)
+            .fold((0, Decimal::ZERO), |(count, cost), (_, c)| (count + 1, cost + c))
+    }
+}
+
+/// Request and session spend baselines per provider and model
+#[derive(Debug, Clone)]
+pub struct SpendBaselines {
+    capacity: usize,
+    min_samples: usize,
+    burst_window: Duration,
+    per_model: HashMap<(String, String), RollingBaseline>,
+    per_provider: HashMap<String, RollingBaseline>,
+    /// Totals of finished sessions
+    sessions: RollingBaseline,
+    active_sessions: HashMap<String, SessionActivity>,
+}
+
+impl SpendBaselines {
+    pub fn new(capacity: usize, min_samples: usize, burst_window: Duration) -> Self {
+        Self {
+            capacity,
+            min_samples,
+            burst_window,
+            per_model: HashMap::new(),
+            per_provider: HashMap::new(),
+            sessions: RollingBaseline::new(capacity),
+            active_sessions: HashMap::new(),
+        }
+    }
+
+    /// Build baselines from the last `days` of recorded usage
+    pub async fn load(&mut self, pool: &SqlitePool, days: u32) -> Result<()> {
+        let since = (Utc::now() - Duration::days(days as i64)).timestamp();
+        let rows = sqlx::query(
+            "SELECT provider, model, cost, conversation_id, timestamp FROM usage_records \
+             WHERE timestamp >= ? ORDER BY timestamp, id",
+        )
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+
+        for row in &rows {
+            let cost = decimal_helpers::string_to_decimal(&row.get::<String, _>("cost"))?;
+            let at = Utc.timestamp_opt(row.get::<i64, _>("timestamp"), 0).single().unwrap_or_else(Utc::now);
+            self.record(
+                &row.get::<String, _>("provider"),
+                &row.get::<String, _>("model"),
+                row.get::<Option<String>, _>("conversation_id").as_deref(),
+                cost,
+                at,
+            );
+        }
+
+        debug!("Loaded spend baselines from {} usage records", rows.len());
+        Ok(())
+    }
+
+    /// Baseline for requests to `provider`/`model`, falling back to the provider's
+    /// when the model has too little history
+    pub fn request_baseline(&self, provider: &str, model: &str) -> Option<&RollingBaseline> {
+        self.per_model.get(&(provider.to_string(), model.to_string()))
+            .filter(|baseline| baseline.len() >= self.min_samples)
+            .or_else(|| self.per_provider.get(provider).filter(|baseline| baseline.len() >= self.min_samples))
+    }
+
+    /// Baseline of finished session totals
+    pub fn session_baseline(&self) -> Option<&RollingBaseline> {
+        Some(&self.sessions).filter(|baseline| baseline.len() >= self.min_samples)
+    }
+
+    pub fn session(&self, conversation_id: &str) -> Option<&SessionActivity> {
+        self.active_sessions.get(conversation_id)
+    }
+
+    pub fn burst_window(&self) -> Duration {
+        self.burst_window
+    }
+
+    /// Add a completed request to the baselines
+    pub fn record(&mut self, provider: &str, model: &str, conversation_id: Option<&str>, cost: Decimal, at: DateTime<Utc>) {
+        let capacity = self.capacity;
+        self.per_model
+            .entry((provider.to_string(), model.to_string()))
+            .or_insert_with(|| RollingBaseline::new(capacity))
+            .push(cost);
+        self.per_provider
+            .entry(provider.to_string())
+            .or_insert_with(|| RollingBaseline::new(capacity))
+            .push(cost);
+
+        self.close_idle_sessions(at);
+
+        if let Some(conversation_id) = conversation_id {
+            let session = self.active_sessions
+                .entry(conversation_id.to_string())
+                .or_insert_with(|| SessionActivity::new(at));
+            session.total += cost;
+            session.last_request = at;
+            session.recent.push_back((at, cost));
+            let since = at - self.burst_window;
+            while session.recent.front().is_some_and(|(t, _)| *t <= since) {
+                session.recent.pop_front();
+            }
+        }
+    }
+
+    /// Move sessions that went quiet into the session baseline
+    fn close_idle_sessions(&mut self, now: DateTime<Utc>) {
+        let cutoff = now - Duration::minutes(SESSION_IDLE_MINUTES);
+        let idle: Vec<String> = self.active_sessions.iter()
+            .filter(|(_, session)| session.last_request < cutoff)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in idle {
+            if let Some(session) = self.active_sessions.remove(&id) {
+                self.sessions.push(session.total);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_median_and_mad() {
+        let mut baseline = RollingBaseline::new(5);
+        for cents in [10, 12, 11, 13, 100] {
+            baseline.push(Decimal::new(cents, 2));
+        }
+        assert_eq!(baseline.median(), Some(Decimal::new(12, 2)));
+        assert_eq!(baseline.mad(), Some(Decimal::new(1, 2)));
+
+        // The outlier barely moves the median and scores far above it
+        assert!(baseline.score(Decimal::new(100, 2)).unwrap() > 10.0);
+        assert!(baseline.score(Decimal::new(12, 2)).unwrap().abs() < f64::EPSILON);
+
+        // Oldest values are evicted once the window is full
+        for _ in 0..5 {
+            baseline.push(Decimal::ONE);
+        }
+        assert_eq!(baseline.len(), 5);
+        assert_eq!(baseline.median(), Some(Decimal::ONE));
+    }
+
+    #[test]
+    fn test_sessions_and_fallback_baselines() {
+        let start = Utc::now() - Duration::hours(5);
+        let mut baselines = SpendBaselines::new(100, 3, Duration::minutes(5));
+
+        for i in 0..4 {
+            let at = start + Duration::hours(i);
+            baselines.record("openai", "gpt-4", Some(&format!("conv-{}", i)), Decimal::new(5, 2), at);
+        }
+        // A new model falls back to the provider baseline
+        assert!(baselines.request_baseline("openai", "gpt-4o").is_some());
+        assert!(baselines.request_baseline("anthropic", "claude-3").is_none());
+        assert!(baselines.session_baseline().is_none());
+
+        let now = start + Duration::hours(4);
+        for i in 0..3 {
+            baselines.record("openai", "gpt-4", Some("conv-3"), Decimal::new(1, 2), now + Duration::seconds(i));
+        }
+
+        // Conversations idle for an hour became the session baseline
+        assert_eq!(baselines.session_baseline().map(|b| b.len()), Some(3));
+        let session = baselines.session("conv-3").unwrap();
+        assert_eq!(session.total, Decimal::new(8, 2));
+        assert_eq!(session.burst(baselines.burst_window()), (3, Decimal::new(3, 2)));
+    }
+}
@@ -1,26 +1,37 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::fs;
+use parking_lot::RwLock;
+use sha2::{Digest, Sha256};
 use sqlx::SqlitePool;
 use tracing::{debug, info, warn, error};
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
-use tokio::time::{interval, Duration};
+use tokio::time::Duration;
 use std::sync::Arc;
 
 use crate::error::{Error, Result};
-use crate::storage::Database;
+use crate::platform::crypto;
+use crate::storage::{Database, Migrator};
 use crate::platform::AppPaths;
 
+/// Header of an incremental backup: the pages that changed since its parent
+const PAGE_DIFF_MAGIC: &[u8; 8] = b"VCPAGES1";
+const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+
 /// Backup and recovery system for ValeChat data
 pub struct BackupSystem {
     database: Arc<Database>,
     pool: SqlitePool,
     paths: Arc<AppPaths>,
     config: BackupConfig,
+    /// Passphrase for encrypting new backups and opening encrypted ones
+    passphrase: RwLock<Option<String>>,
 }
 
 /// Configuration for backup system
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct BackupConfig {
     pub enabled: bool,
     pub backup_dir: PathBuf,
@@ -30,6 +41,12 @@ pub struct BackupConfig {
     pub compress_backups: bool,
     pub verify_backups: bool,
     pub exclude_tables: Vec<String>, // Tables to exclude from backup
+    /// Encrypt backups with a key derived from the backup passphrase
+    pub encrypt: bool,
+    /// Store scheduled backups as the pages changed since the previous backup
+    pub incremental: bool,
+    /// Incremental backups taken on top of a full backup before the next full one
+    pub full_backup_every: u32,
 }
 
 impl Default for BackupConfig {
@@ -43,6 +60,9 @@ impl Default for BackupConfig {
             compress_backups: true,
             verify_backups: true,
             exclude_tables: vec![], // No exclusions by default
+            encrypt: false,
+            incremental: true,
+            full_backup_every: 7,
         }
     }
 }
@@ -60,6 +80,11 @@ pub struct BackupInfo {
     pub database_version: i32,
     pub tables_included: Vec<String>,
     pub metadata: BackupMetadata,
+    /// Backup an incremental backup was taken against
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub encrypted: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -70,6 +95,17 @@ pub enum BackupType {
     Scheduled,
 }
 
+impl BackupType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackupType::Full => "full",
+            BackupType::Incremental => "incremental",
+            BackupType::Manual => "manual",
+            BackupType::Scheduled => "scheduled",
+        }
+    }
+}
+
 /// Metadata about the backup content
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupMetadata {
@@ -77,6 +113,7 @@ pub struct BackupMetadata {
     pub conversations_count: u64,
     pub messages_count: u64,
     pub usage_records_count: u64,
+    /// SHA-256 of the database image the backup restores
     pub checksum: String,
 }
 
@@ -84,6 +121,7 @@ pub struct BackupMetadata {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecoveryOptions {
     pub backup_id: String,
+    /// Write the restored database here instead of replacing the live one
     pub target_path: Option<PathBuf>,
     pub verify_before_restore: bool,
     pub create_backup_before_restore: bool,
@@ -110,15 +148,44 @@ impl BackupSystem {
             pool,
             paths,
             config,
+            passphrase: RwLock::new(None),
+        }
+    }
+
+    pub fn config(&self) -> &BackupConfig {
+        &self.config
+    }
+
+    /// The same backup system under a new configuration, keeping the passphrase
+    pub fn with_config(&self, config: BackupConfig) -> Self {
+        Self {
+            database: self.database.clone(),
+            pool: self.pool.clone(),
+            paths: self.paths.clone(),
+            config,
+            passphrase: RwLock::new(self.passphrase.read().clone()),
         }
     }
 
+    /// Set or clear the passphrase used for encrypted backups
+    pub fn set_passphrase(&self, passphrase: Option<String>) {
+        *self.passphrase.write() = passphrase.filter(|p| !p.is_empty());
+    }
+
+    pub fn has_passphrase(&self) -> bool {
+        self.passphrase.read().is_some()
+    }
+
+    pub fn backup_dir(&self) -> PathBuf {
+        self.paths.data_dir().join(&self.config.backup_dir)
+    }
+
     /// Initialize the backup system
     pub async fn initialize(&self) -> Result<()> {
         info!("Initializing backup system");
 
         // Create backup directory
-        let backup_dir = self.paths.data_dir().join(&self.config.backup_dir);
+        let backup_dir = self.backup_dir();
         if !backup_dir.exists() {
             fs::create_dir_all(&backup_dir)?;
             info!("Created backup directory: {:?}", backup_dir);
@@ -143,68 +210,86 @@ impl BackupSystem {
         Ok(())
     }
 
-    /// Create a full backup
+    /// Create a backup
+    ///
+    /// `BackupType::Incremental` stores only the pages changed since the newest backup,
+    /// and falls back to a full backup when there is none or its chain is long enough.
     pub async fn create_backup(&self, backup_type: BackupType) -> Result<BackupInfo> {
         if !self.config.enabled {
             return Err(Error::platform("Backup system is disabled"));
         }
+        if self.config.encrypt && !self.has_passphrase() {
+            return Err(Error::validation("Backup encryption is enabled but no backup passphrase is set"));
+        }
 
-        let backup_id = format!("backup_{}", Utc::now().format("%Y%m%d_%H%M%S"));
+        let backup_id = format!("backup_{}", Utc::now().format("%Y%m%d_%H%M%S_%3f"));
         info!("Creating backup: {}", backup_id);
 
-        // Generate backup file path
-        let backup_dir = self.paths.data_dir().join(&self.config.backup_dir);
-        let backup_file = backup_dir.join(format!("{}.db", backup_id));
+        let backup_dir = self.backup_dir();
+        fs::create_dir_all(&backup_dir)?;
 
         // Check available space
-        self.check_available_space(&backup_file).await?;
+        self.check_available_space(&backup_dir).await?;
 
-        // Create the backup using SQLite's backup API
+        // Snapshot the live database with SQLite's online backup API
         let start_time = Utc::now();
-        self.database.backup(&backup_file).await?;
-
-        // Get backup file size
-        let _size_bytes = fs::metadata(&backup_file)?.len();
-        
-        // Compress if configured
-        let final_path = if self.config.compress_backups {
-            self.compress_backup(&backup_file).await?
-        } else {
-            backup_file
+        let snapshot_path = backup_dir.join(format!(".{}.snapshot", backup_id));
+        let snapshot = self.database.snapshot(&snapshot_path).await
+            .and_then(|_| Ok(fs::read(&snapshot_path)?));
+        let _ = fs::remove_file(&snapshot_path);
+        let image = snapshot?;
+        let checksum = sha256_hex(&image);
+
+        let parent = match backup_type {
+            BackupType::Incremental => self.incremental_parent().await,
+            _ => None,
         };
-
-        // Get final size after compression
-        let final_size = fs::metadata(&final_path)?.len();
-
-        // Generate metadata
-        let metadata = self.generate_backup_metadata().await?;
-
-        // Verify backup if configured
-        let verified = if self.config.verify_backups {
-            self.verify_backup(&final_path).await.unwrap_or(false)
-        } else {
-            false
+        let (payload, parent_id, backup_type) = match parent {
+            Some((parent, parent_image)) => match page_diff(&parent_image, &image) {
+                Some(diff) => (diff, Some(parent.id), BackupType::Incremental),
+                None => (image, None, BackupType::Full),
+            },
+            None if backup_type == BackupType::Incremental => (image, None, BackupType::Full),
+            None => (image, None, backup_type),
         };
 
-        let backup_info = BackupInfo {
+        let extension = if parent_id.is_some() { "pages" } else { "db" };
+        let mut file_name = format!("{}.{}", backup_id, extension);
+        if self.config.compress_backups {
+            file_name.push_str(".gz");
+        }
+        if self.config.encrypt {
+            file_name.push_str(".enc");
+        }
+        let final_path = backup_dir.join(file_name);
+        fs::write(&final_path, self.encode_payload(payload)?)?;
+
+        let mut backup_info = BackupInfo {
             id: backup_id,
             created_at: start_time,
+            size_bytes: fs::metadata(&final_path)?.len(),
             file_path: final_path,
-            size_bytes: final_size,
             compressed: self.config.compress_backups,
-            verified,
+            verified: false,
             backup_type,
             database_version: self.get_database_version().await?,
             tables_included: self.get_table_list().await?,
-            metadata,
+            metadata: self.generate_backup_metadata(checksum).await?,
+            parent_id,
+            encrypted: self.config.encrypt,
         };
 
+        // Verify backup if configured
+        if self.config.verify_backups {
+            backup_info.verified = self.verify_backup(&backup_info).await.unwrap_or(false);
+        }
+
         // Store backup info
         self.store_backup_info(&backup_info).await?;
 
         info!(
-            "Backup created successfully: {} ({} bytes)",
-            backup_info.id, backup_info.size_bytes
+            "Backup created successfully: {} ({}, {} bytes)",
+            backup_info.id, backup_info.backup_type.as_str(), backup_info.size_bytes
         );
 
         Ok(backup_info)
@@ -214,23 +299,23 @@ impl BackupSystem {
     pub async fn list_backups(&self) -> Result<Vec<BackupInfo>> {
         debug!("Listing available backups");
 
-        let backup_dir = self.paths.data_dir().join(&self.config.backup_dir);
+        let backup_dir = self.backup_dir();
         if !backup_dir.exists() {
             return Ok(Vec::new());
         }
 
-        let mut backups = Vec::new();
+        let mut backups: Vec<BackupInfo> = Vec::new();
         let entries = fs::read_dir(&backup_dir)?;
 
         for entry in entries {
             let entry = entry?;
             let path = entry.path();
-            
+
             // Look for backup info files
             if path.extension().and_then(|s| s.to_str()) == Some("info") {
                 if let Ok(backup_info) = self.load_backup_info(&path).await {
                     // Verify the backup file still exists
-                    if backup_info.file_path.exists() {
+                    if backup_info.file_path.exists() && backups.iter().all(|b| b.id != backup_info.id) {
                         backups.push(backup_info);
                     }
                 }
@@ -244,47 +329,52 @@ impl BackupSystem {
         Ok(backups)
     }
 
+    pub async fn find_backup(&self, backup_id: &str) -> Result<BackupInfo> {
+        self.list_backups().await?
+            .into_iter()
+            .find(|b| b.id == backup_id)
+            .ok_or_else(|| Error::platform(format!("Backup not found: {}", backup_id)))
+    }
+
     /// Restore from a backup
+    ///
+    /// Without a target path the live database is replaced in place and migrated to the
+    /// current schema.
     pub async fn restore_backup(&self, options: RecoveryOptions) -> Result<RecoveryResult> {
         info!("Starting backup restoration: {}", options.backup_id);
 
         let start_time = std::time::Instant::now();
-        let warnings = Vec::new();
+        let mut warnings = Vec::new();
         let mut pre_restore_backup_id = None;
 
-        // Find the backup
+        // Find the backup and rebuild the database image it holds
         let backups = self.list_backups().await?;
         let backup = backups.iter()
             .find(|b| b.id == options.backup_id)
             .ok_or_else(|| Error::platform(format!("Backup not found: {}", options.backup_id)))?;
+        let image = self.materialize(backup, &backups)?;
 
         // Verify backup before restore if requested
-        if options.verify_before_restore && !self.verify_backup(&backup.file_path).await? {
+        if options.verify_before_restore && !self.verify_image(backup, &image).await? {
             return Err(Error::platform("Backup verification failed"));
         }
 
-        // Create backup before restore if requested
-        if options.create_backup_before_restore {
-            let pre_backup = self.create_backup(BackupType::Manual).await?;
-            let backup_id = pre_backup.id.clone();
-            pre_restore_backup_id = Some(pre_backup.id);
-            info!("Created pre-restore backup: {}", backup_id);
-        }
-
-        // Determine target path
-        let target_path = options.target_path.clone().unwrap_or_else(|| {
-            self.paths.database_file()
-        });
+        let restore_file = self.backup_dir().join(format!(".{}.restore", backup.id));
+        fs::write(&restore_file, &image)?;
+        let result = self.restore_image(backup, &restore_file, &options, &mut pre_restore_backup_id).await;
+        let _ = fs::remove_file(&restore_file);
+        result?;
 
-        // Perform the restoration
-        let records_restored = self.perform_restore(backup, &target_path, &options).await?;
+        if options.tables_to_restore.is_some() {
+            warnings.push("Restoring individual tables is not supported; all tables were restored".to_string());
+        }
 
         let duration = start_time.elapsed();
-        
+
         let result = RecoveryResult {
             success: true,
             restored_tables: backup.tables_included.clone(),
-            records_restored,
+            records_restored: backup.metadata.total_records,
             duration_seconds: duration.as_secs(),
             warnings,
             pre_restore_backup_id,
@@ -294,7 +384,58 @@ impl BackupSystem {
         Ok(result)
     }
 
+    async fn restore_image(
+        &self,
+        backup: &BackupInfo,
+        restore_file: &Path,
+        options: &RecoveryOptions,
+        pre_restore_backup_id: &mut Option<String>,
+    ) -> Result<()> {
+        // A backup from a newer release cannot be migrated down by this one
+        let backup_version = image_schema_version(restore_file).await?;
+        let latest = self.database.migrator().latest_version();
+        if backup_version > latest {
+            return Err(Error::validation(format!(
+                "Backup {} has schema version {}, newer than this release supports ({})",
+                backup.id, backup_version, latest
+            )));
+        }
+
+        if let Some(target_path) = &options.target_path {
+            info!("Restoring backup to: {:?}", target_path);
+            fs::copy(restore_file, target_path)?;
+            return Ok(());
+        }
+
+        // Create backup before restore if requested
+        if options.create_backup_before_restore {
+            let pre_backup = self.create_backup(BackupType::Manual).await?;
+            info!("Created pre-restore backup: {}", pre_backup.id);
+            *pre_restore_backup_id = Some(pre_backup.id);
+        }
+
+        info!("Restoring backup {} into the live database", backup.id);
+        self.database.restore_from(restore_file).await?;
+        self.database.migrator().run().await?;
+        Ok(())
+    }
+
+    /// Check that a backup decrypts, rebuilds to the recorded checksum and passes
+    /// SQLite's integrity check, recording the result in its info file
+    pub async fn verify(&self, backup_id: &str) -> Result<bool> {
+        let mut backup = self.find_backup(backup_id).await?;
+        let verified = self.verify_backup(&backup).await?;
+        if backup.verified != verified {
+            backup.verified = verified;
+            self.store_backup_info(&backup).await?;
+        }
+        Ok(verified)
+    }
+
     /// Start automatic backup scheduler
+    ///
+    /// Runs until the task is dropped; the first backup is due one interval after the
+    /// newest existing backup.
     pub async fn start_auto_backup(&self) -> Result<()> {
         if !self.config.enabled || self.config.auto_backup_interval_hours == 0 {
             info!("Auto backup is disabled");
@@ -302,18 +443,26 @@ impl BackupSystem {
         }
 
         info!("Starting auto backup scheduler (every {} hours)", self.config.auto_backup_interval_hours);
+        let interval = chrono::Duration::hours(self.config.auto_backup_interval_hours as i64);
 
-        let mut interval = interval(Duration::from_secs(self.config.auto_backup_interval_hours * 3600));
-        
         loop {
-            interval.tick().await;
-            
-            match self.create_backup(BackupType::Scheduled).await {
+            let newest = self.list_backups().await?.into_iter().next();
+            if let Some(due) = newest.map(|b| b.created_at + interval) {
+                if let Ok(wait) = (due - Utc::now()).to_std() {
+                    debug!("Next scheduled backup at {}", due);
+                    tokio::time::sleep(wait).await;
+                }
+            }
+
+            let backup_type = if self.config.incremental { BackupType::Incremental } else { BackupType::Scheduled };
+            match self.create_backup(backup_type).await {
                 Ok(backup_info) => {
                     info!("Scheduled backup created: {}", backup_info.id);
                 }
                 Err(e) => {
                     error!("Scheduled backup failed: {}", e);
+                    // Retry later rather than spinning on a persistent failure
+                    tokio::time::sleep(Duration::from_secs(3600)).await;
                 }
             }
 
@@ -324,25 +473,59 @@ impl BackupSystem {
         }
     }
 
-    /// Clean up old backups based on retention policy
-    async fn cleanup_old_backups(&self) -> Result<u32> {
-        debug!("Cleaning up old backups");
-
+    /// Delete backup chains older than the retention period
+    ///
+    /// A chain is a full backup and the incremental backups built on it; chains are only
+    /// removed whole, once their newest backup is past retention, and the newest
+    /// `keep_chains` chains are always kept. Returns the backups removed (or that would be,
+    /// with `dry_run`).
+    pub async fn prune_backups(&self, keep_chains: usize, dry_run: bool) -> Result<Vec<BackupInfo>> {
         let backups = self.list_backups().await?;
-        let cutoff_date = Utc::now() - chrono::Duration::days(self.config.retention_days as i64);
-        
-        let mut deleted_count = 0;
-        for backup in backups {
-            if backup.created_at < cutoff_date {
-                if let Err(e) = self.delete_backup(&backup).await {
-                    warn!("Failed to delete old backup {}: {}", backup.id, e);
-                } else {
-                    deleted_count += 1;
+        let cutoff = Utc::now() - chrono::Duration::days(self.config.retention_days as i64);
+
+        let by_id: HashMap<&str, &BackupInfo> = backups.iter().map(|b| (b.id.as_str(), b)).collect();
+        let root_of = |backup: &BackupInfo| -> String {
+            let mut current = backup;
+            for _ in 0..backups.len() {
+                match current.parent_id.as_deref().and_then(|id| by_id.get(id)) {
+                    Some(parent) => current = parent,
+                    None => break,
+                }
+            }
+            current.id.clone()
+        };
+
+        // Backups are newest first, so chains come out ordered by their newest member
+        let mut chains: Vec<(String, DateTime<Utc>, Vec<&BackupInfo>)> = Vec::new();
+        for backup in &backups {
+            let root = root_of(backup);
+            match chains.iter_mut().find(|(id, _, _)| *id == root) {
+                Some((_, _, members)) => members.push(backup),
+                None => chains.push((root, backup.created_at, vec![backup])),
+            }
+        }
+
+        let mut pruned = Vec::new();
+        for (_, newest, members) in chains.into_iter().skip(keep_chains) {
+            if newest >= cutoff {
+                continue;
+            }
+            for backup in members {
+                if !dry_run {
+                    self.delete_backup(backup).await?;
                     info!("Deleted old backup: {}", backup.id);
                 }
+                pruned.push(backup.clone());
             }
         }
+        Ok(pruned)
+    }
 
+    /// Clean up old backups based on retention policy
+    async fn cleanup_old_backups(&self) -> Result<u32> {
+        debug!("Cleaning up old backups");
+
+        let deleted_count = self.prune_backups(1, false).await?.len() as u32;
         if deleted_count > 0 {
             info!("Cleaned up {} old backups", deleted_count);
         }
@@ -357,38 +540,65 @@ impl BackupSystem {
             fs::remove_file(&backup.file_path)?;
         }
 
-        // Delete backup info file
-        let info_file = backup.file_path.with_extension("info");
-        if info_file.exists() {
-            fs::remove_file(&info_file)?;
+        // Delete backup info file, including where older releases put it
+        for info_file in [self.info_path(backup), backup.file_path.with_extension("info")] {
+            if info_file.exists() {
+                fs::remove_file(&info_file)?;
+            }
         }
 
         Ok(())
     }
 
     /// Verify backup integrity
-    async fn verify_backup(&self, backup_path: &Path) -> Result<bool> {
-        debug!("Verifying backup: {:?}", backup_path);
+    async fn verify_backup(&self, backup: &BackupInfo) -> Result<bool> {
+        debug!("Verifying backup: {}", backup.id);
 
+        let backups = self.list_backups().await?;
+        match self.materialize(backup, &backups) {
+            Ok(image) => self.verify_image(backup, &image).await,
+            Err(e) => {
+                warn!("Backup {} could not be read: {}", backup.id, e);
+                Ok(false)
+            }
+        }
+    }
+
+    async fn verify_image(&self, backup: &BackupInfo, image: &[u8]) -> Result<bool> {
+        // Older backups recorded a record count instead of a hash
+        let checksum = &backup.metadata.checksum;
+        if checksum.len() == 64 && *checksum != sha256_hex(image) {
+            warn!("Backup {} does not match its checksum", backup.id);
+            return Ok(false);
+        }
+
+        let verify_file = self.backup_dir().join(format!(".{}.verify", backup.id));
+        fs::write(&verify_file, image)?;
+        let result = self.check_integrity(&verify_file).await;
+        let _ = fs::remove_file(&verify_file);
+        result
+    }
+
+    async fn check_integrity(&self, database_path: &Path) -> Result<bool> {
         // For SQLite backups, we can verify by trying to open and check integrity
-        let backup_url = format!("sqlite:{}?mode=ro", backup_path.display());
-        
+        let backup_url = format!("sqlite:{}?mode=ro", database_path.display());
+
         match SqlitePool::connect(&backup_url).await {
             Ok(pool) => {
                 // Run integrity check
                 let integrity_result: String = sqlx::query_scalar("PRAGMA integrity_check")
                     .fetch_one(&pool)
                     .await?;
-                
+
                 pool.close().await;
-                
+
                 let is_ok = integrity_result == "ok";
                 if is_ok {
                     debug!("Backup verification passed");
                 } else {
                     warn!("Backup verification failed: {}", integrity_result);
                 }
-                
+
                 Ok(is_ok)
             }
             Err(e) => {
@@ -398,42 +608,80 @@ impl BackupSystem {
         }
     }
 
-    /// Compress a backup file
-    async fn compress_backup(&self, backup_path: &Path) -> Result<PathBuf> {
-        debug!("Compressing backup: {:?}", backup_path);
-
-        let compressed_path = backup_path.with_extension("db.gz");
-        
-        // Read the original file
-        let data = fs::read(backup_path)?;
-        
-        // Compress using gzip
-        use flate2::write::GzEncoder;
-        use flate2::Compression;
-        use std::io::Write;
-        
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(&data)?;
-        let compressed_data = encoder.finish()?;
-        
-        // Write compressed data
-        fs::write(&compressed_path, compressed_data)?;
-        
-        // Remove original file
-        fs::remove_file(backup_path)?;
-        
-        info!(
-            "Backup compressed: {} -> {} ({:.1}% reduction)",
-            backup_path.display(),
-            compressed_path.display(),
-            (1.0 - (fs::metadata(&compressed_path)?.len() as f64 / data.len() as f64)) * 100.0
-        );
-        
-        Ok(compressed_path)
+    /// Newest backup and its database image, if an incremental backup can build on it
+    async fn incremental_parent(&self) -> Option<(BackupInfo, Vec<u8>)> {
+        let backups = self.list_backups().await.ok()?;
+        let newest = backups.first()?.clone();
+
+        if self.chain(&newest, &backups).ok()?.len() > self.config.full_backup_every as usize {
+            debug!("Backup chain is complete; taking a full backup");
+            return None;
+        }
+
+        match self.materialize(&newest, &backups) {
+            Ok(image) => Some((newest, image)),
+            Err(e) => {
+                warn!("Cannot build on backup {} ({}); taking a full backup", newest.id, e);
+                None
+            }
+        }
+    }
+
+    /// The backups needed to rebuild `backup`, starting with its full backup
+    fn chain<'a>(&self, backup: &'a BackupInfo, backups: &'a [BackupInfo]) -> Result<Vec<&'a BackupInfo>> {
+        let mut chain = vec![backup];
+        while let Some(parent_id) = &chain.last().unwrap().parent_id {
+            if chain.len() > backups.len() {
+                return Err(Error::platform(format!("Backup chain of {} is circular", backup.id)));
+            }
+            let parent = backups.iter()
+                .find(|b| &b.id == parent_id)
+                .ok_or_else(|| Error::platform(format!("Backup {} depends on missing backup {}", backup.id, parent_id)))?;
+            chain.push(parent);
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// Rebuild the database image a backup restores
+    fn materialize(&self, backup: &BackupInfo, backups: &[BackupInfo]) -> Result<Vec<u8>> {
+        let mut image = Vec::new();
+        for (index, member) in self.chain(backup, backups)?.into_iter().enumerate() {
+            let payload = self.decode_payload(member)?;
+            image = if index == 0 { payload } else { apply_page_diff(image, &payload)? };
+        }
+        Ok(image)
+    }
+
+    /// Compress and encrypt a backup payload as configured
+    fn encode_payload(&self, payload: Vec<u8>) -> Result<Vec<u8>> {
+        let mut data = payload;
+        if self.config.compress_backups {
+            data = compress(&data)?;
+        }
+        if self.config.encrypt {
+            let passphrase = self.passphrase.read().clone()
+                .ok_or_else(|| Error::validation("No backup passphrase is set"))?;
+            data = crypto::seal_with_passphrase(&passphrase, &data)?;
+        }
+        Ok(data)
+    }
+
+    fn decode_payload(&self, backup: &BackupInfo) -> Result<Vec<u8>> {
+        let mut data = fs::read(&backup.file_path)?;
+        if backup.encrypted || crypto::is_passphrase_sealed(&data) {
+            let passphrase = self.passphrase.read().clone()
+                .ok_or_else(|| Error::validation(format!("Backup {} is encrypted and no backup passphrase is set", backup.id)))?;
+            data = crypto::open_with_passphrase(&passphrase, &data)?;
+        }
+        if backup.compressed {
+            data = decompress(&data)?;
+        }
+        Ok(data)
     }
 
     /// Generate backup metadata
-    async fn generate_backup_metadata(&self) -> Result<BackupMetadata> {
+    async fn generate_backup_metadata(&self, checksum: String) -> Result<BackupMetadata> {
         let conversations_count: i64 = sqlx::query_scalar(
             "SELECT COUNT(*) FROM conversations"
         ).fetch_one(&self.pool).await?;
@@ -448,9 +696,6 @@ impl BackupSystem {
 
         let total_records = conversations_count + messages_count + usage_records_count;
 
-        // Generate simple checksum (in real implementation, use proper hashing)
-        let checksum = format!("{:x}", total_records);
-
         Ok(BackupMetadata {
             total_records: total_records as u64,
             conversations_count: conversations_count as u64,
@@ -478,11 +723,14 @@ impl BackupSystem {
         Ok(tables.into_iter().map(|(name,)| name).collect())
     }
 
+    fn info_path(&self, backup_info: &BackupInfo) -> PathBuf {
+        self.backup_dir().join(format!("{}.info", backup_info.id))
+    }
+
     /// Store backup information
     async fn store_backup_info(&self, backup_info: &BackupInfo) -> Result<()> {
-        let info_file = backup_info.file_path.with_extension("info");
         let json_data = serde_json::to_string_pretty(backup_info)?;
-        fs::write(&info_file, json_data)?;
+        fs::write(self.info_path(backup_info), json_data)?;
         Ok(())
     }
 
@@ -510,57 +758,96 @@ impl BackupSystem {
 
         Ok(())
     }
+}
 
-    /// Perform the actual restore operation
-    async fn perform_restore(
-        &self,
-        backup: &BackupInfo,
-        target_path: &Path,
-        _options: &RecoveryOptions,
-    ) -> Result<u64> {
-        info!("Restoring backup to: {:?}", target_path);
-
-        // If backup is compressed, decompress first
-        let restore_source = if backup.compressed {
-            self.decompress_backup(&backup.file_path).await?
-        } else {
-            backup.file_path.clone()
-        };
+/// Schema version of a database file, without the live database's pool
+async fn image_schema_version(path: &Path) -> Result<i64> {
+    let pool = SqlitePool::connect(&format!("sqlite:{}", path.display())).await?;
+    let version = Migrator::new(pool.clone()).current_version().await;
+    pool.close().await;
+    version
+}
 
-        // Copy the backup file to target location
-        fs::copy(&restore_source, target_path)?;
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
 
-        // If we decompressed, clean up the temporary file
-        if backup.compressed && restore_source != backup.file_path {
-            let _ = fs::remove_file(&restore_source);
-        }
+fn compress(data: &[u8]) -> Result<Vec<u8>> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
 
-        // Return estimated record count
-        Ok(backup.metadata.total_records)
+fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let mut decompressed = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+/// Page size of a SQLite database image
+fn page_size(image: &[u8]) -> Option<usize> {
+    if image.len() < 100 || !image.starts_with(SQLITE_MAGIC) {
+        return None;
+    }
+    match u16::from_be_bytes([image[16], image[17]]) {
+        1 => Some(65536),
+        size if size >= 512 => Some(size as usize),
+        _ => None,
+    }
+}
+
+/// Pages of `image` that differ from `base`, or `None` when the images are not comparable
+fn page_diff(base: &[u8], image: &[u8]) -> Option<Vec<u8>> {
+    let size = page_size(image)?;
+    if page_size(base)? != size || !image.len().is_multiple_of(size) {
+        return None;
+    }
+
+    let page_count = image.len() / size;
+    let mut diff = Vec::new();
+    diff.extend_from_slice(PAGE_DIFF_MAGIC);
+    diff.extend_from_slice(&(size as u32).to_le_bytes());
+    diff.extend_from_slice(&(page_count as u32).to_le_bytes());
+    for (index, page) in image.chunks(size).enumerate() {
+        if base.get(index * size..(index + 1) * size) != Some(page) {
+            diff.extend_from_slice(&(index as u32).to_le_bytes());
+            diff.extend_from_slice(page);
+        }
     }
+    Some(diff)
+}
 
-    /// Decompress a backup file
-    async fn decompress_backup(&self, compressed_path: &Path) -> Result<PathBuf> {
-        debug!("Decompressing backup: {:?}", compressed_path);
+/// Apply a diff produced by [`page_diff`] to the image it was taken against
+fn apply_page_diff(mut image: Vec<u8>, diff: &[u8]) -> Result<Vec<u8>> {
+    let invalid = || Error::platform("Incremental backup is corrupt");
+    let header = diff.strip_prefix(PAGE_DIFF_MAGIC.as_slice()).ok_or_else(invalid)?;
+    let read_u32 = |bytes: &[u8]| -> Result<usize> {
+        Ok(u32::from_le_bytes(bytes.try_into().map_err(|_| invalid())?) as usize)
+    };
+    let size = read_u32(header.get(0..4).ok_or_else(invalid)?)?;
+    let page_count = read_u32(header.get(4..8).ok_or_else(invalid)?)?;
+    if size < 512 {
+        return Err(invalid());
+    }
 
-        let temp_path = compressed_path.with_extension("tmp");
-        
-        // Read compressed data
-        let compressed_data = fs::read(compressed_path)?;
-        
-        // Decompress using gzip
-        use flate2::read::GzDecoder;
-        use std::io::Read;
-        
-        let mut decoder = GzDecoder::new(&compressed_data[..]);
-        let mut decompressed_data = Vec::new();
-        decoder.read_to_end(&mut decompressed_data)?;
-        
-        // Write decompressed data
-        fs::write(&temp_path, decompressed_data)?;
-        
-        Ok(temp_path)
+    image.resize(page_count * size, 0);
+    for entry in header[8..].chunks(4 + size) {
+        if entry.len() != 4 + size {
+            return Err(invalid());
+        }
+        let index = read_u32(&entry[..4])?;
+        image.get_mut(index * size..(index + 1) * size)
+            .ok_or_else(invalid)?
+            .copy_from_slice(&entry[4..]);
     }
+    Ok(image)
 }
 
 #[cfg(test)]
@@ -573,11 +860,11 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let paths = Arc::new(AppPaths::with_data_dir(temp_dir.path()).unwrap());
         let db = Arc::new(Database::new(&paths).await.unwrap());
-        
+
         let mut config = BackupConfig::default();
         config.compress_backups = false; // Disable compression for tests
         config.verify_backups = true;
-        
+
         let backup_system = BackupSystem::new(db, paths, config);
         (backup_system, temp_dir)
     }
@@ -600,7 +887,7 @@ mod tests {
         backup_system.initialize().await.unwrap();
 
         let backup_info = backup_system.create_backup(BackupType::Manual).await.unwrap();
-        
+
         assert!(!backup_info.id.is_empty());
         assert!(backup_info.file_path.exists());
         assert!(backup_info.size_bytes > 0);
@@ -626,8 +913,8 @@ mod tests {
         backup_system.initialize().await.unwrap();
 
         let backup_info = backup_system.create_backup(BackupType::Manual).await.unwrap();
-        
-        let is_valid = backup_system.verify_backup(&backup_info.file_path).await.unwrap();
+
+        let is_valid = backup_system.verify_backup(&backup_info).await.unwrap();
         assert!(is_valid);
     }
 
@@ -635,9 +922,71 @@ mod tests {
     async fn test_backup_metadata_generation() {
         let (backup_system, _temp_dir) = create_test_backup_system().await;
 
-        let metadata = backup_system.generate_backup_metadata().await.unwrap();
-        
-        assert!(metadata.total_records >= 0);
+        let metadata = backup_system.generate_backup_metadata(sha256_hex(b"")).await.unwrap();
+
+        assert_eq!(metadata.total_records, 0);
         assert!(!metadata.checksum.is_empty());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_incremental_encrypted_backup_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = Arc::new(AppPaths::with_data_dir(temp_dir.path()).unwrap());
+        let db = Arc::new(Database::new(&paths).await.unwrap());
+        let config = BackupConfig { encrypt: true, ..Default::default() };
+        let backups = BackupSystem::new(db.clone(), paths, config);
+
+        assert!(backups.create_backup(BackupType::Manual).await.is_err());
+        backups.set_passphrase(Some("s3cret".to_string()));
+
+        let insert = |id: &'static str| {
+            sqlx::query("INSERT INTO conversations (id, title) VALUES (?, 'Chat')").bind(id).execute(db.pool())
+        };
+        insert("c1").await.unwrap();
+        let full = backups.create_backup(BackupType::Incremental).await.unwrap();
+        assert_eq!(full.backup_type, BackupType::Full);
+
+        insert("c2").await.unwrap();
+        let incremental = backups.create_backup(BackupType::Incremental).await.unwrap();
+        assert_eq!(incremental.parent_id.as_deref(), Some(full.id.as_str()));
+        assert!(incremental.verified);
+        assert!(incremental.size_bytes < full.size_bytes);
+
+        insert("c3").await.unwrap();
+        backups.restore_backup(RecoveryOptions {
+            backup_id: incremental.id.clone(),
+            target_path: None,
+            verify_before_restore: true,
+            create_backup_before_restore: false,
+            tables_to_restore: None,
+        }).await.unwrap();
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM conversations").fetch_one(db.pool()).await.unwrap();
+        assert_eq!(count, 2);
+
+        // Without the passphrase the chain cannot be read
+        backups.set_passphrase(Some("wrong".to_string()));
+        assert!(!backups.verify(&incremental.id).await.unwrap());
+
+        // The chain is pruned as a whole once it is past retention
+        assert!(backups.prune_backups(0, true).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_page_diff_round_trip() {
+        let mut base = vec![0u8; 4096 * 3];
+        base[..16].copy_from_slice(SQLITE_MAGIC);
+        base[16..18].copy_from_slice(&4096u16.to_be_bytes());
+        let mut image = base.clone();
+        image[4096 + 10] = 7;
+        image.extend_from_slice(&[9u8; 4096]);
+
+        let diff = page_diff(&base, &image).unwrap();
+        assert_eq!(diff.len(), PAGE_DIFF_MAGIC.len() + 8 + 2 * (4 + 4096));
+        assert_eq!(apply_page_diff(base.clone(), &diff).unwrap(), image);
+
+        // Shrinking drops trailing pages
+        let diff = page_diff(&image, &base).unwrap();
+        assert_eq!(apply_page_diff(image, &diff).unwrap(), base);
+        assert!(apply_page_diff(vec![], b"garbage").is_err());
+    }
+}
@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use sqlx::SqlitePool;
 use tracing::{debug, info, warn};
 use rust_decimal::{Decimal, prelude::ToPrimitive};
-use chrono::{DateTime, Utc, Datelike};
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc};
 use serde::{Serialize, Deserialize};
 
 use crate::error::{Error, Result};
@@ -23,23 +23,139 @@ pub struct SpendingLimit {
     pub period: BillingPeriod,
     pub enabled: bool,
     pub alert_threshold: Option<f32>, // Percentage (0.0-1.0) to trigger alerts
+    #[serde(default)]
+    pub source: LimitSource,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SpendingLimitType {
     Global,
     PerProvider(String),
     PerModel(String),
+    PerProject(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BillingPeriod {
     Daily,
     Weekly,
     Monthly,
     Yearly,
+    /// Caps the cost of a single request rather than accumulated spending
+    PerRequest,
+}
+
+/// Where a spending limit was defined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitSource {
+    /// Set at runtime, e.g. through `/budget`
+    #[default]
+    Manual,
+    /// Imported from the configuration file
+    Config,
+}
+
+impl SpendingLimit {
+    /// Create an enabled, manually set limit
+    pub fn new(
+        limit_type: SpendingLimitType,
+        amount: Decimal,
+        period: BillingPeriod,
+        alert_threshold: Option<f32>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            limit_type,
+            amount,
+            period,
+            enabled: true,
+            alert_threshold,
+            source: LimitSource::Manual,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn with_source(mut self, source: LimitSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Whether this limit covers requests to the given provider and model within a project
+    pub fn applies_to(&self, provider: &str, model: &str, project: Option<&str>) -> bool {
+        match &self.limit_type {
+            SpendingLimitType::Global => true,
+            SpendingLimitType::PerProvider(name) => name == provider,
+            SpendingLimitType::PerModel(name) => name == model,
+            SpendingLimitType::PerProject(name) => project == Some(name.as_str()),
+        }
+    }
+
+    /// Human readable name, e.g. "openai daily limit"
+    pub fn describe(&self) -> String {
+        let scope = match &self.limit_type {
+            SpendingLimitType::Global => "global".to_string(),
+            SpendingLimitType::PerProvider(name) | SpendingLimitType::PerModel(name) => name.clone(),
+            SpendingLimitType::PerProject(name) => format!("project {}", name),
+        };
+        format!("{} {} limit", scope, self.period.label())
+    }
+
+    fn storage_key(&self) -> String {
+        limit_storage_key(&self.limit_type, &self.period)
+    }
+}
+
+impl SpendingLimitType {
+    /// Scope identifier, e.g. `global`, `provider:openai` or `model:gpt-4`
+    pub fn key(&self) -> String {
+        match self {
+            SpendingLimitType::Global => "global".to_string(),
+            SpendingLimitType::PerProvider(provider) => format!("provider:{}", provider),
+            SpendingLimitType::PerModel(model) => format!("model:{}", model),
+            SpendingLimitType::PerProject(project) => format!("project:{}", project),
+        }
+    }
+}
+
+impl BillingPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BillingPeriod::Daily => "daily",
+            BillingPeriod::Weekly => "weekly",
+            BillingPeriod::Monthly => "monthly",
+            BillingPeriod::Yearly => "yearly",
+            BillingPeriod::PerRequest => "request",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            BillingPeriod::PerRequest => "per-request",
+            other => other.as_str(),
+        }
+    }
+
+    /// Unix timestamp at which the window containing `now` started (UTC, weeks start on Monday)
+    ///
+    /// Returns `None` for per-request limits, which have no accumulation window.
+    pub fn window_start(&self, now: DateTime<Utc>) -> Option<i64> {
+        let today = now.date_naive();
+        let start = match self {
+            BillingPeriod::Daily => today,
+            BillingPeriod::Weekly => today - Duration::days(today.weekday().num_days_from_monday() as i64),
+            BillingPeriod::Monthly => today.with_day(1)?,
+            BillingPeriod::Yearly => today.with_ordinal(1)?,
+            BillingPeriod::PerRequest => return None,
+        };
+        Some(start.and_time(NaiveTime::MIN).and_utc().timestamp())
+    }
+}
+
+fn limit_storage_key(limit_type: &SpendingLimitType, period: &BillingPeriod) -> String {
+    format!("spending_limit_{}_{}", period.as_str(), limit_type.key())
 }
 
 /// Billing alert configuration and state
@@ -84,6 +200,8 @@ pub struct BillingReport {
     pub cost_discrepancies: Vec<VerificationResult>,
     pub by_provider: HashMap<String, ProviderBilling>,
     pub by_model: HashMap<String, ModelBilling>,
+    /// Costs attributed to projects for charge-back; unassigned usage is not listed
+    pub by_project: HashMap<String, ProjectBilling>,
     pub spending_limits: Vec<SpendingLimitStatus>,
     pub alerts: Vec<BillingAlert>,
 }
@@ -96,6 +214,14 @@ pub struct ProviderBilling {
     pub verification_rate: f32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBilling {
+    pub total_cost: Decimal,
+    pub verified_cost: Decimal,
+    pub request_count: u64,
+    pub verification_rate: f32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelBilling {
     pub provider: String,
@@ -115,6 +241,9 @@ pub struct SpendingLimitStatus {
 }
 
 /// Result of checking spending limits
+///
+/// The limit fields describe the most restrictive applicable limit, i.e. the one with the
+/// least headroom left after the proposed cost.
 #[derive(Debug, Clone)]
 pub struct SpendingCheckResult {
     pub allowed: bool,
@@ -122,6 +251,8 @@ pub struct SpendingCheckResult {
     pub current_spending: Decimal,
     pub limit: Option<Decimal>,
     pub percentage_used: Option<f32>,
+    pub limit_type: Option<SpendingLimitType>,
+    pub period: Option<BillingPeriod>,
 }
 
 impl BillingSystem {
@@ -141,41 +272,81 @@ impl BillingSystem {
     ) -> Result<()> {
         debug!("Setting spending limit: {:?} = ${}", limit_type, amount);
 
-        let limit_key = match &limit_type {
-            SpendingLimitType::Global => "global".to_string(),
-            SpendingLimitType::PerProvider(provider) => format!("provider:{}", provider),
-            SpendingLimitType::PerModel(model) => format!("model:{}", model),
-        };
+        let limit = SpendingLimit::new(limit_type, amount, period, alert_threshold);
+        self.store_limit(&limit).await?;
 
-        let period_str = match period {
-            BillingPeriod::Daily => "daily",
-            BillingPeriod::Weekly => "weekly",
-            BillingPeriod::Monthly => "monthly",
-            BillingPeriod::Yearly => "yearly",
-        };
+        info!("Set spending limit: {} = ${}", limit.storage_key(), amount);
+        Ok(())
+    }
 
-        let limit_json = serde_json::to_string(&SpendingLimit {
-            limit_type,
-            amount,
-            period,
-            enabled: true,
-            alert_threshold,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        }).map_err(|e| Error::Database(sqlx::Error::decode(format!("Failed to serialize limit: {}", e))))?;
+    async fn store_limit(&self, limit: &SpendingLimit) -> Result<()> {
+        let limit_json = serde_json::to_string(limit)
+            .map_err(|e| Error::Database(sqlx::Error::decode(format!("Failed to serialize limit: {}", e))))?;
 
         sqlx::query(
             "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES (?, ?, unixepoch())"
         )
-        .bind(format!("spending_limit_{}_{}", period_str, limit_key))
+        .bind(limit.storage_key())
         .bind(limit_json)
         .execute(&self.pool)
         .await?;
 
-        info!("Set spending limit: {} = ${}", limit_key, amount);
         Ok(())
     }
 
+    /// List every stored spending limit, ordered by storage key
+    pub async fn list_spending_limits(&self) -> Result<Vec<SpendingLimit>> {
+        let settings: Vec<(String, String)> = sqlx::query_as(
+            "SELECT key, value FROM app_settings WHERE key LIKE 'spending_limit_%' ORDER BY key"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(settings.into_iter()
+            .filter_map(|(key, value)| match serde_json::from_str::<SpendingLimit>(&value) {
+                Ok(limit) => Some(limit),
+                Err(e) => {
+                    warn!("Ignoring unreadable spending limit {}: {}", key, e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Replace the limits imported from configuration with `limits`
+    ///
+    /// Previously imported limits that are no longer configured are removed. Limits set
+    /// manually take precedence over a configured limit with the same scope and period.
+    /// Returns the number of limits written.
+    pub async fn sync_config_limits(&self, limits: Vec<SpendingLimit>) -> Result<usize> {
+        let existing = self.list_spending_limits().await?;
+        let configured: HashSet<String> = limits.iter().map(|limit| limit.storage_key()).collect();
+        let manual: HashSet<String> = existing.iter()
+            .filter(|limit| limit.source == LimitSource::Manual)
+            .map(|limit| limit.storage_key())
+            .collect();
+
+        for stale in existing.iter().filter(|limit| {
+            limit.source == LimitSource::Config && !configured.contains(&limit.storage_key())
+        }) {
+            self.remove_spending_limit(&stale.limit_type, &stale.period).await?;
+        }
+
+        let mut imported = 0;
+        for limit in limits {
+            let key = limit.storage_key();
+            if manual.contains(&key) {
+                debug!("Keeping manually set spending limit {} over configured value", key);
+                continue;
+            }
+            self.store_limit(&limit.with_source(LimitSource::Config)).await?;
+            imported += 1;
+        }
+
+        info!("Imported {} spending limits from configuration", imported);
+        Ok(imported)
+    }
+
     /// Check if a proposed cost would exceed spending limits
     pub async fn check_spending_limits(
         &self,
@@ -183,109 +354,91 @@ impl BillingSystem {
         model: &str,
         proposed_cost: Decimal,
     ) -> Result<SpendingCheckResult> {
-        debug!("Checking spending limits for cost: ${}", proposed_cost);
+        self.check_project_spending_limits(provider, model, None, proposed_cost).await
+    }
+
+    /// Check if a proposed cost would exceed spending limits, including those of `project`
+    ///
+    /// Every enabled limit covering the provider, model or project is evaluated over its own
+    /// period; the one with the least headroom decides the result.
+    pub async fn check_project_spending_limits(
+        &self,
+        provider: &str,
+        model: &str,
+        project: Option<&str>,
+        proposed_cost: Decimal,
+    ) -> Result<SpendingCheckResult> {
+        debug!("Checking spending limits for cost: ${} (project: {:?})", proposed_cost, project);
 
-        // Get current spending for different scopes
         let now = Utc::now();
-        
-        // Check global monthly limit first
-        if let Some(global_limit) = self.get_spending_limit(&SpendingLimitType::Global, &BillingPeriod::Monthly).await? {
-            let current_period = format!("{:04}-{:02}", now.year(), now.month());
-            let current_spending = self.get_period_spending(None, None, &current_period).await?;
-            let projected_spending = current_spending + proposed_cost;
-
-            if projected_spending > global_limit.amount {
-                return Ok(SpendingCheckResult {
-                    allowed: false,
-                    reason: Some(format!(
-                        "Would exceed global monthly limit of ${} (current: ${}, proposed: ${})",
-                        global_limit.amount, current_spending, proposed_cost
-                    )),
-                    current_spending,
-                    limit: Some(global_limit.amount),
-                    percentage_used: Some((projected_spending / global_limit.amount * Decimal::from(100)).to_f32().unwrap_or(0.0)),
-                });
-            }
-        }
+        let mut most_restrictive: Option<(SpendingLimit, Decimal)> = None;
 
-        // Check provider-specific limits
-        if let Some(provider_limit) = self.get_spending_limit(
-            &SpendingLimitType::PerProvider(provider.to_string()), 
-            &BillingPeriod::Monthly
-        ).await? {
-            let current_period = format!("{:04}-{:02}", now.year(), now.month());
-            let current_spending = self.get_period_spending(Some(provider), None, &current_period).await?;
-            let projected_spending = current_spending + proposed_cost;
-
-            if projected_spending > provider_limit.amount {
-                return Ok(SpendingCheckResult {
-                    allowed: false,
-                    reason: Some(format!(
-                        "Would exceed {} monthly limit of ${} (current: ${}, proposed: ${})",
-                        provider, provider_limit.amount, current_spending, proposed_cost
-                    )),
-                    current_spending,
-                    limit: Some(provider_limit.amount),
-                    percentage_used: Some((projected_spending / provider_limit.amount * Decimal::from(100)).to_f32().unwrap_or(0.0)),
-                });
+        for limit in self.list_spending_limits().await? {
+            if !limit.enabled || !limit.applies_to(provider, model, project) {
+                continue;
             }
-        }
 
-        // Check model-specific limits
-        if let Some(model_limit) = self.get_spending_limit(
-            &SpendingLimitType::PerModel(model.to_string()), 
-            &BillingPeriod::Monthly
-        ).await? {
-            let current_period = format!("{:04}-{:02}", now.year(), now.month());
-            let current_spending = self.get_model_spending(model, &current_period).await?;
-            let projected_spending = current_spending + proposed_cost;
-
-            if projected_spending > model_limit.amount {
-                return Ok(SpendingCheckResult {
-                    allowed: false,
-                    reason: Some(format!(
-                        "Would exceed {} monthly limit of ${} (current: ${}, proposed: ${})",
-                        model, model_limit.amount, current_spending, proposed_cost
-                    )),
-                    current_spending,
-                    limit: Some(model_limit.amount),
-                    percentage_used: Some((projected_spending / model_limit.amount * Decimal::from(100)).to_f32().unwrap_or(0.0)),
-                });
+            let current_spending = self.get_window_spending(&limit.limit_type, &limit.period, now).await?;
+            let headroom = limit.amount - current_spending;
+            let is_tighter = most_restrictive.as_ref()
+                .is_none_or(|(best, best_spending)| headroom < best.amount - *best_spending);
+
+            if is_tighter {
+                most_restrictive = Some((limit, current_spending));
             }
         }
 
+        let Some((limit, current_spending)) = most_restrictive else {
+            return Ok(SpendingCheckResult {
+                allowed: true,
+                reason: None,
+                current_spending: Decimal::ZERO,
+                limit: None,
+                percentage_used: None,
+                limit_type: None,
+                period: None,
+            });
+        };
+
+        let projected_spending = current_spending + proposed_cost;
+        let allowed = projected_spending <= limit.amount;
+        let reason = (!allowed).then(|| match limit.period {
+            BillingPeriod::PerRequest => format!(
+                "Would exceed {} of ${} (proposed: ${})",
+                limit.describe(), limit.amount, proposed_cost
+            ),
+            _ => format!(
+                "Would exceed {} of ${} (current: ${}, proposed: ${})",
+                limit.describe(), limit.amount, current_spending, proposed_cost
+            ),
+        });
+        let percentage_used = if limit.amount > Decimal::ZERO {
+            (projected_spending / limit.amount * Decimal::from(100)).to_f32().unwrap_or(0.0)
+        } else {
+            100.0
+        };
+
         Ok(SpendingCheckResult {
-            allowed: true,
-            reason: None,
-            current_spending: Decimal::ZERO, // Would need to calculate if needed
-            limit: None,
-            percentage_used: None,
+            allowed,
+            reason,
+            current_spending,
+            limit: Some(limit.amount),
+            percentage_used: Some(percentage_used),
+            limit_type: Some(limit.limit_type),
+            period: Some(limit.period),
         })
     }
 
     /// Get a spending limit from settings
-    async fn get_spending_limit(
+    pub async fn get_spending_limit(
         &self,
         limit_type: &SpendingLimitType,
         period: &BillingPeriod,
     ) -> Result<Option<SpendingLimit>> {
-        let limit_key = match limit_type {
-            SpendingLimitType::Global => "global".to_string(),
-            SpendingLimitType::PerProvider(provider) => format!("provider:{}", provider),
-            SpendingLimitType::PerModel(model) => format!("model:{}", model),
-        };
-
-        let period_str = match period {
-            BillingPeriod::Daily => "daily",
-            BillingPeriod::Weekly => "weekly",
-            BillingPeriod::Monthly => "monthly",
-            BillingPeriod::Yearly => "yearly",
-        };
-
         let limit_json: Option<String> = sqlx::query_scalar(
             "SELECT value FROM app_settings WHERE key = ?"
         )
-        .bind(format!("spending_limit_{}_{}", period_str, limit_key))
+        .bind(limit_storage_key(limit_type, period))
         .fetch_optional(&self.pool)
         .await?;
 
@@ -299,6 +452,51 @@ impl BillingSystem {
         }
     }
 
+    /// Get spending in the current window of `period` for a limit scope
+    async fn get_window_spending(
+        &self,
+        limit_type: &SpendingLimitType,
+        period: &BillingPeriod,
+        now: DateTime<Utc>,
+    ) -> Result<Decimal> {
+        let Some(window_start) = period.window_start(now) else {
+            return Ok(Decimal::ZERO);
+        };
+
+        let base = "SELECT COALESCE(SUM(CAST(cost AS REAL)), 0.0) FROM usage_records WHERE timestamp >= ?";
+        let total_cost: f64 = match limit_type {
+            SpendingLimitType::Global => {
+                sqlx::query_scalar(base)
+                    .bind(window_start)
+                    .fetch_one(&self.pool)
+                    .await?
+            }
+            SpendingLimitType::PerProvider(provider) => {
+                sqlx::query_scalar(&format!("{} AND provider = ?", base))
+                    .bind(window_start)
+                    .bind(provider)
+                    .fetch_one(&self.pool)
+                    .await?
+            }
+            SpendingLimitType::PerModel(model) => {
+                sqlx::query_scalar(&format!("{} AND model = ?", base))
+                    .bind(window_start)
+                    .bind(model)
+                    .fetch_one(&self.pool)
+                    .await?
+            }
+            SpendingLimitType::PerProject(project) => {
+                sqlx::query_scalar(&format!("{} AND project = ?", base))
+                    .bind(window_start)
+                    .bind(project)
+                    .fetch_one(&self.pool)
+                    .await?
+            }
+        };
+
+        Ok(Decimal::try_from(total_cost).unwrap_or(Decimal::ZERO))
+    }
+
     /// Get spending for a period and optional provider filter
     async fn get_period_spending(
         &self,
@@ -344,6 +542,19 @@ impl BillingSystem {
         Ok(Decimal::try_from(total_cost).unwrap_or(Decimal::ZERO))
     }
 
+    /// Get spending attributed to a project
+    async fn get_project_spending(&self, project: &str, billing_period: &str) -> Result<Decimal> {
+        let total_cost: f64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(CAST(cost AS REAL)), 0.0) FROM usage_records WHERE project = ? AND billing_period = ?"
+        )
+        .bind(project)
+        .bind(billing_period)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Decimal::try_from(total_cost).unwrap_or(Decimal::ZERO))
+    }
+
     /// Generate a comprehensive billing report
     pub async fn generate_billing_report(&self, billing_period: &str) -> Result<BillingReport> {
         debug!("Generating billing report for period: {}", billing_period);
@@ -424,6 +635,32 @@ impl BillingSystem {
             };
         }
 
+        // Calculate by project
+        let mut by_project = HashMap::new();
+        for record in &usage_records {
+            let Some(project) = &record.project else { continue };
+            let entry = by_project.entry(project.clone()).or_insert(ProjectBilling {
+                total_cost: Decimal::ZERO,
+                verified_cost: Decimal::ZERO,
+                request_count: 0,
+                verification_rate: 0.0,
+            });
+
+            entry.total_cost += record.cost;
+            entry.request_count += 1;
+            if record.verified {
+                entry.verified_cost += record.cost;
+            }
+        }
+
+        for (_, billing) in by_project.iter_mut() {
+            billing.verification_rate = if billing.total_cost > Decimal::ZERO {
+                (billing.verified_cost / billing.total_cost * Decimal::from(100)).to_f32().unwrap_or(0.0)
+            } else {
+                100.0
+            };
+        }
+
         // Get spending limit statuses
         let spending_limits = self.get_spending_limit_statuses(billing_period).await?;
 
@@ -442,53 +679,51 @@ impl BillingSystem {
             cost_discrepancies,
             by_provider,
             by_model,
+            by_project,
             spending_limits,
             alerts,
         })
     }
 
     /// Get status of all spending limits for a period
-    async fn get_spending_limit_statuses(&self, billing_period: &str) -> Result<Vec<SpendingLimitStatus>> {
+    pub async fn get_spending_limit_statuses(&self, billing_period: &str) -> Result<Vec<SpendingLimitStatus>> {
         let mut statuses = Vec::new();
 
-        // Get all spending limit settings
-        let settings: Vec<(String, String)> = sqlx::query_as(
-            "SELECT key, value FROM app_settings WHERE key LIKE 'spending_limit_%'"
-        )
-        .fetch_all(&self.pool)
-        .await?;
+        let now = Utc::now();
+        for limit in self.list_spending_limits().await? {
+            // Monthly limits follow the report's period; shorter and longer windows
+            // only make sense relative to now
+            let current_spending = match (&limit.period, &limit.limit_type) {
+                (BillingPeriod::Monthly, SpendingLimitType::Global) => {
+                    self.get_period_spending(None, None, billing_period).await?
+                }
+                (BillingPeriod::Monthly, SpendingLimitType::PerProvider(provider)) => {
+                    self.get_period_spending(Some(provider), None, billing_period).await?
+                }
+                (BillingPeriod::Monthly, SpendingLimitType::PerModel(model)) => {
+                    self.get_model_spending(model, billing_period).await?
+                }
+                (BillingPeriod::Monthly, SpendingLimitType::PerProject(project)) => {
+                    self.get_project_spending(project, billing_period).await?
+                }
+                (period, limit_type) => self.get_window_spending(limit_type, period, now).await?,
+            };
 
-        for (_, value) in settings {
-            if let Ok(limit) = serde_json::from_str::<SpendingLimit>(&value) {
-                // Calculate current spending based on limit type
-                let current_spending = match &limit.limit_type {
-                    SpendingLimitType::Global => {
-                        self.get_period_spending(None, None, billing_period).await?
-                    }
-                    SpendingLimitType::PerProvider(provider) => {
-                        self.get_period_spending(Some(provider), None, billing_period).await?
-                    }
-                    SpendingLimitType::PerModel(model) => {
-                        self.get_model_spending(model, billing_period).await?
-                    }
-                };
-
-                let percentage_used = if limit.amount > Decimal::ZERO {
-                    (current_spending / limit.amount * Decimal::from(100)).to_f32().unwrap_or(0.0)
-                } else {
-                    0.0
-                };
-
-                let is_exceeded = current_spending > limit.amount;
-
-                statuses.push(SpendingLimitStatus {
-                    limit,
-                    current_spending,
-                    percentage_used,
-                    is_exceeded,
-                    days_remaining: None, // Could calculate based on period
-                });
-            }
+            let percentage_used = if limit.amount > Decimal::ZERO {
+                (current_spending / limit.amount * Decimal::from(100)).to_f32().unwrap_or(0.0)
+            } else {
+                0.0
+            };
+
+            let is_exceeded = current_spending > limit.amount;
+
+            statuses.push(SpendingLimitStatus {
+                limit,
+                current_spending,
+                percentage_used,
+                is_exceeded,
+                days_remaining: None, // Could calculate based on period
+            });
         }
 
         Ok(statuses)
@@ -554,21 +789,10 @@ impl BillingSystem {
         limit_type: &SpendingLimitType,
         period: &BillingPeriod,
     ) -> Result<()> {
-        let limit_key = match limit_type {
-            SpendingLimitType::Global => "global".to_string(),
-            SpendingLimitType::PerProvider(provider) => format!("provider:{}", provider),
-            SpendingLimitType::PerModel(model) => format!("model:{}", model),
-        };
-
-        let period_str = match period {
-            BillingPeriod::Daily => "daily",
-            BillingPeriod::Weekly => "weekly",
-            BillingPeriod::Monthly => "monthly",
-            BillingPeriod::Yearly => "yearly",
-        };
+        let limit_key = limit_storage_key(limit_type, period);
 
         sqlx::query("DELETE FROM app_settings WHERE key = ?")
-            .bind(format!("spending_limit_{}_{}", period_str, limit_key))
+            .bind(&limit_key)
             .execute(&self.pool)
             .await?;
 
@@ -588,6 +812,7 @@ impl BillingSystem {
 mod tests {
     use super::*;
     use crate::storage::Database;
+    use crate::storage::usage::UsageParams;
     use crate::platform::AppPaths;
     use tempfile::TempDir;
 
@@ -634,15 +859,7 @@ mod tests {
         let (billing, _temp_dir) = create_test_billing_system().await;
 
         // First record some usage
-        let request_id = billing.usage_repo.record_usage(
-            "openai",
-            "gpt-4",
-            100,
-            50,
-            Decimal::new(25, 3), // $0.025
-            None,
-            None,
-        ).await.unwrap();
+        let request_id = billing.usage_repo.record_usage(UsageParams::new("openai", "gpt-4", 100, 50, Decimal::new(25, 3))).await.unwrap();
 
         // Verify with a different cost
         let verifications = vec![(request_id.clone(), Decimal::new(30, 3))]; // $0.030
@@ -659,8 +876,8 @@ mod tests {
         let (billing, _temp_dir) = create_test_billing_system().await;
 
         // Record some usage
-        billing.usage_repo.record_usage("openai", "gpt-4", 100, 50, Decimal::new(25, 3), None, None).await.unwrap();
-        billing.usage_repo.record_usage("anthropic", "claude-3", 200, 100, Decimal::new(40, 3), None, None).await.unwrap();
+        billing.usage_repo.record_usage(UsageParams::new("openai", "gpt-4", 100, 50, Decimal::new(25, 3))).await.unwrap();
+        billing.usage_repo.record_usage(UsageParams::new("anthropic", "claude-3", 200, 100, Decimal::new(40, 3))).await.unwrap();
 
         let now = Utc::now();
         let current_period = format!("{:04}-{:02}", now.year(), now.month());
@@ -703,12 +920,155 @@ mod tests {
         let (billing, _temp_dir) = create_test_billing_system().await;
 
         // Record unverified usage
-        billing.usage_repo.record_usage("openai", "gpt-4", 100, 50, Decimal::new(25, 3), None, None).await.unwrap();
-        billing.usage_repo.record_usage("openai", "gpt-3.5", 200, 100, Decimal::new(15, 3), None, None).await.unwrap();
+        billing.usage_repo.record_usage(UsageParams::new("openai", "gpt-4", 100, 50, Decimal::new(25, 3))).await.unwrap();
+        billing.usage_repo.record_usage(UsageParams::new("openai", "gpt-3.5", 200, 100, Decimal::new(15, 3))).await.unwrap();
 
         let unverified = billing.get_unverified_costs(Some(10)).await.unwrap();
         assert_eq!(unverified.len(), 2);
         assert!(!unverified[0].verified);
         assert!(!unverified[1].verified);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_most_restrictive_limit_wins() {
+        let (billing, _temp_dir) = create_test_billing_system().await;
+
+        billing.set_spending_limit(SpendingLimitType::Global, Decimal::from(100), BillingPeriod::Monthly, None).await.unwrap();
+        billing.set_spending_limit(
+            SpendingLimitType::PerProvider("openai".to_string()),
+            Decimal::from(1),
+            BillingPeriod::Daily,
+            None,
+        ).await.unwrap();
+        billing.usage_repo.record_usage(UsageParams::new("openai", "gpt-4", 100, 50, Decimal::new(90, 2))).await.unwrap();
+
+        let result = billing.check_spending_limits("openai", "gpt-4", Decimal::new(20, 2)).await.unwrap();
+        assert!(!result.allowed);
+        assert_eq!(result.limit_type, Some(SpendingLimitType::PerProvider("openai".to_string())));
+        assert_eq!(result.period, Some(BillingPeriod::Daily));
+        assert_eq!(result.current_spending, Decimal::new(90, 2));
+        assert!(result.reason.unwrap().contains("openai daily limit"));
+
+        // The provider limit does not cover anthropic, so the global limit is reported
+        let result = billing.check_spending_limits("anthropic", "claude-3", Decimal::new(20, 2)).await.unwrap();
+        assert!(result.allowed);
+        assert_eq!(result.limit_type, Some(SpendingLimitType::Global));
+        assert_eq!(result.period, Some(BillingPeriod::Monthly));
+    }
+
+    #[tokio::test]
+    async fn test_per_request_and_weekly_model_limits() {
+        let (billing, _temp_dir) = create_test_billing_system().await;
+
+        billing.set_spending_limit(
+            SpendingLimitType::PerModel("gpt-4".to_string()),
+            Decimal::new(50, 2),
+            BillingPeriod::PerRequest,
+            None,
+        ).await.unwrap();
+        billing.set_spending_limit(
+            SpendingLimitType::PerModel("gpt-4".to_string()),
+            Decimal::from(5),
+            BillingPeriod::Weekly,
+            None,
+        ).await.unwrap();
+        billing.usage_repo.record_usage(UsageParams::new("openai", "gpt-4", 100, 50, Decimal::new(480, 2))).await.unwrap();
+
+        // Within the per-request cap but over the weekly budget
+        let result = billing.check_spending_limits("openai", "gpt-4", Decimal::new(30, 2)).await.unwrap();
+        assert!(!result.allowed);
+        assert_eq!(result.period, Some(BillingPeriod::Weekly));
+
+        // A single request above the cap is refused regardless of accumulated spending
+        let result = billing.check_spending_limits("openai", "gpt-4", Decimal::new(60, 2)).await.unwrap();
+        assert!(!result.allowed);
+
+        let result = billing.check_spending_limits("openai", "gpt-4o", Decimal::new(60, 2)).await.unwrap();
+        assert!(result.allowed);
+        assert!(result.limit.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sync_config_limits() {
+        let (billing, _temp_dir) = create_test_billing_system().await;
+
+        billing.set_spending_limit(SpendingLimitType::Global, Decimal::from(5), BillingPeriod::Daily, None).await.unwrap();
+
+        let imported = billing.sync_config_limits(vec![
+            SpendingLimit::new(SpendingLimitType::Global, Decimal::from(10), BillingPeriod::Daily, None),
+            SpendingLimit::new(SpendingLimitType::PerModel("gpt-4".to_string()), Decimal::from(25), BillingPeriod::Monthly, None),
+        ]).await.unwrap();
+        assert_eq!(imported, 1);
+
+        let daily = billing.get_spending_limit(&SpendingLimitType::Global, &BillingPeriod::Daily).await.unwrap().unwrap();
+        assert_eq!(daily.amount, Decimal::from(5));
+        assert_eq!(daily.source, LimitSource::Manual);
+
+        let model = billing.get_spending_limit(
+            &SpendingLimitType::PerModel("gpt-4".to_string()),
+            &BillingPeriod::Monthly,
+        ).await.unwrap().unwrap();
+        assert_eq!(model.source, LimitSource::Config);
+
+        // Dropping a limit from the configuration removes the imported copy only
+        billing.sync_config_limits(Vec::new()).await.unwrap();
+        let limits = billing.list_spending_limits().await.unwrap();
+        assert_eq!(limits.len(), 1);
+        assert_eq!(limits[0].source, LimitSource::Manual);
+    }
+
+    #[tokio::test]
+    async fn test_project_limits_and_report() {
+        let (billing, _temp_dir) = create_test_billing_system().await;
+        let conversations = crate::storage::ConversationRepository::new(billing.pool.clone());
+        let projects = crate::storage::ProjectRepository::new(billing.pool.clone());
+
+        let session = crate::chat::types::ChatSession::new("Roadmap", "openai", "gpt-4");
+        conversations.create_conversation(&session).await.unwrap();
+        projects.assign_conversation(&session.id, Some("alpha")).await.unwrap();
+
+        billing.set_spending_limit(
+            SpendingLimitType::PerProject("alpha".to_string()),
+            Decimal::from(2),
+            BillingPeriod::Monthly,
+            None,
+        ).await.unwrap();
+        billing.usage_repo.record_usage(UsageParams::new("openai", "gpt-4", 100, 50, Decimal::new(150, 2))
+            .with_conversation_id(&session.id)).await.unwrap();
+        billing.usage_repo.record_usage(UsageParams::new("openai", "gpt-4", 100, 50, Decimal::new(300, 2))).await.unwrap();
+
+        let result = billing.check_project_spending_limits("openai", "gpt-4", Some("alpha"), Decimal::new(60, 2)).await.unwrap();
+        assert!(!result.allowed);
+        assert_eq!(result.limit_type, Some(SpendingLimitType::PerProject("alpha".to_string())));
+        assert_eq!(result.current_spending, Decimal::new(150, 2));
+
+        // Requests outside the project are not held to its budget
+        let result = billing.check_spending_limits("openai", "gpt-4", Decimal::new(60, 2)).await.unwrap();
+        assert!(result.allowed);
+
+        let report = billing.get_current_month_summary().await.unwrap();
+        assert_eq!(report.by_project.len(), 1);
+        assert_eq!(report.by_project["alpha"].total_cost, Decimal::new(150, 2));
+        assert_eq!(report.by_project["alpha"].request_count, 1);
+
+        let stats = billing.usage_repo.get_project_usage_statistics("alpha").await.unwrap();
+        assert_eq!(stats.total_requests, 1);
+        assert_eq!(stats.total_cost, Decimal::new(150, 2));
+        let stats = billing.usage_repo.get_usage_statistics().await.unwrap();
+        assert_eq!(stats.total_requests, 2);
+        assert_eq!(stats.by_project["alpha"].cost, Decimal::new(150, 2));
+    }
+
+    #[test]
+    fn test_period_window_start() {
+        // Thursday 2024-03-14 15:30 UTC
+        let now = DateTime::parse_from_rfc3339("2024-03-14T15:30:00Z").unwrap().with_timezone(&Utc);
+        let at = |date: &str| DateTime::parse_from_rfc3339(date).unwrap().timestamp();
+
+        assert_eq!(BillingPeriod::Daily.window_start(now), Some(at("2024-03-14T00:00:00Z")));
+        assert_eq!(BillingPeriod::Weekly.window_start(now), Some(at("2024-03-11T00:00:00Z")));
+        assert_eq!(BillingPeriod::Monthly.window_start(now), Some(at("2024-03-01T00:00:00Z")));
+        assert_eq!(BillingPeriod::Yearly.window_start(now), Some(at("2024-01-01T00:00:00Z")));
+        assert_eq!(BillingPeriod::PerRequest.window_start(now), None);
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use sqlx::{SqlitePool, Row};
+use sqlx::{sqlite::SqliteRow, SqlitePool, Row};
 use tracing::{debug, info};
 use rust_decimal::{Decimal, prelude::ToPrimitive};
 use chrono::{DateTime, Utc};
@@ -7,17 +7,27 @@ use serde_json;
 
 use crate::error::{Error, Result};
 use crate::chat::types::{ChatSession, ChatMessage, MessageRole, MessageContent, ToolInvocation, SessionSettings, SessionStatus};
+use crate::mcp::permissions::ToolDecision;
 use crate::storage::database::decimal_helpers;
+use crate::storage::encryption::{self, FieldCipher};
 
 /// Repository for managing conversations and messages in the database
 pub struct ConversationRepository {
     pool: SqlitePool,
+    /// Encrypts message content and tool arguments/results when set
+    cipher: Option<FieldCipher>,
 }
 
 impl ConversationRepository {
     /// Create a new conversation repository
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self { pool, cipher: None }
+    }
+
+    /// Encrypt conversation content with `cipher`; existing plain-text rows stay readable
+    pub fn with_cipher(mut self, cipher: Option<FieldCipher>) -> Self {
+        self.cipher = cipher;
+        self
     }
 
     /// Save a new conversation to the database
@@ -243,26 +253,15 @@ impl ConversationRepository {
     }
 
     /// Delete a conversation and all its messages
+    ///
+    /// Usage records of the conversation are kept for billing and detached from it.
     pub async fn delete_conversation(&self, conversation_id: &str) -> Result<()> {
         debug!("Deleting conversation: {}", conversation_id);
 
         // Start a transaction
         let mut tx = self.pool.begin().await?;
 
-        // Delete all messages first (foreign key constraint)
-        sqlx::query("DELETE FROM messages WHERE conversation_id = ?")
-            .bind(conversation_id)
-            .execute(&mut *tx)
-            .await?;
-
-        // Delete the conversation
-        let rows_affected = sqlx::query("DELETE FROM conversations WHERE id = ?")
-            .bind(conversation_id)
-            .execute(&mut *tx)
-            .await?
-            .rows_affected();
-
-        if rows_affected == 0 {
+        if !delete_conversation_tx(&mut tx, conversation_id).await? {
             tx.rollback().await?;
             return Err(Error::Database(sqlx::Error::RowNotFound));
         }
@@ -323,7 +322,7 @@ impl ConversationRepository {
         .bind(&message.id)
         .bind(&message.session_id)
         .bind(role_str)
-        .bind(&content_str)
+        .bind(seal(self.cipher.as_ref(), "messages.content", &message.id, content_str)?)
         .bind(content_type)
         .bind(message.timestamp.timestamp())
         .bind::<Option<String>>(None) // model_used - will be set later
@@ -338,7 +337,7 @@ impl ConversationRepository {
 
         // Save tool invocations if any
         for invocation in &message.tool_invocations {
-            self.create_tool_invocation(invocation).await?;
+            self.create_tool_invocation(&message.id, invocation).await?;
         }
 
         debug!("Successfully created message: {}", message.id);
@@ -346,7 +345,7 @@ impl ConversationRepository {
     }
 
     /// Save a tool invocation to the database
-    async fn create_tool_invocation(&self, invocation: &ToolInvocation) -> Result<()> {
+    async fn create_tool_invocation(&self, message_id: &str, invocation: &ToolInvocation) -> Result<()> {
         debug!("Creating tool invocation: {}", invocation.id);
 
         let arguments_json = serde_json::to_string(&invocation.arguments)
@@ -362,20 +361,21 @@ impl ConversationRepository {
             r#"
             INSERT INTO tool_invocations (
                 id, message_id, tool_name, server_name, arguments, result,
-                error, duration_ms, timestamp, cost
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                error, duration_ms, timestamp, cost, decision
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#
         )
         .bind(&invocation.id)
-        .bind("") // message_id will be set by the caller
+        .bind(message_id)
         .bind(&invocation.tool_name)
         .bind(&invocation.server_name)
-        .bind(&arguments_json)
-        .bind(&result_json)
+        .bind(seal(self.cipher.as_ref(), "tool_invocations.arguments", &invocation.id, arguments_json)?)
+        .bind(result_json.map(|result| seal(self.cipher.as_ref(), "tool_invocations.result", &invocation.id, result)).transpose()?)
         .bind(&invocation.error)
         .bind(invocation.duration_ms.map(|d| d as i32))
         .bind(invocation.timestamp.timestamp())
         .bind("0.00") // cost - for future use
+        .bind(invocation.decision.map(|d| d.as_str()))
         .execute(&self.pool)
         .await?;
 
@@ -412,7 +412,8 @@ impl ConversationRepository {
                 _ => MessageRole::User,
             };
 
-            let content_str: String = row.get("content");
+            let message_id: String = row.get("id");
+            let content_str = unseal(self.cipher.as_ref(), "messages.content", &message_id, row.get("content"))?;
             let content_type: String = row.get("content_type");
             let content = match content_type.as_str() {
                 "text" => MessageContent::Text(content_str),
@@ -455,7 +456,7 @@ impl ConversationRepository {
                 content,
             );
 
-            message.id = row.get("id");
+            message.id = message_id;
             message.timestamp = timestamp;
             message.metadata = metadata;
 
@@ -474,7 +475,7 @@ impl ConversationRepository {
         let rows = sqlx::query(
             r#"
             SELECT id, tool_name, server_name, arguments, result, error,
-                   duration_ms, timestamp, cost
+                   duration_ms, timestamp, cost, decision
             FROM tool_invocations 
             WHERE message_id = ?
             ORDER BY timestamp ASC
@@ -484,38 +485,70 @@ impl ConversationRepository {
         .fetch_all(&self.pool)
         .await?;
 
-        let mut invocations = Vec::new();
-        for row in rows {
-            let arguments_json: String = row.get("arguments");
-            let arguments: serde_json::Value = serde_json::from_str(&arguments_json)
-                .unwrap_or(serde_json::json!({}));
-
-            let result_json: Option<String> = row.get("result");
-            let result = match result_json {
-                Some(json_str) => serde_json::from_str(&json_str).ok(),
-                None => None,
-            };
+        let invocations = rows.iter()
+            .map(|row| row_to_tool_invocation(row, self.cipher.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
 
-            let timestamp_unix: i64 = row.get("timestamp");
-            let timestamp = DateTime::from_timestamp(timestamp_unix, 0)
-                .unwrap_or_else(Utc::now);
+        Ok(invocations)
+    }
 
-            let mut invocation = ToolInvocation::new(
-                row.get::<String, _>("tool_name"),
-                row.get::<String, _>("server_name"),
-                arguments,
-            );
+    /// List recorded tool invocations, most recent first
+    pub async fn list_tool_invocations(&self, filter: &ToolInvocationFilter) -> Result<Vec<ToolInvocationRecord>> {
+        debug!("Listing tool invocations with filter: {:?}", filter);
 
-            invocation.id = row.get("id");
-            invocation.result = result;
-            invocation.error = row.get("error");
-            invocation.duration_ms = row.get::<Option<i32>, _>("duration_ms").map(|d| d as u64);
-            invocation.timestamp = timestamp;
+        let mut query = String::from(
+            r#"
+            SELECT t.id, t.tool_name, t.server_name, t.arguments, t.result, t.error,
+                   t.duration_ms, t.timestamp, t.cost, t.decision, m.conversation_id
+            FROM tool_invocations t
+            JOIN messages m ON m.id = t.message_id
+            WHERE 1=1
+            "#
+        );
 
-            invocations.push(invocation);
+        if filter.conversation_id.is_some() {
+            query.push_str(" AND m.conversation_id = ?");
+        }
+        if filter.server_name.is_some() {
+            query.push_str(" AND t.server_name = ?");
+        }
+        if filter.tool_name.is_some() {
+            query.push_str(" AND t.tool_name = ?");
+        }
+        match filter.status {
+            Some(ToolInvocationStatus::Succeeded) => query.push_str(" AND t.error IS NULL"),
+            Some(ToolInvocationStatus::Failed) => query.push_str(
+                " AND t.error IS NOT NULL AND COALESCE(t.decision, '') NOT IN ('policy_deny', 'user_deny')"
+            ),
+            Some(ToolInvocationStatus::Denied) => query.push_str(" AND t.decision IN ('policy_deny', 'user_deny')"),
+            None => {}
         }
 
-        Ok(invocations)
+        query.push_str(" ORDER BY t.timestamp DESC");
+        query.push_str(&format!(" LIMIT {}", filter.limit.unwrap_or(20)));
+
+        let mut query_builder = sqlx::query(&query);
+        if let Some(conversation_id) = &filter.conversation_id {
+            query_builder = query_builder.bind(conversation_id);
+        }
+        if let Some(server_name) = &filter.server_name {
+            query_builder = query_builder.bind(server_name);
+        }
+        if let Some(tool_name) = &filter.tool_name {
+            query_builder = query_builder.bind(tool_name);
+        }
+
+        let rows = query_builder.fetch_all(&self.pool).await?;
+
+        let records = rows.iter()
+            .map(|row| Ok(ToolInvocationRecord {
+                conversation_id: row.get("conversation_id"),
+                invocation: row_to_tool_invocation(row, self.cipher.as_ref())?,
+            }))
+            .collect::<Result<Vec<_>>>()?;
+
+        debug!("Retrieved {} tool invocations", records.len());
+        Ok(records)
     }
 
     /// Update conversation title
@@ -540,6 +573,50 @@ impl ConversationRepository {
         Ok(())
     }
 
+    /// Pin a resource to a conversation; returns false if it was already pinned
+    pub async fn pin_resource(&self, conversation_id: &str, uri: &str) -> Result<bool> {
+        let rows_affected = sqlx::query("INSERT OR IGNORE INTO pinned_resources (conversation_id, uri) VALUES (?, ?)")
+            .bind(conversation_id)
+            .bind(uri)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        Ok(rows_affected > 0)
+    }
+
+    /// Unpin a resource from a conversation; returns whether it was pinned
+    pub async fn unpin_resource(&self, conversation_id: &str, uri: &str) -> Result<bool> {
+        let rows_affected = sqlx::query("DELETE FROM pinned_resources WHERE conversation_id = ? AND uri = ?")
+            .bind(conversation_id)
+            .bind(uri)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        Ok(rows_affected > 0)
+    }
+
+    /// Resource URIs pinned to a conversation, in the order they were pinned
+    pub async fn list_pinned_resources(&self, conversation_id: &str) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar("SELECT uri FROM pinned_resources WHERE conversation_id = ? ORDER BY pinned_at, rowid")
+            .bind(conversation_id)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    /// Every pin as (conversation ID, URI)
+    pub async fn list_all_pins(&self) -> Result<Vec<(String, String)>> {
+        Ok(sqlx::query_as("SELECT conversation_id, uri FROM pinned_resources ORDER BY uri, conversation_id")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    /// Every resource URI pinned to at least one conversation
+    pub async fn list_all_pinned_resources(&self) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar("SELECT DISTINCT uri FROM pinned_resources ORDER BY uri")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
     /// Get conversation statistics
     pub async fn get_conversation_statistics(&self) -> Result<ConversationStatistics> {
         debug!("Getting conversation statistics");
@@ -573,6 +650,129 @@ impl ConversationRepository {
     }
 }
 
+/// Outcome used to filter tool invocations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolInvocationStatus {
+    Succeeded,
+    Failed,
+    Denied,
+}
+
+/// Filter for listing recorded tool invocations
+#[derive(Debug, Clone, Default)]
+pub struct ToolInvocationFilter {
+    pub conversation_id: Option<String>,
+    pub server_name: Option<String>,
+    pub tool_name: Option<String>,
+    pub status: Option<ToolInvocationStatus>,
+    pub limit: Option<u32>,
+}
+
+/// Delete a conversation, its messages and their tool invocations, returning false if it
+/// does not exist
+pub(crate) async fn delete_conversation_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    conversation_id: &str,
+) -> Result<bool> {
+    // Usage records reference both; keep them for billing without the link
+    sqlx::query(
+        "UPDATE usage_records SET conversation_id = NULL, message_id = NULL \
+         WHERE conversation_id = ?1 OR message_id IN (SELECT id FROM messages WHERE conversation_id = ?1)"
+    )
+    .bind(conversation_id)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query("DELETE FROM tool_invocations WHERE message_id IN (SELECT id FROM messages WHERE conversation_id = ?)")
+        .bind(conversation_id)
+        .execute(&mut **tx)
+        .await?;
+
+    sqlx::query("DELETE FROM pinned_resources WHERE conversation_id = ?")
+        .bind(conversation_id)
+        .execute(&mut **tx)
+        .await?;
+
+    sqlx::query("DELETE FROM api_key_selections WHERE scope = 'conversation' AND scope_id = ?")
+        .bind(conversation_id)
+        .execute(&mut **tx)
+        .await?;
+
+    // Delete all messages first (foreign key constraint)
+    sqlx::query("DELETE FROM messages WHERE conversation_id = ?")
+        .bind(conversation_id)
+        .execute(&mut **tx)
+        .await?;
+
+    // Delete the conversation
+    let rows_affected = sqlx::query("DELETE FROM conversations WHERE id = ?")
+        .bind(conversation_id)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+    Ok(rows_affected > 0)
+}
+
+/// A tool invocation together with the conversation it belongs to
+#[derive(Debug, Clone)]
+pub struct ToolInvocationRecord {
+    pub conversation_id: String,
+    pub invocation: ToolInvocation,
+}
+
+/// Encrypt a column value when the repository has a cipher
+fn seal(cipher: Option<&FieldCipher>, column: &str, row_id: &str, value: String) -> Result<String> {
+    match cipher {
+        Some(cipher) => cipher.encrypt(column, row_id, &value),
+        None => Ok(value),
+    }
+}
+
+/// Decrypt a column value, which may be plain text written before encryption was enabled
+fn unseal(cipher: Option<&FieldCipher>, column: &str, row_id: &str, stored: String) -> Result<String> {
+    match cipher {
+        Some(cipher) => cipher.decrypt(column, row_id, &stored),
+        None if encryption::is_encrypted(&stored) => Err(Error::validation(
+            "Conversation content is encrypted and the database encryption key is not loaded",
+        )),
+        None => Ok(stored),
+    }
+}
+
+fn row_to_tool_invocation(row: &SqliteRow, cipher: Option<&FieldCipher>) -> Result<ToolInvocation> {
+    let id: String = row.get("id");
+    let arguments_json = unseal(cipher, "tool_invocations.arguments", &id, row.get("arguments"))?;
+    let arguments: serde_json::Value = serde_json::from_str(&arguments_json)
+        .unwrap_or(serde_json::json!({}));
+
+    let result_json: Option<String> = row.get("result");
+    let result = match result_json {
+        Some(json_str) => serde_json::from_str(&unseal(cipher, "tool_invocations.result", &id, json_str)?).ok(),
+        None => None,
+    };
+
+    let timestamp_unix: i64 = row.get("timestamp");
+    let timestamp = DateTime::from_timestamp(timestamp_unix, 0)
+        .unwrap_or_else(Utc::now);
+
+    let mut invocation = ToolInvocation::new(
+        row.get::<String, _>("tool_name"),
+        row.get::<String, _>("server_name"),
+        arguments,
+    );
+
+    invocation.id = id;
+    invocation.result = result;
+    invocation.error = row.get("error");
+    invocation.duration_ms = row.get::<Option<i32>, _>("duration_ms").map(|d| d as u64);
+    invocation.timestamp = timestamp;
+    invocation.decision = row.get::<Option<String>, _>("decision")
+        .and_then(|d| ToolDecision::parse(&d));
+
+    Ok(invocation)
+}
+
 /// Statistics about conversations
 #[derive(Debug, Clone)]
 pub struct ConversationStatistics {
@@ -643,6 +843,106 @@ mod tests {
         assert_eq!(messages[0].role, MessageRole::User);
     }
 
+    #[tokio::test]
+    async fn test_tool_invocation_decision_recorded() {
+        let (repo, _temp_dir) = create_test_repository().await;
+
+        let session = ChatSession::new("Test Chat", "openai", "gpt-4");
+        repo.create_conversation(&session).await.unwrap();
+
+        let invocation = ToolInvocation::new("read_file".to_string(), "fs".to_string(), serde_json::json!({"path": "/tmp/a"}))
+            .with_decision(ToolDecision::DeniedByUser)
+            .with_error("denied by user".to_string(), 0);
+        let mut message = ChatMessage::new(
+            session.id.clone(),
+            MessageRole::Tool,
+            MessageContent::tool_error(invocation.id.clone(), serde_json::json!({"error": "denied by user"})),
+        );
+        message.add_tool_invocation(invocation);
+        repo.create_message(&message).await.unwrap();
+
+        let messages = repo.get_messages(&session.id).await.unwrap();
+        let invocations = &messages[0].tool_invocations;
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].decision, Some(ToolDecision::DeniedByUser));
+        assert_eq!(invocations[0].server_name, "fs");
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_content_round_trip() {
+        let (repo, _temp_dir) = create_test_repository().await;
+        let plain = ConversationRepository::new(repo.pool.clone());
+        let repo = repo.with_cipher(Some(FieldCipher::generate().unwrap()));
+
+        let session = ChatSession::new("Test Chat", "openai", "gpt-4");
+        repo.create_conversation(&session).await.unwrap();
+        let invocation = ToolInvocation::new("read_file".to_string(), "fs".to_string(), serde_json::json!({"path": "/srv/secret"}))
+            .with_result(serde_json::json!({"content": "token"}), 5);
+        let mut message = ChatMessage::new(session.id.clone(), MessageRole::User, MessageContent::text("customer data"));
+        message.add_tool_invocation(invocation);
+        repo.create_message(&message).await.unwrap();
+
+        let stored: (String, String, String) = sqlx::query_as(
+            "SELECT m.content, t.arguments, t.result FROM messages m JOIN tool_invocations t ON t.message_id = m.id"
+        ).fetch_one(&repo.pool).await.unwrap();
+        assert!(encryption::is_encrypted(&stored.0) && encryption::is_encrypted(&stored.1) && encryption::is_encrypted(&stored.2));
+
+        let messages = repo.get_messages(&session.id).await.unwrap();
+        assert_eq!(messages[0].content.get_text(), Some("customer data"));
+        assert_eq!(messages[0].tool_invocations[0].arguments["path"], "/srv/secret");
+        assert_eq!(messages[0].tool_invocations[0].result, Some(serde_json::json!({"content": "token"})));
+
+        // Without the key the content is refused rather than returned as ciphertext
+        assert!(plain.get_messages(&session.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_tool_invocations_with_filters() {
+        let (repo, _temp_dir) = create_test_repository().await;
+
+        let session = ChatSession::new("Test Chat", "openai", "gpt-4");
+        repo.create_conversation(&session).await.unwrap();
+
+        let invocations = vec![
+            ToolInvocation::new("read_file".to_string(), "fs".to_string(), serde_json::json!({}))
+                .with_decision(ToolDecision::AllowedByPolicy)
+                .with_result(serde_json::json!({"content": []}), 5),
+            ToolInvocation::new("write_file".to_string(), "fs".to_string(), serde_json::json!({}))
+                .with_decision(ToolDecision::AllowedOnce)
+                .with_error("disk full".to_string(), 7),
+            ToolInvocation::new("fetch".to_string(), "web".to_string(), serde_json::json!({}))
+                .with_decision(ToolDecision::DeniedByUser)
+                .with_error("denied by user".to_string(), 0),
+        ];
+        for invocation in invocations {
+            let mut message = ChatMessage::new(
+                session.id.clone(),
+                MessageRole::Tool,
+                MessageContent::tool_result(invocation.id.clone(), serde_json::json!({})),
+            );
+            message.add_tool_invocation(invocation);
+            repo.create_message(&message).await.unwrap();
+        }
+
+        let all = repo.list_tool_invocations(&ToolInvocationFilter::default()).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all.iter().all(|r| r.conversation_id == session.id));
+
+        let fs_only = ToolInvocationFilter { server_name: Some("fs".to_string()), ..Default::default() };
+        assert_eq!(repo.list_tool_invocations(&fs_only).await.unwrap().len(), 2);
+
+        let failed = ToolInvocationFilter { status: Some(ToolInvocationStatus::Failed), ..Default::default() };
+        let failed = repo.list_tool_invocations(&failed).await.unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].invocation.tool_name, "write_file");
+
+        let denied = ToolInvocationFilter { status: Some(ToolInvocationStatus::Denied), ..Default::default() };
+        assert_eq!(repo.list_tool_invocations(&denied).await.unwrap()[0].invocation.tool_name, "fetch");
+
+        let limited = ToolInvocationFilter { limit: Some(1), ..Default::default() };
+        assert_eq!(repo.list_tool_invocations(&limited).await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_list_conversations() {
         let (repo, _temp_dir) = create_test_repository().await;
@@ -658,6 +958,32 @@ mod tests {
         assert_eq!(conversations.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_pinned_resources() {
+        let (repo, _temp_dir) = create_test_repository().await;
+
+        let first = ChatSession::new("First", "openai", "gpt-4");
+        let second = ChatSession::new("Second", "openai", "gpt-4");
+        repo.create_conversation(&first).await.unwrap();
+        repo.create_conversation(&second).await.unwrap();
+
+        assert!(repo.pin_resource(&first.id, "file:///b.md").await.unwrap());
+        assert!(repo.pin_resource(&first.id, "file:///a.md").await.unwrap());
+        assert!(!repo.pin_resource(&first.id, "file:///a.md").await.unwrap());
+        assert!(repo.pin_resource(&second.id, "file:///a.md").await.unwrap());
+        assert_eq!(repo.list_pinned_resources(&first.id).await.unwrap(), vec!["file:///b.md", "file:///a.md"]);
+        assert_eq!(repo.list_all_pinned_resources().await.unwrap(), vec!["file:///a.md", "file:///b.md"]);
+        assert_eq!(repo.list_all_pins().await.unwrap().len(), 3);
+
+        assert!(repo.unpin_resource(&first.id, "file:///b.md").await.unwrap());
+        assert!(!repo.unpin_resource(&first.id, "file:///b.md").await.unwrap());
+
+        // Deleting a conversation drops its pins only
+        repo.delete_conversation(&first.id).await.unwrap();
+        assert!(repo.list_pinned_resources(&first.id).await.unwrap().is_empty());
+        assert_eq!(repo.list_all_pinned_resources().await.unwrap(), vec!["file:///a.md"]);
+    }
+
     #[tokio::test]
     async fn test_delete_conversation() {
         let (repo, _temp_dir) = create_test_repository().await;
@@ -677,6 +1003,30 @@ mod tests {
         assert!(retrieved.is_none());
     }
 
+    #[tokio::test]
+    async fn test_delete_conversation_drops_key_selections() {
+        use crate::storage::{KeyScope, KeySelectionRepository};
+
+        let (repo, _temp_dir) = create_test_repository().await;
+        let selections = KeySelectionRepository::new(repo.pool.clone());
+
+        let first = ChatSession::new("First", "openai", "gpt-4");
+        let second = ChatSession::new("Second", "openai", "gpt-4");
+        repo.create_conversation(&first).await.unwrap();
+        repo.create_conversation(&second).await.unwrap();
+        selections.select(KeyScope::Conversation(&first.id), "openai/team-a").await.unwrap();
+        selections.select(KeyScope::Conversation(&second.id), "openai/team-b").await.unwrap();
+
+        repo.delete_conversation(&first.id).await.unwrap();
+        let orphaned: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM api_key_selections WHERE scope_id = ?")
+            .bind(&first.id)
+            .fetch_one(&repo.pool)
+            .await
+            .unwrap();
+        assert_eq!(orphaned, 0);
+        assert_eq!(selections.list(KeyScope::Conversation(&second.id)).await.unwrap(), vec![("openai".to_string(), "openai/team-b".to_string())]);
+    }
+
     #[tokio::test]
     async fn test_conversation_statistics() {
         let (repo, _temp_dir) = create_test_repository().await;
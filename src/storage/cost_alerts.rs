@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 use chrono::{DateTime, Utc, Duration};
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, prelude::ToPrimitive};
 use serde::{Serialize, Deserialize};
 use tracing::{info, error, debug};
+use sqlx::{Row, SqlitePool};
 use tokio::sync::mpsc;
 
-use crate::error::Result;
+use crate::billing::currency::CurrencyFormatter;
+use crate::error::{Error, Result};
+use crate::storage::anomaly::SpendBaselines;
+use crate::storage::forecast::SpendForecast;
 
 /// Alert types for cost tracking
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -22,6 +26,8 @@ pub enum CostAlertType {
     HighCostRequest { cost: Decimal, average_cost: Decimal },
     /// Running low on budget
     BudgetWarning { remaining: Decimal, days_left: u32 },
+    /// Locally tracked cost differs from what the provider billed
+    CostDiscrepancy { provider: String, local_cost: Decimal, provider_cost: Decimal },
 }
 
 /// Alert severity levels
@@ -33,6 +39,27 @@ pub enum AlertSeverity {
     Emergency,
 }
 
+impl AlertSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertSeverity::Info => "info",
+            AlertSeverity::Warning => "warning",
+            AlertSeverity::Critical => "critical",
+            AlertSeverity::Emergency => "emergency",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "info" => Some(AlertSeverity::Info),
+            "warning" => Some(AlertSeverity::Warning),
+            "critical" => Some(AlertSeverity::Critical),
+            "emergency" => Some(AlertSeverity::Emergency),
+            _ => None,
+        }
+    }
+}
+
 /// Cost alert notification
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CostAlert {
@@ -71,16 +98,61 @@ pub struct CostAlertConfig {
     pub monthly_critical_limit: Option<Decimal>,
     /// Per-provider spending limits
     pub provider_limits: HashMap<String, Decimal>,
-    /// Spending spike detection threshold (multiplier of baseline)
+    /// Session spend spike threshold (multiplier of the median session)
     pub spike_threshold: f32,
-    /// High cost request threshold (multiplier of average)
+    /// High cost request threshold (multiplier of the median request)
     pub high_cost_threshold: f32,
     /// Days to look back for baseline calculations
     pub baseline_days: u32,
+    /// Robust z-score (distance from the median in scaled MADs) that counts as anomalous
+    #[serde(default = "default_anomaly_score")]
+    pub anomaly_score: f32,
+    /// Anomalies cheaper than this are ignored
+    #[serde(default = "default_min_anomaly_cost")]
+    pub min_anomaly_cost: Decimal,
+    /// Requests within the burst window of one conversation that look like a runaway loop
+    #[serde(default = "default_burst_requests")]
+    pub burst_requests: u32,
+    #[serde(default = "default_burst_window_minutes")]
+    pub burst_window_minutes: u32,
+    /// Raise severe anomalies as emergencies, which engage the spending emergency stop
+    #[serde(default)]
+    pub emergency_stop_on_anomaly: bool,
     /// Minimum budget warning threshold
     pub budget_warning_days: u32,
+    /// Relative difference between local and provider costs that raises an alert (0.05 = 5%)
+    #[serde(default = "default_discrepancy_threshold")]
+    pub discrepancy_threshold: f32,
+    /// Formats the amounts in alert messages in the display currency
+    #[serde(skip)]
+    pub money: CurrencyFormatter,
+}
+
+fn default_discrepancy_threshold() -> f32 {
+    0.05
+}
+
+fn default_anomaly_score() -> f32 {
+    3.5
+}
+
+fn default_min_anomaly_cost() -> Decimal {
+    Decimal::new(5, 2)
+}
+
+fn default_burst_requests() -> u32 {
+    20
 }
 
+fn default_burst_window_minutes() -> u32 {
+    5
+}
+
+/// Most recent requests or sessions a baseline is computed over
+const BASELINE_CAPACITY: usize = 500;
+/// History needed before a baseline is trusted
+const BASELINE_MIN_SAMPLES: usize = 10;
+
 impl Default for CostAlertConfig {
     fn default() -> Self {
         Self {
@@ -90,10 +162,17 @@ impl Default for CostAlertConfig {
             monthly_warning_limit: Some(Decimal::new(1000, 0)), // $1000/month
             monthly_critical_limit: Some(Decimal::new(2000, 0)), // $2000/month
             provider_limits: HashMap::new(),
-            spike_threshold: 3.0, // 3x baseline
-            high_cost_threshold: 5.0, // 5x average
+            spike_threshold: 3.0, // 3x median session
+            high_cost_threshold: 5.0, // 5x median request
             baseline_days: 7,
+            anomaly_score: default_anomaly_score(),
+            min_anomaly_cost: default_min_anomaly_cost(),
+            burst_requests: default_burst_requests(),
+            burst_window_minutes: default_burst_window_minutes(),
+            emergency_stop_on_anomaly: false,
             budget_warning_days: 3,
+            discrepancy_threshold: default_discrepancy_threshold(),
+            money: CurrencyFormatter::usd(),
         }
     }
 }
@@ -104,37 +183,58 @@ pub struct CostAlertSystem {
     alert_sender: mpsc::UnboundedSender<CostAlert>,
     /// Recent alerts to avoid spam
     recent_alerts: HashMap<String, DateTime<Utc>>,
-    /// Cached baseline metrics
-    baseline_cache: HashMap<String, BaselineMetrics>,
+    /// Request and session spend baselines for anomaly detection
+    baselines: SpendBaselines,
     /// Alert history for analysis
     alert_history: Vec<CostAlert>,
-}
-
-#[derive(Debug, Clone)]
-struct BaselineMetrics {
-    average_daily_cost: Decimal,
-    average_request_cost: Decimal,
-    last_updated: DateTime<Utc>,
-    sample_period: Duration,
+    /// Persistent store for acknowledgements, if any
+    store: Option<AlertStore>,
 }
 
 impl CostAlertSystem {
     /// Create new cost alert system
     pub fn new(config: CostAlertConfig) -> (Self, mpsc::UnboundedReceiver<CostAlert>) {
         let (alert_sender, alert_receiver) = mpsc::unbounded_channel();
+        let baselines = Self::empty_baselines(&config);
         
         let system = Self {
             config,
             alert_sender,
             recent_alerts: HashMap::new(),
-            baseline_cache: HashMap::new(),
+            baselines,
             alert_history: Vec::new(),
+            store: None,
         };
         
         (system, alert_receiver)
     }
 
+    /// Persist acknowledgements in `store`
+    pub fn with_store(mut self, store: AlertStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    fn empty_baselines(config: &CostAlertConfig) -> SpendBaselines {
+        SpendBaselines::new(
+            BASELINE_CAPACITY,
+            BASELINE_MIN_SAMPLES,
+            Duration::minutes(config.burst_window_minutes.max(1) as i64),
+        )
+    }
+
+    /// Rebuild the anomaly baselines from recorded usage
+    pub async fn load_baselines(&mut self, pool: &SqlitePool) -> Result<()> {
+        let mut baselines = Self::empty_baselines(&self.config);
+        baselines.load(pool, self.config.baseline_days).await?;
+        self.baselines = baselines;
+        Ok(())
+    }
+
     /// Check spending against configured limits and generate alerts
+    ///
+    /// `recent_cost` is the request that was just recorded; it is scored against the
+    /// anomaly baselines and then added to them.
     pub async fn check_spending_alerts(
         &mut self,
         current_daily: Decimal,
@@ -143,12 +243,13 @@ impl CostAlertSystem {
         recent_cost: Decimal,
         context: AlertContext,
     ) -> Result<Vec<CostAlert>> {
+        let now = Utc::now();
         if !self.config.enabled {
+            self.record_request(recent_cost, &context, now);
             return Ok(Vec::new());
         }
 
         let mut alerts = Vec::new();
-        let now = Utc::now();
 
         // Check daily limits
         if let Some(daily_warning) = self.config.daily_warning_limit {
@@ -173,14 +274,14 @@ impl CostAlertSystem {
                         },
                         severity,
                         message: format!(
-                            "Daily spending ${:.2} {} ${:.2} limit",
-                            current_daily,
+                            "Daily spending {} {} {} limit",
+                            self.config.money.format(current_daily),
                             if current_daily >= self.config.daily_critical_limit.unwrap_or(Decimal::MAX) {
                                 "exceeded"
                             } else {
                                 "approaching"
                             },
-                            daily_warning
+                            self.config.money.format(daily_warning)
                         ),
                         timestamp: now,
                         acknowledged: false,
@@ -217,14 +318,14 @@ impl CostAlertSystem {
                         },
                         severity,
                         message: format!(
-                            "Monthly spending ${:.2} {} ${:.2} limit",
-                            current_monthly,
+                            "Monthly spending {} {} {} limit",
+                            self.config.money.format(current_monthly),
                             if current_monthly >= self.config.monthly_critical_limit.unwrap_or(Decimal::MAX) {
                                 "exceeded"
                             } else {
                                 "approaching"
                             },
-                            monthly_warning
+                            self.config.money.format(monthly_warning)
                         ),
                         timestamp: now,
                         acknowledged: false,
@@ -253,8 +354,8 @@ impl CostAlertSystem {
                             },
                             severity: AlertSeverity::Warning,
                             message: format!(
-                                "Provider {} spending ${:.2} exceeded ${:.2} limit",
-                                provider, current_spending, provider_limit
+                                "Provider {} spending {} exceeded {} limit",
+                                provider, self.config.money.format(*current_spending), self.config.money.format(*provider_limit)
                             ),
                             timestamp: now,
                             acknowledged: false,
@@ -269,35 +370,8 @@ impl CostAlertSystem {
             }
         }
 
-        // Check for spending spikes
-        if let Some(baseline) = self.get_baseline_metrics(&context).await {
-            if recent_cost > baseline.average_request_cost * Decimal::from_f32_retain(self.config.high_cost_threshold).unwrap_or(Decimal::from(5)) {
-                let alert_key = format!("high_cost_{}", context.provider.as_deref().unwrap_or("unknown"));
-                if self.should_send_alert(&alert_key, AlertSeverity::Info) {
-                    let alert = CostAlert {
-                        id: uuid::Uuid::new_v4().to_string(),
-                        alert_type: CostAlertType::HighCostRequest {
-                            cost: recent_cost,
-                            average_cost: baseline.average_request_cost,
-                        },
-                        severity: AlertSeverity::Info,
-                        message: format!(
-                            "High cost request: ${:.4} ({}x average of ${:.4})",
-                            recent_cost,
-                            recent_cost / baseline.average_request_cost,
-                            baseline.average_request_cost
-                        ),
-                        timestamp: now,
-                        acknowledged: false,
-                        suggested_action: Some("Review request parameters and model selection".to_string()),
-                        context: context.clone(),
-                    };
-                    
-                    alerts.push(alert);
-                    self.recent_alerts.insert(alert_key, now);
-                }
-            }
-        }
+        // Unusually expensive requests and runaway sessions
+        alerts.extend(self.detect_anomalies(recent_cost, &context, now));
 
         // Send alerts
         for alert in &alerts {
@@ -319,6 +393,137 @@ impl CostAlertSystem {
         Ok(alerts)
     }
 
+    /// Compare local and provider-billed costs for a period and alert on a mismatch
+    ///
+    /// Differences below one cent are ignored regardless of the threshold.
+    pub fn check_cost_discrepancy(
+        &mut self,
+        provider: &str,
+        local_cost: Decimal,
+        provider_cost: Decimal,
+        billing_period: &str,
+    ) -> Option<CostAlert> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let difference = (provider_cost - local_cost).abs();
+        if difference <= Decimal::new(1, 2) {
+            return None;
+        }
+
+        let baseline = provider_cost.max(local_cost);
+        let ratio = (difference / baseline).to_f32().unwrap_or(f32::MAX);
+        if ratio < self.config.discrepancy_threshold {
+            return None;
+        }
+
+        let severity = if ratio >= self.config.discrepancy_threshold * 4.0 {
+            AlertSeverity::Critical
+        } else {
+            AlertSeverity::Warning
+        };
+
+        let alert_key = format!("cost_discrepancy_{}_{}", provider, billing_period);
+        if !self.should_send_alert(&alert_key, severity.clone()) {
+            return None;
+        }
+
+        let now = Utc::now();
+        let alert = CostAlert {
+            id: uuid::Uuid::new_v4().to_string(),
+            alert_type: CostAlertType::CostDiscrepancy {
+                provider: provider.to_string(),
+                local_cost,
+                provider_cost,
+            },
+            severity,
+            message: format!(
+                "{} billed {} for {} but {} was tracked locally ({:.1}% difference)",
+                provider, self.config.money.format(provider_cost), billing_period, self.config.money.format(local_cost), ratio * 100.0
+            ),
+            timestamp: now,
+            acknowledged: false,
+            suggested_action: Some("Check the pricing table and usage recorded outside ValeChat".to_string()),
+            context: AlertContext {
+                provider: Some(provider.to_string()),
+                model: None,
+                conversation_id: None,
+                billing_period: billing_period.to_string(),
+            },
+        };
+
+        self.recent_alerts.insert(alert_key, now);
+        if let Err(e) = self.alert_sender.send(alert.clone()) {
+            error!("Failed to send cost alert: {}", e);
+        }
+        self.alert_history.push(alert.clone());
+
+        Some(alert)
+    }
+
+    /// Warn about budgets the forecast expects to run out within `budget_warning_days`
+    pub fn check_budget_forecast(&mut self, forecast: &SpendForecast, context: AlertContext) -> Vec<CostAlert> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+
+        let now = Utc::now();
+        let mut alerts = Vec::new();
+        for budget in &forecast.budgets {
+            let (Some(date), Some(days_left)) = (budget.exhaustion_date, budget.days_left) else {
+                continue;
+            };
+            if days_left > self.config.budget_warning_days {
+                continue;
+            }
+
+            let remaining = (budget.limit - budget.spent).max(Decimal::ZERO);
+            let severity = if remaining.is_zero() { AlertSeverity::Critical } else { AlertSeverity::Warning };
+            let alert_key = format!("budget_forecast_{}_{}", budget.description, forecast.billing_period);
+            if !self.should_send_alert(&alert_key, severity.clone()) {
+                continue;
+            }
+
+            let money = &self.config.money;
+            let message = if remaining.is_zero() {
+                format!("{} of {} is used up", capitalize(&budget.description), money.format(budget.limit))
+            } else if days_left == 0 {
+                format!("{} is projected to run out today ({} left)", capitalize(&budget.description), money.format(remaining))
+            } else {
+                format!(
+                    "{} is projected to run out in {} day{} on {} ({} left, {} projected)",
+                    capitalize(&budget.description),
+                    days_left,
+                    if days_left == 1 { "" } else { "s" },
+                    date,
+                    money.format(remaining),
+                    money.format(budget.projected)
+                )
+            };
+
+            let alert = CostAlert {
+                id: uuid::Uuid::new_v4().to_string(),
+                alert_type: CostAlertType::BudgetWarning { remaining, days_left },
+                severity,
+                message,
+                timestamp: now,
+                acknowledged: false,
+                suggested_action: Some("Slow down usage, switch to a cheaper model or raise the limit".to_string()),
+                context: context.clone(),
+            };
+
+            self.recent_alerts.insert(alert_key, now);
+            if let Err(e) = self.alert_sender.send(alert.clone()) {
+                error!("Failed to send cost alert: {}", e);
+            }
+            alerts.push(alert);
+        }
+
+        self.alert_history.extend(alerts.clone());
+        alerts
+    }
+
     /// Determine if an alert should be sent (avoid spam)
     fn should_send_alert(&self, alert_key: &str, severity: AlertSeverity) -> bool {
         if let Some(last_sent) = self.recent_alerts.get(alert_key) {
@@ -335,23 +540,151 @@ impl CostAlertSystem {
         }
     }
 
-    /// Get baseline metrics for anomaly detection
-    async fn get_baseline_metrics(&self, context: &AlertContext) -> Option<BaselineMetrics> {
-        let cache_key = format!(
-            "{}:{}",
-            context.provider.as_deref().unwrap_or("all"),
-            context.model.as_deref().unwrap_or("all")
-        );
+    fn record_request(&mut self, cost: Decimal, context: &AlertContext, now: DateTime<Utc>) {
+        // Requests without a price say nothing about spend and would drag the medians to zero
+        if let (Some(provider), Some(model), true) = (&context.provider, &context.model, cost > Decimal::ZERO) {
+            self.baselines.record(provider, model, context.conversation_id.as_deref(), cost, now);
+        }
+    }
+
+    /// Score a request against its provider/model baseline and its conversation against
+    /// the session baseline, then add it to both
+    fn detect_anomalies(&mut self, cost: Decimal, context: &AlertContext, now: DateTime<Utc>) -> Vec<CostAlert> {
+        let mut alerts = Vec::new();
+        let (Some(provider), Some(model)) = (context.provider.clone(), context.model.clone()) else {
+            return alerts;
+        };
+        let threshold = self.config.anomaly_score as f64;
+        let multiple = |value: f32| Decimal::from_f32_retain(value).unwrap_or(Decimal::ONE);
+
+        // Score before recording so the request is not part of its own baseline
+        let request_outlier = self.baselines.request_baseline(&provider, &model)
+            .and_then(|baseline| Some((baseline.median()?, baseline.score(cost)?)))
+            .filter(|(median, score)| {
+                *score >= threshold
+                    && cost >= *median * multiple(self.config.high_cost_threshold)
+                    && cost >= self.config.min_anomaly_cost
+            });
+        self.record_request(cost, context, now);
+
+        if let Some((median, score)) = request_outlier {
+            let severity = self.anomaly_severity(score >= threshold * 3.0);
+            let alert_key = format!("anomaly_request_{}_{}", provider, model);
+            if self.should_send_alert(&alert_key, severity.clone()) {
+                alerts.push(self.anomaly_alert(
+                    CostAlertType::HighCostRequest { cost, average_cost: median },
+                    severity,
+                    format!(
+                        "Unusually expensive {}/{} request: {}, {:.1}x the usual {}",
+                        provider, model, self.config.money.format_precise(cost), cost / median,
+                        self.config.money.format_precise(median)
+                    ),
+                    "Check for a large paste or attachment, or switch to a cheaper model",
+                    context,
+                    now,
+                ));
+                self.recent_alerts.insert(alert_key, now);
+            }
+        }
+
+        let Some(conversation_id) = context.conversation_id.as_deref() else {
+            return alerts;
+        };
+        let Some(session) = self.baselines.session(conversation_id) else {
+            return alerts;
+        };
+        let session_total = session.total;
+        let window = self.baselines.burst_window();
+        let (burst_count, burst_cost) = session.burst(window);
+        let session_median = self.baselines.session_baseline().and_then(|baseline| baseline.median());
 
-        if let Some(cached) = self.baseline_cache.get(&cache_key) {
-            if Utc::now() - cached.last_updated < Duration::hours(1) {
-                return Some(cached.clone());
+        if burst_count >= self.config.burst_requests as usize && burst_cost >= self.config.min_anomaly_cost {
+            // Many paid requests in a few minutes: most likely a tool-calling loop
+            let severity = self.anomaly_severity(true);
+            let alert_key = format!("anomaly_burst_{}", conversation_id);
+            if self.should_send_alert(&alert_key, severity.clone()) {
+                alerts.push(self.anomaly_alert(
+                    CostAlertType::SpendingSpike {
+                        current_rate: burst_cost,
+                        baseline_rate: session_median.unwrap_or(Decimal::ZERO),
+                    },
+                    severity,
+                    format!(
+                        "Runaway conversation: {} requests costing {} in {} minutes",
+                        burst_count, self.config.money.format_precise(burst_cost), window.num_minutes()
+                    ),
+                    "Stop the conversation and check for a tool-calling loop",
+                    context,
+                    now,
+                ));
+                self.recent_alerts.insert(alert_key, now);
+            }
+        } else if let Some(baseline) = self.baselines.session_baseline() {
+            let session_outlier = baseline.median()
+                .zip(baseline.score(session_total))
+                .filter(|(median, score)| {
+                    *score >= threshold
+                        && session_total >= *median * multiple(self.config.spike_threshold)
+                        && session_total >= self.config.min_anomaly_cost
+                });
+
+            if let Some((median, score)) = session_outlier {
+                let severity = self.anomaly_severity(score >= threshold * 3.0);
+                let alert_key = format!("anomaly_session_{}", conversation_id);
+                if self.should_send_alert(&alert_key, severity.clone()) {
+                    alerts.push(self.anomaly_alert(
+                        CostAlertType::SpendingSpike { current_rate: session_total, baseline_rate: median },
+                        severity,
+                        format!(
+                            "Conversation spend {} is {:.1}x a typical session ({})",
+                            self.config.money.format_precise(session_total), session_total / median,
+                            self.config.money.format_precise(median)
+                        ),
+                        "Review the conversation history; long contexts make every request more expensive",
+                        context,
+                        now,
+                    ));
+                    self.recent_alerts.insert(alert_key, now);
+                }
             }
         }
 
-        // In a real implementation, this would query the usage database
-        // For now, return a placeholder
-        None
+        alerts
+    }
+
+    fn anomaly_severity(&self, severe: bool) -> AlertSeverity {
+        match (severe, self.config.emergency_stop_on_anomaly) {
+            (true, true) => AlertSeverity::Emergency,
+            (true, false) => AlertSeverity::Critical,
+            (false, _) => AlertSeverity::Warning,
+        }
+    }
+
+    fn anomaly_alert(
+        &self,
+        alert_type: CostAlertType,
+        severity: AlertSeverity,
+        message: String,
+        action: &str,
+        context: &AlertContext,
+        now: DateTime<Utc>,
+    ) -> CostAlert {
+        let suggested_action = if severity == AlertSeverity::Emergency {
+            format!("{}. Requests are paused; resume with `/budget resume`", action)
+        } else {
+            action.to_string()
+        };
+
+        CostAlert {
+            id: uuid::Uuid::new_v4().to_string(),
+            alert_type,
+            severity,
+            message,
+            timestamp: now,
+            acknowledged: false,
+            suggested_action: Some(suggested_action),
+            context: context.clone(),
+        }
     }
 
     /// Get recent alert history
@@ -365,12 +698,24 @@ impl CostAlertSystem {
     }
 
     /// Acknowledge an alert
-    pub fn acknowledge_alert(&mut self, alert_id: &str) -> Result<()> {
+    ///
+    /// With a store attached the acknowledgement is persisted, so alerts raised in an
+    /// earlier session can be acknowledged too.
+    pub async fn acknowledge_alert(&mut self, alert_id: &str) -> Result<bool> {
+        let mut found = false;
         if let Some(alert) = self.alert_history.iter_mut().find(|a| a.id == alert_id) {
             alert.acknowledged = true;
+            found = true;
+        }
+
+        if let Some(store) = &self.store {
+            found |= store.acknowledge(alert_id).await?;
+        }
+
+        if found {
             info!("Acknowledged cost alert: {}", alert_id);
         }
-        Ok(())
+        Ok(found)
     }
 
     /// Update alert configuration
@@ -391,13 +736,114 @@ impl CostAlertSystem {
         // Clean recent alerts cache
         self.recent_alerts.retain(|_, timestamp| *timestamp > cutoff);
         
-        // Clean baseline cache
-        self.baseline_cache.retain(|_, metrics| metrics.last_updated > cutoff);
-        
         debug!("Cleaned up cost alert system caches");
     }
 }
 
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Persistent record of delivered cost alerts
+#[derive(Clone)]
+pub struct AlertStore {
+    pool: SqlitePool,
+}
+
+impl AlertStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Record an alert; saving the same alert twice is a no-op
+    pub async fn save(&self, alert: &CostAlert) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO cost_alerts
+                (id, alert_type, severity, message, suggested_action, context, timestamp, acknowledged)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&alert.id)
+        .bind(serde_json::to_string(&alert.alert_type)?)
+        .bind(alert.severity.as_str())
+        .bind(&alert.message)
+        .bind(&alert.suggested_action)
+        .bind(serde_json::to_string(&alert.context)?)
+        .bind(alert.timestamp.timestamp())
+        .bind(alert.acknowledged)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark an alert as acknowledged, returning false if it is unknown
+    pub async fn acknowledge(&self, alert_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE cost_alerts SET acknowledged = TRUE, acknowledged_at = ? WHERE id = ? AND acknowledged = FALSE"
+        )
+        .bind(Utc::now().timestamp())
+        .bind(alert_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Acknowledge every outstanding alert
+    pub async fn acknowledge_all(&self) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE cost_alerts SET acknowledged = TRUE, acknowledged_at = ? WHERE acknowledged = FALSE"
+        )
+        .bind(Utc::now().timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Most recent alerts first
+    pub async fn list(&self, unacknowledged_only: bool, limit: u32) -> Result<Vec<CostAlert>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, alert_type, severity, message, suggested_action, context, timestamp, acknowledged
+            FROM cost_alerts
+            WHERE (? = FALSE OR acknowledged = FALSE)
+            ORDER BY timestamp DESC
+            LIMIT ?
+            "#
+        )
+        .bind(unacknowledged_only)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let severity: String = row.get("severity");
+                let alert_type: String = row.get("alert_type");
+                let context: String = row.get("context");
+                Ok(CostAlert {
+                    id: row.get("id"),
+                    alert_type: serde_json::from_str(&alert_type)?,
+                    severity: AlertSeverity::parse(&severity)
+                        .ok_or_else(|| Error::unknown(format!("Unknown alert severity: {}", severity)))?,
+                    message: row.get("message"),
+                    timestamp: DateTime::from_timestamp(row.get("timestamp"), 0).unwrap_or_default(),
+                    acknowledged: row.get("acknowledged"),
+                    suggested_action: row.get("suggested_action"),
+                    context: serde_json::from_str(&context)?,
+                })
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -431,6 +877,7 @@ mod tests {
         assert_eq!(alerts.len(), 1);
         assert!(matches!(alerts[0].alert_type, CostAlertType::DailyLimit { .. }));
         assert_eq!(alerts[0].severity, AlertSeverity::Warning);
+        assert_eq!(alerts[0].message, "Daily spending $75.00 approaching $50.00 limit");
     }
 
     #[tokio::test]
@@ -462,6 +909,45 @@ mod tests {
         assert!(matches!(alerts[0].alert_type, CostAlertType::ProviderLimit { .. }));
     }
 
+    #[tokio::test]
+    async fn test_anomalous_request_and_runaway_session() {
+        let config = CostAlertConfig {
+            emergency_stop_on_anomaly: true,
+            ..Default::default()
+        };
+        let (mut system, _receiver) = CostAlertSystem::new(config);
+
+        async fn check(system: &mut CostAlertSystem, cost: Decimal, conversation_id: Option<&str>) -> Vec<CostAlert> {
+            let context = AlertContext {
+                provider: Some("openai".to_string()),
+                model: Some("gpt-4".to_string()),
+                conversation_id: conversation_id.map(str::to_string),
+                billing_period: "2024-01".to_string(),
+            };
+            system.check_spending_alerts(Decimal::ZERO, Decimal::ZERO, &HashMap::new(), cost, context).await.unwrap()
+        }
+
+        // Nothing is flagged until there is enough history
+        assert!(check(&mut system, Decimal::ONE, None).await.is_empty());
+        for _ in 0..BASELINE_MIN_SAMPLES {
+            assert!(check(&mut system, Decimal::new(1, 2), None).await.is_empty());
+        }
+
+        let alerts = check(&mut system, Decimal::new(150, 2), None).await;
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].alert_type, CostAlertType::HighCostRequest { .. }));
+        assert_eq!(alerts[0].severity, AlertSeverity::Emergency);
+
+        // Cheap requests become a runaway once enough land in the burst window
+        for _ in 1..default_burst_requests() {
+            assert!(check(&mut system, Decimal::new(1, 2), Some("loop")).await.is_empty());
+        }
+        let alerts = check(&mut system, Decimal::new(1, 2), Some("loop")).await;
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].alert_type, CostAlertType::SpendingSpike { .. }));
+        assert_eq!(alerts[0].severity, AlertSeverity::Emergency);
+    }
+
     #[test]
     fn test_alert_cooldown() {
         let config = CostAlertConfig::default();
@@ -475,7 +961,72 @@ mod tests {
     }
 
     #[test]
-    fn test_alert_acknowledgment() {
+    fn test_cost_discrepancy_alert() {
+        let (mut system, mut receiver) = CostAlertSystem::new(CostAlertConfig::default());
+
+        // Within threshold
+        assert!(system.check_cost_discrepancy("openai", Decimal::new(100, 0), Decimal::new(102, 0), "2026-09").is_none());
+
+        let alert = system
+            .check_cost_discrepancy("openai", Decimal::new(100, 0), Decimal::new(150, 0), "2026-09")
+            .unwrap();
+        assert!(matches!(alert.alert_type, CostAlertType::CostDiscrepancy { .. }));
+        assert_eq!(alert.severity, AlertSeverity::Critical);
+        assert!(receiver.try_recv().is_ok());
+
+        // Cooldown suppresses a repeat for the same provider and period
+        assert!(system.check_cost_discrepancy("openai", Decimal::new(100, 0), Decimal::new(150, 0), "2026-09").is_none());
+    }
+
+    #[test]
+    fn test_budget_forecast_alert() {
+        use crate::storage::forecast::BudgetProjection;
+        use crate::storage::dashboard::TrendDirection;
+        use crate::storage::BillingPeriod;
+
+        let (mut system, mut receiver) = CostAlertSystem::new(CostAlertConfig::default());
+        let today = Utc::now().date_naive();
+        let budget = |description: &str, spent: i64, days_left: Option<u32>| BudgetProjection {
+            description: description.to_string(),
+            period: BillingPeriod::Monthly,
+            limit: Decimal::new(100, 0),
+            spent: Decimal::new(spent, 0),
+            projected: Decimal::new(130, 0),
+            exhaustion_date: days_left.map(|days| today + Duration::days(days as i64)),
+            days_left,
+        };
+        let forecast = SpendForecast {
+            billing_period: "2026-10".to_string(),
+            generated_on: today,
+            month_to_date: Decimal::new(90, 0),
+            projected_month_end: Decimal::new(130, 0),
+            trend: TrendDirection::Increasing,
+            by_provider: HashMap::new(),
+            budgets: vec![
+                budget("global monthly limit", 90, Some(2)),
+                budget("openai monthly limit", 40, Some(10)),
+                budget("anthropic monthly limit", 10, None),
+            ],
+        };
+        let context = AlertContext {
+            provider: None,
+            model: None,
+            conversation_id: None,
+            billing_period: "2026-10".to_string(),
+        };
+
+        let alerts = system.check_budget_forecast(&forecast, context.clone());
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].alert_type, CostAlertType::BudgetWarning { remaining: Decimal::new(10, 0), days_left: 2 });
+        assert!(alerts[0].message.starts_with("Global monthly limit is projected to run out in 2 days"));
+        assert!(receiver.try_recv().is_ok());
+
+        // Cooldown suppresses a repeat warning for the same budget
+        assert!(system.check_budget_forecast(&forecast, context).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_alert_acknowledgment() {
         let config = CostAlertConfig::default();
         let (mut system, _receiver) = CostAlertSystem::new(config);
         
@@ -502,7 +1053,7 @@ mod tests {
         system.alert_history.push(alert);
         
         // Acknowledge the alert
-        system.acknowledge_alert("test-alert-123").unwrap();
+        assert!(system.acknowledge_alert("test-alert-123").await.unwrap());
         
         // Verify it's acknowledged
         assert!(system.alert_history[0].acknowledged);
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use sqlx::SqlitePool;
+use sqlx::{Row, SqlitePool};
 use tracing::debug;
 use rust_decimal::{Decimal, prelude::ToPrimitive};
 use chrono::{DateTime, Utc, Datelike, NaiveDate};
@@ -133,6 +133,29 @@ pub struct ModelSpend {
     pub tokens_used: u64, // Total input + output tokens
 }
 
+/// Conversation spending data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationSpend {
+    /// Empty for usage not tied to a conversation
+    pub conversation_id: String,
+    pub title: String,
+    pub amount: Decimal,
+    pub requests: u64,
+    pub percentage_of_total: f32,
+}
+
+/// Spending breakdown for a single billing period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodReport {
+    pub period: String,
+    pub total_spend: Decimal,
+    pub total_requests: u64,
+    pub providers: Vec<ProviderSpend>,
+    pub models: Vec<ModelSpend>,
+    pub conversations: Vec<ConversationSpend>,
+    pub daily_breakdown: Vec<DailySpend>,
+}
+
 /// Cost breakdown by categories
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CostBreakdown {
@@ -180,6 +203,10 @@ pub struct UsagePatterns {
     pub average_session_cost: Decimal,
     pub most_active_models: Vec<String>,
     pub efficiency_score: f32, // 0-100 score based on cost per output
+    /// Spend for every hour of the day (UTC), index = hour
+    pub hourly_heatmap: Vec<HourlyUsage>,
+    /// Spend for every weekday, Monday first
+    pub weekday_heatmap: Vec<WeekdayUsage>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -189,6 +216,13 @@ pub struct HourlyUsage {
     pub cost: Decimal,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeekdayUsage {
+    pub weekday: String, // "Monday"
+    pub requests: u64,
+    pub cost: Decimal,
+}
+
 /// Efficiency metrics for optimization
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EfficiencyMetrics {
@@ -421,12 +455,19 @@ impl BillingDashboard {
             total_spend / Decimal::from(days.max(1))
         };
 
-        // Determine trend direction
-        let (trend_direction, trend_percentage) = if daily_trend.len() >= 7 {
-            let recent_avg = daily_trend[..7].iter().map(|d| d.amount).sum::<Decimal>() / Decimal::from(7);
-            let older_avg = daily_trend[7..].iter().map(|d| d.amount).sum::<Decimal>() / Decimal::from((daily_trend.len() - 7).max(1));
+        // Determine trend direction; the trend is oldest first, so the last 7 days are the recent week
+        let (trend_direction, trend_percentage) = if daily_trend.len() > 7 {
+            let split = daily_trend.len() - 7;
+            let recent_avg = daily_trend[split..].iter().map(|d| d.amount).sum::<Decimal>() / Decimal::from(7);
+            let older_avg = daily_trend[..split].iter().map(|d| d.amount).sum::<Decimal>() / Decimal::from(split);
             
-            if recent_avg > older_avg * Decimal::new(105, 2) { // 5% increase
+            if older_avg.is_zero() {
+                if recent_avg > Decimal::ZERO {
+                    (TrendDirection::Increasing, 100.0)
+                } else {
+                    (TrendDirection::Stable, 0.0)
+                }
+            } else if recent_avg > older_avg * Decimal::new(105, 2) { // 5% increase
                 (TrendDirection::Increasing, ((recent_avg - older_avg) / older_avg * Decimal::from(100)).to_f32().unwrap_or(0.0))
             } else if recent_avg < older_avg * Decimal::new(95, 2) { // 5% decrease
                 (TrendDirection::Decreasing, ((older_avg - recent_avg) / older_avg * Decimal::from(100)).to_f32().unwrap_or(0.0))
@@ -441,7 +482,7 @@ impl BillingDashboard {
         let seasonal_patterns = vec![]; // Seasonal analysis will be implemented in Phase 5
 
         // Generate usage patterns
-        let usage_patterns = self.get_usage_patterns().await?;
+        let usage_patterns = self.get_usage_patterns(days).await?;
 
         Ok(SpendingTrends {
             daily_trend,
@@ -583,10 +624,10 @@ impl BillingDashboard {
         Ok(count as u64)
     }
 
-    async fn get_most_expensive_day(&self, _period: &str) -> Result<Option<DailySpend>> {
-        // This would require more complex date handling in SQL
-        // For now, return None as placeholder
-        Ok(None)
+    /// Day with the highest spend in a billing period
+    pub async fn get_most_expensive_day(&self, period: &str) -> Result<Option<DailySpend>> {
+        let breakdown = self.get_daily_breakdown(period).await?;
+        Ok(breakdown.into_iter().max_by(|a, b| a.amount.cmp(&b.amount)))
     }
 
     fn get_days_in_current_month(&self) -> u32 {
@@ -606,54 +647,480 @@ impl BillingDashboard {
         }
     }
 
-    async fn get_daily_breakdown(&self, _period: &str) -> Result<Vec<DailySpend>> {
-        // Placeholder implementation
-        Ok(Vec::new())
+    /// Billing periods that have usage, newest first
+    pub async fn get_available_periods(&self) -> Result<Vec<String>> {
+        let periods: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT billing_period FROM usage_records WHERE billing_period IS NOT NULL ORDER BY billing_period DESC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(periods)
+    }
+
+    /// Full spending breakdown for a billing period (`YYYY-MM`)
+    pub async fn get_period_report(&self, period: &str) -> Result<PeriodReport> {
+        let providers = self.get_provider_breakdown(period).await?;
+        let models = self.get_model_breakdown(period).await?;
+        let conversations = self.get_conversation_breakdown(period, None, 50).await?;
+        let daily_breakdown = self.get_daily_breakdown(period).await?;
+
+        Ok(PeriodReport {
+            period: period.to_string(),
+            total_spend: providers.iter().map(|p| p.amount).sum(),
+            total_requests: providers.iter().map(|p| p.requests).sum(),
+            providers,
+            models,
+            conversations,
+            daily_breakdown,
+        })
+    }
+
+    /// Spend per provider for a billing period, most expensive first
+    pub async fn get_provider_breakdown(&self, period: &str) -> Result<Vec<ProviderSpend>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT provider, SUM(CAST(cost AS REAL)) as cost, COUNT(*) as requests
+            FROM usage_records
+            WHERE billing_period = ?
+            GROUP BY provider
+            ORDER BY cost DESC
+            "#
+        )
+        .bind(period)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let spends: Vec<(String, Decimal, u64)> = rows.iter()
+            .map(|row| (
+                row.get("provider"),
+                Decimal::try_from(row.get::<f64, _>("cost")).unwrap_or(Decimal::ZERO),
+                row.get::<i64, _>("requests") as u64,
+            ))
+            .collect();
+        let total: Decimal = spends.iter().map(|(_, cost, _)| *cost).sum();
+
+        Ok(spends.into_iter()
+            .map(|(provider, amount, requests)| ProviderSpend {
+                provider,
+                amount,
+                requests,
+                percentage_of_total: percentage_of(amount, total),
+                average_cost_per_request: if requests > 0 { amount / Decimal::from(requests) } else { Decimal::ZERO },
+            })
+            .collect())
+    }
+
+    /// Spend per model for a billing period, most expensive first
+    pub async fn get_model_breakdown(&self, period: &str) -> Result<Vec<ModelSpend>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT model, provider, SUM(CAST(cost AS REAL)) as cost, COUNT(*) as requests,
+                   SUM(input_tokens + output_tokens) as tokens
+            FROM usage_records
+            WHERE billing_period = ?
+            GROUP BY model, provider
+            ORDER BY cost DESC
+            "#
+        )
+        .bind(period)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut models: Vec<ModelSpend> = rows.iter()
+            .map(|row| ModelSpend {
+                model: row.get("model"),
+                provider: row.get("provider"),
+                amount: Decimal::try_from(row.get::<f64, _>("cost")).unwrap_or(Decimal::ZERO),
+                requests: row.get::<i64, _>("requests") as u64,
+                percentage_of_total: 0.0,
+                tokens_used: row.get::<i64, _>("tokens") as u64,
+            })
+            .collect();
+
+        let total: Decimal = models.iter().map(|m| m.amount).sum();
+        for model in &mut models {
+            model.percentage_of_total = percentage_of(model.amount, total);
+        }
+
+        Ok(models)
+    }
+
+    /// Conversations that spent the most in a billing period
+    ///
+    /// With `model` set, only usage of that model counts, which drills down from a model
+    /// to the conversations that used it.
+    pub async fn get_conversation_breakdown(
+        &self,
+        period: &str,
+        model: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<ConversationSpend>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT COALESCE(u.conversation_id, '') as conversation_id,
+                   COALESCE(c.title, '') as title,
+                   SUM(CAST(u.cost AS REAL)) as cost, COUNT(*) as requests
+            FROM usage_records u
+            LEFT JOIN conversations c ON c.id = u.conversation_id
+            WHERE u.billing_period = ? AND (? IS NULL OR u.model = ?)
+            GROUP BY u.conversation_id
+            ORDER BY cost DESC
+            "#
+        )
+        .bind(period)
+        .bind(model)
+        .bind(model)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut conversations: Vec<ConversationSpend> = rows.iter()
+            .map(|row| {
+                let conversation_id: String = row.get("conversation_id");
+                let title: String = row.get("title");
+                ConversationSpend {
+                    title: if conversation_id.is_empty() {
+                        "(no conversation)".to_string()
+                    } else if title.is_empty() {
+                        "(deleted conversation)".to_string()
+                    } else {
+                        title
+                    },
+                    conversation_id,
+                    amount: Decimal::try_from(row.get::<f64, _>("cost")).unwrap_or(Decimal::ZERO),
+                    requests: row.get::<i64, _>("requests") as u64,
+                    percentage_of_total: 0.0,
+                }
+            })
+            .collect();
+
+        let total: Decimal = conversations.iter().map(|c| c.amount).sum();
+        for conversation in &mut conversations {
+            conversation.percentage_of_total = percentage_of(conversation.amount, total);
+        }
+        conversations.truncate(limit as usize);
+
+        Ok(conversations)
     }
 
-    async fn get_daily_trend(&self, _days: u32) -> Result<Vec<DailySpend>> {
-        // Placeholder implementation
-        Ok(Vec::new())
+    /// Per-day spend for a billing period (`YYYY-MM`), oldest first
+    ///
+    /// Only days with usage are included.
+    pub async fn get_daily_breakdown(&self, period: &str) -> Result<Vec<DailySpend>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT date(timestamp, 'unixepoch') as day, provider,
+                   SUM(CAST(cost AS REAL)) as cost, COUNT(*) as requests
+            FROM usage_records
+            WHERE billing_period = ?
+            GROUP BY day, provider
+            ORDER BY day
+            "#
+        )
+        .bind(period)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut days: Vec<DailySpend> = Vec::new();
+        for row in rows {
+            let day: String = row.get("day");
+            let Ok(date) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") else {
+                continue;
+            };
+            let provider: String = row.get("provider");
+            let cost = Decimal::try_from(row.get::<f64, _>("cost")).unwrap_or(Decimal::ZERO);
+            let requests = row.get::<i64, _>("requests") as u64;
+
+            if days.last().map(|d| d.date) != Some(date) {
+                days.push(DailySpend {
+                    date,
+                    amount: Decimal::ZERO,
+                    requests: 0,
+                    providers: HashMap::new(),
+                });
+            }
+            if let Some(entry) = days.last_mut() {
+                entry.amount += cost;
+                entry.requests += requests;
+                *entry.providers.entry(provider).or_insert(Decimal::ZERO) += cost;
+            }
+        }
+
+        Ok(days)
     }
 
-    async fn get_usage_patterns(&self) -> Result<UsagePatterns> {
-        // Placeholder implementation
+    /// Per-day spend for the last `days` days including today, oldest first
+    ///
+    /// Days without usage are filled with zero so the series is continuous.
+    pub async fn get_daily_trend(&self, days: u32) -> Result<Vec<DailySpend>> {
+        let days = days.max(1);
+        let today = Utc::now().date_naive();
+        let first_day = today - chrono::Duration::days(days as i64 - 1);
+        let start = first_day.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc().timestamp()).unwrap_or(0);
+
+        let rows = sqlx::query(
+            r#"
+            SELECT date(timestamp, 'unixepoch') as day, provider,
+                   SUM(CAST(cost AS REAL)) as cost, COUNT(*) as requests
+            FROM usage_records
+            WHERE timestamp >= ?
+            GROUP BY day, provider
+            "#
+        )
+        .bind(start)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut trend: Vec<DailySpend> = (0..days)
+            .map(|offset| DailySpend {
+                date: first_day + chrono::Duration::days(offset as i64),
+                amount: Decimal::ZERO,
+                requests: 0,
+                providers: HashMap::new(),
+            })
+            .collect();
+
+        for row in rows {
+            let day: String = row.get("day");
+            let Ok(date) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") else {
+                continue;
+            };
+            let index = (date - first_day).num_days();
+            let Some(entry) = usize::try_from(index).ok().and_then(|i| trend.get_mut(i)) else {
+                continue;
+            };
+            let provider: String = row.get("provider");
+            let cost = Decimal::try_from(row.get::<f64, _>("cost")).unwrap_or(Decimal::ZERO);
+
+            entry.amount += cost;
+            entry.requests += row.get::<i64, _>("requests") as u64;
+            *entry.providers.entry(provider).or_insert(Decimal::ZERO) += cost;
+        }
+
+        Ok(trend)
+    }
+
+    /// When and where spend happens over the last `days` days
+    ///
+    /// Hours and weekdays are in UTC, matching billing periods.
+    pub async fn get_usage_patterns(&self, days: u32) -> Result<UsagePatterns> {
+        let start = (Utc::now() - chrono::Duration::days(days.max(1) as i64)).timestamp();
+
+        // Hour-of-day heatmap
+        let hour_rows = sqlx::query(
+            r#"
+            SELECT CAST(strftime('%H', timestamp, 'unixepoch') AS INTEGER) as hour,
+                   COUNT(*) as requests, SUM(CAST(cost AS REAL)) as cost
+            FROM usage_records
+            WHERE timestamp >= ?
+            GROUP BY hour
+            "#
+        )
+        .bind(start)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut hourly_heatmap: Vec<HourlyUsage> = (0..24)
+            .map(|hour| HourlyUsage { hour, requests: 0, cost: Decimal::ZERO })
+            .collect();
+        for row in hour_rows {
+            let hour: i64 = row.get("hour");
+            if let Some(entry) = usize::try_from(hour).ok().and_then(|h| hourly_heatmap.get_mut(h)) {
+                entry.requests = row.get::<i64, _>("requests") as u64;
+                entry.cost = Decimal::try_from(row.get::<f64, _>("cost")).unwrap_or(Decimal::ZERO);
+            }
+        }
+
+        // Weekday heatmap; SQLite numbers days from Sunday = 0
+        let weekday_rows = sqlx::query(
+            r#"
+            SELECT CAST(strftime('%w', timestamp, 'unixepoch') AS INTEGER) as weekday,
+                   COUNT(*) as requests, SUM(CAST(cost AS REAL)) as cost
+            FROM usage_records
+            WHERE timestamp >= ?
+            GROUP BY weekday
+            "#
+        )
+        .bind(start)
+        .fetch_all(&self.pool)
+        .await?;
+
+        const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+        let mut weekday_heatmap: Vec<WeekdayUsage> = WEEKDAYS.iter()
+            .map(|day| WeekdayUsage { weekday: day.to_string(), requests: 0, cost: Decimal::ZERO })
+            .collect();
+        for row in weekday_rows {
+            let weekday: i64 = row.get("weekday");
+            let index = ((weekday + 6) % 7) as usize;
+            if let Some(entry) = weekday_heatmap.get_mut(index) {
+                entry.requests = row.get::<i64, _>("requests") as u64;
+                entry.cost = Decimal::try_from(row.get::<f64, _>("cost")).unwrap_or(Decimal::ZERO);
+            }
+        }
+
+        let mut peak_hours: Vec<HourlyUsage> = hourly_heatmap.iter()
+            .filter(|h| h.requests > 0)
+            .cloned()
+            .collect();
+        peak_hours.sort_by(|a, b| b.cost.cmp(&a.cost).then(b.requests.cmp(&a.requests)));
+        peak_hours.truncate(3);
+
+        let mut busiest_days: Vec<&WeekdayUsage> = weekday_heatmap.iter()
+            .filter(|d| d.requests > 0)
+            .collect();
+        busiest_days.sort_by(|a, b| b.cost.cmp(&a.cost).then(b.requests.cmp(&a.requests)));
+        let peak_days = busiest_days.iter().take(2).map(|d| d.weekday.clone()).collect();
+
+        // Most active models and their cost per output token
+        let model_rows = sqlx::query(
+            r#"
+            SELECT model, COUNT(*) as requests, SUM(CAST(cost AS REAL)) as cost,
+                   SUM(output_tokens) as output_tokens
+            FROM usage_records
+            WHERE timestamp >= ?
+            GROUP BY model
+            ORDER BY requests DESC, cost DESC
+            "#
+        )
+        .bind(start)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let most_active_models = model_rows.iter()
+            .take(5)
+            .map(|row| row.get::<String, _>("model"))
+            .collect();
+
+        let model_rates: Vec<(f64, i64)> = model_rows.iter()
+            .map(|row| (row.get::<f64, _>("cost"), row.get::<i64, _>("output_tokens")))
+            .collect();
+        let efficiency_score = efficiency_score(&model_rates);
+
+        // Average cost of a conversation that had any usage
+        let average_session_cost: f64 = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(AVG(session_cost), 0.0) FROM (
+                SELECT SUM(CAST(u.cost AS REAL)) as session_cost
+                FROM usage_records u
+                JOIN conversations c ON c.id = u.conversation_id
+                WHERE u.timestamp >= ?
+                GROUP BY c.id
+            )
+            "#
+        )
+        .bind(start)
+        .fetch_one(&self.pool)
+        .await?;
+
         Ok(UsagePatterns {
-            peak_hours: Vec::new(),
-            peak_days: Vec::new(),
-            average_session_cost: Decimal::ZERO,
-            most_active_models: Vec::new(),
-            efficiency_score: 75.0,
+            peak_hours,
+            peak_days,
+            average_session_cost: Decimal::try_from(average_session_cost).unwrap_or(Decimal::ZERO),
+            most_active_models,
+            efficiency_score,
+            hourly_heatmap,
+            weekday_heatmap,
         })
     }
 
     /// Export dashboard data to various formats
-    pub async fn export_report(&self, format: ExportFormat, _period: Option<String>) -> Result<String> {
-        let dashboard_data = self.get_dashboard_data().await?;
-        
+    ///
+    /// Without a period, JSON contains the full dashboard and CSV covers the current month.
+    pub async fn export_report(&self, format: ExportFormat, period: Option<String>) -> Result<String> {
         match format {
-            ExportFormat::Json => Ok(serde_json::to_string_pretty(&dashboard_data)?),
+            ExportFormat::Json => match period {
+                Some(period) => Ok(serde_json::to_string_pretty(&self.get_period_report(&period).await?)?),
+                None => Ok(serde_json::to_string_pretty(&self.get_dashboard_data().await?)?),
+            },
             ExportFormat::Csv => {
-                // Create CSV export (simplified)
+                let period = period.unwrap_or_else(|| Utc::now().format("%Y-%m").to_string());
+                let report = self.get_period_report(&period).await?;
+
                 let mut csv = String::new();
                 csv.push_str("Provider,Amount,Requests,Percentage\n");
-                
-                for provider in &dashboard_data.current_month.top_providers {
+                for provider in &report.providers {
                     csv.push_str(&format!(
                         "{},{},{},{:.2}%\n",
-                        provider.provider,
+                        csv_field(&provider.provider),
                         provider.amount,
                         provider.requests,
                         provider.percentage_of_total
                     ));
                 }
-                
+
+                csv.push_str("\nModel,Provider,Amount,Requests,Tokens,Percentage\n");
+                for model in &report.models {
+                    csv.push_str(&format!(
+                        "{},{},{},{},{},{:.2}%\n",
+                        csv_field(&model.model),
+                        csv_field(&model.provider),
+                        model.amount,
+                        model.requests,
+                        model.tokens_used,
+                        model.percentage_of_total
+                    ));
+                }
+
+                csv.push_str("\nConversation,Title,Amount,Requests,Percentage\n");
+                for conversation in &report.conversations {
+                    csv.push_str(&format!(
+                        "{},{},{},{},{:.2}%\n",
+                        csv_field(&conversation.conversation_id),
+                        csv_field(&conversation.title),
+                        conversation.amount,
+                        conversation.requests,
+                        conversation.percentage_of_total
+                    ));
+                }
+
+                csv.push_str("\nDate,Amount,Requests\n");
+                for day in &report.daily_breakdown {
+                    csv.push_str(&format!("{},{},{}\n", day.date, day.amount, day.requests));
+                }
+
                 Ok(csv)
             }
         }
     }
 }
 
+fn percentage_of(amount: Decimal, total: Decimal) -> f32 {
+    if total > Decimal::ZERO {
+        (amount / total * Decimal::from(100)).to_f32().unwrap_or(0.0)
+    } else {
+        0.0
+    }
+}
+
+/// Score 0-100 comparing the overall cost per output token with the cheapest model used
+///
+/// 100 means all output came at the rate of the most cost-effective model.
+fn efficiency_score(model_rates: &[(f64, i64)]) -> f32 {
+    let rates: Vec<f64> = model_rates.iter()
+        .filter(|(cost, output)| *output > 0 && *cost > 0.0)
+        .map(|(cost, output)| cost / *output as f64)
+        .collect();
+    let total_cost: f64 = model_rates.iter().filter(|(_, output)| *output > 0).map(|(cost, _)| cost).sum();
+    let total_output: i64 = model_rates.iter().map(|(_, output)| output).sum();
+
+    if rates.is_empty() || total_output == 0 || total_cost <= 0.0 {
+        return 0.0;
+    }
+
+    let best = rates.iter().cloned().fold(f64::INFINITY, f64::min);
+    let overall = total_cost / total_output as f64;
+    ((best / overall) * 100.0).clamp(0.0, 100.0) as f32
+}
+
+/// Quote a CSV field when it contains separators or quotes
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ExportFormat {
     Json,
@@ -665,8 +1132,41 @@ mod tests {
     use super::*;
     use crate::storage::Database;
     use crate::platform::AppPaths;
+    use chrono::TimeZone;
     use tempfile::TempDir;
 
+    async fn insert_usage(
+        dashboard: &BillingDashboard,
+        timestamp: DateTime<Utc>,
+        provider: &str,
+        model: &str,
+        cost: &str,
+        conversation_id: &str,
+    ) {
+        sqlx::query("INSERT OR IGNORE INTO conversations (id, title) VALUES (?, 'Test')")
+            .bind(conversation_id)
+            .execute(&dashboard.pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO usage_records (timestamp, provider, model, input_tokens, output_tokens, cost,
+                                       conversation_id, request_id, billing_period)
+            VALUES (?, ?, ?, 100, 1000, ?, ?, ?, ?)
+            "#
+        )
+        .bind(timestamp.timestamp())
+        .bind(provider)
+        .bind(model)
+        .bind(cost)
+        .bind(conversation_id)
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(timestamp.format("%Y-%m").to_string())
+        .execute(&dashboard.pool)
+        .await
+        .unwrap();
+    }
+
     async fn create_test_dashboard() -> (BillingDashboard, TempDir) {
         let temp_dir = TempDir::new().unwrap();
         let paths = AppPaths::with_data_dir(temp_dir.path()).unwrap();
@@ -723,4 +1223,90 @@ mod tests {
         assert!(metrics.cost_per_successful_request >= Decimal::ZERO);
         assert!(metrics.verification_rate >= 0.0 && metrics.verification_rate <= 100.0);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_daily_breakdown_and_most_expensive_day() {
+        let (dashboard, _temp_dir) = create_test_dashboard().await;
+        let monday = Utc.with_ymd_and_hms(2026, 9, 7, 10, 0, 0).unwrap();
+
+        insert_usage(&dashboard, monday, "openai", "gpt-4o", "1.00", "c1").await;
+        insert_usage(&dashboard, monday + chrono::Duration::hours(4), "anthropic", "claude-3-haiku", "0.50", "c1").await;
+        insert_usage(&dashboard, monday + chrono::Duration::days(2), "openai", "gpt-4o", "2.00", "c2").await;
+
+        let breakdown = dashboard.get_daily_breakdown("2026-09").await.unwrap();
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown[0].date, monday.date_naive());
+        assert_eq!(breakdown[0].amount, Decimal::new(15, 1));
+        assert_eq!(breakdown[0].requests, 2);
+        assert_eq!(breakdown[0].providers.len(), 2);
+
+        let most_expensive = dashboard.get_most_expensive_day("2026-09").await.unwrap().unwrap();
+        assert_eq!(most_expensive.date, (monday + chrono::Duration::days(2)).date_naive());
+        assert!(dashboard.get_most_expensive_day("2026-08").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_daily_trend_and_usage_patterns() {
+        let (dashboard, _temp_dir) = create_test_dashboard().await;
+        let yesterday = (Utc::now() - chrono::Duration::days(1)).date_naive();
+        let morning = yesterday.and_hms_opt(10, 0, 0).unwrap().and_utc();
+
+        insert_usage(&dashboard, morning, "openai", "gpt-4o", "1.00", "c1").await;
+        insert_usage(&dashboard, morning + chrono::Duration::minutes(20), "openai", "gpt-4o", "1.00", "c1").await;
+        insert_usage(&dashboard, morning + chrono::Duration::hours(5), "anthropic", "claude-3-haiku", "0.50", "c2").await;
+
+        let trend = dashboard.get_daily_trend(7).await.unwrap();
+        assert_eq!(trend.len(), 7);
+        assert_eq!(trend[5].date, yesterday);
+        assert_eq!(trend[5].amount, Decimal::new(25, 1));
+        assert_eq!(trend[6].amount, Decimal::ZERO);
+
+        let patterns = dashboard.get_usage_patterns(30).await.unwrap();
+        assert_eq!(patterns.hourly_heatmap.len(), 24);
+        assert_eq!(patterns.hourly_heatmap[10].requests, 2);
+        assert_eq!(patterns.peak_hours[0].hour, 10);
+        assert_eq!(patterns.weekday_heatmap.iter().map(|d| d.requests).sum::<u64>(), 3);
+        assert_eq!(patterns.peak_days.len(), 1);
+        assert_eq!(patterns.most_active_models[0], "gpt-4o");
+        assert_eq!(patterns.average_session_cost, Decimal::new(125, 2));
+        assert!((patterns.efficiency_score - 60.0).abs() < 0.1);
+    }
+
+    #[tokio::test]
+    async fn test_model_drill_down_and_period_export() {
+        let (dashboard, _temp_dir) = create_test_dashboard().await;
+        let day = Utc.with_ymd_and_hms(2026, 9, 7, 10, 0, 0).unwrap();
+
+        insert_usage(&dashboard, day, "openai", "gpt-4o", "3.00", "c1").await;
+        insert_usage(&dashboard, day, "openai", "gpt-4o", "1.00", "c2").await;
+        insert_usage(&dashboard, day, "anthropic", "claude-3-haiku", "5.00", "c2").await;
+        sqlx::query("UPDATE conversations SET title = 'Budget, review' WHERE id = 'c1'")
+            .execute(&dashboard.pool)
+            .await
+            .unwrap();
+
+        let models = dashboard.get_model_breakdown("2026-09").await.unwrap();
+        assert_eq!(models[0].model, "claude-3-haiku");
+        assert_eq!(models[1].tokens_used, 2200);
+
+        let all = dashboard.get_conversation_breakdown("2026-09", None, 10).await.unwrap();
+        assert_eq!(all[0].conversation_id, "c2");
+        assert_eq!(all[0].amount, Decimal::new(6, 0));
+
+        let gpt = dashboard.get_conversation_breakdown("2026-09", Some("gpt-4o"), 10).await.unwrap();
+        assert_eq!(gpt.len(), 2);
+        assert_eq!(gpt[0].conversation_id, "c1");
+        assert_eq!(gpt[0].title, "Budget, review");
+        assert!((gpt[0].percentage_of_total - 75.0).abs() < 0.01);
+
+        assert_eq!(dashboard.get_available_periods().await.unwrap(), vec!["2026-09".to_string()]);
+
+        let csv = dashboard.export_report(ExportFormat::Csv, Some("2026-09".to_string())).await.unwrap();
+        assert!(csv.contains("\"Budget, review\""));
+        assert!(csv.contains("Model,Provider,Amount,Requests,Tokens,Percentage"));
+
+        let json = dashboard.export_report(ExportFormat::Json, Some("2026-09".to_string())).await.unwrap();
+        let report: PeriodReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(report.total_requests, 3);
+    }
+}
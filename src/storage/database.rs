@@ -1,9 +1,10 @@
 use std::path::Path;
 use sqlx::{SqlitePool, migrate::MigrateDatabase};
-use tracing::{info, debug, error, warn};
+use tracing::{info, error};
 
 use crate::error::{Error, Result};
 use crate::platform::AppPaths;
+use crate::storage::migrations::Migrator;
 
 /// Database connection manager with migration support
 pub struct Database {
@@ -13,6 +14,17 @@ pub struct Database {
 impl Database {
     /// Create a new database connection and run migrations
     pub async fn new(paths: &AppPaths) -> Result<Self> {
+        let db = Self::open(paths).await?;
+
+        // Run migrations
+        db.migrator().run().await?;
+
+        info!("Database initialized successfully");
+        Ok(db)
+    }
+
+    /// Open the database without touching its schema
+    pub async fn open(paths: &AppPaths) -> Result<Self> {
         let db_path = paths.data_dir().join("valechat.db");
         
         info!("Initializing database at: {:?}", db_path);
@@ -27,13 +39,7 @@ impl Database {
         let database_url = format!("sqlite:{}?mode=rwc", db_path.display());
         let pool = SqlitePool::connect(&database_url).await?;
 
-        let db = Self { pool };
-
-        // Run migrations
-        db.run_migrations().await?;
-
-        info!("Database initialized successfully");
-        Ok(db)
+        Ok(Self { pool })
     }
 
     /// Get a reference to the database connection pool
@@ -41,103 +47,9 @@ impl Database {
         self.pool.clone()
     }
 
-    /// Run all pending database migrations
-    async fn run_migrations(&self) -> Result<()> {
-        info!("Running database migrations");
-
-        // Check current database version
-        let current_version = self.get_database_version().await?;
-        debug!("Current database version: {}", current_version);
-
-        // Load and execute migrations
-        let migrations_dir = std::env::current_dir()?.join("migrations");
-        if !migrations_dir.exists() {
-            warn!("Migrations directory not found: {:?}", migrations_dir);
-            return Ok(());
-        }
-
-        let mut migration_files = std::fs::read_dir(&migrations_dir)?
-            .filter_map(|entry| entry.ok())
-            .map(|entry| entry.path())
-            .filter(|path| path.extension().is_some_and(|ext| ext == "sql"))
-            .collect::<Vec<_>>();
-
-        migration_files.sort();
-
-        for migration_file in migration_files {
-            let file_name = migration_file.file_stem()
-                .and_then(|name| name.to_str())
-                .unwrap_or("unknown");
-
-            // Extract migration number from filename (e.g., "001_initial" -> 1)
-            let migration_number: i32 = file_name
-                .split('_')
-                .next()
-                .and_then(|num| num.parse().ok())
-                .unwrap_or(0);
-
-            if migration_number <= current_version {
-                debug!("Skipping migration {} (already applied)", file_name);
-                continue;
-            }
-
-            info!("Applying migration: {}", file_name);
-            let sql_content = std::fs::read_to_string(&migration_file)?;
-
-            // Execute the migration
-            sqlx::query(&sql_content).execute(&self.pool).await.map_err(|e| {
-                error!("Failed to apply migration {}: {}", file_name, e);
-                Error::Database(e)
-            })?;
-
-            // Update database version
-            self.update_database_version(migration_number).await?;
-            info!("Successfully applied migration: {}", file_name);
-        }
-
-        info!("All migrations completed successfully");
-        Ok(())
-    }
-
-    /// Get the current database version
-    async fn get_database_version(&self) -> Result<i32> {
-        // First check if the app_settings table exists
-        let table_exists = sqlx::query(
-            "SELECT name FROM sqlite_master WHERE type='table' AND name='app_settings'"
-        )
-        .fetch_optional(&self.pool)
-        .await?
-        .is_some();
-
-        if !table_exists {
-            return Ok(0); // No migrations applied yet
-        }
-
-        // Get the database version from settings
-        let version: Option<String> = sqlx::query_scalar(
-            "SELECT value FROM app_settings WHERE key = 'database_version'"
-        )
-        .fetch_optional(&self.pool)
-        .await?;
-
-        match version {
-            Some(version_str) => version_str.parse().map_err(|e| {
-                Error::Database(sqlx::Error::Decode(format!("Invalid database version: {}", e).into()))
-            }),
-            None => Ok(0),
-        }
-    }
-
-    /// Update the database version
-    async fn update_database_version(&self, version: i32) -> Result<()> {
-        sqlx::query(
-            "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES ('database_version', ?, unixepoch())"
-        )
-        .bind(version.to_string())
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+    /// Schema migrator for this database
+    pub fn migrator(&self) -> Migrator {
+        Migrator::new(self.pool.clone())
     }
 
     /// Get the database connection pool
@@ -217,6 +129,71 @@ impl Database {
         Ok(())
     }
 
+    /// Copy the database page for page into `path` with SQLite's online backup API
+    ///
+    /// Unlike [`Database::backup`], the copy keeps the page layout of the live file, so
+    /// consecutive snapshots differ only in the pages that were written in between.
+    pub async fn snapshot<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.copy_pages(path.as_ref(), CopyDirection::ToFile).await
+    }
+
+    /// Replace the contents of the live database with the database file at `path`
+    pub async fn restore_from<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.copy_pages(path.as_ref(), CopyDirection::FromFile).await
+    }
+
+    async fn copy_pages(&self, path: &Path, direction: CopyDirection) -> Result<()> {
+        use libsqlite3_sys as ffi;
+        use std::ffi::{CStr, CString};
+
+        let c_path = CString::new(path.to_string_lossy().as_bytes())
+            .map_err(|_| Error::validation(format!("Invalid database path: {}", path.display())))?;
+        let flags = match direction {
+            CopyDirection::ToFile => ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE,
+            CopyDirection::FromFile => ffi::SQLITE_OPEN_READONLY,
+        };
+
+        let mut conn = self.pool.acquire().await?;
+        let mut handle = conn.lock_handle().await?;
+        let live = handle.as_raw_handle().as_ptr();
+
+        // SAFETY: `live` stays valid and unused by sqlx while `handle` holds the connection
+        // lock, and `file` is a connection owned and closed by this block.
+        unsafe {
+            let error_message = |db: *mut ffi::sqlite3| {
+                CStr::from_ptr(ffi::sqlite3_errmsg(db)).to_string_lossy().into_owned()
+            };
+
+            let mut file = std::ptr::null_mut();
+            if ffi::sqlite3_open_v2(c_path.as_ptr(), &mut file, flags, std::ptr::null()) != ffi::SQLITE_OK {
+                let message = error_message(file);
+                ffi::sqlite3_close(file);
+                return Err(Error::platform(format!("Failed to open {}: {}", path.display(), message)));
+            }
+
+            let (destination, source) = match direction {
+                CopyDirection::ToFile => (file, live),
+                CopyDirection::FromFile => (live, file),
+            };
+            let main = c"main".as_ptr();
+            let backup = ffi::sqlite3_backup_init(destination, main, source, main);
+            let result = if backup.is_null() {
+                Err(error_message(destination))
+            } else {
+                let step = ffi::sqlite3_backup_step(backup, -1);
+                let finish = ffi::sqlite3_backup_finish(backup);
+                if step != ffi::SQLITE_DONE || finish != ffi::SQLITE_OK {
+                    Err(error_message(destination))
+                } else {
+                    Ok(())
+                }
+            };
+            ffi::sqlite3_close(file);
+
+            result.map_err(|message| Error::platform(format!("Online backup failed: {}", message)))
+        }
+    }
+
     /// Verify database integrity
     pub async fn verify_integrity(&self) -> Result<bool> {
         info!("Verifying database integrity");
@@ -247,6 +224,12 @@ impl Database {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+enum CopyDirection {
+    ToFile,
+    FromFile,
+}
+
 /// Database statistics for monitoring
 #[derive(Debug, Clone)]
 pub struct DatabaseStatistics {
@@ -0,0 +1,386 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use tracing::{debug, info, warn};
+
+use crate::error::{Error, Result};
+use crate::platform::{crypto, SecureStorageManager};
+
+/// Prefix of an encrypted column value; anything else is stored in plain text
+pub const ENCRYPTED_PREFIX: &str = "enc2:";
+/// Values sealed with 96-bit random nonces; still read, and re-encrypted by [`DatabaseEncryption::encrypt_existing`]
+const LEGACY_PREFIX: &str = "enc1:";
+
+/// Secret holding the database field key in secure storage
+const DATABASE_KEY_SECRET: &str = "database_key";
+/// app_settings row proving which key the database is encrypted with
+const KEY_CHECK_SETTING: &str = "field_encryption_check";
+const KEY_CHECK_PLAINTEXT: &str = "valechat";
+/// Rows encrypted or decrypted per statement batch
+const BATCH_SIZE: i64 = 500;
+
+/// Columns holding conversation content, as (table, column)
+const ENCRYPTED_COLUMNS: [(&str, &str); 3] = [
+    ("messages", "content"),
+    ("tool_invocations", "arguments"),
+    ("tool_invocations", "result"),
+];
+
+/// Encryption of conversation content at rest
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EncryptionConfig {
+    /// Encrypt message content and tool arguments/results, migrating existing rows at startup
+    pub enabled: bool,
+}
+
+/// Encrypts individual column values with the database field key
+///
+/// Values are sealed with XChaCha20-Poly1305, as one key covers every message ever stored. Each value is bound to its column and row ID, so encrypted values cannot be moved
+/// between rows without failing to decrypt.
+#[derive(Clone)]
+pub struct FieldCipher {
+    key: [u8; crypto::KEY_LEN],
+}
+
+impl std::fmt::Debug for FieldCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FieldCipher { .. }")
+    }
+}
+
+impl FieldCipher {
+    pub fn new(key: [u8; crypto::KEY_LEN]) -> Self {
+        Self { key }
+    }
+
+    pub fn generate() -> Result<Self> {
+        Ok(Self::new(crypto::random_bytes()?))
+    }
+
+    /// Parse a key exported with [`FieldCipher::export_key`]
+    pub fn from_exported(encoded: &str) -> Result<Self> {
+        let key = BASE64.decode(encoded.trim()).ok()
+            .and_then(|bytes| <[u8; crypto::KEY_LEN]>::try_from(bytes).ok())
+            .ok_or_else(|| Error::validation("Invalid database encryption key"))?;
+        Ok(Self::new(key))
+    }
+
+    pub fn export_key(&self) -> String {
+        BASE64.encode(self.key)
+    }
+
+    pub fn encrypt(&self, column: &str, row_id: &str, plaintext: &str) -> Result<String> {
+        let sealed = crypto::xseal(&self.key, plaintext.as_bytes(), &aad(column, row_id))?;
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(sealed)))
+    }
+
+    /// Decrypt a stored value, passing through values that were never encrypted
+    pub fn decrypt(&self, column: &str, row_id: &str, stored: &str) -> Result<String> {
+        let (encoded, legacy) = if let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) {
+            (encoded, false)
+        } else if let Some(encoded) = stored.strip_prefix(LEGACY_PREFIX) {
+            (encoded, true)
+        } else {
+            return Ok(stored.to_string());
+        };
+        let sealed = BASE64.decode(encoded)
+            .map_err(|_| Error::validation(format!("Corrupt encrypted value in {} of {}", column, row_id)))?;
+        let aad = aad(column, row_id);
+        let plaintext = if legacy { crypto::open(&self.key, &sealed, &aad)? } else { crypto::xopen(&self.key, &sealed, &aad)? };
+        String::from_utf8(plaintext)
+            .map_err(|_| Error::validation(format!("Corrupt encrypted value in {} of {}", column, row_id)))
+    }
+}
+
+fn aad(column: &str, row_id: &str) -> Vec<u8> {
+    format!("{}:{}", column, row_id).into_bytes()
+}
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX) || value.starts_with(LEGACY_PREFIX)
+}
+
+/// Encryption state of the conversation tables
+#[derive(Debug, Clone, Default)]
+pub struct EncryptionStatus {
+    /// The database has been encrypted and new content is written encrypted
+    pub enabled: bool,
+    pub encrypted_values: u64,
+    pub plaintext_values: u64,
+    /// Encrypted values still in the `enc1:` format, upgraded by [`DatabaseEncryption::encrypt_existing`]
+    pub legacy_values: u64,
+}
+
+/// Moves the conversation tables between plain text and encrypted storage
+pub struct DatabaseEncryption {
+    pool: SqlitePool,
+}
+
+impl DatabaseEncryption {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn status(&self) -> Result<EncryptionStatus> {
+        let mut status = EncryptionStatus {
+            enabled: self.key_check().await?.is_some(),
+            ..Default::default()
+        };
+        for (table, column) in ENCRYPTED_COLUMNS {
+            let (encrypted, legacy, total): (i64, i64, i64) = sqlx::query_as(&format!(
+                "SELECT COALESCE(SUM({column} LIKE '{prefix}%'), 0), COALESCE(SUM({column} LIKE '{legacy}%'), 0), \
+                 COUNT({column}) FROM {table}",
+                prefix = ENCRYPTED_PREFIX,
+                legacy = LEGACY_PREFIX,
+            ))
+            .fetch_one(&self.pool)
+            .await?;
+            status.encrypted_values += (encrypted + legacy) as u64;
+            status.legacy_values += legacy as u64;
+            status.plaintext_values += (total - encrypted - legacy) as u64;
+        }
+        Ok(status)
+    }
+
+    /// Check that `cipher` holds the key the database was encrypted with
+    pub async fn verify_key(&self, cipher: &FieldCipher) -> Result<()> {
+        if let Some(check) = self.key_check().await? {
+            let matches = cipher.decrypt("app_settings.value", KEY_CHECK_SETTING, &check)
+                .is_ok_and(|value| value == KEY_CHECK_PLAINTEXT);
+            if !matches {
+                return Err(Error::validation(
+                    "The database encryption key does not match the key this database was encrypted with",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Encrypt every plain-text value in place, returning the number of values encrypted
+    ///
+    /// Values in the older `enc1:` format are re-encrypted in the current one and counted too.
+    ///
+    /// The file is vacuumed afterwards so the plain text does not linger in free pages.
+    pub async fn encrypt_existing(&self, cipher: &FieldCipher) -> Result<u64> {
+        self.verify_key(cipher).await?;
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("INSERT OR REPLACE INTO app_settings (key, value) VALUES (?, ?)")
+            .bind(KEY_CHECK_SETTING)
+            .bind(cipher.encrypt("app_settings.value", KEY_CHECK_SETTING, KEY_CHECK_PLAINTEXT)?)
+            .execute(&mut *tx)
+            .await?;
+        let count = rewrite_columns(&mut tx, |column, id, value| {
+            if value.starts_with(ENCRYPTED_PREFIX) {
+                return Ok(None);
+            }
+            let plaintext = cipher.decrypt(column, id, value)?;
+            cipher.encrypt(column, id, &plaintext).map(Some)
+        }).await?;
+        tx.commit().await?;
+
+        self.vacuum().await;
+        info!("Encrypted {} stored values", count);
+        Ok(count)
+    }
+
+    /// Decrypt every encrypted value in place and stop encrypting new content
+    pub async fn decrypt_existing(&self, cipher: &FieldCipher) -> Result<u64> {
+        self.verify_key(cipher).await?;
+
+        let mut tx = self.pool.begin().await?;
+        let count = rewrite_columns(&mut tx, |column, id, value| {
+            if is_encrypted(value) { cipher.decrypt(column, id, value).map(Some) } else { Ok(None) }
+        }).await?;
+        sqlx::query("DELETE FROM app_settings WHERE key = ?")
+            .bind(KEY_CHECK_SETTING)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        self.vacuum().await;
+        info!("Decrypted {} stored values", count);
+        Ok(count)
+    }
+
+    async fn key_check(&self) -> Result<Option<String>> {
+        Ok(sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+            .bind(KEY_CHECK_SETTING)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    async fn vacuum(&self) {
+        if let Err(e) = sqlx::query("VACUUM").execute(&self.pool).await {
+            warn!("Failed to vacuum the database: {}", e);
+        }
+    }
+}
+
+/// Apply `rewrite` to every value of the encrypted columns, updating the values it changes
+async fn rewrite_columns<F>(tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>, rewrite: F) -> Result<u64>
+where
+    F: Fn(&str, &str, &str) -> Result<Option<String>>,
+{
+    let mut count = 0;
+    for (table, column) in ENCRYPTED_COLUMNS {
+        let qualified = format!("{}.{}", table, column);
+        let mut last_rowid = 0i64;
+        loop {
+            let rows = sqlx::query(&format!(
+                "SELECT rowid, id, {column} AS value FROM {table} \
+                 WHERE rowid > ? AND {column} IS NOT NULL ORDER BY rowid LIMIT ?"
+            ))
+            .bind(last_rowid)
+            .bind(BATCH_SIZE)
+            .fetch_all(&mut **tx)
+            .await?;
+            let Some(last) = rows.last() else { break };
+            last_rowid = last.get("rowid");
+
+            for row in &rows {
+                let id: String = row.get("id");
+                if let Some(value) = rewrite(&qualified, &id, &row.get::<String, _>("value"))? {
+                    sqlx::query(&format!("UPDATE {table} SET {column} = ? WHERE id = ?"))
+                        .bind(value)
+                        .bind(&id)
+                        .execute(&mut **tx)
+                        .await?;
+                    count += 1;
+                }
+            }
+        }
+        debug!("Rewrote {} up to row {}", qualified, last_rowid);
+    }
+    Ok(count)
+}
+
+/// Load the field key for the database, creating it and encrypting existing content when
+/// encryption is first enabled
+///
+/// Returns `None` when content is stored in plain text. A database that has been encrypted
+/// keeps being encrypted until `valechat db decrypt` is run, whatever the configuration says.
+pub async fn open_field_cipher(
+    pool: &SqlitePool,
+    secure_storage: &SecureStorageManager,
+    config: &EncryptionConfig,
+) -> Result<Option<FieldCipher>> {
+    let encryption = DatabaseEncryption::new(pool.clone());
+    let encrypted = encryption.key_check().await?.is_some();
+    if !encrypted && !config.enabled {
+        return Ok(None);
+    }
+    if encrypted && !config.enabled {
+        warn!("The database is encrypted although encryption is disabled; run `valechat db decrypt` to store it in plain text");
+    }
+
+    let cipher = match secure_storage.retrieve_secret(DATABASE_KEY_SECRET).await? {
+        Some(exported) => FieldCipher::from_exported(&exported)?,
+        None if encrypted => {
+            return Err(Error::validation(
+                "The conversation database is encrypted but its key is missing from the system keychain",
+            ));
+        }
+        None => {
+            let cipher = FieldCipher::generate()?;
+            secure_storage.store_secret(DATABASE_KEY_SECRET, &cipher.export_key()).await?;
+            info!("Created the database encryption key");
+            cipher
+        }
+    };
+    encryption.verify_key(&cipher).await?;
+
+    let status = encryption.status().await?;
+    if (config.enabled && (!encrypted || status.plaintext_values > 0)) || status.legacy_values > 0 {
+        info!("Encrypting existing conversation content");
+        encryption.encrypt_existing(&cipher).await?;
+    }
+    Ok(Some(cipher))
+}
+
+/// Load the field key of an encrypted database without enabling encryption
+pub async fn load_field_cipher(secure_storage: &SecureStorageManager) -> Result<Option<FieldCipher>> {
+    secure_storage.retrieve_secret(DATABASE_KEY_SECRET).await?
+        .map(|exported| FieldCipher::from_exported(&exported))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::AppPaths;
+    use crate::storage::Database;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_field_round_trip() {
+        let cipher = FieldCipher::generate().unwrap();
+        let stored = cipher.encrypt("messages.content", "m1", "secret code").unwrap();
+        assert!(is_encrypted(&stored));
+        assert!(!stored.contains("secret"));
+        assert_eq!(cipher.decrypt("messages.content", "m1", &stored).unwrap(), "secret code");
+
+        // Values are bound to their row, and plain text passes through
+        assert!(cipher.decrypt("messages.content", "m2", &stored).is_err());
+        assert_eq!(cipher.decrypt("messages.content", "m1", "plain").unwrap(), "plain");
+
+        let restored = FieldCipher::from_exported(&cipher.export_key()).unwrap();
+        assert_eq!(restored.decrypt("messages.content", "m1", &stored).unwrap(), "secret code");
+
+        // Values in the earlier format stay readable
+        let legacy = crypto::seal(&cipher.key, b"old secret", &aad("messages.content", "m1")).unwrap();
+        let legacy = format!("{}{}", LEGACY_PREFIX, BASE64.encode(legacy));
+        assert!(is_encrypted(&legacy));
+        assert_eq!(cipher.decrypt("messages.content", "m1", &legacy).unwrap(), "old secret");
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_existing_in_place() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(&AppPaths::with_data_dir(temp_dir.path()).unwrap()).await.unwrap();
+        let pool = db.get_pool();
+        sqlx::raw_sql(
+            "INSERT INTO conversations (id, title) VALUES ('c1', 'Chat');
+             INSERT INTO messages (id, conversation_id, role, content) VALUES ('m1', 'c1', 'user', 'hello');
+             INSERT INTO tool_invocations (id, message_id, tool_name, server_name, arguments, timestamp)
+                 VALUES ('t1', 'm1', 'read', 'fs', '{\"path\":\"/etc\"}', 0);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let encryption = DatabaseEncryption::new(pool.clone());
+        let cipher = FieldCipher::generate().unwrap();
+        assert_eq!(encryption.encrypt_existing(&cipher).await.unwrap(), 2);
+        let status = encryption.status().await.unwrap();
+        assert!(status.enabled);
+        assert_eq!((status.encrypted_values, status.plaintext_values), (2, 0));
+
+        let content: String = sqlx::query_scalar("SELECT content FROM messages").fetch_one(&pool).await.unwrap();
+        assert_eq!(cipher.decrypt("messages.content", "m1", &content).unwrap(), "hello");
+
+        // A different key is refused rather than mixing keys in one database
+        let other = FieldCipher::generate().unwrap();
+        assert!(encryption.verify_key(&other).await.is_err());
+        assert!(encryption.encrypt_existing(&other).await.is_err());
+
+        // Values in the earlier format are upgraded
+        let legacy = crypto::seal(&cipher.key, b"hello", &aad("messages.content", "m1")).unwrap();
+        sqlx::query("UPDATE messages SET content = ? WHERE id = 'm1'")
+            .bind(format!("{}{}", LEGACY_PREFIX, BASE64.encode(legacy)))
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(encryption.status().await.unwrap().legacy_values, 1);
+        assert_eq!(encryption.encrypt_existing(&cipher).await.unwrap(), 1);
+        let content: String = sqlx::query_scalar("SELECT content FROM messages").fetch_one(&pool).await.unwrap();
+        assert!(content.starts_with(ENCRYPTED_PREFIX));
+        assert_eq!(encryption.status().await.unwrap().legacy_values, 0);
+
+        assert_eq!(encryption.decrypt_existing(&cipher).await.unwrap(), 2);
+        let status = encryption.status().await.unwrap();
+        assert!(!status.enabled);
+        assert_eq!(status.encrypted_values, 0);
+    }
+}
@@ -6,7 +6,7 @@ use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 
 use crate::error::Result;
-use crate::storage::billing::{BillingSystem, SpendingCheckResult};
+use crate::storage::billing::{BillingSystem, SpendingCheckResult, SpendingLimitType};
 
 /// Spending limits enforcement service
 pub struct SpendingEnforcement {
@@ -66,7 +66,10 @@ pub enum EnforcementAction {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LimitInfo {
+    /// Scope of the limit that was evaluated, e.g. `global` or `provider:openai`
     pub limit_type: String,
+    /// Period of that limit, e.g. `daily` or `request`
+    pub period: String,
     pub current: Decimal,
     pub maximum: Decimal,
     pub percentage_used: f32,
@@ -119,10 +122,22 @@ impl SpendingEnforcement {
         model: &str,
         estimated_cost: Decimal,
         config: &EnforcementConfig,
+    ) -> Result<EnforcementResult> {
+        self.check_project_request(provider, model, None, estimated_cost, config).await
+    }
+
+    /// Check a request made on behalf of a project, so project budgets apply as well
+    pub async fn check_project_request(
+        &self,
+        provider: &str,
+        model: &str,
+        project: Option<&str>,
+        estimated_cost: Decimal,
+        config: &EnforcementConfig,
     ) -> Result<EnforcementResult> {
         debug!(
-            "Checking spending enforcement: provider={}, model={}, cost=${}",
-            provider, model, estimated_cost
+            "Checking spending enforcement: provider={}, model={}, project={:?}, cost=${}",
+            provider, model, project, estimated_cost
         );
 
         // Check if enforcement is disabled
@@ -157,7 +172,7 @@ impl SpendingEnforcement {
         }
 
         // Check cached result first
-        let cache_key = format!("{}:{}:{}", provider, model, estimated_cost);
+        let cache_key = format!("{}:{}:{}:{}", provider, model, estimated_cost, project.unwrap_or(""));
         let cached_result = {
             let state = self.state.read().await;
             state.check_cache.get(&cache_key).and_then(|cached| {
@@ -176,18 +191,15 @@ impl SpendingEnforcement {
                 reason: cached.reason.clone(),
                 action_taken: if cached.allowed { EnforcementAction::Allow } else { EnforcementAction::Block },
                 current_spending: Some(cached.current_spending),
-                limit_info: cached.limit.map(|limit| LimitInfo {
-                    limit_type: "cached".to_string(),
-                    current: cached.current_spending,
-                    maximum: limit,
-                    percentage_used: cached.percentage_used.unwrap_or(0.0),
-                }),
+                limit_info: limit_info(&cached),
                 retry_after_seconds: None,
             });
         }
 
         // Perform actual spending check
-        let spending_check = self.billing.check_spending_limits(provider, model, estimated_cost).await?;
+        let spending_check = self.billing
+            .check_project_spending_limits(provider, model, project, estimated_cost)
+            .await?;
 
         // Cache the result
         {
@@ -205,11 +217,12 @@ impl SpendingEnforcement {
             });
         }
 
-        // Determine action based on spending check
+        // Determine action based on spending check; only the global limit can trip the emergency stop
+        let is_global = matches!(spending_check.limit_type, Some(SpendingLimitType::Global));
         let action = if !spending_check.allowed {
             EnforcementAction::Block
         } else if let Some(percentage) = spending_check.percentage_used {
-            if percentage >= config.emergency_stop_threshold * 100.0 {
+            if is_global && percentage >= config.emergency_stop_threshold * 100.0 {
                 // Activate emergency stop
                 let mut state = self.state.write().await;
                 state.emergency_stop = true;
@@ -224,12 +237,7 @@ impl SpendingEnforcement {
             EnforcementAction::Allow
         };
 
-        let limit_info = spending_check.limit.map(|limit| LimitInfo {
-            limit_type: "global".to_string(), // TODO: Determine actual limit type
-            current: spending_check.current_spending,
-            maximum: limit,
-            percentage_used: spending_check.percentage_used.unwrap_or(0.0),
-        });
+        let limit_info = limit_info(&spending_check);
 
         Ok(EnforcementResult {
             allowed: spending_check.allowed && !matches!(action, EnforcementAction::EmergencyStop),
@@ -398,6 +406,18 @@ impl SpendingEnforcement {
     }
 }
 
+/// Describe the limit a spending check was decided by
+fn limit_info(check: &SpendingCheckResult) -> Option<LimitInfo> {
+    let maximum = check.limit?;
+    Some(LimitInfo {
+        limit_type: check.limit_type.as_ref().map_or_else(|| "unknown".to_string(), |t| t.key()),
+        period: check.period.as_ref().map_or("unknown", |p| p.as_str()).to_string(),
+        current: check.current_spending,
+        maximum,
+        percentage_used: check.percentage_used.unwrap_or(0.0),
+    })
+}
+
 /// Status information about enforcement
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnforcementStatus {
@@ -440,6 +460,27 @@ mod tests {
         assert!(matches!(result.action_taken, EnforcementAction::Allow));
     }
 
+    #[tokio::test]
+    async fn test_enforcement_reports_limit_hit() {
+        let (enforcement, _temp_dir) = create_test_enforcement().await;
+        let config = EnforcementConfig::default();
+
+        enforcement.billing.set_spending_limit(
+            SpendingLimitType::PerProvider("openai".to_string()),
+            Decimal::new(50, 2),
+            crate::storage::BillingPeriod::PerRequest,
+            None,
+        ).await.unwrap();
+
+        let result = enforcement.check_request("openai", "gpt-4", Decimal::from(1), &config).await.unwrap();
+        assert!(!result.allowed);
+        assert!(matches!(result.action_taken, EnforcementAction::Block));
+        let info = result.limit_info.unwrap();
+        assert_eq!(info.limit_type, "provider:openai");
+        assert_eq!(info.period, "request");
+        assert_eq!(info.maximum, Decimal::new(50, 2));
+    }
+
     #[tokio::test]
     async fn test_enforcement_disabled() {
         let (enforcement, _temp_dir) = create_test_enforcement().await;
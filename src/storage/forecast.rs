@@ -0,0 +1,428 @@
+use std::collections::{BTreeMap, HashMap};
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use sqlx::SqlitePool;
+use tracing::debug;
+
+use crate::error::Result;
+use crate::storage::billing::{BillingPeriod, SpendingLimit, SpendingLimitType};
+use crate::storage::dashboard::TrendDirection;
+use crate::storage::usage::UsageRepository;
+
+/// Days of history the forecast is fitted on
+const LOOKBACK_DAYS: u32 = 56;
+/// Most recent days weighted by the moving average
+const WMA_WINDOW: usize = 7;
+/// History needed before weekday seasonality is estimated
+const MIN_SEASONAL_DAYS: usize = 14;
+/// History needed before the regression trend is used
+const MIN_REGRESSION_DAYS: usize = 7;
+/// Weekly change, relative to the current level, below which spend counts as stable
+const STABLE_TREND_RATIO: f64 = 0.05;
+
+/// Daily spend model
+///
+/// A weighted moving average of recent days is blended with a least-squares trend line,
+/// both fitted on weekday-adjusted values, and scaled back by a per-weekday index.
+#[derive(Debug, Clone)]
+pub struct ForecastModel {
+    start: NaiveDate,
+    level: f64,
+    intercept: f64,
+    slope: f64,
+    use_trend: bool,
+    seasonality: [f64; 7],
+}
+
+impl ForecastModel {
+    /// Fit on consecutive daily costs, the first of which is `start`
+    pub fn fit(start: NaiveDate, history: &[f64]) -> Self {
+        let seasonality = weekday_seasonality(start, history);
+        // Weekday-adjusted points; weekdays that never see spend carry no level information
+        let adjusted: Vec<(f64, f64)> = history.iter()
+            .enumerate()
+            .filter_map(|(i, value)| {
+                let index = seasonality[weekday_index(start + Duration::days(i as i64))];
+                (index > 0.0).then(|| (i as f64, value / index))
+            })
+            .collect();
+
+        let recent = &adjusted[adjusted.len().saturating_sub(WMA_WINDOW)..];
+        let weights: f64 = (1..=recent.len()).map(|w| w as f64).sum();
+        let level = if recent.is_empty() {
+            0.0
+        } else {
+            recent.iter().enumerate().map(|(i, (_, value))| (i + 1) as f64 * value).sum::<f64>() / weights
+        };
+
+        let (intercept, slope) = linear_regression(&adjusted);
+
+        Self {
+            start,
+            level,
+            intercept,
+            slope,
+            use_trend: history.len() >= MIN_REGRESSION_DAYS,
+            seasonality,
+        }
+    }
+
+    /// Expected spend on `date`
+    pub fn predict(&self, date: NaiveDate) -> f64 {
+        let base = if self.use_trend {
+            let t = (date - self.start).num_days() as f64;
+            (self.level + self.intercept + self.slope * t) / 2.0
+        } else {
+            self.level
+        };
+        (base * self.seasonality[weekday_index(date)]).max(0.0)
+    }
+
+    /// Direction of the fitted trend
+    pub fn trend(&self) -> TrendDirection {
+        if !self.use_trend || self.level <= 0.0 {
+            return TrendDirection::Stable;
+        }
+
+        let weekly_change = self.slope * 7.0 / self.level;
+        if weekly_change > STABLE_TREND_RATIO {
+            TrendDirection::Increasing
+        } else if weekly_change < -STABLE_TREND_RATIO {
+            TrendDirection::Decreasing
+        } else {
+            TrendDirection::Stable
+        }
+    }
+}
+
+fn weekday_index(date: NaiveDate) -> usize {
+    date.weekday().num_days_from_monday() as usize
+}
+
+/// Average ratio of each weekday's spend to the trend line, normalized to a mean of 1
+///
+/// Measuring against the trend rather than the overall mean keeps growth from being
+/// mistaken for a weekday pattern.
+fn weekday_seasonality(start: NaiveDate, history: &[f64]) -> [f64; 7] {
+    let mut indices = [1.0; 7];
+    if history.len() < MIN_SEASONAL_DAYS {
+        return indices;
+    }
+
+    let points: Vec<(f64, f64)> = history.iter().enumerate().map(|(i, value)| (i as f64, *value)).collect();
+    let (intercept, slope) = linear_regression(&points);
+
+    let mut sums = [0.0; 7];
+    let mut counts = [0usize; 7];
+    for (i, value) in history.iter().enumerate() {
+        let fitted = intercept + slope * i as f64;
+        if fitted > 0.0 {
+            let day = weekday_index(start + Duration::days(i as i64));
+            sums[day] += value / fitted;
+            counts[day] += 1;
+        }
+    }
+    for day in 0..7 {
+        if counts[day] > 0 {
+            indices[day] = sums[day] / counts[day] as f64;
+        }
+    }
+
+    let mean = indices.iter().sum::<f64>() / 7.0;
+    if mean > 0.0 {
+        indices.iter_mut().for_each(|index| *index /= mean);
+    }
+    indices
+}
+
+/// Least-squares fit of `(x, y)` points, as (intercept, slope)
+fn linear_regression(points: &[(f64, f64)]) -> (f64, f64) {
+    let n = points.len() as f64;
+    if points.len() < 2 {
+        return (points.first().map_or(0.0, |(_, y)| *y), 0.0);
+    }
+
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (covariance, variance) = points.iter()
+        .fold((0.0, 0.0), |(cov, var), (x, y)| {
+            let dx = x - mean_x;
+            (cov + dx * (y - mean_y), var + dx * dx)
+        });
+
+    let slope = covariance / variance;
+    (mean_y - slope * mean_x, slope)
+}
+
+/// Projected spend for the current month
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendForecast {
+    pub billing_period: String,
+    pub generated_on: NaiveDate,
+    pub month_to_date: Decimal,
+    pub projected_month_end: Decimal,
+    pub trend: TrendDirection,
+    pub by_provider: HashMap<String, ProviderForecast>,
+    /// Projections for daily, weekly and monthly global and provider limits
+    pub budgets: Vec<BudgetProjection>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderForecast {
+    pub month_to_date: Decimal,
+    pub projected_month_end: Decimal,
+}
+
+/// Where a spending limit is headed by the end of its period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetProjection {
+    pub description: String,
+    pub period: BillingPeriod,
+    pub limit: Decimal,
+    pub spent: Decimal,
+    /// Expected spend at the end of the limit's period
+    pub projected: Decimal,
+    /// First day the limit is expected to be reached, if within the period
+    pub exhaustion_date: Option<NaiveDate>,
+    /// Days from today until `exhaustion_date`
+    pub days_left: Option<u32>,
+}
+
+impl SpendForecast {
+    /// Budget expected to run out first
+    pub fn earliest_exhaustion(&self) -> Option<&BudgetProjection> {
+        self.budgets.iter()
+            .filter(|budget| budget.exhaustion_date.is_some())
+            .min_by_key(|budget| budget.exhaustion_date)
+    }
+}
+
+/// Forecasts spend from the daily cost trend
+pub struct SpendForecaster {
+    usage: UsageRepository,
+}
+
+impl SpendForecaster {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { usage: UsageRepository::new(pool) }
+    }
+
+    /// Forecast the current month and project each applicable limit
+    ///
+    /// Yearly, per-request, model and project limits are not projected.
+    pub async fn forecast(&self, limits: &[SpendingLimit]) -> Result<SpendForecast> {
+        let total = self.usage.get_cost_trend(LOOKBACK_DAYS).await?;
+        let by_provider = self.usage.get_cost_trend_by_provider(LOOKBACK_DAYS).await?;
+
+        let forecast = build_forecast(Utc::now().date_naive(), &total, &by_provider, limits);
+        debug!(
+            "Forecast for {}: ${} projected, {} budget(s) projected",
+            forecast.billing_period, forecast.projected_month_end, forecast.budgets.len()
+        );
+        Ok(forecast)
+    }
+}
+
+/// Daily costs keyed by date; unparseable dates are skipped
+fn daily_costs(trend: &[(String, Decimal)]) -> BTreeMap<NaiveDate, f64> {
+    trend.iter()
+        .filter_map(|(date, cost)| {
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+            Some((date, rust_decimal::prelude::ToPrimitive::to_f64(cost).unwrap_or(0.0)))
+        })
+        .collect()
+}
+
+/// Spend history and the model fitted on its complete days
+struct Series {
+    actuals: BTreeMap<NaiveDate, f64>,
+    model: ForecastModel,
+}
+
+impl Series {
+    fn new(trend: &[(String, Decimal)], today: NaiveDate) -> Self {
+        let actuals = daily_costs(trend);
+        // Fit from the first day with spend up to yesterday; today is still incomplete
+        let start = actuals.keys().next().copied().unwrap_or(today).min(today);
+        let history: Vec<f64> = start.iter_days()
+            .take_while(|date| *date < today)
+            .map(|date| actuals.get(&date).copied().unwrap_or(0.0))
+            .collect();
+
+        Self { model: ForecastModel::fit(start, &history), actuals }
+    }
+
+    /// Actual spend in `[from, to]`
+    fn spent(&self, from: NaiveDate, to: NaiveDate) -> f64 {
+        self.actuals.range(from..=to).map(|(_, cost)| cost).sum()
+    }
+
+    /// Spend expected from now until the end of `end`, with the day each cumulative total crosses `limit`
+    fn project(&self, today: NaiveDate, start: NaiveDate, end: NaiveDate, limit: Option<f64>) -> (f64, f64, Option<NaiveDate>) {
+        let spent = self.spent(start, today);
+        let mut total = spent;
+        let mut exhaustion = limit.filter(|limit| total >= *limit).map(|_| today);
+
+        for date in today.iter_days().take_while(|date| *date <= end) {
+            let predicted = self.model.predict(date);
+            total += if date == today {
+                (predicted - self.actuals.get(&today).copied().unwrap_or(0.0)).max(0.0)
+            } else {
+                predicted
+            };
+            if exhaustion.is_none() && limit.is_some_and(|limit| total >= limit) {
+                exhaustion = Some(date);
+            }
+        }
+
+        (spent, total, exhaustion)
+    }
+}
+
+fn to_decimal(value: f64) -> Decimal {
+    Decimal::try_from(value).unwrap_or(Decimal::ZERO).round_dp(6)
+}
+
+fn month_end(date: NaiveDate) -> NaiveDate {
+    let (year, month) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+    NaiveDate::from_ymd_opt(year, month, 1).map_or(date, |next| next - Duration::days(1))
+}
+
+/// First and last day of the period containing `today`
+fn period_bounds(period: &BillingPeriod, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    match period {
+        BillingPeriod::Daily => Some((today, today)),
+        BillingPeriod::Weekly => {
+            let start = today - Duration::days(weekday_index(today) as i64);
+            Some((start, start + Duration::days(6)))
+        }
+        BillingPeriod::Monthly => Some((today.with_day(1)?, month_end(today))),
+        BillingPeriod::Yearly | BillingPeriod::PerRequest => None,
+    }
+}
+
+fn build_forecast(
+    today: NaiveDate,
+    total: &[(String, Decimal)],
+    by_provider: &HashMap<String, Vec<(String, Decimal)>>,
+    limits: &[SpendingLimit],
+) -> SpendForecast {
+    let month_start = today.with_day(1).unwrap_or(today);
+    let month_end = month_end(today);
+
+    let total_series = Series::new(total, today);
+    let provider_series: HashMap<&String, Series> = by_provider.iter()
+        .map(|(provider, trend)| (provider, Series::new(trend, today)))
+        .collect();
+
+    let (month_to_date, projected_month_end, _) = total_series.project(today, month_start, month_end, None);
+    let provider_forecasts = provider_series.iter()
+        .map(|(provider, series)| {
+            let (spent, projected, _) = series.project(today, month_start, month_end, None);
+            ((*provider).clone(), ProviderForecast {
+                month_to_date: to_decimal(spent),
+                projected_month_end: to_decimal(projected),
+            })
+        })
+        .collect();
+
+    let budgets = limits.iter()
+        .filter(|limit| limit.enabled)
+        .filter_map(|limit| {
+            let series = match &limit.limit_type {
+                SpendingLimitType::Global => &total_series,
+                SpendingLimitType::PerProvider(provider) => provider_series.get(provider)?,
+                SpendingLimitType::PerModel(_) | SpendingLimitType::PerProject(_) => return None,
+            };
+            let (start, end) = period_bounds(&limit.period, today)?;
+            let amount = rust_decimal::prelude::ToPrimitive::to_f64(&limit.amount)?;
+            let (spent, projected, exhaustion_date) = series.project(today, start, end, Some(amount));
+
+            Some(BudgetProjection {
+                description: limit.describe(),
+                period: limit.period.clone(),
+                limit: limit.amount,
+                spent: to_decimal(spent),
+                projected: to_decimal(projected),
+                exhaustion_date,
+                days_left: exhaustion_date.map(|date| (date - today).num_days() as u32),
+            })
+        })
+        .collect();
+
+    SpendForecast {
+        billing_period: today.format("%Y-%m").to_string(),
+        generated_on: today,
+        month_to_date: to_decimal(month_to_date),
+        projected_month_end: to_decimal(projected_month_end),
+        trend: total_series.model.trend(),
+        by_provider: provider_forecasts,
+        budgets,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn trend(start: NaiveDate, costs: &[f64]) -> Vec<(String, Decimal)> {
+        costs.iter()
+            .enumerate()
+            .map(|(i, cost)| {
+                let day = start + Duration::days(i as i64);
+                (day.format("%Y-%m-%d").to_string(), Decimal::try_from(*cost).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_model_trend_and_seasonality() {
+        // 2026-09-07 is a Monday; weekends have no spend
+        let start = date("2026-09-07");
+        let weekdays_only: Vec<f64> = (0..28).map(|i| if i % 7 < 5 { 7.0 } else { 0.0 }).collect();
+        let model = ForecastModel::fit(start, &weekdays_only);
+        assert!((model.predict(date("2026-10-05")) - 7.0).abs() < 0.5);
+        assert_eq!(model.predict(date("2026-10-10")), 0.0);
+        assert!(matches!(model.trend(), TrendDirection::Stable));
+
+        let rising: Vec<f64> = (0..21).map(|i| 1.0 + i as f64).collect();
+        let model = ForecastModel::fit(start, &rising);
+        assert!(matches!(model.trend(), TrendDirection::Increasing));
+        assert!(model.predict(date("2026-10-01")) > 21.0);
+    }
+
+    #[test]
+    fn test_month_end_and_budget_exhaustion() {
+        let today = date("2026-10-15");
+        // $2/day since the start of September, $1 spent so far today
+        let mut costs = vec![2.0; 44];
+        costs.push(1.0);
+        let total = trend(date("2026-09-01"), &costs);
+        let by_provider = HashMap::from([("openai".to_string(), total.clone())]);
+
+        let limits = vec![
+            SpendingLimit::new(SpendingLimitType::Global, Decimal::from(40), BillingPeriod::Monthly, None),
+            SpendingLimit::new(SpendingLimitType::PerProvider("openai".to_string()), Decimal::from(100), BillingPeriod::Monthly, None),
+            SpendingLimit::new(SpendingLimitType::PerModel("gpt-4".to_string()), Decimal::from(10), BillingPeriod::Monthly, None),
+        ];
+
+        let forecast = build_forecast(today, &total, &by_provider, &limits);
+        assert_eq!(forecast.billing_period, "2026-10");
+        assert_eq!(forecast.month_to_date, Decimal::from(29));
+        // 14 full days plus the rest of today at $2/day
+        assert_eq!(forecast.projected_month_end.round_dp(2), Decimal::from(62));
+        assert_eq!(forecast.by_provider["openai"].projected_month_end.round_dp(2), Decimal::from(62));
+
+        assert_eq!(forecast.budgets.len(), 2);
+        let global = &forecast.budgets[0];
+        assert_eq!(global.exhaustion_date, Some(date("2026-10-20")));
+        assert_eq!(global.days_left, Some(5));
+        assert!(forecast.budgets[1].exhaustion_date.is_none());
+        assert_eq!(forecast.earliest_exhaustion().unwrap().description, global.description);
+    }
+}
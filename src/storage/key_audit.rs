@@ -0,0 +1,341 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::error::Result;
+
+/// Operations recorded in `api_key_audit`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOperation {
+    Created,
+    Updated,
+    Deleted,
+    Accessed,
+}
+
+impl KeyOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyOperation::Created => "created",
+            KeyOperation::Updated => "updated",
+            KeyOperation::Deleted => "deleted",
+            KeyOperation::Accessed => "accessed",
+        }
+    }
+}
+
+/// One row of the key audit log
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyAuditEntry {
+    pub id: i64,
+    pub provider: String,
+    pub operation: String,
+    pub timestamp: DateTime<Utc>,
+    pub success: bool,
+    pub error_message: Option<String>,
+    /// Where an accessed key came from, e.g. `env:OPENAI_API_KEY`
+    pub source: Option<String>,
+    /// ValeChat command that performed the operation
+    pub command: Option<String>,
+}
+
+/// Days of the key audit log reviewed for anomalies unless another period is asked for
+pub const DEFAULT_REVIEW_DAYS: u32 = 30;
+
+#[derive(Debug, Clone)]
+pub struct KeyAuditFilter {
+    /// A provider with all its named keys, or a single key such as `openai/team-a`
+    pub provider: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub failures_only: bool,
+    pub limit: u32,
+}
+
+impl Default for KeyAuditFilter {
+    fn default() -> Self {
+        Self { provider: None, since: None, failures_only: false, limit: 100 }
+    }
+}
+
+/// What counts as suspicious in the key audit log
+#[derive(Debug, Clone)]
+pub struct KeyAuditRules {
+    /// Commands with a reason to read or change keys
+    pub expected_commands: Vec<String>,
+    /// Failed operations for one provider within `burst_window` that are flagged
+    pub burst_failures: usize,
+    pub burst_window: Duration,
+}
+
+impl Default for KeyAuditRules {
+    fn default() -> Self {
+        Self {
+            expected_commands: ["chat", "api-key", "billing"].map(String::from).to_vec(),
+            burst_failures: 5,
+            burst_window: Duration::minutes(10),
+        }
+    }
+}
+
+impl KeyAuditRules {
+    /// Whether the entry was written by a command outside `expected_commands`; entries without a command are not judged
+    pub fn is_unexpected(&self, entry: &KeyAuditEntry) -> bool {
+        entry.command.as_ref().is_some_and(|command| !self.expected_commands.contains(command))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum KeyAuditAnomaly {
+    /// A key was read or changed by a command with no reason to
+    UnexpectedCommand {
+        provider: String,
+        command: String,
+        count: usize,
+        last: DateTime<Utc>,
+    },
+    /// Many failed operations for one provider in a short time
+    FailureBurst {
+        provider: String,
+        failures: usize,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+}
+
+impl fmt::Display for KeyAuditAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyAuditAnomaly::UnexpectedCommand { provider, command, count, last } => write!(
+                f, "{} key used {} time(s) by `valechat {}`, last at {}",
+                provider, count, command, last.format("%Y-%m-%d %H:%M")
+            ),
+            KeyAuditAnomaly::FailureBurst { provider, failures, start, end } => write!(
+                f, "{} {} key operation(s) failed between {} and {}",
+                failures, provider, start.format("%Y-%m-%d %H:%M"), end.format("%Y-%m-%d %H:%M")
+            ),
+        }
+    }
+}
+
+/// Security log of API key operations; key values are never written
+#[derive(Clone)]
+pub struct ApiKeyAuditLog {
+    pool: SqlitePool,
+}
+
+impl ApiKeyAuditLog {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Record one operation; `error` describes why it failed
+    pub async fn record(
+        &self,
+        provider: &str,
+        operation: KeyOperation,
+        source: Option<&str>,
+        command: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO api_key_audit (provider, operation, success, error_message, source, command)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(provider)
+        .bind(operation.as_str())
+        .bind(error.is_none())
+        .bind(error)
+        .bind(source)
+        .bind(command)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Matching entries, most recent first
+    pub async fn list(&self, filter: &KeyAuditFilter) -> Result<Vec<KeyAuditEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, provider, operation, timestamp, success, error_message, source, command
+            FROM api_key_audit
+            WHERE (?1 IS NULL OR provider = ?1 OR substr(provider, 1, length(?1) + 1) = ?1 || '/')
+              AND (?2 IS NULL OR timestamp >= ?2)
+              AND (NOT ?3 OR success = FALSE)
+            ORDER BY timestamp DESC, id DESC
+            LIMIT ?4
+            "#
+        )
+        .bind(&filter.provider)
+        .bind(filter.since.map(|since| since.timestamp()))
+        .bind(filter.failures_only)
+        .bind(filter.limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter()
+            .map(|row| KeyAuditEntry {
+                id: row.get("id"),
+                provider: row.get("provider"),
+                operation: row.get("operation"),
+                timestamp: Utc.timestamp_opt(row.get("timestamp"), 0).single().unwrap_or_default(),
+                success: row.get("success"),
+                error_message: row.get("error_message"),
+                source: row.get("source"),
+                command: row.get("command"),
+            })
+            .collect())
+    }
+
+    /// Every entry of the last `days` days, most recent first, with the anomalies found among them
+    pub async fn review(&self, provider: Option<String>, days: u32, rules: &KeyAuditRules) -> Result<(Vec<KeyAuditEntry>, Vec<KeyAuditAnomaly>)> {
+        let filter = KeyAuditFilter {
+            provider,
+            since: Some(Utc::now() - Duration::days(days as i64)),
+            failures_only: false,
+            limit: u32::MAX,
+        };
+        let entries = self.list(&filter).await?;
+        let anomalies = detect_anomalies(&entries, rules);
+        Ok((entries, anomalies))
+    }
+}
+
+/// Unexpected commands and failure bursts among `entries`, in any order
+pub fn detect_anomalies(entries: &[KeyAuditEntry], rules: &KeyAuditRules) -> Vec<KeyAuditAnomaly> {
+    let mut anomalies = Vec::new();
+
+    let mut unexpected: BTreeMap<(&str, &str), (usize, DateTime<Utc>)> = BTreeMap::new();
+    for entry in entries.iter().filter(|entry| rules.is_unexpected(entry)) {
+        let command = entry.command.as_deref().unwrap_or_default();
+        let seen = unexpected.entry((&entry.provider, command)).or_insert((0, entry.timestamp));
+        seen.0 += 1;
+        seen.1 = seen.1.max(entry.timestamp);
+    }
+    anomalies.extend(unexpected.into_iter().map(|((provider, command), (count, last))| {
+        KeyAuditAnomaly::UnexpectedCommand { provider: provider.to_string(), command: command.to_string(), count, last }
+    }));
+
+    let mut failures: BTreeMap<&str, Vec<DateTime<Utc>>> = BTreeMap::new();
+    for entry in entries.iter().filter(|entry| !entry.success) {
+        failures.entry(&entry.provider).or_default().push(entry.timestamp);
+    }
+    for (provider, mut times) in failures {
+        times.sort();
+        // Overlapping windows with enough failures are reported as one burst
+        let mut burst: Option<(usize, usize)> = None;
+        let mut start = 0;
+        for end in 0..times.len() {
+            while times[end] - times[start] > rules.burst_window {
+                start += 1;
+            }
+            if end + 1 - start < rules.burst_failures.max(1) {
+                continue;
+            }
+            burst = match burst {
+                Some((first, last)) if start <= last => Some((first, end)),
+                Some((first, last)) => {
+                    anomalies.push(failure_burst(provider, &times[first..=last]));
+                    Some((start, end))
+                }
+                None => Some((start, end)),
+            };
+        }
+        if let Some((first, last)) = burst {
+            anomalies.push(failure_burst(provider, &times[first..=last]));
+        }
+    }
+
+    anomalies
+}
+
+fn failure_burst(provider: &str, times: &[DateTime<Utc>]) -> KeyAuditAnomaly {
+    KeyAuditAnomaly::FailureBurst {
+        provider: provider.to_string(),
+        failures: times.len(),
+        start: times[0],
+        end: times[times.len() - 1],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::AppPaths;
+    use crate::storage::Database;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_record_and_list() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(&AppPaths::with_data_dir(temp_dir.path()).unwrap()).await.unwrap();
+        let audit = ApiKeyAuditLog::new(db.get_pool());
+
+        audit.record("openai", KeyOperation::Created, None, Some("api-key"), None).await.unwrap();
+        audit.record("openai", KeyOperation::Accessed, Some("env:OPENAI_API_KEY"), Some("chat"), None).await.unwrap();
+        audit.record("anthropic", KeyOperation::Accessed, None, Some("export"), Some("api_key_command failed")).await.unwrap();
+        audit.record("openai-admin", KeyOperation::Accessed, Some("storage"), Some("billing"), None).await.unwrap();
+
+        audit.record("openai/team-a", KeyOperation::Accessed, Some("storage"), Some("chat"), None).await.unwrap();
+
+        let all = audit.list(&KeyAuditFilter::default()).await.unwrap();
+        assert_eq!(all.len(), 5);
+        assert_eq!(all[2].provider, "anthropic");
+        assert_eq!(all[2].error_message.as_deref(), Some("api_key_command failed"));
+        assert_eq!(all[3].source.as_deref(), Some("env:OPENAI_API_KEY"));
+        assert_eq!(all[4].operation, "created");
+
+        // A provider filter covers its named keys, but not other providers sharing its prefix
+        let openai = audit.list(&KeyAuditFilter { provider: Some("openai".into()), ..Default::default() }).await.unwrap();
+        assert_eq!(openai.len(), 3);
+        let team_a = audit.list(&KeyAuditFilter { provider: Some("openai/team-a".into()), ..Default::default() }).await.unwrap();
+        assert_eq!(team_a.len(), 1);
+        let failures = audit.list(&KeyAuditFilter { failures_only: true, ..Default::default() }).await.unwrap();
+        assert_eq!(failures.len(), 1);
+        assert!(!failures[0].success);
+
+        let (entries, anomalies) = audit.review(None, DEFAULT_REVIEW_DAYS, &KeyAuditRules::default()).await.unwrap();
+        assert_eq!(entries.len(), 5);
+        assert!(matches!(&anomalies[..],
+            [KeyAuditAnomaly::UnexpectedCommand { provider, command, count: 1, .. }] if provider == "anthropic" && command == "export"));
+    }
+
+    fn entry(provider: &str, command: &str, minute: i64, success: bool) -> KeyAuditEntry {
+        KeyAuditEntry {
+            id: minute,
+            provider: provider.to_string(),
+            operation: "accessed".to_string(),
+            timestamp: Utc.timestamp_opt(1_700_000_000 + minute * 60, 0).unwrap(),
+            success,
+            error_message: None,
+            source: None,
+            command: Some(command.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_detect_anomalies() {
+        let rules = KeyAuditRules { burst_failures: 3, ..Default::default() };
+        let mut entries = vec![
+            entry("openai", "chat", 0, true),
+            entry("openai", "export", 1, true),
+            entry("openai", "export", 2, true),
+        ];
+        // Three failures within ten minutes, then two more that continue the same burst
+        entries.extend((10..15).map(|minute| entry("anthropic", "chat", minute, false)));
+        // Two failures far apart are not a burst
+        entries.push(entry("gemini", "chat", 0, false));
+        entries.push(entry("gemini", "chat", 60, false));
+
+        let anomalies = detect_anomalies(&entries, &rules);
+        assert_eq!(anomalies.len(), 2);
+        assert!(matches!(&anomalies[0],
+            KeyAuditAnomaly::UnexpectedCommand { provider, command, count: 2, .. } if provider == "openai" && command == "export"));
+        assert!(matches!(&anomalies[1],
+            KeyAuditAnomaly::FailureBurst { provider, failures: 5, .. } if provider == "anthropic"));
+    }
+}
@@ -0,0 +1,154 @@
+use sqlx::{Row, SqlitePool};
+use tracing::debug;
+
+use crate::error::{Error, Result};
+
+/// What an API key is chosen for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyScope<'a> {
+    Conversation(&'a str),
+    Project(&'a str),
+}
+
+impl<'a> KeyScope<'a> {
+    fn parts(&self) -> (&'static str, &'a str) {
+        match self {
+            KeyScope::Conversation(id) => ("conversation", id),
+            KeyScope::Project(name) => ("project", name),
+        }
+    }
+}
+
+/// Repository for the API keys chosen per conversation and project
+///
+/// Keys are stored by id (`openai/team-a`); the values stay in secure storage.
+pub struct KeySelectionRepository {
+    pool: SqlitePool,
+}
+
+impl KeySelectionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Use `key_id` for its provider within `scope`, replacing an earlier choice
+    pub async fn select(&self, scope: KeyScope<'_>, key_id: &str) -> Result<()> {
+        let (scope_type, scope_id) = scope.parts();
+        if scope_id.trim().is_empty() {
+            return Err(Error::validation(format!("A {} is required to choose an API key", scope_type)));
+        }
+        let provider = key_id.split_once('/').map_or(key_id, |(provider, _)| provider);
+        debug!("Selecting API key {} for {} {}", key_id, scope_type, scope_id);
+
+        sqlx::query(
+            r#"
+            INSERT INTO api_key_selections (scope, scope_id, provider, key_id) VALUES (?, ?, ?, ?)
+            ON CONFLICT(scope, scope_id, provider) DO UPDATE SET key_id = excluded.key_id, updated_at = unixepoch()
+            "#
+        )
+        .bind(scope_type)
+        .bind(scope_id)
+        .bind(provider)
+        .bind(key_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the choice for `provider` within `scope`; returns whether there was one
+    pub async fn unselect(&self, scope: KeyScope<'_>, provider: &str) -> Result<bool> {
+        let (scope_type, scope_id) = scope.parts();
+        let rows_affected = sqlx::query("DELETE FROM api_key_selections WHERE scope = ? AND scope_id = ? AND provider = ?")
+            .bind(scope_type)
+            .bind(scope_id)
+            .bind(provider)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        Ok(rows_affected > 0)
+    }
+
+    /// Keys chosen within `scope` as (provider, key id), by provider
+    pub async fn list(&self, scope: KeyScope<'_>) -> Result<Vec<(String, String)>> {
+        let (scope_type, scope_id) = scope.parts();
+        let rows = sqlx::query("SELECT provider, key_id FROM api_key_selections WHERE scope = ? AND scope_id = ? ORDER BY provider")
+            .bind(scope_type)
+            .bind(scope_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(|row| (row.get("provider"), row.get("key_id"))).collect())
+    }
+
+    /// Key id a conversation uses for `provider`: its own choice, else its project's, else the default key
+    pub async fn active_key_id(&self, conversation_id: &str, provider: &str) -> Result<String> {
+        let selected: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT s.key_id FROM api_key_selections s
+            WHERE s.provider = ?1
+              AND ((s.scope = 'conversation' AND s.scope_id = ?2)
+                   OR (s.scope = 'project' AND s.scope_id = (SELECT project FROM conversations WHERE id = ?2)))
+            ORDER BY s.scope = 'conversation' DESC
+            LIMIT 1
+            "#
+        )
+        .bind(provider)
+        .bind(conversation_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(selected.unwrap_or_else(|| provider.to_string()))
+    }
+
+    /// Forget every choice made for a scope, e.g. when its project is deleted
+    pub async fn clear(&self, scope: KeyScope<'_>) -> Result<()> {
+        let (scope_type, scope_id) = scope.parts();
+        sqlx::query("DELETE FROM api_key_selections WHERE scope = ? AND scope_id = ?")
+            .bind(scope_type)
+            .bind(scope_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::types::ChatSession;
+    use crate::platform::AppPaths;
+    use crate::storage::{ConversationRepository, Database, ProjectRepository};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_conversation_choice_overrides_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(&AppPaths::with_data_dir(temp_dir.path()).unwrap()).await.unwrap();
+        let selections = KeySelectionRepository::new(db.get_pool());
+        let conversations = ConversationRepository::new(db.get_pool());
+
+        let session = ChatSession::new("Roadmap", "openai", "gpt-4");
+        conversations.create_conversation(&session).await.unwrap();
+        assert_eq!(selections.active_key_id(&session.id, "openai").await.unwrap(), "openai");
+
+        ProjectRepository::new(db.get_pool()).assign_conversation(&session.id, Some("alpha")).await.unwrap();
+        selections.select(KeyScope::Project("alpha"), "openai/team-a").await.unwrap();
+        assert_eq!(selections.active_key_id(&session.id, "openai").await.unwrap(), "openai/team-a");
+        assert_eq!(selections.active_key_id(&session.id, "anthropic").await.unwrap(), "anthropic");
+
+        selections.select(KeyScope::Conversation(&session.id), "openai/team-b").await.unwrap();
+        assert_eq!(selections.active_key_id(&session.id, "openai").await.unwrap(), "openai/team-b");
+
+        // The default key can be chosen explicitly over the project's key
+        selections.select(KeyScope::Conversation(&session.id), "openai").await.unwrap();
+        assert_eq!(selections.active_key_id(&session.id, "openai").await.unwrap(), "openai");
+        assert_eq!(selections.list(KeyScope::Conversation(&session.id)).await.unwrap(), vec![("openai".to_string(), "openai".to_string())]);
+
+        // Without its own choice, the conversation follows its project again
+        assert!(selections.unselect(KeyScope::Conversation(&session.id), "openai").await.unwrap());
+        assert!(!selections.unselect(KeyScope::Conversation(&session.id), "openai").await.unwrap());
+        assert_eq!(selections.active_key_id(&session.id, "openai").await.unwrap(), "openai/team-a");
+
+        assert!(selections.select(KeyScope::Project(" "), "openai/team-a").await.is_err());
+        selections.clear(KeyScope::Project("alpha")).await.unwrap();
+        assert_eq!(selections.active_key_id(&session.id, "openai").await.unwrap(), "openai");
+    }
+}
@@ -0,0 +1,443 @@
+// Versioned schema migrations compiled into the binary
+//
+// Each migration is a pair of SQL files in `migrations/`: `NNN_name.sql` applies it and
+// `NNN_name.down.sql` reverts it. Applied migrations are recorded in `schema_migrations`
+// with a checksum of the SQL they ran, so edits to an applied migration are caught.
+
+use std::time::Instant;
+use chrono::{DateTime, TimeZone, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::migrate::MigrateError;
+use sqlx::{Connection, Row, SqliteConnection, SqlitePool};
+use tracing::{error, info, warn};
+
+use crate::error::{Error, Result};
+
+/// A schema migration embedded at compile time
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    up: &'static str,
+    down: &'static str,
+}
+
+impl Migration {
+    /// SHA-256 of the forward SQL, hex encoded
+    pub fn checksum(&self) -> String {
+        format!("{:x}", Sha256::digest(self.up.as_bytes()))
+    }
+}
+
+macro_rules! migration {
+    ($version:literal, $file:literal) => {
+        Migration {
+            version: $version,
+            name: $file,
+            up: include_str!(concat!("../../migrations/", $file, ".sql")),
+            down: include_str!(concat!("../../migrations/", $file, ".down.sql")),
+        }
+    };
+}
+
+/// All migrations, in the order they are applied
+pub static MIGRATIONS: &[Migration] = &[
+    migration!(1, "001_initial"),
+    migration!(2, "002_tool_permissions"),
+    migration!(3, "003_projects"),
+    migration!(4, "004_cost_alerts"),
+    migration!(5, "005_api_key_sources"),
+    migration!(6, "006_api_key_audit_command"),
+    migration!(7, "007_api_key_ids"),
+    migration!(8, "008_pinned_resources"),
+];
+
+/// State of one migration in a database
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationState {
+    Pending,
+    Applied { applied_at: DateTime<Utc> },
+    /// Applied, but the embedded SQL no longer matches what ran
+    Modified { applied_at: DateTime<Utc> },
+    /// Applied by a newer build; this binary does not know it
+    Unknown { applied_at: DateTime<Utc> },
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: String,
+    pub state: MigrationState,
+}
+
+struct AppliedMigration {
+    version: i64,
+    name: String,
+    checksum: String,
+    applied_at: DateTime<Utc>,
+}
+
+/// Applies and reverts the embedded migrations
+pub struct Migrator {
+    pool: SqlitePool,
+    migrations: &'static [Migration],
+}
+
+impl Migrator {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool, migrations: MIGRATIONS }
+    }
+
+    /// Latest version this build knows about
+    pub fn latest_version(&self) -> i64 {
+        self.migrations.last().map_or(0, |m| m.version)
+    }
+
+    /// Highest applied version, 0 for an empty database
+    pub async fn current_version(&self) -> Result<i64> {
+        self.ensure_table().await?;
+        let version: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM schema_migrations")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(version.unwrap_or(0))
+    }
+
+    /// Apply all pending migrations, returning the versions applied
+    ///
+    /// Refuses to run when an applied migration was modified or the database was
+    /// migrated by a newer build.
+    pub async fn run(&self) -> Result<Vec<i64>> {
+        self.ensure_table().await?;
+        let applied = self.applied().await?;
+
+        for record in &applied {
+            match self.migrations.iter().find(|m| m.version == record.version) {
+                Some(migration) if migration.checksum() != record.checksum => {
+                    return Err(migrate_error(MigrateError::VersionMismatch(record.version)));
+                }
+                Some(_) => {}
+                None => return Err(migrate_error(MigrateError::VersionMissing(record.version))),
+            }
+        }
+
+        let mut newly_applied = Vec::new();
+        for migration in self.migrations {
+            if applied.iter().any(|record| record.version == migration.version) {
+                continue;
+            }
+            self.apply(migration).await?;
+            newly_applied.push(migration.version);
+        }
+
+        if newly_applied.is_empty() {
+            info!("Database schema is up to date (version {})", self.latest_version());
+        }
+        Ok(newly_applied)
+    }
+
+    /// Revert applied migrations newer than `target`, newest first, returning the versions reverted
+    pub async fn rollback(&self, target: i64) -> Result<Vec<i64>> {
+        self.ensure_table().await?;
+        let applied = self.applied().await?;
+
+        let mut reverted = Vec::new();
+        for record in applied.iter().rev().filter(|record| record.version > target) {
+            let migration = self.migrations.iter()
+                .find(|m| m.version == record.version)
+                .ok_or_else(|| migrate_error(MigrateError::VersionMissing(record.version)))?;
+            self.revert(migration).await?;
+            reverted.push(migration.version);
+        }
+        Ok(reverted)
+    }
+
+    /// Every known or applied migration with its state, ordered by version
+    pub async fn status(&self) -> Result<Vec<MigrationStatus>> {
+        self.ensure_table().await?;
+        let applied = self.applied().await?;
+
+        let mut statuses: Vec<MigrationStatus> = self.migrations.iter()
+            .map(|migration| {
+                let state = match applied.iter().find(|record| record.version == migration.version) {
+                    Some(record) if record.checksum != migration.checksum() => {
+                        MigrationState::Modified { applied_at: record.applied_at }
+                    }
+                    Some(record) => MigrationState::Applied { applied_at: record.applied_at },
+                    None => MigrationState::Pending,
+                };
+                MigrationStatus { version: migration.version, name: migration.name.to_string(), state }
+            })
+            .collect();
+
+        statuses.extend(
+            applied.into_iter()
+                .filter(|record| self.migrations.iter().all(|m| m.version != record.version))
+                .map(|record| MigrationStatus {
+                    version: record.version,
+                    name: record.name,
+                    state: MigrationState::Unknown { applied_at: record.applied_at },
+                }),
+        );
+        statuses.sort_by_key(|status| status.version);
+        Ok(statuses)
+    }
+
+    async fn ensure_table(&self) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                applied_at INTEGER NOT NULL DEFAULT (unixepoch()),
+                execution_ms INTEGER NOT NULL DEFAULT 0
+            )",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.adopt_legacy_version().await
+    }
+
+    /// Databases created before `schema_migrations` tracked their version in `app_settings`;
+    /// record those migrations as applied so they are not run twice
+    async fn adopt_legacy_version(&self) -> Result<()> {
+        let recorded: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM schema_migrations")
+            .fetch_one(&self.pool)
+            .await?;
+        if recorded > 0 || !table_exists(&self.pool, "app_settings").await? {
+            return Ok(());
+        }
+
+        let legacy: Option<String> = sqlx::query_scalar(
+            "SELECT value FROM app_settings WHERE key = 'database_version'"
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(legacy) = legacy.and_then(|v| v.parse::<i64>().ok()).filter(|v| *v > 0) else {
+            return Ok(());
+        };
+
+        info!("Adopting legacy database version {} into schema_migrations", legacy);
+        for migration in self.migrations.iter().filter(|m| m.version <= legacy) {
+            sqlx::query("INSERT INTO schema_migrations (version, name, checksum) VALUES (?, ?, ?)")
+                .bind(migration.version)
+                .bind(migration.name)
+                .bind(migration.checksum())
+                .execute(&self.pool)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn applied(&self) -> Result<Vec<AppliedMigration>> {
+        let rows = sqlx::query("SELECT version, name, checksum, applied_at FROM schema_migrations ORDER BY version")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter()
+            .map(|row| AppliedMigration {
+                version: row.get("version"),
+                name: row.get("name"),
+                checksum: row.get("checksum"),
+                applied_at: Utc.timestamp_opt(row.get("applied_at"), 0).single().unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    async fn apply(&self, migration: &Migration) -> Result<()> {
+        info!("Applying migration {}", migration.name);
+        self.in_transaction(migration, Direction::Up).await
+    }
+
+    async fn revert(&self, migration: &Migration) -> Result<()> {
+        warn!("Reverting migration {}", migration.name);
+        self.in_transaction(migration, Direction::Down).await
+    }
+
+    /// Run one direction of a migration and its bookkeeping as a single transaction
+    ///
+    /// Foreign keys are switched off for the duration, as SQLite recommends for schema
+    /// changes, and checked before committing.
+    async fn in_transaction(&self, migration: &Migration, direction: Direction) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await?;
+
+        let started = Instant::now();
+        let result = async {
+            let mut tx = conn.begin().await?;
+            let sql = match direction {
+                Direction::Up => migration.up,
+                Direction::Down => migration.down,
+            };
+            sqlx::raw_sql(sql).execute(&mut *tx).await?;
+
+            let violations: Vec<(String,)> = sqlx::query_as("SELECT \"table\" FROM pragma_foreign_key_check")
+                .fetch_all(&mut *tx)
+                .await?;
+            if let Some((table,)) = violations.first() {
+                return Err(Error::validation(format!(
+                    "Migration {} leaves {} foreign key violation(s), first in {}",
+                    migration.name, violations.len(), table
+                )));
+            }
+
+            match direction {
+                Direction::Up => {
+                    sqlx::query("INSERT INTO schema_migrations (version, name, checksum, execution_ms) VALUES (?, ?, ?, ?)")
+                        .bind(migration.version)
+                        .bind(migration.name)
+                        .bind(migration.checksum())
+                        .bind(started.elapsed().as_millis() as i64)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+                Direction::Down => {
+                    sqlx::query("DELETE FROM schema_migrations WHERE version = ?")
+                        .bind(migration.version)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+            }
+            sync_legacy_version(&mut tx).await?;
+            tx.commit().await?;
+            Ok(())
+        }
+        .await;
+
+        let restored = sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await;
+        match result {
+            Ok(()) => {
+                info!("Migration {} finished in {:?}", migration.name, started.elapsed());
+                restored?;
+                Ok(())
+            }
+            Err(e) => {
+                error!("Migration {} failed: {}", migration.name, e);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    Up,
+    Down,
+}
+
+async fn table_exists<'e, E>(executor: E, table: &str) -> Result<bool>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    Ok(sqlx::query("SELECT name FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(table)
+        .fetch_optional(executor)
+        .await?
+        .is_some())
+}
+
+/// Keep `app_settings.database_version`, read by backups, in step with the applied migrations
+async fn sync_legacy_version(conn: &mut SqliteConnection) -> Result<()> {
+    if !table_exists(&mut *conn, "app_settings").await? {
+        return Ok(());
+    }
+    sqlx::query(
+        "INSERT OR REPLACE INTO app_settings (key, value, updated_at)
+         SELECT 'database_version', CAST(COALESCE(MAX(version), 0) AS TEXT), unixepoch() FROM schema_migrations"
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+fn migrate_error(e: MigrateError) -> Error {
+    Error::Database(sqlx::Error::Migrate(Box::new(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn test_pool() -> (SqlitePool, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let url = format!("sqlite:{}?mode=rwc", temp_dir.path().join("test.db").display());
+        (SqlitePool::connect(&url).await.unwrap(), temp_dir)
+    }
+
+    #[test]
+    fn test_embedded_migrations_are_ordered() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, index as i64 + 1);
+            assert!(migration.name.starts_with(&format!("{:03}_", migration.version)));
+            assert_eq!(migration.checksum().len(), 64);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migrate_rollback_and_reapply() {
+        let (pool, _temp_dir) = test_pool().await;
+        let migrator = Migrator::new(pool.clone());
+
+        let applied = migrator.run().await.unwrap();
+        assert_eq!(applied.len(), MIGRATIONS.len());
+        assert!(migrator.run().await.unwrap().is_empty());
+        assert_eq!(migrator.current_version().await.unwrap(), migrator.latest_version());
+
+        sqlx::query("INSERT INTO projects (name) VALUES ('alpha')").execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO conversations (id, title, project) VALUES ('c1', 'Chat', 'alpha')")
+            .execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO messages (id, conversation_id, role, content) VALUES ('m1', 'c1', 'user', 'hi')")
+            .execute(&pool).await.unwrap();
+
+        // Undoing projects keeps conversations and their messages
+        assert_eq!(migrator.rollback(2).await.unwrap(), vec![8, 7, 6, 5, 4, 3]);
+        assert_eq!(migrator.current_version().await.unwrap(), 2);
+        let messages: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM messages").fetch_one(&pool).await.unwrap();
+        assert_eq!(messages, 1);
+        let version: String = sqlx::query_scalar("SELECT value FROM app_settings WHERE key = 'database_version'")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(version, "2");
+
+        let status = migrator.status().await.unwrap();
+        assert_eq!(status[2].state, MigrationState::Pending);
+
+        assert_eq!(migrator.run().await.unwrap(), vec![3, 4, 5, 6, 7, 8]);
+        assert_eq!(migrator.rollback(0).await.unwrap(), vec![8, 7, 6, 5, 4, 3, 2, 1]);
+        assert!(!table_exists(&pool, "conversations").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_modified_and_unknown_migrations_block_run() {
+        let (pool, _temp_dir) = test_pool().await;
+        let migrator = Migrator::new(pool.clone());
+        migrator.run().await.unwrap();
+
+        sqlx::query("UPDATE schema_migrations SET checksum = 'edited' WHERE version = 2")
+            .execute(&pool).await.unwrap();
+        assert!(migrator.run().await.is_err());
+        let status = migrator.status().await.unwrap();
+        assert!(matches!(status[1].state, MigrationState::Modified { .. }));
+
+        sqlx::query("UPDATE schema_migrations SET checksum = ? WHERE version = 2")
+            .bind(MIGRATIONS[1].checksum())
+            .execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO schema_migrations (version, name, checksum) VALUES (99, '099_future', 'x')")
+            .execute(&pool).await.unwrap();
+        assert!(migrator.run().await.is_err());
+        assert!(matches!(migrator.status().await.unwrap().last().unwrap().state, MigrationState::Unknown { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_version_is_adopted() {
+        let (pool, _temp_dir) = test_pool().await;
+        for migration in &MIGRATIONS[..2] {
+            sqlx::raw_sql(migration.up).execute(&pool).await.unwrap();
+        }
+        sqlx::query("UPDATE app_settings SET value = '2' WHERE key = 'database_version'")
+            .execute(&pool).await.unwrap();
+
+        let migrator = Migrator::new(pool);
+        assert_eq!(migrator.run().await.unwrap(), vec![3, 4, 5, 6, 7, 8]);
+    }
+}
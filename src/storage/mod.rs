@@ -1,6 +1,7 @@
 // Storage layer for persistent data
 
 pub mod database;
+pub mod migrations;
 pub mod conversations;
 pub mod usage;
 pub mod billing;
@@ -8,14 +9,32 @@ pub mod enforcement;
 pub mod dashboard;
 pub mod backup;
 pub mod cost_alerts;
+pub mod projects;
+pub mod forecast;
+pub mod anomaly;
+pub mod encryption;
+pub mod retention;
+pub mod key_audit;
+pub mod key_selections;
 
 pub use database::{Database, DatabaseStatistics, decimal_helpers};
-pub use conversations::{ConversationRepository, ConversationStatistics};
-pub use usage::{UsageRepository, UsageRecord, BillingSummary, UsageStatistics, ProviderUsage, ModelUsage};
-pub use billing::{BillingSystem, SpendingLimit, SpendingLimitType, BillingPeriod, BillingAlert, AlertType, 
-                  VerificationResult, BillingReport, SpendingCheckResult, SpendingLimitStatus};
+pub use migrations::{Migrator, Migration, MigrationState, MigrationStatus, MIGRATIONS};
+pub use conversations::{ConversationRepository, ConversationStatistics, ToolInvocationFilter, ToolInvocationRecord,
+                        ToolInvocationStatus};
+pub use usage::{UsageRepository, UsageRecord, BillingSummary, UsageStatistics, ProviderUsage, ModelUsage, ProjectUsage, KeyUsage};
+pub use projects::{ProjectRepository, Project};
+pub use billing::{BillingSystem, SpendingLimit, SpendingLimitType, BillingPeriod, LimitSource, BillingAlert, AlertType, 
+                  VerificationResult, BillingReport, ProjectBilling, SpendingCheckResult, SpendingLimitStatus};
 pub use enforcement::{SpendingEnforcement, EnforcementResult, EnforcementAction, EnforcementConfig, 
                       EnforcementStatus, LimitInfo};
-pub use dashboard::{BillingDashboard, DashboardData, BillingOverview, MonthlyReport, ExportFormat};
+pub use dashboard::{BillingDashboard, DashboardData, BillingOverview, MonthlyReport, ExportFormat, DailySpend,
+                    ConversationSpend, ModelSpend, PeriodReport, ProviderSpend,
+                    UsagePatterns, HourlyUsage, WeekdayUsage};
+pub use forecast::{SpendForecaster, SpendForecast, ProviderForecast, BudgetProjection, ForecastModel};
+pub use anomaly::{RollingBaseline, SpendBaselines, SessionActivity};
+pub use encryption::{DatabaseEncryption, EncryptionConfig, EncryptionStatus, FieldCipher};
+pub use key_audit::{detect_anomalies, ApiKeyAuditLog, DEFAULT_REVIEW_DAYS, KeyAuditAnomaly, KeyAuditEntry, KeyAuditFilter, KeyAuditRules, KeyOperation};
+pub use key_selections::{KeyScope, KeySelectionRepository};
+pub use retention::{RetentionManager, RetentionConfig, RetentionRule, RetentionAction, PurgeCandidate, PurgeReport};
 pub use backup::{BackupSystem, BackupConfig, BackupInfo, BackupType, RecoveryOptions, RecoveryResult};
-pub use cost_alerts::{CostAlertSystem, CostAlert, CostAlertType, AlertSeverity, CostAlertConfig, AlertContext};
\ No newline at end of file
+pub use cost_alerts::{CostAlertSystem, CostAlert, CostAlertType, AlertSeverity, CostAlertConfig, AlertContext, AlertStore};
\ No newline at end of file
@@ -0,0 +1,186 @@
+use sqlx::{Row, SqlitePool};
+use tracing::{debug, info};
+use rust_decimal::Decimal;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use crate::error::{Error, Result};
+
+/// Repository for projects that conversations and their costs are attributed to
+pub struct ProjectRepository {
+    pool: SqlitePool,
+}
+
+/// A project with its current attribution totals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub conversation_count: u64,
+    pub total_cost: Decimal,
+}
+
+impl ProjectRepository {
+    /// Create a new project repository
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a project if it does not exist yet
+    pub async fn ensure_project(&self, name: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::validation("Project name cannot be empty"));
+        }
+
+        let created = sqlx::query("INSERT OR IGNORE INTO projects (name) VALUES (?)")
+            .bind(name)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+
+        if created > 0 {
+            info!("Created project: {}", name);
+        }
+        Ok(())
+    }
+
+    /// Get a single project
+    pub async fn get_project(&self, name: &str) -> Result<Option<Project>> {
+        Ok(self.list_projects().await?.into_iter().find(|project| project.name == name))
+    }
+
+    /// List all projects with their conversation counts and attributed spending
+    pub async fn list_projects(&self) -> Result<Vec<Project>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT p.name, p.description, p.created_at,
+                   (SELECT COUNT(*) FROM conversations c WHERE c.project = p.name) as conversation_count,
+                   (SELECT COALESCE(SUM(CAST(u.cost AS REAL)), 0.0) FROM usage_records u WHERE u.project = p.name) as total_cost
+            FROM projects p
+            ORDER BY p.name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter()
+            .map(|row| Project {
+                name: row.get("name"),
+                description: row.get("description"),
+                created_at: DateTime::from_timestamp(row.get("created_at"), 0).unwrap_or_else(Utc::now),
+                conversation_count: row.get::<i64, _>("conversation_count") as u64,
+                total_cost: Decimal::try_from(row.get::<f64, _>("total_cost")).unwrap_or(Decimal::ZERO),
+            })
+            .collect())
+    }
+
+    /// Assign a conversation to a project, creating the project if needed; `None` unassigns it
+    ///
+    /// Only future usage is attributed to the new project.
+    pub async fn assign_conversation(&self, conversation_id: &str, project: Option<&str>) -> Result<()> {
+        debug!("Assigning conversation {} to project {:?}", conversation_id, project);
+
+        if let Some(name) = project {
+            self.ensure_project(name).await?;
+        }
+
+        let rows_affected = sqlx::query("UPDATE conversations SET project = ?, updated_at = ? WHERE id = ?")
+            .bind(project.map(str::trim))
+            .bind(Utc::now().timestamp())
+            .bind(conversation_id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(Error::Database(sqlx::Error::RowNotFound));
+        }
+        Ok(())
+    }
+
+    /// Get the project a conversation is assigned to
+    pub async fn get_conversation_project(&self, conversation_id: &str) -> Result<Option<String>> {
+        let project: Option<Option<String>> = sqlx::query_scalar("SELECT project FROM conversations WHERE id = ?")
+            .bind(conversation_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(project.flatten())
+    }
+
+    /// Delete a project; its conversations become unassigned while recorded usage keeps the name
+    pub async fn delete_project(&self, name: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE conversations SET project = NULL WHERE project = ?")
+            .bind(name)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM api_key_selections WHERE scope = 'project' AND scope_id = ?")
+            .bind(name)
+            .execute(&mut *tx)
+            .await?;
+
+        let rows_affected = sqlx::query("DELETE FROM projects WHERE name = ?")
+            .bind(name)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        if rows_affected == 0 {
+            tx.rollback().await?;
+            return Err(Error::Database(sqlx::Error::RowNotFound));
+        }
+
+        tx.commit().await?;
+        info!("Deleted project: {}", name);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::types::ChatSession;
+    use crate::platform::AppPaths;
+    use crate::storage::{ConversationRepository, Database, UsageRepository};
+    use crate::storage::usage::UsageParams;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_usage_is_attributed_to_the_conversation_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = AppPaths::with_data_dir(temp_dir.path()).unwrap();
+        let db = Database::new(&paths).await.unwrap();
+        let projects = ProjectRepository::new(db.get_pool());
+        let conversations = ConversationRepository::new(db.get_pool());
+        let usage = UsageRepository::new(db.get_pool());
+
+        let session = ChatSession::new("Roadmap", "openai", "gpt-4");
+        conversations.create_conversation(&session).await.unwrap();
+
+        projects.assign_conversation(&session.id, Some("alpha")).await.unwrap();
+        assert_eq!(projects.get_conversation_project(&session.id).await.unwrap().as_deref(), Some("alpha"));
+        usage.record_usage(UsageParams::new("openai", "gpt-4", 100, 50, Decimal::new(125, 2))
+            .with_conversation_id(&session.id)).await.unwrap();
+
+        // Usage recorded after reassignment goes to the new project only
+        projects.assign_conversation(&session.id, Some("beta")).await.unwrap();
+        usage.record_usage(UsageParams::new("openai", "gpt-4", 100, 50, Decimal::new(50, 2))
+            .with_conversation_id(&session.id)).await.unwrap();
+
+        let listed = projects.list_projects().await.unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].name, "alpha");
+        assert_eq!(listed[0].conversation_count, 0);
+        assert_eq!(listed[0].total_cost, Decimal::new(125, 2));
+        assert_eq!(listed[1].conversation_count, 1);
+        assert_eq!(listed[1].total_cost, Decimal::new(50, 2));
+
+        projects.delete_project("beta").await.unwrap();
+        assert_eq!(projects.get_conversation_project(&session.id).await.unwrap(), None);
+        assert!(projects.delete_project("beta").await.is_err());
+    }
+}
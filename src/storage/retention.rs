@@ -0,0 +1,300 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use tracing::{debug, info, warn};
+
+use crate::error::{Error, Result};
+use crate::storage::conversations::delete_conversation_tx;
+use crate::storage::UsageRepository;
+
+/// What happens to a conversation once a retention rule applies to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionAction {
+    /// Remove the conversation, its messages and tool invocations
+    Delete,
+    /// Keep the conversation but mark it archived
+    Archive,
+}
+
+impl RetentionAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RetentionAction::Delete => "delete",
+            RetentionAction::Archive => "archive",
+        }
+    }
+}
+
+fn default_action() -> RetentionAction {
+    RetentionAction::Delete
+}
+
+/// Applies `action` to conversations with no activity for `older_than_days`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionRule {
+    pub older_than_days: u32,
+    #[serde(default = "default_action")]
+    pub action: RetentionAction,
+    /// Only conversations with this provider
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Only conversations in this project
+    #[serde(default)]
+    pub project: Option<String>,
+}
+
+impl RetentionRule {
+    fn matches(&self, conversation: &PurgeCandidate, now: DateTime<Utc>) -> bool {
+        conversation.last_activity < now - Duration::days(self.older_than_days as i64)
+            && self.provider.as_deref().is_none_or(|p| p.eq_ignore_ascii_case(&conversation.provider))
+            && self.project.as_deref().is_none_or(|p| conversation.project.as_deref() == Some(p))
+    }
+}
+
+/// Retention policy for conversation history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionConfig {
+    /// Deletion rules win over archive rules when both apply
+    pub rules: Vec<RetentionRule>,
+    /// Delete verified usage records older than this; billing summaries are always kept
+    pub usage_records_days: Option<u32>,
+    /// Apply the rules whenever the chat interface starts
+    pub enforce_on_startup: bool,
+    /// Rewrite the database file after a purge so deleted content does not remain in free pages
+    pub vacuum: bool,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            usage_records_days: None,
+            enforce_on_startup: true,
+            vacuum: true,
+        }
+    }
+}
+
+/// A conversation a purge deletes or archives
+#[derive(Debug, Clone)]
+pub struct PurgeCandidate {
+    pub id: String,
+    pub title: String,
+    pub provider: String,
+    pub project: Option<String>,
+    /// Time of the newest message, or creation time of an empty conversation
+    pub last_activity: DateTime<Utc>,
+    pub message_count: u64,
+    pub action: RetentionAction,
+}
+
+/// Outcome of applying the retention rules
+#[derive(Debug, Clone, Default)]
+pub struct PurgeReport {
+    pub dry_run: bool,
+    pub conversations: Vec<PurgeCandidate>,
+    pub usage_records_deleted: u64,
+    pub vacuumed: bool,
+}
+
+impl PurgeReport {
+    pub fn count(&self, action: RetentionAction) -> usize {
+        self.conversations.iter().filter(|c| c.action == action).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conversations.is_empty() && self.usage_records_deleted == 0
+    }
+}
+
+/// Applies retention rules to stored conversations
+pub struct RetentionManager {
+    pool: SqlitePool,
+    config: RetentionConfig,
+}
+
+impl RetentionManager {
+    pub fn new(pool: SqlitePool, config: RetentionConfig) -> Self {
+        Self { pool, config }
+    }
+
+    /// Conversations the rules currently delete or archive
+    ///
+    /// Conversations that were soft-deleted are always deleted for good.
+    pub async fn plan(&self) -> Result<Vec<PurgeCandidate>> {
+        for rule in &self.config.rules {
+            if rule.older_than_days == 0 {
+                return Err(Error::validation("Retention rules need older_than_days of at least 1"));
+            }
+        }
+
+        let now = Utc::now();
+        // updated_at moves whenever the row is touched, so age is taken from the messages
+        let rows = sqlx::query(
+            "SELECT c.id, c.title, c.model_provider, c.project, c.message_count, c.status, \
+                    COALESCE((SELECT MAX(m.timestamp) FROM messages m WHERE m.conversation_id = c.id), c.created_at) \
+                        AS last_activity \
+             FROM conversations c ORDER BY last_activity",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut candidates = Vec::new();
+        for row in rows {
+            let status: String = row.get("status");
+            let mut candidate = PurgeCandidate {
+                id: row.get("id"),
+                title: row.get("title"),
+                provider: row.get("model_provider"),
+                project: row.get("project"),
+                last_activity: DateTime::from_timestamp(row.get("last_activity"), 0).unwrap_or_else(Utc::now),
+                message_count: row.get::<i64, _>("message_count").max(0) as u64,
+                action: RetentionAction::Delete,
+            };
+
+            let applies = |action| self.config.rules.iter()
+                .any(|rule| rule.action == action && rule.matches(&candidate, now));
+            let action = if status == "deleted" || applies(RetentionAction::Delete) {
+                Some(RetentionAction::Delete)
+            } else if status != "archived" && applies(RetentionAction::Archive) {
+                Some(RetentionAction::Archive)
+            } else {
+                None
+            };
+
+            if let Some(action) = action {
+                candidate.action = action;
+                candidates.push(candidate);
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Apply the retention rules, or with `dry_run` only report what they would do
+    pub async fn purge(&self, dry_run: bool) -> Result<PurgeReport> {
+        let conversations = self.plan().await?;
+        let mut report = PurgeReport { dry_run, conversations, ..Default::default() };
+        if dry_run {
+            if let Some(days) = self.config.usage_records_days {
+                report.usage_records_deleted = sqlx::query_scalar::<_, i64>(
+                    "SELECT COUNT(*) FROM usage_records WHERE timestamp < ? AND verified = TRUE",
+                )
+                .bind((Utc::now() - Duration::days(days as i64)).timestamp())
+                .fetch_one(&self.pool)
+                .await? as u64;
+            }
+            return Ok(report);
+        }
+
+        let mut tx = self.pool.begin().await?;
+        for conversation in &report.conversations {
+            match conversation.action {
+                RetentionAction::Delete => {
+                    delete_conversation_tx(&mut tx, &conversation.id).await?;
+                }
+                RetentionAction::Archive => {
+                    sqlx::query("UPDATE conversations SET status = 'archived' WHERE id = ?")
+                        .bind(&conversation.id)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+            }
+        }
+        tx.commit().await?;
+
+        if let Some(days) = self.config.usage_records_days {
+            report.usage_records_deleted = UsageRepository::new(self.pool.clone()).cleanup_old_records(days).await?;
+        }
+
+        let deleted = report.count(RetentionAction::Delete) > 0 || report.usage_records_deleted > 0;
+        if deleted && self.config.vacuum {
+            match sqlx::query("VACUUM").execute(&self.pool).await {
+                Ok(_) => report.vacuumed = true,
+                Err(e) => warn!("Failed to vacuum the database after purging: {}", e),
+            }
+        }
+
+        info!(
+            "Retention purge deleted {} and archived {} conversation(s), deleted {} usage record(s)",
+            report.count(RetentionAction::Delete),
+            report.count(RetentionAction::Archive),
+            report.usage_records_deleted
+        );
+        debug!("Database vacuumed after purge: {}", report.vacuumed);
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::AppPaths;
+    use crate::storage::Database;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_purge_keeps_billing_and_honours_filters() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(&AppPaths::with_data_dir(temp_dir.path()).unwrap()).await.unwrap();
+        let pool = db.get_pool();
+        let old = (Utc::now() - Duration::days(100)).timestamp();
+        sqlx::raw_sql(&format!(
+            "INSERT INTO projects (name) VALUES ('legal');
+             INSERT INTO conversations (id, title, model_provider, created_at) VALUES ('old', 'Old', 'openai', {old});
+             INSERT INTO conversations (id, title, model_provider, created_at, project) VALUES ('legal', 'Legal', 'openai', {old}, 'legal');
+             INSERT INTO conversations (id, title, model_provider, created_at) VALUES ('claude', 'Claude', 'anthropic', {old});
+             INSERT INTO conversations (id, title, model_provider) VALUES ('new', 'New', 'openai');
+             INSERT INTO conversations (id, title, model_provider, status) VALUES ('trash', 'Trash', 'openai', 'deleted');
+             INSERT INTO messages (id, conversation_id, role, content, timestamp) VALUES ('m1', 'old', 'user', 'secret', {old});
+             INSERT INTO tool_invocations (id, message_id, tool_name, server_name, arguments) VALUES ('t1', 'm1', 'read', 'fs', '{{}}');
+             INSERT INTO usage_records (provider, model, input_tokens, output_tokens, cost, conversation_id, message_id)
+                 VALUES ('openai', 'gpt-4', 10, 10, '0.50', 'old', 'm1');
+             INSERT INTO billing_summaries (billing_period, provider, model, total_cost, request_count)
+                 VALUES ('2024-01', 'openai', 'gpt-4', '0.50', 1);"
+        ))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let config = RetentionConfig {
+            rules: vec![
+                RetentionRule { older_than_days: 90, action: RetentionAction::Delete, provider: Some("openai".into()), project: None },
+                RetentionRule { older_than_days: 30, action: RetentionAction::Archive, provider: None, project: None },
+            ],
+            ..Default::default()
+        };
+        let retention = RetentionManager::new(pool.clone(), config);
+
+        let plan = retention.purge(true).await.unwrap();
+        let actions: Vec<(&str, RetentionAction)> = plan.conversations.iter().map(|c| (c.id.as_str(), c.action)).collect();
+        assert!(actions.contains(&("old", RetentionAction::Delete)));
+        assert!(actions.contains(&("legal", RetentionAction::Delete)));
+        assert!(actions.contains(&("claude", RetentionAction::Archive)));
+        assert!(actions.contains(&("trash", RetentionAction::Delete)));
+        assert_eq!(actions.len(), 4);
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM conversations").fetch_one(&pool).await.unwrap();
+        assert_eq!(remaining, 5);
+
+        let report = retention.purge(false).await.unwrap();
+        assert_eq!((report.count(RetentionAction::Delete), report.count(RetentionAction::Archive)), (3, 1));
+        assert!(report.vacuumed);
+
+        let ids: Vec<String> = sqlx::query_scalar("SELECT id FROM conversations ORDER BY id").fetch_all(&pool).await.unwrap();
+        assert_eq!(ids, vec!["claude", "new"]);
+        let messages: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM messages").fetch_one(&pool).await.unwrap();
+        let invocations: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tool_invocations").fetch_one(&pool).await.unwrap();
+        assert_eq!((messages, invocations), (0, 0));
+
+        // Billing data outlives the conversation
+        let usage: (i64, Option<String>) = sqlx::query_as("SELECT COUNT(*), MAX(conversation_id) FROM usage_records")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(usage, (1, None));
+        let summaries: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM billing_summaries").fetch_one(&pool).await.unwrap();
+        assert_eq!(summaries, 1);
+
+        // Archived conversations are not archived again
+        assert!(retention.purge(true).await.unwrap().is_empty());
+    }
+}
@@ -19,6 +19,8 @@ pub struct UsageParams<'a> {
     pub cost: Decimal,
     pub conversation_id: Option<&'a str>,
     pub message_id: Option<&'a str>,
+    /// API key the request was sent with; defaults to the provider's default key
+    pub key_id: Option<&'a str>,
 }
 
 /// Parameters for updating billing summary
@@ -42,6 +44,7 @@ impl<'a> UsageParams<'a> {
             cost,
             conversation_id: None,
             message_id: None,
+            key_id: None,
         }
     }
     
@@ -54,6 +57,11 @@ impl<'a> UsageParams<'a> {
         self.message_id = Some(message_id);
         self
     }
+
+    pub fn with_key_id(mut self, key_id: &'a str) -> Self {
+        self.key_id = Some(key_id);
+        self
+    }
 }
 
 /// Repository for managing usage tracking and billing
@@ -77,6 +85,8 @@ pub struct UsageRecord {
     pub billing_period: String, // YYYY-MM format
     pub verified: bool,
     pub verification_timestamp: Option<DateTime<Utc>>,
+    pub project: Option<String>,
+    pub key_id: Option<String>,
 }
 
 /// Billing summary for a specific period and model
@@ -101,6 +111,8 @@ pub struct UsageStatistics {
     pub total_cost: Decimal,
     pub by_provider: HashMap<String, ProviderUsage>,
     pub by_model: HashMap<String, ModelUsage>,
+    /// Usage attributed to projects; unassigned usage is not listed
+    pub by_project: HashMap<String, ProjectUsage>,
     pub current_month_cost: Decimal,
     pub previous_month_cost: Decimal,
 }
@@ -113,6 +125,25 @@ pub struct ProviderUsage {
     pub cost: Decimal,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectUsage {
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost: Decimal,
+}
+
+/// Usage sent with one API key in a billing period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyUsage {
+    pub key_id: String,
+    pub provider: String,
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost: Decimal,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelUsage {
     pub provider: String,
@@ -147,8 +178,8 @@ impl UsageRepository {
             r#"
             INSERT INTO usage_records (
                 timestamp, provider, model, input_tokens, output_tokens, cost,
-                conversation_id, message_id, request_id, billing_period, verified
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                conversation_id, message_id, request_id, billing_period, verified, project, key_id
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, (SELECT project FROM conversations WHERE id = ?), ?)
             "#
         )
         .bind(now.timestamp())
@@ -162,6 +193,8 @@ impl UsageRepository {
         .bind(&request_id)
         .bind(&billing_period)
         .bind(false) // Not verified initially
+        .bind(params.conversation_id)
+        .bind(params.key_id.unwrap_or(params.provider))
         .execute(&mut *tx)
         .await?;
 
@@ -340,7 +373,7 @@ impl UsageRepository {
         let mut query = String::from(
             r#"
             SELECT id, timestamp, provider, model, input_tokens, output_tokens, cost,
-                   conversation_id, message_id, request_id, billing_period, verified, verification_timestamp
+                   conversation_id, message_id, request_id, billing_period, verified, verification_timestamp, project, key_id
             FROM usage_records WHERE 1=1
             "#
         );
@@ -405,6 +438,8 @@ impl UsageRepository {
                 billing_period: row.get("billing_period"),
                 verified: row.get("verified"),
                 verification_timestamp,
+                project: row.get("project"),
+                key_id: row.get("key_id"),
             });
         }
 
@@ -481,7 +516,17 @@ impl UsageRepository {
 
     /// Get comprehensive usage statistics
     pub async fn get_usage_statistics(&self) -> Result<UsageStatistics> {
-        debug!("Calculating usage statistics");
+        self.usage_statistics(None).await
+    }
+
+    /// Get usage statistics limited to the usage attributed to one project
+    pub async fn get_project_usage_statistics(&self, project: &str) -> Result<UsageStatistics> {
+        self.usage_statistics(Some(project)).await
+    }
+
+    /// Usage statistics across all records, or only those charged to `project`
+    async fn usage_statistics(&self, project: Option<&str>) -> Result<UsageStatistics> {
+        debug!("Calculating usage statistics (project: {:?})", project);
 
         // Get total stats
         let total_stats = sqlx::query(
@@ -492,8 +537,11 @@ impl UsageRepository {
                 COALESCE(SUM(output_tokens), 0) as total_output_tokens,
                 COALESCE(SUM(CAST(cost AS REAL)), 0.0) as total_cost
             FROM usage_records
+            WHERE (? IS NULL OR project = ?)
             "#
         )
+        .bind(project)
+        .bind(project)
         .fetch_one(&self.pool)
         .await?;
 
@@ -512,8 +560,8 @@ impl UsageRepository {
             format!("{:04}-{:02}", now.year(), now.month() - 1)
         };
 
-        let current_month_cost = self.get_period_cost(&current_period).await?;
-        let previous_month_cost = self.get_period_cost(&previous_month).await?;
+        let current_month_cost = self.get_period_cost(&current_period, project).await?;
+        let previous_month_cost = self.get_period_cost(&previous_month, project).await?;
 
         // Get stats by provider
         let provider_rows = sqlx::query(
@@ -525,9 +573,12 @@ impl UsageRepository {
                 COALESCE(SUM(output_tokens), 0) as output_tokens,
                 COALESCE(SUM(CAST(cost AS REAL)), 0.0) as cost
             FROM usage_records
+            WHERE (? IS NULL OR project = ?)
             GROUP BY provider
             "#
         )
+        .bind(project)
+        .bind(project)
         .fetch_all(&self.pool)
         .await?;
 
@@ -558,9 +609,12 @@ impl UsageRepository {
                 COALESCE(SUM(output_tokens), 0) as output_tokens,
                 COALESCE(SUM(CAST(cost AS REAL)), 0.0) as cost
             FROM usage_records
+            WHERE (? IS NULL OR project = ?)
             GROUP BY model, provider
             "#
         )
+        .bind(project)
+        .bind(project)
         .fetch_all(&self.pool)
         .await?;
 
@@ -582,6 +636,41 @@ impl UsageRepository {
             });
         }
 
+        // Get stats by project
+        let project_rows = sqlx::query(
+            r#"
+            SELECT 
+                project,
+                COUNT(*) as requests,
+                COALESCE(SUM(input_tokens), 0) as input_tokens,
+                COALESCE(SUM(output_tokens), 0) as output_tokens,
+                COALESCE(SUM(CAST(cost AS REAL)), 0.0) as cost
+            FROM usage_records
+            WHERE project IS NOT NULL AND (? IS NULL OR project = ?)
+            GROUP BY project
+            "#
+        )
+        .bind(project)
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut by_project = HashMap::new();
+        for row in project_rows {
+            let name: String = row.get("project");
+            let requests: i64 = row.get("requests");
+            let input_tokens: i64 = row.get("input_tokens");
+            let output_tokens: i64 = row.get("output_tokens");
+            let cost_f64: f64 = row.get("cost");
+
+            by_project.insert(name, ProjectUsage {
+                requests: requests as u64,
+                input_tokens: input_tokens as u64,
+                output_tokens: output_tokens as u64,
+                cost: Decimal::try_from(cost_f64).unwrap_or(Decimal::ZERO),
+            });
+        }
+
         Ok(UsageStatistics {
             total_requests: total_requests as u64,
             total_input_tokens: total_input_tokens as u64,
@@ -589,23 +678,76 @@ impl UsageRepository {
             total_cost: Decimal::try_from(total_cost_f64).unwrap_or(Decimal::ZERO),
             by_provider,
             by_model,
+            by_project,
             current_month_cost,
             previous_month_cost,
         })
     }
 
     /// Get total cost for a billing period
-    async fn get_period_cost(&self, billing_period: &str) -> Result<Decimal> {
+    async fn get_period_cost(&self, billing_period: &str, project: Option<&str>) -> Result<Decimal> {
         let cost_result: Option<f64> = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(CAST(cost AS REAL)), 0.0) FROM usage_records WHERE billing_period = ?"
+            "SELECT COALESCE(SUM(CAST(cost AS REAL)), 0.0) FROM usage_records WHERE billing_period = ? AND (? IS NULL OR project = ?)"
         )
         .bind(billing_period)
+        .bind(project)
+        .bind(project)
         .fetch_optional(&self.pool)
         .await?;
 
         Ok(Decimal::try_from(cost_result.unwrap_or(0.0)).unwrap_or(Decimal::ZERO))
     }
 
+    /// Get total cost per provider for a billing period
+    pub async fn get_provider_period_costs(&self, billing_period: &str) -> Result<HashMap<String, Decimal>> {
+        let rows = sqlx::query(
+            "SELECT provider, COALESCE(SUM(CAST(cost AS REAL)), 0.0) as cost FROM usage_records WHERE billing_period = ? GROUP BY provider"
+        )
+        .bind(billing_period)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter()
+            .map(|row| {
+                let cost: f64 = row.get("cost");
+                (row.get("provider"), Decimal::try_from(cost).unwrap_or(Decimal::ZERO))
+            })
+            .collect())
+    }
+
+    /// Usage per API key for a billing period, highest cost first, to compare with each organization's invoice
+    pub async fn get_key_usage(&self, billing_period: &str) -> Result<Vec<KeyUsage>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                COALESCE(key_id, provider) as key_id,
+                provider,
+                COUNT(*) as requests,
+                COALESCE(SUM(input_tokens), 0) as input_tokens,
+                COALESCE(SUM(output_tokens), 0) as output_tokens,
+                COALESCE(SUM(CAST(cost AS REAL)), 0.0) as cost
+            FROM usage_records
+            WHERE billing_period = ?
+            GROUP BY COALESCE(key_id, provider), provider
+            ORDER BY cost DESC, key_id
+            "#
+        )
+        .bind(billing_period)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter()
+            .map(|row| KeyUsage {
+                key_id: row.get("key_id"),
+                provider: row.get("provider"),
+                requests: row.get::<i64, _>("requests") as u64,
+                input_tokens: row.get::<i64, _>("input_tokens") as u64,
+                output_tokens: row.get::<i64, _>("output_tokens") as u64,
+                cost: Decimal::try_from(row.get::<f64, _>("cost")).unwrap_or(Decimal::ZERO),
+            })
+            .collect())
+    }
+
     /// Get unverified usage records that need verification
     pub async fn get_unverified_records(&self, limit: Option<i32>) -> Result<Vec<UsageRecord>> {
         debug!("Getting unverified usage records");
@@ -615,7 +757,7 @@ impl UsageRepository {
         let rows = sqlx::query(
             r#"
             SELECT id, timestamp, provider, model, input_tokens, output_tokens, cost,
-                   conversation_id, message_id, request_id, billing_period, verified, verification_timestamp
+                   conversation_id, message_id, request_id, billing_period, verified, verification_timestamp, project, key_id
             FROM usage_records 
             WHERE verified = FALSE
             ORDER BY timestamp ASC
@@ -649,6 +791,8 @@ impl UsageRepository {
                 billing_period: row.get("billing_period"),
                 verified: row.get("verified"),
                 verification_timestamp: None,
+                project: row.get("project"),
+                key_id: row.get("key_id"),
             });
         }
 
@@ -717,6 +861,37 @@ impl UsageRepository {
         Ok(trend_data)
     }
 
+    /// Get per-provider daily cost for the last N days
+    pub async fn get_cost_trend_by_provider(&self, days: u32) -> Result<HashMap<String, Vec<(String, Decimal)>>> {
+        let start_timestamp = (Utc::now() - chrono::Duration::days(days as i64)).timestamp();
+
+        let rows = sqlx::query(
+            r#"
+            SELECT 
+                provider,
+                date(timestamp, 'unixepoch') as date,
+                SUM(CAST(cost AS REAL)) as daily_cost
+            FROM usage_records 
+            WHERE timestamp >= ?
+            GROUP BY provider, date(timestamp, 'unixepoch')
+            ORDER BY date
+            "#
+        )
+        .bind(start_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut trends: HashMap<String, Vec<(String, Decimal)>> = HashMap::new();
+        for row in rows {
+            let daily_cost: f64 = row.get("daily_cost");
+            trends.entry(row.get("provider"))
+                .or_default()
+                .push((row.get("date"), Decimal::from_f64_retain(daily_cost).unwrap_or(Decimal::ZERO)));
+        }
+
+        Ok(trends)
+    }
+
     /// Delete old usage records for cleanup (keeps summaries)
     pub async fn cleanup_old_records(&self, days_to_keep: u32) -> Result<u64> {
         info!("Cleaning up usage records older than {} days", days_to_keep);
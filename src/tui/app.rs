@@ -6,17 +6,24 @@ use ratatui::{
 use rust_decimal::prelude::ToPrimitive;
 use std::sync::Arc;
 use tokio::sync::mpsc;
+use tracing::{debug, warn};
 
 use crate::tui::{
-    commands::{CommandParser, CommandExecutor},
+    commands::{complete_mcp_command, pinned_resource_listener, CommandParser, CommandExecutor, MCPAction, SlashCommand},
     components::{
-        chat_view::{ChatMessage, ChatView, MessageRole},
+        chat_view::{ChatMessage, ChatView, MessageRole, ToolBlock},
         conversation_list::{ConversationItem, ConversationList},
-        Component, HelpPopup, InputBox, StatusBar, CostTracker, status_bar::{KeyHint, ConnectionStatus}
+        BillingDashboardView, Component, DashboardAction, HelpPopup, InputBox, KeyAuditAction, KeyAuditView, SettingsAction, SettingsEditorView, StatusBar, CostTracker,
+        status_bar::{KeyHint, ConnectionStatus}
     },
     Event, Theme,
 };
-use valechat::{app::AppState, chat::{types::{ChatSession, MessageRole as ChatMessageRole}}};
+use valechat::{app::{apply_changes, AppState, RequestCostContext}, chat::{types::{ChatSession, MessageRole as ChatMessageRole}}};
+use valechat::mcp::MCPServerInstance;
+use valechat::mcp::permissions::{ApprovalResponse, ToolApprovalRequest};
+use valechat::storage::{
+    BillingDashboard, BillingPeriod, ExportFormat, KeyAuditRules, DEFAULT_REVIEW_DAYS, SpendingLimitType, UsageRepository,
+};
 
 // Constants for repeated status messages
 const STATUS_TYPE_MESSAGE: &str = "Type your message (Enter to send)";
@@ -44,6 +51,9 @@ pub struct App {
     status_bar: StatusBar,
     help_popup: HelpPopup,
     cost_tracker: CostTracker,
+    dashboard_view: BillingDashboardView,
+    settings_view: SettingsEditorView,
+    key_audit_view: KeyAuditView,
     
     // State
     focused_panel: FocusedPanel,
@@ -53,8 +63,17 @@ pub struct App {
     // Rename mode state
     rename_mode: Option<RenameMode>,
     
+    // Tool calls waiting for the user's approval
+    tool_approvals: mpsc::UnboundedReceiver<ToolApprovalRequest>,
+    pending_approval: Option<ToolApprovalRequest>,
+
+    // Draft cost estimation and the draft waiting for cost confirmation
+    cost_context: Option<RequestCostContext>,
+    pending_cost_confirmation: Option<String>,
+    
     // Backend integration
     app_state: Arc<AppState>,
+    billing_dashboard: BillingDashboard,
     event_sender: mpsc::UnboundedSender<Event>,
     command_executor: CommandExecutor,
     
@@ -79,6 +98,27 @@ impl App {
     ) -> Self {
         let command_executor = CommandExecutor::new(app_state.clone(), event_sender.clone());
         
+        let (approval_sender, tool_approvals) = mpsc::unbounded_channel();
+        app_state.set_tool_approver(approval_sender);
+
+        let (alert_sender, mut alerts) = mpsc::unbounded_channel();
+        app_state.set_alert_listener(alert_sender);
+        let alert_events = event_sender.clone();
+        tokio::spawn(async move {
+            while let Some(alert) = alerts.recv().await {
+                if alert_events.send(Event::CostAlert(alert)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let pool = app_state.get_database().get_pool();
+        let billing_dashboard = BillingDashboard::new(
+            pool.clone(),
+            app_state.get_billing(),
+            Arc::new(UsageRepository::new(pool)),
+        );
+        
         let mut app = Self {
             conversation_list: ConversationList::new(),
             chat_view: ChatView::new(),
@@ -86,11 +126,19 @@ impl App {
             status_bar: StatusBar::new(),
             help_popup: HelpPopup::new(),
             cost_tracker: CostTracker::new(),
+            dashboard_view: BillingDashboardView::new(),
+            settings_view: SettingsEditorView::new(),
+            key_audit_view: KeyAuditView::new(),
             focused_panel: FocusedPanel::ConversationList,
             theme: Theme::dark(),
             should_quit: false,
             rename_mode: None,
+            tool_approvals,
+            pending_approval: None,
+            cost_context: None,
+            pending_cost_confirmation: None,
             app_state,
+            billing_dashboard,
             event_sender,
             command_executor,
             preferred_provider,
@@ -99,6 +147,7 @@ impl App {
 
         // Set initial focus
         app.update_focus();
+        app.apply_currency();
         app
     }
 
@@ -122,6 +171,54 @@ impl App {
     }
 
     pub async fn handle_event(&mut self, event: Event) {
+        if self.pending_approval.is_none() {
+            if let Ok(request) = self.tool_approvals.try_recv() {
+                self.status_bar.set_status(format!(
+                    "Approve tool {}/{}? (a: allow once, A: allow always, d: deny)",
+                    request.server_name, request.tool_name
+                ));
+                self.pending_approval = Some(request);
+            }
+        }
+
+        // A pending tool approval blocks all other input
+        if self.pending_approval.is_some() {
+            if let Event::Key(key) = event {
+                self.handle_approval_keys(key);
+                return;
+            }
+        }
+
+        // The billing dashboard is full screen and takes all keys while open
+        if self.dashboard_view.is_visible() {
+            if let Event::Key(key) = event {
+                let action = self.dashboard_view.handle_key(key);
+                self.handle_dashboard_action(action).await;
+                return;
+            }
+        }
+
+        // So does the settings editor
+        if self.settings_view.is_visible() {
+            if let Event::Key(key) = event {
+                let action = self.settings_view.handle_key(key);
+                self.handle_settings_action(action).await;
+                return;
+            }
+        }
+
+        // And the key audit log
+        if self.key_audit_view.is_visible() {
+            if let Event::Key(key) = event {
+                match self.key_audit_view.handle_key(key) {
+                    KeyAuditAction::None => {}
+                    KeyAuditAction::Close => self.key_audit_view.hide(),
+                    KeyAuditAction::Reload => self.load_key_audit().await,
+                }
+                return;
+            }
+        }
+
         // Help popup takes priority
         if self.help_popup.is_visible() && self.help_popup.handle_event(&event) {
             return;
@@ -138,6 +235,11 @@ impl App {
 
         match event {
             Event::Key(key) => {
+                if key.code == KeyCode::Tab && key.modifiers == KeyModifiers::NONE
+                    && self.focused_panel == FocusedPanel::InputBox
+                    && self.complete_command_input().await {
+                    return;
+                }
                 if self.handle_global_keys(key) {
                     return;
                 }
@@ -146,6 +248,45 @@ impl App {
             Event::SendMessage(content) => {
                 self.send_message(content).await;
             }
+            Event::RefreshCostTracker => {
+                self.update_cost_tracker().await;
+            }
+            Event::OpenBillingDashboard => {
+                self.dashboard_view.show();
+                self.load_billing_dashboard().await;
+            }
+            Event::OpenSettings(section) => {
+                self.settings_view.show(self.app_state.get_config(), section.as_deref());
+            }
+            Event::OpenKeyAudit => {
+                self.key_audit_view.show();
+                self.load_key_audit().await;
+            }
+            Event::McpServerTested(name, result) => {
+                let (message, failed) = match result {
+                    Ok(tools) if tools.is_empty() => (format!("✅ {} answered; it offers no tools", name), false),
+                    Ok(tools) => (format!("✅ {} answered with {} tool(s): {}", name, tools.len(), tools.join(", ")), false),
+                    Err(e) => (format!("❌ {} failed: {}", name, e), true),
+                };
+                if !self.settings_view.is_visible() {
+                    self.status_bar.set_status(message);
+                } else if failed {
+                    self.settings_view.set_error(message);
+                } else {
+                    self.settings_view.set_message(message);
+                }
+            }
+            Event::CostAlert(alert) => {
+                let short_id: String = alert.id.chars().take(8).collect();
+                self.status_bar.set_status(format!(
+                    "🔔 {} (/cost ack {} to dismiss)",
+                    alert.message, short_id
+                ));
+                self.cost_tracker.add_alert(alert);
+            }
+            Event::AlertsAcknowledged(alert_id) => {
+                self.cost_tracker.acknowledge_alert(alert_id.as_deref());
+            }
             Event::MessageReceived(conversation_id, content) => {
                 self.handle_message_received(conversation_id, content).await;
             }
@@ -163,6 +304,7 @@ impl App {
             }
             Event::SetProvider(provider) => {
                 self.set_current_provider(provider);
+                self.refresh_cost_context().await;
             }
             Event::SetModel(model) => {
                 self.set_current_model(model);
@@ -173,6 +315,28 @@ impl App {
             Event::StatusUpdate(status) => {
                 self.status_bar.set_status(status);
             }
+            Event::ConfigReloaded(applied) => {
+                self.apply_currency();
+                self.refresh_model_info();
+                self.refresh_cost_context().await;
+                let summary = applied.summary();
+                self.status_bar.set_status(if summary.is_empty() {
+                    "Configuration reloaded".to_string()
+                } else {
+                    format!("Configuration reloaded; {}", summary)
+                });
+            }
+            Event::ResourceUpdated(uri) => {
+                self.status_bar.set_status(format!("📌 Pinned resource updated: {}", uri));
+            }
+            Event::ToolCallFinished(conversation_id, summary) => {
+                let is_current = self.conversation_list.get_selected_conversation()
+                    .is_some_and(|c| c.id == conversation_id);
+                if is_current {
+                    self.load_conversation(conversation_id).await;
+                }
+                self.status_bar.set_status(summary);
+            }
             Event::Quit => {
                 self.should_quit = true;
             }
@@ -180,6 +344,25 @@ impl App {
         }
     }
 
+    fn handle_approval_keys(&mut self, key: KeyEvent) {
+        let response = match key.code {
+            KeyCode::Char('a') | KeyCode::Char('y') => ApprovalResponse::AllowOnce,
+            KeyCode::Char('A') => ApprovalResponse::AllowAlways,
+            KeyCode::Char('d') | KeyCode::Char('n') | KeyCode::Esc => ApprovalResponse::Deny,
+            _ => return,
+        };
+
+        if let Some(request) = self.pending_approval.take() {
+            let verb = match response {
+                ApprovalResponse::AllowOnce => "Allowed once",
+                ApprovalResponse::AllowAlways => "Always allowing",
+                ApprovalResponse::Deny => "Denied",
+            };
+            self.status_bar.set_status(format!("{} tool {}/{}", verb, request.server_name, request.tool_name));
+            let _ = request.responder.send(response);
+        }
+    }
+
     fn handle_global_keys(&mut self, key: KeyEvent) -> bool {
         match (key.code, key.modifiers) {
             (KeyCode::Char('c'), KeyModifiers::CONTROL) |
@@ -197,6 +380,21 @@ impl App {
             }
             (KeyCode::Char('m'), KeyModifiers::CONTROL) => {
                 self.cost_tracker.toggle_details();
+                if self.cost_tracker.show_details {
+                    let _ = self.event_sender.send(Event::RefreshCostTracker);
+                }
+                true
+            }
+            (KeyCode::F(2), _) | (KeyCode::Char('b'), KeyModifiers::CONTROL) => {
+                let _ = self.event_sender.send(Event::OpenBillingDashboard);
+                true
+            }
+            (KeyCode::F(3), _) | (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
+                let _ = self.event_sender.send(Event::OpenSettings(None));
+                true
+            }
+            (KeyCode::F(4), _) => {
+                let _ = self.event_sender.send(Event::OpenKeyAudit);
                 true
             }
             (KeyCode::Tab, KeyModifiers::NONE) => {
@@ -233,7 +431,12 @@ impl App {
                 self.set_focused_panel(FocusedPanel::InputBox);
                 true
             }
-            // Escape to return to conversation list
+            // Escape cancels a pending cost confirmation, otherwise returns to conversation list
+            (KeyCode::Esc, KeyModifiers::NONE) if self.pending_cost_confirmation.is_some() => {
+                self.pending_cost_confirmation = None;
+                self.status_bar.set_status("Send cancelled".to_string());
+                true
+            }
             (KeyCode::Esc, KeyModifiers::NONE) => {
                 self.set_focused_panel(FocusedPanel::ConversationList);
                 true
@@ -263,6 +466,11 @@ impl App {
                 if self.focused_panel == FocusedPanel::InputBox {
                     self.status_bar.set_status(STATUS_TYPE_MESSAGE.to_string());
                 }
+                if result {
+                    // Editing the draft withdraws a pending cost confirmation
+                    self.pending_cost_confirmation = None;
+                    self.update_cost_estimate();
+                }
                 
                 result
             }
@@ -301,7 +509,17 @@ impl App {
                 if key.code == KeyCode::Enter && !key.modifiers.contains(KeyModifiers::SHIFT) {
                     let content = self.input_box.get_content();
                     if !content.trim().is_empty() {
+                        let confirmed = self.pending_cost_confirmation.take().as_ref() == Some(&content);
+                        if !confirmed {
+                            if let Some(status) = self.cost_confirmation_prompt(&content) {
+                                self.pending_cost_confirmation = Some(content);
+                                self.status_bar.set_status(status);
+                                return;
+                            }
+                        }
+
                         self.input_box.clear();
+                        self.update_cost_estimate();
                         // Show "Sending..." since user is actively sending from input box
                         self.status_bar.set_status(STATUS_SENDING.to_string());
                         let _ = self.event_sender.send(Event::SendMessage(content));
@@ -368,6 +586,7 @@ impl App {
                 KeyHint::new("↑/↓", "Scroll"),
                 KeyHint::new("PgUp/PgDn", "Page"),
                 KeyHint::new("Home/End", "Top/Bottom"),
+                KeyHint::new("t/Enter", "Tool calls"),
                 KeyHint::new("Ctrl/Alt+1/2/3", "Panels"),
             ],
             FocusedPanel::InputBox => vec![
@@ -381,7 +600,20 @@ impl App {
     }
 
     /// Update cost tracker with current usage data
+    /// Push the configured display currency to every component that shows costs
+    fn apply_currency(&mut self) {
+        let currency = self.app_state.get_config().currency_formatter();
+        self.conversation_list.set_currency(currency.clone());
+        self.chat_view.set_currency(currency.clone());
+        self.status_bar.set_currency(currency.clone());
+        self.cost_tracker.set_currency(currency.clone());
+        self.dashboard_view.set_currency(currency);
+    }
+
     pub async fn update_cost_tracker(&mut self) {
+        // Picks up display currency changes made since the last refresh
+        self.apply_currency();
+
         // Get usage statistics
         if let Ok(stats) = self.app_state.get_usage_repo().get_usage_statistics().await {
             self.cost_tracker.update_stats(stats);
@@ -398,12 +630,152 @@ impl App {
             }
         }
 
+        // Global limits drive the spending gauges
+        let billing = self.app_state.get_billing();
+        match (
+            billing.get_spending_limit(&SpendingLimitType::Global, &BillingPeriod::Daily).await,
+            billing.get_spending_limit(&SpendingLimitType::Global, &BillingPeriod::Monthly).await,
+        ) {
+            (Ok(daily), Ok(monthly)) => self.cost_tracker.update_limits(
+                daily.filter(|limit| limit.enabled).map(|limit| limit.amount),
+                monthly.filter(|limit| limit.enabled).map(|limit| limit.amount),
+            ),
+            (Err(e), _) | (_, Err(e)) => warn!("Failed to load spending limits: {}", e),
+        }
+
+        match self.app_state.forecast_spending().await {
+            Ok(forecast) => self.cost_tracker.update_forecast(forecast),
+            Err(e) => warn!("Failed to forecast spending: {}", e),
+        }
+
+        // Analytics for the detailed view
+        match (
+            self.billing_dashboard.get_daily_trend(30).await,
+            self.billing_dashboard.get_usage_patterns(30).await,
+        ) {
+            (Ok(trend), Ok(patterns)) => self.cost_tracker.update_analytics(trend, patterns),
+            (Err(e), _) | (_, Err(e)) => warn!("Failed to load billing analytics: {}", e),
+        }
+
         // Update status bar with cost summary
         let cost_summary = self.cost_tracker.get_status_summary();
         // This would need a method on StatusBar to accept cost info
         // For now, we'll just keep the cost tracker updated
     }
 
+    /// Load dashboard data for the selected period, keeping any model drill-down
+    async fn load_billing_dashboard(&mut self) {
+        let period = self.dashboard_view.period().to_string();
+        let data = self.billing_dashboard.get_dashboard_data().await;
+        let report = self.billing_dashboard.get_period_report(&period).await;
+
+        match (data, report) {
+            (Ok(data), Ok(report)) => self.dashboard_view.set_data(data, report),
+            (Err(e), _) | (_, Err(e)) => {
+                self.dashboard_view.set_message(format!("❌ Failed to load billing data: {}", e));
+                return;
+            }
+        }
+
+        if let Some(model) = self.dashboard_view.drill_down_model().map(str::to_string) {
+            self.drill_down_model(model).await;
+        }
+    }
+
+    /// Load the last 30 days of the key audit log and its anomalies
+    async fn load_key_audit(&mut self) {
+        // Same period as `valechat audit keys`, so both flag the same anomalies; only the log is shortened
+        match self.app_state.get_key_audit().review(None, DEFAULT_REVIEW_DAYS, &KeyAuditRules::default()).await {
+            Ok((mut entries, anomalies)) => {
+                entries.truncate(500);
+                self.key_audit_view.set_data(entries, anomalies);
+            }
+            Err(e) => self.key_audit_view.set_message(format!("❌ Failed to load the key audit log: {}", e)),
+        }
+    }
+
+    async fn drill_down_model(&mut self, model: String) {
+        let period = self.dashboard_view.period().to_string();
+        match self.billing_dashboard.get_conversation_breakdown(&period, Some(&model), 20).await {
+            Ok(conversations) => self.dashboard_view.set_drill_down(model, conversations),
+            Err(e) => self.dashboard_view.set_message(format!("❌ Failed to load conversations: {}", e)),
+        }
+    }
+
+    async fn handle_dashboard_action(&mut self, action: DashboardAction) {
+        match action {
+            DashboardAction::None => {}
+            DashboardAction::Close => self.dashboard_view.hide(),
+            DashboardAction::Reload => self.load_billing_dashboard().await,
+            DashboardAction::DrillDown(model) => self.drill_down_model(model).await,
+            DashboardAction::OpenConversation(conversation_id) => {
+                self.dashboard_view.hide();
+                self.conversation_list.select_conversation(&conversation_id);
+                self.load_conversation(conversation_id).await;
+                self.set_focused_panel(FocusedPanel::ChatView);
+            }
+            DashboardAction::Export(format) => {
+                let period = self.dashboard_view.period().to_string();
+                let extension = match format {
+                    ExportFormat::Csv => "csv",
+                    ExportFormat::Json => "json",
+                };
+                let path = self.app_state.get_paths().data_dir()
+                    .join("exports")
+                    .join(format!("billing-{}.{}", period, extension));
+
+                let result = match self.billing_dashboard.export_report(format, Some(period)).await {
+                    Ok(content) => path.parent()
+                        .map_or(Ok(()), std::fs::create_dir_all)
+                        .and_then(|_| std::fs::write(&path, content))
+                        .map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+
+                self.dashboard_view.set_message(match result {
+                    Ok(()) => format!("✅ Exported to {}", path.display()),
+                    Err(e) => format!("❌ Export failed: {}", e),
+                });
+            }
+        }
+    }
+
+    async fn handle_settings_action(&mut self, action: SettingsAction) {
+        match action {
+            SettingsAction::None => {}
+            SettingsAction::Close => self.settings_view.hide(),
+            SettingsAction::Save(changes) => {
+                let result = self.app_state.try_update_config(move |config| {
+                    *config = apply_changes(config, &changes)?;
+                    Ok(())
+                }).await;
+                match result {
+                    Ok(applied) => {
+                        // Changed MCP servers were started, restarted or stopped while saving
+                        self.settings_view.saved(self.app_state.get_config());
+                        let summary = applied.summary();
+                        if !summary.is_empty() {
+                            self.settings_view.set_message(format!("Settings saved; {}", summary));
+                        }
+                        self.apply_currency();
+                        self.refresh_model_info();
+                        self.refresh_cost_context().await;
+                    }
+                    Err(e) => self.settings_view.set_error(format!("❌ Not saved: {}", e)),
+                }
+            }
+            SettingsAction::TestMcpServer(name, config) => {
+                let events = self.event_sender.clone();
+                tokio::spawn(async move {
+                    let result = MCPServerInstance::test_connection(&name, config).await
+                        .map(|tools| tools.into_iter().map(|tool| tool.name).collect())
+                        .map_err(|e| e.to_string());
+                    let _ = events.send(Event::McpServerTested(name, result));
+                });
+            }
+        }
+    }
+
     fn update_status_for_focused_panel(&mut self) {
         match self.focused_panel {
             FocusedPanel::ConversationList => {
@@ -462,10 +834,10 @@ impl App {
                     Ok(messages) => {
                         let message_count = messages.len();
                         for message in messages {
-                            let role = if message.role == ChatMessageRole::User { 
-                                MessageRole::User 
-                            } else { 
-                                MessageRole::Assistant 
+                            let role = match message.role {
+                                ChatMessageRole::User => MessageRole::User,
+                                ChatMessageRole::Tool => MessageRole::Tool,
+                                _ => MessageRole::Assistant,
                             };
                             
                             let content = if let Some(text) = message.content.get_text() {
@@ -473,6 +845,7 @@ impl App {
                             } else {
                                 "[Non-text content]".to_string()
                             };
+                            let tool = message.tool_invocations.first().map(ToolBlock::from);
                             
                             let chat_message = ChatMessage {
                                 id: message.id,
@@ -483,10 +856,12 @@ impl App {
                                 input_tokens: None, // Will be populated from database if available  
                                 output_tokens: None, // Will be populated from database if available  
                                 model_used: None, // Will be populated from database if available
+                                tool,
                             };
                             self.chat_view.add_message(chat_message);
                         }
                         self.status_bar.set_status(format!("Loaded {} messages", message_count));
+                        self.refresh_cost_context().await;
                     }
                     Err(e) => {
                         self.status_bar.set_status(format!("Error loading messages: {}", e));
@@ -540,6 +915,7 @@ impl App {
                 self.chat_view.set_conversation_title(new_session.title);
                 
                 self.status_bar.set_status("New conversation created".to_string());
+                self.refresh_cost_context().await;
             }
             Err(e) => {
                 self.status_bar.set_status(format!("Error creating conversation: {}", e));
@@ -559,6 +935,52 @@ impl App {
         }
     }
 
+    /// Reload what the draft cost estimate is based on for the selected conversation
+    async fn refresh_cost_context(&mut self) {
+        self.cost_context = match self.conversation_list.get_selected_conversation() {
+            Some(conversation) => {
+                match self.app_state.request_cost_context(&conversation.id, self.preferred_provider.as_deref()).await {
+                    Ok(context) => Some(context),
+                    Err(e) => {
+                        warn!("Failed to load cost context: {}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        self.update_cost_estimate();
+    }
+
+    /// Recompute the estimate shown in the input box border for the current draft
+    fn update_cost_estimate(&mut self) {
+        let content = self.input_box.get_content();
+        let estimate = match &self.cost_context {
+            Some(context) if !content.trim().is_empty() && !content.starts_with('/') => {
+                let cost = self.app_state.estimate_request_cost(context, content.len());
+                let over_threshold = context.confirm_above.is_some_and(|threshold| cost > threshold);
+                Some((self.app_state.get_config().currency_formatter().format_precise(cost), over_threshold))
+            }
+            _ => None,
+        };
+        self.input_box.set_cost_estimate(estimate);
+    }
+
+    /// Status asking for confirmation when the draft is estimated above the threshold
+    fn cost_confirmation_prompt(&self, content: &str) -> Option<String> {
+        if content.starts_with('/') {
+            return None;
+        }
+        let context = self.cost_context.as_ref()?;
+        let threshold = context.confirm_above?;
+        let cost = self.app_state.estimate_request_cost(context, content.len());
+        let money = self.app_state.get_config().currency_formatter();
+        (cost > threshold).then(|| format!(
+            "⚠ Estimated {} on {}/{} exceeds {} (Enter to send anyway, Esc to cancel)",
+            money.format_precise(cost), context.provider, context.model, money.format(threshold)
+        ))
+    }
+
     async fn send_message(&mut self, content: String) {
         // Check if this is a slash command
         if let Some(command) = CommandParser::parse(&content) {
@@ -588,6 +1010,7 @@ impl App {
                 input_tokens: Some(0),
                 output_tokens: Some(0),
                 model_used: Some("user".to_string()),
+                tool: None,
             };
             self.chat_view.add_message(user_message);
             
@@ -597,11 +1020,14 @@ impl App {
             }
             
             // Send message through provider
-            match self.app_state.send_message_with_provider(
+            let result = self.app_state.send_message_with_provider(
                 &current_conversation.id, 
                 &content,
-                self.preferred_provider.as_deref()
-            ).await {
+                self.preferred_provider.as_deref(),
+            ).await;
+            self.refresh_cost_context().await;
+
+            match result {
                 Ok(response) => {
                     // Add assistant response to chat view
                     let assistant_message = ChatMessage {
@@ -613,6 +1039,7 @@ impl App {
                         input_tokens: Some(0),
                         output_tokens: Some(0),
                         model_used: Some("assistant".to_string()),
+                        tool: None,
                     };
                     self.chat_view.add_message(assistant_message);
                     
@@ -640,7 +1067,54 @@ impl App {
         }
     }
 
-    async fn execute_slash_command(&mut self, command: crate::tui::commands::SlashCommand, original_input: String) {
+    /// Tab-complete `/mcp` resource URIs and prompt arguments; returns false if nothing applies
+    async fn complete_command_input(&mut self) -> bool {
+        let content = self.input_box.get_content();
+        if !content.starts_with("/mcp ") {
+            return false;
+        }
+
+        let resource_uris = self.app_state.list_known_resource_uris().await;
+        let prompts = self.app_state.list_known_prompts().await;
+        match complete_mcp_command(&content, &resource_uris, &prompts) {
+            Some(completion) => {
+                self.input_box.set_content(completion.input);
+                if completion.candidates.len() > 1 {
+                    self.status_bar.set_status(completion.candidates.join("  "));
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    async fn execute_slash_command(&mut self, command: SlashCommand, original_input: String) {
+        // Attaching content inserts messages, so the conversation is reloaded before echoing the command
+        let inserts_messages = matches!(
+            command,
+            SlashCommand::Mcp { action: MCPAction::Attach(_) | MCPAction::Prompt { .. } }
+        );
+
+        // Show executing status
+        if self.focused_panel == FocusedPanel::InputBox {
+            self.status_bar.set_status(STATUS_COMMAND_EXECUTING.to_string());
+        }
+
+        // Execute the command
+        let current_conversation = self.conversation_list.get_selected_conversation().map(|c| c.id.clone());
+        let response = self.command_executor.execute_with_context(
+            command, 
+            self.preferred_provider.as_ref(),
+            self.preferred_model.as_ref(),
+            current_conversation.as_ref(),
+        ).await;
+
+        if inserts_messages {
+            if let Some(conversation_id) = current_conversation {
+                self.load_conversation(conversation_id).await;
+            }
+        }
+
         // Add the command to chat as a user message
         let user_message = ChatMessage {
             id: uuid::Uuid::new_v4().to_string(),
@@ -651,21 +1125,10 @@ impl App {
             input_tokens: Some(0),
             output_tokens: Some(0),
             model_used: Some("command".to_string()),
+            tool: None,
         };
         self.chat_view.add_message(user_message);
 
-        // Show executing status
-        if self.focused_panel == FocusedPanel::InputBox {
-            self.status_bar.set_status(STATUS_COMMAND_EXECUTING.to_string());
-        }
-
-        // Execute the command
-        let response = self.command_executor.execute_with_context(
-            command, 
-            self.preferred_provider.as_ref(),
-            self.preferred_model.as_ref()
-        ).await;
-
         // Add the command response to chat
         let response_message = ChatMessage {
             id: uuid::Uuid::new_v4().to_string(),
@@ -676,6 +1139,7 @@ impl App {
             input_tokens: Some(0),
             output_tokens: Some(0),
             model_used: Some("system".to_string()),
+            tool: None,
         };
         self.chat_view.add_message(response_message);
 
@@ -696,6 +1160,7 @@ impl App {
             input_tokens: None,
             output_tokens: None,
             model_used: None,
+            tool: None,
         };
         
         // Only add if this is the currently selected conversation
@@ -798,10 +1263,8 @@ impl App {
         self.load_conversations().await;
     }
 
-    pub async fn initialize(&mut self) {
-        self.load_conversations().await;
-        
-        // Set initial model info and connection status
+    /// Show the provider and model new messages go to, after startup or a configuration change
+    fn refresh_model_info(&mut self) {
         let (provider, model) = if let Some(ref preferred_provider) = self.preferred_provider {
             // Use CLI-specified provider if available
             let model = if let Some(ref preferred_model) = self.preferred_model {
@@ -823,8 +1286,78 @@ impl App {
         };
         
         self.status_bar.set_model_info(&provider, &model);
+    }
+
+    pub async fn initialize(&mut self) {
+        self.load_conversations().await;
+        
+        // Set initial model info and connection status
+        self.refresh_model_info();
         self.status_bar.set_connection_status(ConnectionStatus::Disconnected);
 
+        // Retention rules are applied once per session
+        let retention = self.app_state.get_config().retention;
+        if retention.enforce_on_startup && !retention.rules.is_empty() {
+            let retention = self.app_state.get_retention();
+            tokio::spawn(async move {
+                if let Err(e) = retention.purge(false).await {
+                    warn!("Failed to apply retention rules: {}", e);
+                }
+            });
+        }
+
+        // Scheduled backups run for as long as the interface is open
+        self.app_state.start_backup_schedule().await;
+
+        // Resources pinned in earlier sessions keep refreshing when their server reports changes
+        let app_state = self.app_state.clone();
+        let events = self.event_sender.clone();
+        tokio::spawn(async move {
+            match app_state.restore_pinned_resources(pinned_resource_listener(events.clone())).await {
+                Ok(failed) if !failed.is_empty() => {
+                    let _ = events.send(Event::StatusUpdate(format!("Pinned resources unavailable: {}", failed.join(", "))));
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Failed to restore pinned resources: {}", e),
+            }
+        });
+
+        // Keys past their rotation date are pointed out once per session
+        let app_state = self.app_state.clone();
+        let events = self.event_sender.clone();
+        tokio::spawn(async move {
+            match app_state.keys_due_for_rotation().await {
+                Ok(keys) if !keys.is_empty() => {
+                    let ids: Vec<&str> = keys.iter().map(|key| key.id.as_str()).collect();
+                    let _ = events.send(Event::StatusUpdate(format!(
+                        "🔑 {} API key(s) due for rotation: {}; see /apikey list", ids.len(), ids.join(", ")
+                    )));
+                }
+                Ok(_) => {}
+                Err(e) => debug!("Could not check API key rotation: {}", e),
+            }
+        });
+
+        // Edits to the configuration files are picked up without a restart
+        let app_state = self.app_state.clone();
+        let events = self.event_sender.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(2));
+            loop {
+                interval.tick().await;
+                if !app_state.config_files_changed() {
+                    continue;
+                }
+                let event = match app_state.reload_config().await {
+                    Ok(applied) => Event::ConfigReloaded(applied),
+                    Err(e) => Event::Error(format!("Configuration not reloaded: {}", e)),
+                };
+                if events.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
         self.status_bar.set_status("ValeChat initialized".to_string());
     }
 
@@ -835,7 +1368,7 @@ impl App {
         let main_constraints = if show_cost_details {
             vec![
                 Constraint::Min(1),      // Main content
-                Constraint::Length(8),   // Cost tracker details
+                Constraint::Length(26),  // Cost tracker details
                 Constraint::Length(1),   // Status bar
             ]
         } else {
@@ -887,10 +1420,78 @@ impl App {
             self.render_rename_dialog(frame, main_chunks[0]);
         }
 
+        if self.dashboard_view.is_visible() {
+            self.dashboard_view.render(frame, frame.size(), &self.theme);
+        }
+
+        if self.settings_view.is_visible() {
+            self.settings_view.render(frame, frame.size(), &self.theme);
+        }
+
+        if self.key_audit_view.is_visible() {
+            self.key_audit_view.render(frame, frame.size(), &self.theme);
+        }
+
+        if self.pending_approval.is_some() {
+            self.render_approval_dialog(frame, frame.size());
+        }
+
         // Render help popup last (on top)
         self.help_popup.render(frame, frame.size(), &self.theme);
     }
 
+    fn render_approval_dialog(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+        use ratatui::text::{Line, Span};
+
+        let Some(request) = &self.pending_approval else {
+            return;
+        };
+
+        let arguments = serde_json::to_string_pretty(&request.arguments)
+            .unwrap_or_else(|_| request.arguments.to_string());
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Server: ", self.theme.secondary()),
+                Span::styled(request.server_name.clone(), self.theme.normal()),
+            ]),
+            Line::from(vec![
+                Span::styled("Tool:   ", self.theme.secondary()),
+                Span::styled(request.tool_name.clone(), self.theme.normal()),
+            ]),
+            Line::from(Span::styled("Arguments:", self.theme.secondary())),
+        ];
+        lines.extend(arguments.lines().map(|line| Line::from(Span::styled(line.to_string(), self.theme.normal()))));
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "[a] Allow once   [A] Allow always   [d] Deny",
+            self.theme.accent(),
+        )));
+
+        let width = (area.width * 7 / 10).max(40).min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let dialog_area = ratatui::layout::Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        frame.render_widget(Clear, dialog_area);
+
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Allow Tool Call? ")
+                    .border_style(self.theme.accent()),
+            )
+            .wrap(Wrap { trim: false });
+
+        frame.render_widget(paragraph, dialog_area);
+    }
+
     fn render_rename_dialog(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
         use ratatui::widgets::{Block, Borders, Clear, Paragraph};
         use ratatui::layout::Margin;
@@ -1,9 +1,13 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::debug;
 use rust_decimal::prelude::ToPrimitive;
 use tokio::sync::mpsc;
 
 use valechat::app::AppState;
+use valechat::billing::CurrencyFormatter;
+use valechat::mcp::resources::ResourceUpdateEvent;
+use valechat::storage::{AlertSeverity, BackupType, BillingPeriod, KeyScope, SpendingLimit, SpendingLimitType, ToolInvocationFilter, ToolInvocationStatus};
 use crate::tui::Event;
 
 #[derive(Debug, Clone)]
@@ -35,6 +39,18 @@ pub enum SlashCommand {
     Mcp {
         action: MCPAction,
     },
+    Project {
+        action: ProjectAction,
+    },
+    Backup {
+        action: BackupAction,
+    },
+    /// Open the settings editor, optionally at a section
+    Settings {
+        section: Option<String>,
+    },
+    /// Open the API key audit log
+    Audit,
     Quit,
     Help,
     Unknown(String),
@@ -59,6 +75,15 @@ pub enum ApiKeyAction {
     Status,
     Set(String),
     Remove,
+    /// Stored keys with their rotation dates, optionally for one provider
+    List,
+    Validate,
+    /// Remind to rotate the key every this many days
+    Rotate(u32),
+    /// Use the key in the current conversation, or in its project
+    Use { project: bool },
+    /// Go back to the project's key or the default key
+    Reset { project: bool },
 }
 
 #[derive(Debug, Clone)]
@@ -68,16 +93,36 @@ pub enum CostAction {
     Week,
     Month,
     Breakdown,
+    /// This month's spend per API key
+    Keys,
     Alerts,
+    /// Acknowledge an alert by id prefix, or `all`
+    Acknowledge(String),
+    Dashboard,
+}
+
+#[derive(Debug, Clone)]
+pub enum ProjectAction {
+    Show,
+    List,
+    Use(String),
+    Clear,
+}
+
+#[derive(Debug, Clone)]
+pub enum BackupAction {
+    List,
+    Create,
+    Verify(String),
 }
 
 #[derive(Debug, Clone)]
 pub enum BudgetAction {
     Show,
-    SetDaily(String),
-    SetMonthly(String),
-    SetProvider { provider: String, limit: String },
+    Set { scope: SpendingLimitType, period: BillingPeriod, amount: String },
+    Remove { scope: SpendingLimitType, period: BillingPeriod },
     Alerts,
+    Resume,
 }
 
 #[derive(Debug, Clone)]
@@ -89,6 +134,25 @@ pub enum MCPAction {
     Tools { server: Option<String> },
     Resources { server: Option<String> },
     Prompts { server: Option<String> },
+    Pin(String),
+    Unpin(String),
+    Pinned,
+    Attach(String),
+    Prompt {
+        server: String,
+        name: String,
+        arguments: HashMap<String, String>,
+    },
+    Call {
+        server: String,
+        tool: String,
+        arguments: serde_json::Value,
+    },
+    Permissions,
+    History {
+        filter: ToolInvocationFilter,
+        current_conversation_only: bool,
+    },
 }
 
 pub struct CommandParser;
@@ -118,6 +182,10 @@ impl CommandParser {
             "cost" => parse_cost_command(args),
             "budget" => parse_budget_command(args),
             "mcp" => parse_mcp_command(args),
+            "project" => parse_project_command(args),
+            "backup" => parse_backup_command(args),
+            "settings" | "config" => Some(SlashCommand::Settings { section: args.first().map(|s| s.to_string()) }),
+            "audit" => Some(SlashCommand::Audit),
             "quit" | "exit" => Some(SlashCommand::Quit),
             "help" => Some(SlashCommand::Help),
             _ => Some(SlashCommand::Unknown(parts[0].to_string())), // Use original case for error message
@@ -130,7 +198,15 @@ fn parse_apikey_command(args: &[&str]) -> Option<SlashCommand> {
         return Some(SlashCommand::Unknown("apikey".to_string()));
     }
 
+    if args[0].eq_ignore_ascii_case("list") {
+        return Some(SlashCommand::ApiKey {
+            provider: args.get(1).map(|p| p.to_string()).unwrap_or_default(),
+            action: ApiKeyAction::List,
+        });
+    }
+
     let provider = args[0].to_string();
+    let project = args.get(2).is_some_and(|scope| scope.eq_ignore_ascii_case("project"));
     
     if args.len() == 1 {
         // Just provider name - show status
@@ -159,6 +235,22 @@ fn parse_apikey_command(args: &[&str]) -> Option<SlashCommand> {
             provider,
             action: ApiKeyAction::Remove,
         }),
+        "validate" => Some(SlashCommand::ApiKey {
+            provider,
+            action: ApiKeyAction::Validate,
+        }),
+        "rotate" => match args.get(2).and_then(|days| days.parse().ok()) {
+            Some(days) => Some(SlashCommand::ApiKey { provider, action: ApiKeyAction::Rotate(days) }),
+            None => Some(SlashCommand::Unknown("apikey rotate requires a number of days".to_string())),
+        },
+        "use" => Some(SlashCommand::ApiKey {
+            provider,
+            action: ApiKeyAction::Use { project },
+        }),
+        "reset" => Some(SlashCommand::ApiKey {
+            provider,
+            action: ApiKeyAction::Reset { project },
+        }),
         _ => Some(SlashCommand::Unknown("apikey".to_string())),
     }
 }
@@ -286,9 +378,18 @@ fn parse_cost_command(args: &[&str]) -> Option<SlashCommand> {
         "breakdown" | "by-provider" => Some(SlashCommand::Cost {
             action: CostAction::Breakdown,
         }),
+        "keys" | "by-key" => Some(SlashCommand::Cost {
+            action: CostAction::Keys,
+        }),
         "alerts" => Some(SlashCommand::Cost {
             action: CostAction::Alerts,
         }),
+        "ack" | "acknowledge" => Some(SlashCommand::Cost {
+            action: args.get(1).map_or(CostAction::Alerts, |target| CostAction::Acknowledge(target.to_string())),
+        }),
+        "dashboard" => Some(SlashCommand::Cost {
+            action: CostAction::Dashboard,
+        }),
         _ => Some(SlashCommand::Cost {
             action: CostAction::Show,
         }),
@@ -296,34 +397,102 @@ fn parse_cost_command(args: &[&str]) -> Option<SlashCommand> {
 }
 
 fn parse_budget_command(args: &[&str]) -> Option<SlashCommand> {
+    let show = Some(SlashCommand::Budget { action: BudgetAction::Show });
     if args.is_empty() {
-        return Some(SlashCommand::Budget {
-            action: BudgetAction::Show,
-        });
+        return show;
     }
 
     match args[0].to_lowercase().as_str() {
-        "daily" if args.len() >= 2 => Some(SlashCommand::Budget {
-            action: BudgetAction::SetDaily(args[1].to_string()),
-        }),
-        "monthly" if args.len() >= 2 => Some(SlashCommand::Budget {
-            action: BudgetAction::SetMonthly(args[1].to_string()),
-        }),
-        "provider" if args.len() >= 3 => Some(SlashCommand::Budget {
-            action: BudgetAction::SetProvider {
-                provider: args[1].to_string(),
-                limit: args[2].to_string(),
-            },
-        }),
         "alerts" => Some(SlashCommand::Budget {
             action: BudgetAction::Alerts,
         }),
-        _ => Some(SlashCommand::Budget {
-            action: BudgetAction::Show,
+        "resume" => Some(SlashCommand::Budget {
+            action: BudgetAction::Resume,
         }),
+        "remove" if args.len() >= 2 => {
+            let period = parse_budget_period(args[1])?;
+            let scope = parse_budget_scope(&args[2..]).map(|(scope, _)| scope)?;
+            Some(SlashCommand::Budget {
+                action: BudgetAction::Remove { scope, period },
+            })
+        }
+        "provider" | "model" | "project" => {
+            let (scope, rest) = parse_budget_scope(args)?;
+            let amount = rest.first()?.to_string();
+            let period = match rest.get(1) {
+                Some(period) => parse_budget_period(period)?,
+                None => BillingPeriod::Monthly,
+            };
+            Some(SlashCommand::Budget {
+                action: BudgetAction::Set { scope, period, amount },
+            })
+        }
+        period if args.len() >= 2 => match parse_budget_period(period) {
+            Some(period) => Some(SlashCommand::Budget {
+                action: BudgetAction::Set {
+                    scope: SpendingLimitType::Global,
+                    period,
+                    amount: args[1].to_string(),
+                },
+            }),
+            None => show,
+        },
+        _ => show,
+    }
+}
+
+fn parse_backup_command(args: &[&str]) -> Option<SlashCommand> {
+    let action = match args.first().map(|a| a.to_lowercase()).as_deref() {
+        None | Some("list") => BackupAction::List,
+        Some("create") => BackupAction::Create,
+        Some("verify") => BackupAction::Verify(args.get(1)?.to_string()),
+        _ => BackupAction::List,
+    };
+    Some(SlashCommand::Backup { action })
+}
+
+/// Parse an optional `provider <name>` / `model <name>` prefix, defaulting to the global scope
+fn parse_budget_scope<'a>(args: &'a [&'a str]) -> Option<(SpendingLimitType, &'a [&'a str])> {
+    match args.first().map(|arg| arg.to_lowercase()).as_deref() {
+        Some("provider") => Some((SpendingLimitType::PerProvider(args.get(1)?.to_string()), &args[2..])),
+        Some("model") => Some((SpendingLimitType::PerModel(args.get(1)?.to_string()), &args[2..])),
+        Some("project") => Some((SpendingLimitType::PerProject(args.get(1)?.to_string()), &args[2..])),
+        Some("global") => Some((SpendingLimitType::Global, &args[1..])),
+        None => Some((SpendingLimitType::Global, args)),
+        Some(_) => None,
     }
 }
 
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn parse_budget_period(period: &str) -> Option<BillingPeriod> {
+    match period.to_lowercase().as_str() {
+        "daily" | "day" => Some(BillingPeriod::Daily),
+        "weekly" | "week" => Some(BillingPeriod::Weekly),
+        "monthly" | "month" => Some(BillingPeriod::Monthly),
+        "yearly" | "year" => Some(BillingPeriod::Yearly),
+        "request" | "per-request" => Some(BillingPeriod::PerRequest),
+        _ => None,
+    }
+}
+
+fn parse_project_command(args: &[&str]) -> Option<SlashCommand> {
+    let action = match args.first().map(|arg| arg.to_lowercase()).as_deref() {
+        None => ProjectAction::Show,
+        Some("list") => ProjectAction::List,
+        Some("use") if args.len() >= 2 => ProjectAction::Use(args[1..].join(" ")),
+        Some("clear") | Some("none") => ProjectAction::Clear,
+        Some(_) => return Some(SlashCommand::Unknown(format!("project {}", args.join(" ")))),
+    };
+    Some(SlashCommand::Project { action })
+}
+
 fn parse_mcp_command(args: &[&str]) -> Option<SlashCommand> {
     if args.is_empty() {
         return Some(SlashCommand::Mcp {
@@ -374,12 +543,188 @@ fn parse_mcp_command(args: &[&str]) -> Option<SlashCommand> {
                 action: MCPAction::Prompts { server },
             })
         }
+        "pin" if args.len() >= 2 => Some(SlashCommand::Mcp {
+            action: MCPAction::Pin(args[1].to_string()),
+        }),
+        "unpin" if args.len() >= 2 => Some(SlashCommand::Mcp {
+            action: MCPAction::Unpin(args[1].to_string()),
+        }),
+        "pinned" => Some(SlashCommand::Mcp {
+            action: MCPAction::Pinned,
+        }),
+        "attach" if args.len() >= 2 => Some(SlashCommand::Mcp {
+            action: MCPAction::Attach(args[1].to_string()),
+        }),
+        "prompt" if args.len() >= 2 => parse_mcp_prompt_command(&args[1..]),
+        "call" if args.len() >= 2 => parse_mcp_call_command(&args[1..]),
+        "permissions" => Some(SlashCommand::Mcp {
+            action: MCPAction::Permissions,
+        }),
+        "history" => parse_mcp_history_command(&args[1..]),
         _ => Some(SlashCommand::Mcp {
             action: MCPAction::List,
         }),
     }
 }
 
+/// Parse `<server>/<name> key=value...`; words without `=` extend the previous value
+fn parse_mcp_prompt_command(args: &[&str]) -> Option<SlashCommand> {
+    let (server, name) = match args[0].split_once('/') {
+        Some((server, name)) if !server.is_empty() && !name.is_empty() => (server, name),
+        _ => return Some(SlashCommand::Unknown("mcp prompt".to_string())),
+    };
+
+    let mut arguments = HashMap::new();
+    let mut last_key: Option<String> = None;
+    for arg in &args[1..] {
+        if let Some((key, value)) = arg.split_once('=') {
+            arguments.insert(key.to_string(), value.to_string());
+            last_key = Some(key.to_string());
+        } else if let Some(value) = last_key.as_ref().and_then(|key| arguments.get_mut(key)) {
+            value.push(' ');
+            value.push_str(arg);
+        } else {
+            return Some(SlashCommand::Unknown("mcp prompt".to_string()));
+        }
+    }
+
+    Some(SlashCommand::Mcp {
+        action: MCPAction::Prompt {
+            server: server.to_string(),
+            name: name.to_string(),
+            arguments,
+        },
+    })
+}
+
+/// Parse `<server>/<tool> [json arguments]`
+fn parse_mcp_call_command(args: &[&str]) -> Option<SlashCommand> {
+    let (server, tool) = match args[0].split_once('/') {
+        Some((server, tool)) if !server.is_empty() && !tool.is_empty() => (server, tool),
+        _ => return Some(SlashCommand::Unknown("mcp call".to_string())),
+    };
+
+    let arguments = if args.len() > 1 {
+        match serde_json::from_str::<serde_json::Value>(&args[1..].join(" ")) {
+            Ok(value @ serde_json::Value::Object(_)) => value,
+            _ => return Some(SlashCommand::Unknown("mcp call (arguments must be a JSON object)".to_string())),
+        }
+    } else {
+        serde_json::json!({})
+    };
+
+    Some(SlashCommand::Mcp {
+        action: MCPAction::Call {
+            server: server.to_string(),
+            tool: tool.to_string(),
+            arguments,
+        },
+    })
+}
+
+/// Parse `[server=<name>] [tool=<name>] [status=ok|error|denied] [limit=N] [here]`
+fn parse_mcp_history_command(args: &[&str]) -> Option<SlashCommand> {
+    let mut filter = ToolInvocationFilter::default();
+    let mut current_conversation_only = false;
+
+    for arg in args {
+        match arg.split_once('=') {
+            Some(("server", server)) => filter.server_name = Some(server.to_string()),
+            Some(("tool", tool)) => filter.tool_name = Some(tool.to_string()),
+            Some(("status", status)) => {
+                filter.status = Some(match status {
+                    "ok" | "success" => ToolInvocationStatus::Succeeded,
+                    "error" | "failed" => ToolInvocationStatus::Failed,
+                    "denied" => ToolInvocationStatus::Denied,
+                    _ => return Some(SlashCommand::Unknown("mcp history (status must be ok, error or denied)".to_string())),
+                });
+            }
+            Some(("limit", limit)) => match limit.parse() {
+                Ok(limit) => filter.limit = Some(limit),
+                Err(_) => return Some(SlashCommand::Unknown("mcp history (limit must be a number)".to_string())),
+            },
+            None if *arg == "here" => current_conversation_only = true,
+            _ => return Some(SlashCommand::Unknown(format!("mcp history ({})", arg))),
+        }
+    }
+
+    Some(SlashCommand::Mcp {
+        action: MCPAction::History {
+            filter,
+            current_conversation_only,
+        },
+    })
+}
+
+/// Result of tab-completing a partially typed command
+#[derive(Debug, Clone)]
+pub struct Completion {
+    pub input: String,
+    pub candidates: Vec<String>,
+}
+
+/// Complete resource URIs and prompt names/arguments for `/mcp` commands
+pub fn complete_mcp_command(
+    input: &str,
+    resource_uris: &[String],
+    prompts: &[(String, Vec<String>)],
+) -> Option<Completion> {
+    let lower = input.to_lowercase();
+
+    let uri_commands = ["/mcp attach ", "/mcp pin ", "/mcp unpin "];
+    let (prefix_len, candidates) = if let Some(command) = uri_commands.iter().find(|c| lower.starts_with(*c)) {
+        let partial = &input[command.len()..];
+        let candidates: Vec<String> = resource_uris.iter()
+            .filter(|uri| uri.starts_with(partial))
+            .cloned()
+            .collect();
+        (command.len(), candidates)
+    } else if lower.starts_with("/mcp prompt ") {
+        let rest = &input["/mcp prompt ".len()..];
+        match rest.split_once(' ') {
+            None => {
+                let candidates = prompts.iter()
+                    .map(|(name, _)| name.clone())
+                    .filter(|name| name.starts_with(rest))
+                    .collect();
+                ("/mcp prompt ".len(), candidates)
+            }
+            Some((prompt_name, arguments)) => {
+                let partial = arguments.rsplit(' ').next().unwrap_or("");
+                if partial.contains('=') {
+                    return None;
+                }
+                let used: Vec<&str> = arguments.split_whitespace()
+                    .filter_map(|arg| arg.split_once('=').map(|(key, _)| key))
+                    .collect();
+                let candidates = prompts.iter()
+                    .find(|(name, _)| name == prompt_name)
+                    .map(|(_, args)| args.iter()
+                        .filter(|arg| arg.starts_with(partial) && !used.contains(&arg.as_str()))
+                        .map(|arg| format!("{}=", arg))
+                        .collect())
+                    .unwrap_or_default();
+                (input.len() - partial.len(), candidates)
+            }
+        }
+    } else {
+        return None;
+    };
+
+    let first = candidates.first()?;
+    let common_len = candidates.iter().skip(1).fold(first.len(), |len, candidate| {
+        first.bytes().zip(candidate.bytes()).take(len).take_while(|(a, b)| a == b).count()
+    });
+    let common_len = (0..=common_len).rev().find(|len| first.is_char_boundary(*len)).unwrap_or(0);
+
+    let mut completed = format!("{}{}", &input[..prefix_len], &first[..common_len]);
+    if candidates.len() == 1 && !completed.ends_with('=') {
+        completed.push(' ');
+    }
+
+    Some(Completion { input: completed, candidates })
+}
+
 pub struct CommandExecutor {
     app_state: Arc<AppState>,
     event_sender: mpsc::UnboundedSender<Event>,
@@ -393,11 +738,17 @@ impl CommandExecutor {
         }
     }
 
+    /// Formatter for the configured display currency
+    fn currency(&self) -> CurrencyFormatter {
+        self.app_state.get_config().currency_formatter()
+    }
+
     pub async fn execute_with_context(
         &self, 
         command: SlashCommand,
         current_provider: Option<&String>,
         current_model: Option<&String>,
+        current_conversation: Option<&String>,
     ) -> String {
         debug!("Executing slash command: {:?}", command);
 
@@ -408,6 +759,20 @@ impl CommandExecutor {
             SlashCommand::Model { action } => {
                 self.execute_model_command(action, current_model).await
             }
+            SlashCommand::Mcp { action: MCPAction::History { filter, current_conversation_only } } => {
+                let conversation = if current_conversation_only { current_conversation } else { None };
+                self.execute_mcp_history(filter, current_conversation_only, conversation).await
+            }
+            SlashCommand::Mcp { action: action @ (MCPAction::Pin(_) | MCPAction::Unpin(_) | MCPAction::Pinned
+                | MCPAction::Attach(_) | MCPAction::Prompt { .. } | MCPAction::Call { .. }) } => {
+                self.execute_conversation_mcp_command(action, current_conversation).await
+            }
+            SlashCommand::Project { action } => {
+                self.execute_project_command(action, current_conversation).await
+            }
+            SlashCommand::ApiKey { provider, action } => {
+                self.execute_apikey_command(provider, action, current_conversation).await
+            }
             _ => self.execute(command).await,
         }
     }
@@ -417,7 +782,7 @@ impl CommandExecutor {
 
         match command {
             SlashCommand::ApiKey { provider, action } => {
-                self.execute_apikey_command(provider, action).await
+                self.execute_apikey_command(provider, action, None).await
             }
             SlashCommand::Usage { period, provider } => {
                 self.execute_usage_command(period, provider).await
@@ -434,6 +799,20 @@ impl CommandExecutor {
             SlashCommand::Mcp { action } => {
                 self.execute_mcp_command(action).await
             }
+            SlashCommand::Project { action } => {
+                self.execute_project_command(action, None).await
+            }
+            SlashCommand::Backup { action } => {
+                self.execute_backup_command(action).await
+            }
+            SlashCommand::Settings { section } => {
+                let _ = self.event_sender.send(Event::OpenSettings(section));
+                "⚙️ Opening settings...".to_string()
+            }
+            SlashCommand::Audit => {
+                let _ = self.event_sender.send(Event::OpenKeyAudit);
+                "🔐 Opening the API key audit log...".to_string()
+            }
             SlashCommand::Quit => {
                 // Signal the app to quit
                 let _ = self.event_sender.send(Event::Quit);
@@ -452,20 +831,91 @@ impl CommandExecutor {
         }
     }
 
-    async fn execute_apikey_command(&self, provider: String, action: ApiKeyAction) -> String {
+    async fn execute_apikey_command(&self, provider: String, action: ApiKeyAction, current_conversation: Option<&String>) -> String {
         match action {
             ApiKeyAction::Status => {
-                match self.app_state.get_api_key(&provider).await {
-                    Ok(Some(key)) => {
+                let mut output = match self.app_state.resolve_api_key(&provider).await {
+                    Ok(Some(resolved)) => {
+                        let key = &resolved.value;
                         let preview = if key.len() > 10 {
                             format!("{}...{}", &key[..4], &key[key.len()-4..])
                         } else {
                             "*".repeat(key.len())
                         };
-                        format!("✅ API key configured for provider: {} ({})", provider, preview)
+                        format!("✅ API key configured for provider: {} ({}, from {})", provider, preview, resolved.source)
                     }
                     Ok(None) => format!("❌ No API key configured for provider: {}", provider),
-                    Err(e) => format!("❌ Error checking API key: {}", e),
+                    Err(e) => return format!("❌ Error checking API key: {}", e),
+                };
+                if let (Some(conversation_id), false) = (current_conversation, provider.contains('/')) {
+                    if let Ok(active) = self.app_state.get_key_selections().active_key_id(conversation_id, &provider).await {
+                        output.push_str(&format!("\n\n🔑 This conversation uses `{}`", active));
+                    }
+                }
+                output
+            }
+            ApiKeyAction::List => match self.app_state.list_stored_api_keys().await {
+                Ok(keys) => {
+                    let now = chrono::Utc::now();
+                    let keys: Vec<_> = keys.into_iter()
+                        .filter(|key| provider.is_empty() || key.id == provider || key.id.starts_with(&format!("{}/", provider)))
+                        .collect();
+                    if keys.is_empty() {
+                        return "🔑 **No API keys in secure storage**".to_string();
+                    }
+                    let mut output = String::from("🔑 **Stored API Keys**\n\n");
+                    for key in keys {
+                        let due = match key.rotation_due() {
+                            Some(due) if key.is_rotation_due(now) => format!(" - ⚠️ rotation due since {}", due.format("%Y-%m-%d")),
+                            Some(due) => format!(" - rotate by {}", due.format("%Y-%m-%d")),
+                            None => String::new(),
+                        };
+                        output.push_str(&format!("• **{}**{}\n", key.id, due));
+                    }
+                    output
+                }
+                Err(e) => format!("❌ Error listing API keys: {}", e),
+            },
+            ApiKeyAction::Validate => match self.app_state.validate_provider_credentials(&provider).await {
+                Ok(true) => format!("✅ **{}** was accepted by the provider", provider),
+                Ok(false) => format!("❌ **{}** was rejected by the provider or could not be checked", provider),
+                Err(e) => format!("❌ Error validating API key: {}", e),
+            },
+            ApiKeyAction::Rotate(days) => match self.app_state.set_api_key_rotation(&provider, Some(days)).await {
+                Ok(()) if days == 0 => format!("✅ Rotation reminders turned off for {}", provider),
+                Ok(()) => format!("✅ {} will be due for rotation every {} day(s)", provider, days),
+                Err(e) => format!("❌ Error scheduling rotation: {}", e),
+            },
+            ApiKeyAction::Use { project } | ApiKeyAction::Reset { project } => {
+                let Some(conversation_id) = current_conversation else {
+                    return "❌ **No conversation selected** - open a conversation first".to_string();
+                };
+                let scope = if project {
+                    match self.app_state.get_project_repo().get_conversation_project(conversation_id).await {
+                        Ok(Some(name)) => Some(name),
+                        Ok(None) => return "❌ **No project assigned** - use `/project use <name>` first".to_string(),
+                        Err(e) => return format!("❌ **Failed to load project**: {}", e),
+                    }
+                } else {
+                    None
+                };
+                let (target, scope) = match &scope {
+                    Some(name) => (format!("project {}", name), KeyScope::Project(name)),
+                    None => ("this conversation".to_string(), KeyScope::Conversation(conversation_id)),
+                };
+
+                if matches!(action, ApiKeyAction::Use { .. }) {
+                    match self.app_state.select_api_key(scope, &provider).await {
+                        Ok(()) => format!("✅ **{}** is now used in {}", provider, target),
+                        Err(e) => format!("❌ Error choosing API key: {}", e),
+                    }
+                } else {
+                    let (provider, _) = provider.split_once('/').unwrap_or((&provider, ""));
+                    match self.app_state.get_key_selections().unselect(scope, provider).await {
+                        Ok(true) => format!("✅ {} no longer chooses a {} key", target, provider),
+                        Ok(false) => format!("ℹ️ {} has not chosen a {} key", target, provider),
+                        Err(e) => format!("❌ Error resetting API key: {}", e),
+                    }
                 }
             }
             ApiKeyAction::Set(key) => {
@@ -588,20 +1038,21 @@ impl CommandExecutor {
     async fn execute_usage_command(&self, _period: Option<String>, _provider: Option<String>) -> String {
         match self.app_state.get_usage_repo().get_usage_statistics().await {
             Ok(stats) => {
+                let money = self.currency();
                 format!(
                     "📊 **Usage Statistics**\n\n\
                     Total Requests: {}\n\
-                    Total Cost: ${:.4}\n\
+                    Total Cost: {}\n\
                     Input Tokens: {}\n\
                     Output Tokens: {}\n\
-                    Current Month: ${:.4}\n\
-                    Previous Month: ${:.4}",
+                    Current Month: {}\n\
+                    Previous Month: {}",
                     stats.total_requests,
-                    stats.total_cost.to_f64().unwrap_or(0.0),
+                    money.format_precise(stats.total_cost),
                     stats.total_input_tokens,
                     stats.total_output_tokens,
-                    stats.current_month_cost.to_f64().unwrap_or(0.0),
-                    stats.previous_month_cost.to_f64().unwrap_or(0.0)
+                    money.format_precise(stats.current_month_cost),
+                    money.format_precise(stats.previous_month_cost)
                 )
             }
             Err(e) => format!("❌ Error getting usage statistics: {}", e),
@@ -645,6 +1096,12 @@ impl CommandExecutor {
 • `/apikey <provider>` - Show API key status
 • `/apikey <provider> set <key>` - Set API key
 • `/apikey <provider> remove` - Remove API key
+• `/apikey <provider>/<name> set <key>` - Set a named key, e.g. `openai/team-a`
+• `/apikey list [provider]` - List stored keys and their rotation dates
+• `/apikey <key> validate` - Check a key with the provider
+• `/apikey <key> rotate <days>` - Remind to rotate a key (0 turns it off)
+• `/apikey <key> use [project]` - Use a key in this conversation or its project
+• `/apikey <provider> reset [project]` - Go back to the project's or the default key
 
 **Usage & Billing:** (matches CLI `usage` command)
 • `/usage` - Show usage statistics
@@ -657,6 +1114,8 @@ impl CommandExecutor {
 • `/export conversation <id>` - Export specific conversation
 
 **Other:**
+• `/settings [section]` - Edit models, MCP servers, billing, UI, fallback, rate limiting and capability settings (F3)
+• `/audit` - Review API key operations and anomalies (F4)
 • `/help` - Show this help message
 • `/quit` or `/exit` - Exit ValeChat
 
@@ -666,14 +1125,32 @@ impl CommandExecutor {
 • `/cost week` - Show this week's spending
 • `/cost month` - Show monthly spending
 • `/cost breakdown` - Show spending by provider
-• `/cost alerts` - Show recent cost alerts
+• `/cost keys` - Show this month's spending by API key
+• `/cost alerts` - Show unacknowledged cost alerts
+• `/cost ack <id|all>` - Acknowledge cost alerts
+• `/cost dashboard` - Open the full-screen billing dashboard (F2)
 
 **Budget Management:**
 • `/budget` - Show current budget limits
-• `/budget daily <amount>` - Set daily spending limit
-• `/budget monthly <amount>` - Set monthly spending limit
-• `/budget provider <name> <limit>` - Set provider spending limit
-• `/budget alerts` - Show budget alert configuration
+• `/budget daily|weekly|monthly|yearly <amount>` - Set a global spending limit
+• `/budget request <amount>` - Cap the cost of a single request
+• `/budget provider <name> <limit> [period]` - Set provider spending limit (monthly by default)
+• `/budget model <name> <limit> [period]` - Set model spending limit (monthly by default)
+• `/budget project <name> <limit> [period]` - Set project spending limit (monthly by default)
+• `/budget remove <period> [provider|model|project <name>]` - Remove a spending limit
+• `/budget alerts` - Show alert thresholds and delivery
+• `/budget resume` - Lift an emergency stop after reviewing runaway spend
+
+**Projects:**
+• `/project` - Show the current conversation's project
+• `/project use <name>` - Assign the current conversation to a project (created if needed)
+• `/project clear` - Remove the current conversation from its project
+• `/project list` - List projects with their attributed spending
+
+**Backups:** (restore with the CLI `backup restore` command)
+• `/backup` or `/backup list` - List database backups
+• `/backup create` - Back up the database now
+• `/backup verify <id>` - Check that a backup can be restored
 
 **MCP (Model Context Protocol):**
 • `/mcp` or `/mcp list` - List configured MCP servers
@@ -683,6 +1160,15 @@ impl CommandExecutor {
 • `/mcp tools [server]` - List available tools (all or specific server)
 • `/mcp resources [server]` - List available resources
 • `/mcp prompts [server]` - List available prompts
+• `/mcp pin <uri>` - Pin a resource into the current conversation (kept up to date)
+• `/mcp unpin <uri>` - Remove a pinned resource
+• `/mcp pinned` - List resources pinned to the current conversation
+• `/mcp attach <uri>` - Insert a resource's contents into the conversation
+• `/mcp prompt <server>/<name> key=value...` - Run a prompt and insert the result
+• `/mcp call <server>/<tool> {json}` - Call a tool (subject to tool permission rules)
+• `/mcp permissions` - Show tool permission rules
+• `/mcp history [server=..] [tool=..] [status=ok|error|denied] [limit=N] [here]` - Recent tool calls
+  (Tab completes resource URIs, prompt names and argument names)
 
 **Examples:**
 • `/PROVIDER gemini` - Switch to Gemini (case insensitive)
@@ -691,30 +1177,31 @@ impl CommandExecutor {
 • `/MODEL list` - List all models (case insensitive)
 • `/APIKEY openai` - Check OpenAI API key status
 • `/cost today` - Show today's API spending
-• `/budget daily 50` - Set $50 daily limit
+• `/budget daily 50` - Set a daily limit of 50 (in the limit currency)
 • `/mcp start filesystem` - Start the filesystem MCP server
 • `/mcp tools` - List all available MCP tools"#.to_string()
     }
 
     async fn execute_cost_command(&self, action: CostAction) -> String {
+        let money = self.currency();
         match action {
             CostAction::Show => {
                 match self.app_state.get_usage_repo().get_usage_statistics().await {
                     Ok(stats) => {
                         format!(
                             "💰 **Cost Overview**\n\n\
-                            **Total Spending**: ${:.4}\n\
+                            **Total Spending**: {}\n\
                             **Total Requests**: {}\n\
-                            **This Month**: ${:.4}\n\
-                            **Last Month**: ${:.4}\n\
-                            **Average per Request**: ${:.6}",
-                            stats.total_cost.to_f64().unwrap_or(0.0),
+                            **This Month**: {}\n\
+                            **Last Month**: {}\n\
+                            **Average per Request**: {}",
+                            money.format_precise(stats.total_cost),
                             stats.total_requests,
-                            stats.current_month_cost.to_f64().unwrap_or(0.0),
-                            stats.previous_month_cost.to_f64().unwrap_or(0.0),
-                            if stats.total_requests > 0 {
-                                stats.total_cost.to_f64().unwrap_or(0.0) / stats.total_requests as f64
-                            } else { 0.0 }
+                            money.format_precise(stats.current_month_cost),
+                            money.format_precise(stats.previous_month_cost),
+                            money.format_dp(if stats.total_requests > 0 {
+                                stats.total_cost / rust_decimal::Decimal::from(stats.total_requests)
+                            } else { rust_decimal::Decimal::ZERO }, 6)
                         )
                     }
                     Err(e) => format!("❌ Error getting cost data: {}", e),
@@ -725,14 +1212,14 @@ impl CommandExecutor {
                     Ok((daily_cost, daily_tokens)) => {
                         format!(
                             "📅 **Today's Spending**\n\n\
-                            **Cost**: ${:.4}\n\
+                            **Cost**: {}\n\
                             **Tokens Used**: {}\n\
-                            **Estimated Rate**: ${:.6}/token",
-                            daily_cost.to_f64().unwrap_or(0.0),
+                            **Estimated Rate**: {}/token",
+                            money.format_precise(daily_cost),
                             daily_tokens,
-                            if daily_tokens > 0 {
-                                daily_cost.to_f64().unwrap_or(0.0) / daily_tokens as f64
-                            } else { 0.0 }
+                            money.format_dp(if daily_tokens > 0 {
+                                daily_cost / rust_decimal::Decimal::from(daily_tokens)
+                            } else { rust_decimal::Decimal::ZERO }, 6)
                         )
                     }
                     Err(e) => format!("❌ Error getting daily cost data: {}", e),
@@ -741,12 +1228,12 @@ impl CommandExecutor {
             CostAction::Week => {
                 match self.app_state.get_usage_repo().get_cost_trend(7).await {
                     Ok(trend_data) => {
-                        let total_week: f64 = trend_data.iter()
-                            .map(|(_, cost)| cost.to_f64().unwrap_or(0.0))
+                        let total_week: rust_decimal::Decimal = trend_data.iter()
+                            .map(|(_, cost)| *cost)
                             .sum();
-                        let mut output = format!("📊 **This Week's Spending**: ${:.4}\n\n**Daily Breakdown:**\n", total_week);
+                        let mut output = format!("📊 **This Week's Spending**: {}\n\n**Daily Breakdown:**\n", money.format_precise(total_week));
                         for (date, cost) in trend_data {
-                            output.push_str(&format!("• {}: ${:.4}\n", date, cost.to_f64().unwrap_or(0.0)));
+                            output.push_str(&format!("• {}: {}\n", date, money.format_precise(cost)));
                         }
                         output
                     }
@@ -758,13 +1245,13 @@ impl CommandExecutor {
                     Ok(stats) => {
                         format!(
                             "📊 **Monthly Spending**\n\n\
-                            **This Month**: ${:.4}\n\
-                            **Previous Month**: ${:.4}\n\
-                            **Change**: {}${:.4} ({})",
-                            stats.current_month_cost.to_f64().unwrap_or(0.0),
-                            stats.previous_month_cost.to_f64().unwrap_or(0.0),
+                            **This Month**: {}\n\
+                            **Previous Month**: {}\n\
+                            **Change**: {}{} ({})",
+                            money.format_precise(stats.current_month_cost),
+                            money.format_precise(stats.previous_month_cost),
                             if stats.current_month_cost >= stats.previous_month_cost { "+" } else { "" },
-                            (stats.current_month_cost - stats.previous_month_cost).to_f64().unwrap_or(0.0),
+                            money.format_precise(stats.current_month_cost - stats.previous_month_cost),
                             if stats.previous_month_cost.to_f64().unwrap_or(0.0) > 0.0 {
                                 let change_pct = ((stats.current_month_cost - stats.previous_month_cost).to_f64().unwrap_or(0.0) / stats.previous_month_cost.to_f64().unwrap_or(0.0)) * 100.0;
                                 format!("{:+.1}%", change_pct)
@@ -786,8 +1273,8 @@ impl CommandExecutor {
                             let cost = usage.cost.to_f64().unwrap_or(0.0);
                             let percentage = if total > 0.0 { (cost / total) * 100.0 } else { 0.0 };
                             output.push_str(&format!(
-                                "**{}**: ${:.4} ({:.1}%) - {} requests\n",
-                                provider, cost, percentage, usage.requests
+                                "**{}**: {} ({:.1}%) - {} requests\n",
+                                provider, money.format_precise(usage.cost), percentage, usage.requests
                             ));
                         }
                         
@@ -796,8 +1283,8 @@ impl CommandExecutor {
                             let cost = usage.cost.to_f64().unwrap_or(0.0);
                             let percentage = if total > 0.0 { (cost / total) * 100.0 } else { 0.0 };
                             output.push_str(&format!(
-                                "• {} ({}): ${:.4} ({:.1}%)\n",
-                                model, usage.provider, cost, percentage
+                                "• {} ({}): {} ({:.1}%)\n",
+                                model, usage.provider, money.format_precise(usage.cost), percentage
                             ));
                         }
                         
@@ -806,45 +1293,295 @@ impl CommandExecutor {
                     Err(e) => format!("❌ Error getting cost breakdown: {}", e),
                 }
             }
+            CostAction::Keys => {
+                let period = chrono::Utc::now().format("%Y-%m").to_string();
+                match self.app_state.get_usage_repo().get_key_usage(&period).await {
+                    Ok(usage) if usage.is_empty() => format!("🔑 **Spending by API Key ({})**\n\nNo usage recorded", period),
+                    Ok(usage) => {
+                        let mut output = format!("🔑 **Spending by API Key ({})**\n\n", period);
+                        for key in usage {
+                            output.push_str(&format!(
+                                "**{}**: {} - {} requests, {} tokens\n",
+                                key.key_id, money.format_precise(key.cost), key.requests, key.input_tokens + key.output_tokens
+                            ));
+                        }
+                        output
+                    }
+                    Err(e) => format!("❌ Error getting spending by key: {}", e),
+                }
+            }
+            CostAction::Dashboard => {
+                let _ = self.event_sender.send(Event::OpenBillingDashboard);
+                "📊 Opening billing dashboard (F2 or Ctrl+B)".to_string()
+            }
             CostAction::Alerts => {
-                // This would integrate with the cost alert system
-                "🔔 **Cost Alerts**\n\nNo recent alerts (alert system integration pending)".to_string()
+                match self.app_state.get_alert_store().list(true, 20).await {
+                    Ok(alerts) if alerts.is_empty() => "🔔 **Cost Alerts**\n\nNo unacknowledged alerts".to_string(),
+                    Ok(alerts) => {
+                        let mut output = "🔔 **Cost Alerts**\n\n".to_string();
+                        for alert in alerts {
+                            output.push_str(&format!(
+                                "• `{}` [{}] {} ({})\n",
+                                alert.id.chars().take(8).collect::<String>(),
+                                alert.severity.as_str(),
+                                alert.message,
+                                alert.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                            ));
+                            if let Some(action) = &alert.suggested_action {
+                                output.push_str(&format!("  {}\n", action));
+                            }
+                        }
+                        output.push_str("\nUse `/cost ack <id>` or `/cost ack all` to dismiss.");
+                        output
+                    }
+                    Err(e) => format!("❌ Error loading cost alerts: {}", e),
+                }
+            }
+            CostAction::Acknowledge(target) => self.acknowledge_alerts(&target).await,
+        }
+    }
+
+    async fn acknowledge_alerts(&self, target: &str) -> String {
+        let outstanding = match self.app_state.get_alert_store().list(true, u32::MAX).await {
+            Ok(alerts) => alerts,
+            Err(e) => return format!("❌ Error loading cost alerts: {}", e),
+        };
+
+        let matching: Vec<_> = if target.eq_ignore_ascii_case("all") {
+            outstanding
+        } else {
+            outstanding.into_iter().filter(|alert| alert.id.starts_with(target)).collect()
+        };
+
+        match matching.as_slice() {
+            [] => format!("❌ No unacknowledged alert matches `{}`", target),
+            [alert] => match self.app_state.acknowledge_alert(&alert.id).await {
+                Ok(_) => {
+                    let _ = self.event_sender.send(Event::AlertsAcknowledged(Some(alert.id.clone())));
+                    format!("✅ Acknowledged alert: {}", alert.message)
+                }
+                Err(e) => format!("❌ Failed to acknowledge alert: {}", e),
+            },
+            _ if !target.eq_ignore_ascii_case("all") => {
+                format!("❌ `{}` matches {} alerts; use a longer id", target, matching.len())
+            }
+            alerts => {
+                for alert in alerts {
+                    if let Err(e) = self.app_state.acknowledge_alert(&alert.id).await {
+                        return format!("❌ Failed to acknowledge alert {}: {}", alert.id, e);
+                    }
+                }
+                let _ = self.event_sender.send(Event::AlertsAcknowledged(None));
+                format!("✅ Acknowledged {} alerts", alerts.len())
             }
         }
     }
 
     async fn execute_budget_command(&self, action: BudgetAction) -> String {
+        let billing = self.app_state.get_billing();
+        let money = self.currency();
         match action {
             BudgetAction::Show => {
-                "💳 **Budget Limits**\n\nDaily: Not set\nMonthly: Not set\nProvider limits: None configured\n\nUse `/budget daily <amount>` to set daily limit".to_string()
-            }
-            BudgetAction::SetDaily(amount) => {
-                match amount.parse::<f64>() {
-                    Ok(limit) if limit > 0.0 => {
-                        format!("✅ **Daily budget set to**: ${:.2}\n\nThis will be enforced for future requests.", limit)
+                let period = chrono::Utc::now().format("%Y-%m").to_string();
+                match billing.get_spending_limit_statuses(&period).await {
+                    Ok(statuses) if statuses.is_empty() => {
+                        "💳 **Budget Limits**\n\nNo limits configured\n\nUse `/budget daily <amount>` to set daily limit".to_string()
                     }
-                    _ => "❌ **Invalid amount**. Please provide a positive number (e.g., `/budget daily 50`)".to_string()
+                    Ok(statuses) => {
+                        let mut output = String::from("💳 **Budget Limits**\n\n");
+                        for status in statuses {
+                            let marker = if status.is_exceeded { "🔴" } else if status.percentage_used >= 80.0 { "🟡" } else { "🟢" };
+                            let disabled = if status.limit.enabled { "" } else { " (disabled)" };
+                            if status.limit.period == BillingPeriod::PerRequest {
+                                output.push_str(&format!(
+                                    "{} **{}**: {}{}\n",
+                                    marker, capitalize(&status.limit.describe()),
+                                    money.format(status.limit.amount), disabled
+                                ));
+                            } else {
+                                output.push_str(&format!(
+                                    "{} **{}**: {} of {} ({:.0}%){}\n",
+                                    marker, capitalize(&status.limit.describe()),
+                                    money.format(status.current_spending),
+                                    money.format(status.limit.amount),
+                                    status.percentage_used, disabled
+                                ));
+                            }
+                        }
+                        output
+                    }
+                    Err(e) => format!("❌ **Failed to load budget limits**: {}", e),
                 }
             }
-            BudgetAction::SetMonthly(amount) => {
-                match amount.parse::<f64>() {
-                    Ok(limit) if limit > 0.0 => {
-                        format!("✅ **Monthly budget set to**: ${:.2}\n\nThis will be enforced for future requests.", limit)
+            BudgetAction::Set { scope, period, amount } => {
+                // Amounts are typed in the limit currency and stored in USD
+                let config = self.app_state.get_config();
+                match amount.parse::<rust_decimal::Decimal>().map(|amount| (amount, config.limit_to_usd(amount))) {
+                    Ok((_, None)) => format!(
+                        "❌ **No exchange rate for {}** - add one with `valechat currency set-rate`",
+                        config.billing.currency.limits
+                    ),
+                    Ok((_, Some(limit))) if limit > rust_decimal::Decimal::ZERO => {
+                        let description = SpendingLimit::new(scope.clone(), limit, period.clone(), None).describe();
+                        match billing.set_spending_limit(scope, limit, period, Some(0.8)).await {
+                            Ok(()) => {
+                                let _ = self.app_state.get_enforcement().clear_cache().await;
+                                format!("✅ **{} set to**: {}\n\nThis will be enforced for future requests.", capitalize(&description), money.format(limit))
+                            }
+                            Err(e) => format!("❌ **Failed to set {}**: {}", description, e),
+                        }
                     }
-                    _ => "❌ **Invalid amount**. Please provide a positive number (e.g., `/budget monthly 1000`)".to_string()
+                    _ => "❌ **Invalid amount**. Please provide a positive number (e.g., `/budget daily 50`)".to_string()
                 }
             }
-            BudgetAction::SetProvider { provider, limit } => {
-                match limit.parse::<f64>() {
-                    Ok(limit_amount) if limit_amount > 0.0 => {
-                        format!("✅ **{} provider budget set to**: ${:.2}\n\nThis will be enforced for future {} requests.", provider, limit_amount, provider)
-                    }
-                    _ => format!("❌ **Invalid amount**. Please provide a positive number (e.g., `/budget provider {} 200`)", provider)
+            BudgetAction::Remove { scope, period } => {
+                let description = SpendingLimit::new(scope.clone(), rust_decimal::Decimal::ZERO, period.clone(), None).describe();
+                match billing.get_spending_limit(&scope, &period).await {
+                    Ok(Some(_)) => match billing.remove_spending_limit(&scope, &period).await {
+                        Ok(()) => {
+                            let _ = self.app_state.get_enforcement().clear_cache().await;
+                            format!("✅ **Removed {}**", description)
+                        }
+                        Err(e) => format!("❌ **Failed to remove {}**: {}", description, e),
+                    },
+                    Ok(None) => format!("❌ **No {} is set**", description),
+                    Err(e) => format!("❌ **Failed to load {}**: {}", description, e),
                 }
             }
             BudgetAction::Alerts => {
-                "🔔 **Budget Alert Configuration**\n\nDaily alerts: Enabled at 80% of limit\nMonthly alerts: Enabled at 80% of limit\nCritical alerts: Enabled at 95% of limit\n\n(Full alert configuration UI pending)".to_string()
+                let config = self.app_state.get_config();
+                let thresholds = config.cost_alert_config();
+                let amount = |value: Option<rust_decimal::Decimal>| {
+                    value.map_or("not set".to_string(), |v| money.format(v))
+                };
+
+                let mut output = format!(
+                    "🔔 **Budget Alert Configuration**\n\n\
+                    Daily: warning at {}, critical at {}\n\
+                    Monthly: warning at {}, critical at {}\n",
+                    amount(thresholds.daily_warning_limit),
+                    amount(thresholds.daily_critical_limit),
+                    amount(thresholds.monthly_warning_limit),
+                    amount(thresholds.monthly_critical_limit),
+                );
+                for (provider, limit) in &thresholds.provider_limits {
+                    output.push_str(&format!("{}: {}/month\n", provider, money.format(*limit)));
+                }
+                output.push_str(&format!(
+                    "Anomalies: {:.1} MADs above the median, or {} requests within {} minutes{}\n",
+                    thresholds.anomaly_score,
+                    thresholds.burst_requests,
+                    thresholds.burst_window_minutes,
+                    if thresholds.emergency_stop_on_anomaly { " (emergency stop)" } else { "" },
+                ));
+
+                output.push_str("\n**Delivery**\n");
+                for severity in [AlertSeverity::Info, AlertSeverity::Warning, AlertSeverity::Critical, AlertSeverity::Emergency] {
+                    let sinks: Vec<&str> = config.alerts.sinks_for(&severity).iter().map(|sink| sink.as_str()).collect();
+                    output.push_str(&format!(
+                        "• {}: {}\n",
+                        severity.as_str(),
+                        if sinks.is_empty() { "none".to_string() } else { sinks.join(", ") }
+                    ));
+                }
+                if config.alerts.webhook_url.is_none() {
+                    output.push_str("\nNo webhook URL set; webhook routes are skipped.");
+                }
+                output
             }
+            BudgetAction::Resume => match self.app_state.get_enforcement().set_emergency_stop(false).await {
+                Ok(()) => "▶️ **Emergency stop lifted** - requests are allowed again".to_string(),
+                Err(e) => format!("❌ **Failed to lift emergency stop**: {}", e),
+            },
+        }
+    }
+
+    async fn execute_backup_command(&self, action: BackupAction) -> String {
+        let backups = self.app_state.get_backups();
+        if let Err(e) = self.app_state.unlock_backups().await {
+            debug!("Backup passphrase unavailable: {}", e);
+        }
+
+        match action {
+            BackupAction::List => match backups.list_backups().await {
+                Ok(list) if list.is_empty() => {
+                    "💾 **No backups yet**\n\nUse `/backup create` to back up the database now.".to_string()
+                }
+                Ok(list) => {
+                    let mut output = String::from("💾 **Backups**\n\n");
+                    for backup in list.iter().take(20) {
+                        output.push_str(&format!(
+                            "• `{}` - {} {}, {} KB{}{}\n",
+                            backup.id,
+                            backup.created_at.format("%Y-%m-%d %H:%M"),
+                            backup.backup_type.as_str(),
+                            backup.size_bytes.div_ceil(1024),
+                            if backup.encrypted { ", encrypted" } else { "" },
+                            if backup.verified { "" } else { ", unverified" },
+                        ));
+                    }
+                    if list.len() > 20 {
+                        output.push_str(&format!("\n...and {} older backup(s)", list.len() - 20));
+                    }
+                    output
+                }
+                Err(e) => format!("❌ **Failed to list backups**: {}", e),
+            },
+            BackupAction::Create => {
+                let backup_type = if backups.config().incremental { BackupType::Incremental } else { BackupType::Full };
+                match backups.create_backup(backup_type).await {
+                    Ok(backup) => format!(
+                        "✅ **Backup created**: `{}` ({}, {} KB{})",
+                        backup.id,
+                        backup.backup_type.as_str(),
+                        backup.size_bytes.div_ceil(1024),
+                        if backup.verified { ", verified" } else { ", NOT verified" },
+                    ),
+                    Err(e) => format!("❌ **Backup failed**: {}", e),
+                }
+            }
+            BackupAction::Verify(id) => match backups.verify(&id).await {
+                Ok(true) => format!("✅ **Backup `{}` is intact**", id),
+                Ok(false) => format!("⚠️ **Backup `{}` failed verification**", id),
+                Err(e) => format!("❌ **Failed to verify backup**: {}", e),
+            },
+        }
+    }
+
+    async fn execute_project_command(&self, action: ProjectAction, current_conversation: Option<&String>) -> String {
+        let projects = self.app_state.get_project_repo();
+
+        match (action, current_conversation) {
+            (ProjectAction::List, _) => match projects.list_projects().await {
+                Ok(list) if list.is_empty() => {
+                    "📁 **No projects yet**\n\nUse `/project use <name>` to assign this conversation to one.".to_string()
+                }
+                Ok(list) => {
+                    let mut output = String::from("📁 **Projects**\n\n");
+                    for project in list {
+                        output.push_str(&format!(
+                            "• **{}** - {} conversation(s), {} attributed\n",
+                            project.name, project.conversation_count, self.currency().format_precise(project.total_cost)
+                        ));
+                    }
+                    output
+                }
+                Err(e) => format!("❌ **Failed to list projects**: {}", e),
+            },
+            (_, None) => "❌ **No conversation selected** - open a conversation first".to_string(),
+            (ProjectAction::Show, Some(conversation_id)) => match projects.get_conversation_project(conversation_id).await {
+                Ok(Some(project)) => format!("📁 **Project**: {}", project),
+                Ok(None) => "📁 **No project assigned**\n\nUse `/project use <name>` to attribute this conversation's costs.".to_string(),
+                Err(e) => format!("❌ **Failed to load project**: {}", e),
+            },
+            (ProjectAction::Use(name), Some(conversation_id)) => match projects.assign_conversation(conversation_id, Some(&name)).await {
+                Ok(()) => format!("✅ **Conversation assigned to project**: {}\n\nNew usage will be charged to this project.", name.trim()),
+                Err(e) => format!("❌ **Failed to assign project**: {}", e),
+            },
+            (ProjectAction::Clear, Some(conversation_id)) => match projects.assign_conversation(conversation_id, None).await {
+                Ok(()) => "✅ **Conversation removed from its project**".to_string(),
+                Err(e) => format!("❌ **Failed to clear project**: {}", e),
+            },
         }
     }
 
@@ -979,11 +1716,272 @@ impl CommandExecutor {
                 }
             }
             MCPAction::Resources { server } => {
-                "🗂️ **MCP Resources**\n\nResource listing not yet implemented.".to_string()
+                match self.app_state.list_mcp_resources().await {
+                    Ok(resources_by_server) => {
+                        if resources_by_server.is_empty() {
+                            return "🗂️ **No MCP resources available** (no servers running)".to_string();
+                        }
+                        
+                        let mut output = String::from("🗂️ **Available MCP Resources**\n\n");
+                        
+                        for (server_name, resources) in resources_by_server {
+                            if let Some(ref specific_server) = server {
+                                if &server_name != specific_server {
+                                    continue;
+                                }
+                            }
+                            
+                            if resources.is_empty() {
+                                output.push_str(&format!("**{}**: No resources available\n\n", server_name));
+                                continue;
+                            }
+                            
+                            output.push_str(&format!("**{}** ({} resources):\n", server_name, resources.len()));
+                            
+                            for resource in resources {
+                                output.push_str(&format!("  • **{}**: `{}`", resource.name, resource.uri));
+                                if let Some(mime_type) = &resource.mime_type {
+                                    output.push_str(&format!(" ({})", mime_type));
+                                }
+                                output.push('\n');
+                                if let Some(description) = &resource.description {
+                                    output.push_str(&format!("    {}\n", description));
+                                }
+                            }
+                            output.push('\n');
+                        }
+                        
+                        output
+                    }
+                    Err(e) => format!("❌ **Error listing MCP resources**: {}", e),
+                }
             }
             MCPAction::Prompts { server } => {
-                "💬 **MCP Prompts**\n\nPrompt listing not yet implemented.".to_string()
+                let prompts = self.app_state.list_known_prompts().await;
+                let prompts: Vec<_> = prompts.into_iter()
+                    .filter(|(name, _)| server.as_ref().is_none_or(|s| name.starts_with(&format!("{}/", s))))
+                    .collect();
+                
+                if prompts.is_empty() {
+                    return "💬 **No MCP prompts available** (no servers running)".to_string();
+                }
+                
+                let mut output = String::from("💬 **Available MCP Prompts**\n\n");
+                for (name, arguments) in prompts {
+                    output.push_str(&format!("  • **{}**", name));
+                    if !arguments.is_empty() {
+                        output.push_str(&format!(" ({})", arguments.join(", ")));
+                    }
+                    output.push('\n');
+                }
+                output.push_str("\nRun one with `/mcp prompt <server>/<name> key=value...`");
+                output
+            }
+            MCPAction::Permissions => {
+                let permissions = self.app_state.get_config().tool_permissions;
+                let mut output = format!(
+                    "🛡️ **Tool Permissions**\n\nDefault action: **{:?}**\n\n",
+                    permissions.default_action
+                );
+                
+                if permissions.rules.is_empty() {
+                    output.push_str("No rules configured. Add `[[tool_permissions.rules]]` entries to your configuration file.\n");
+                }
+                
+                for (index, rule) in permissions.rules.iter().enumerate() {
+                    output.push_str(&format!("{}. `{}/{}` → **{:?}**\n", index + 1, rule.server, rule.tool, rule.action));
+                    for constraint in &rule.arguments {
+                        let mut conditions = Vec::new();
+                        if let Some(prefix) = &constraint.path_prefix {
+                            conditions.push(format!("inside `{}`", prefix));
+                        }
+                        if let Some(pattern) = &constraint.pattern {
+                            conditions.push(format!("matches `{}`", pattern));
+                        }
+                        if let Some(values) = &constraint.one_of {
+                            conditions.push(format!("one of {}", values.join(", ")));
+                        }
+                        output.push_str(&format!("     `{}` {}\n", constraint.argument, conditions.join(", ")));
+                    }
+                }
+                
+                output
+            }
+            MCPAction::History { filter, current_conversation_only } => {
+                self.execute_mcp_history(filter, current_conversation_only, None).await
+            }
+            MCPAction::Pin(_) | MCPAction::Unpin(_) | MCPAction::Pinned
+            | MCPAction::Attach(_) | MCPAction::Prompt { .. } | MCPAction::Call { .. } => {
+                self.execute_conversation_mcp_command(action, None).await
             }
         }
     }
-}
\ No newline at end of file
+
+    async fn execute_mcp_history(
+        &self,
+        mut filter: ToolInvocationFilter,
+        current_conversation_only: bool,
+        current_conversation: Option<&String>,
+    ) -> String {
+        if current_conversation_only {
+            match current_conversation {
+                Some(id) => filter.conversation_id = Some(id.clone()),
+                None => return "❌ **No conversation selected** - open a conversation first".to_string(),
+            }
+        }
+
+        let records = match self.app_state.get_conversation_repo().list_tool_invocations(&filter).await {
+            Ok(records) => records,
+            Err(e) => return format!("❌ **Failed to load tool history**: {}", e),
+        };
+
+        if records.is_empty() {
+            return "🔧 **No tool invocations found**".to_string();
+        }
+
+        let mut output = format!("🔧 **Tool History** ({} most recent)\n\n", records.len());
+        for record in records {
+            let invocation = record.invocation;
+            let icon = match (invocation.decision, &invocation.error) {
+                (Some(decision), _) if !decision.is_allowed() => "🚫",
+                (_, Some(_)) => "❌",
+                _ => "✅",
+            };
+
+            output.push_str(&format!(
+                "{} **{}/{}** {}",
+                icon,
+                invocation.server_name,
+                invocation.tool_name,
+                invocation.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+            ));
+            if let Some(duration) = invocation.duration_ms {
+                output.push_str(&format!(" · {}ms", duration));
+            }
+            if let Some(decision) = invocation.decision {
+                output.push_str(&format!(" · {}", decision));
+            }
+            output.push('\n');
+
+            let arguments = invocation.arguments.to_string();
+            output.push_str(&format!("   Args: `{}`\n", truncate_chars(&arguments, 120)));
+            if let Some(error) = &invocation.error {
+                output.push_str(&format!("   Error: {}\n", truncate_chars(error, 200)));
+            }
+            if filter.conversation_id.is_none() {
+                output.push_str(&format!("   Conversation: {}\n", record.conversation_id));
+            }
+        }
+
+        output
+    }
+
+    async fn execute_conversation_mcp_command(&self, action: MCPAction, current_conversation: Option<&String>) -> String {
+        let conversation_id = match current_conversation {
+            Some(id) => id,
+            None => return "❌ **No conversation selected** - open a conversation first".to_string(),
+        };
+        
+        match action {
+            MCPAction::Attach(uri) => {
+                match self.app_state.attach_resource(conversation_id, &uri).await {
+                    Ok(messages) => format_attached(&format!("`{}`", uri), &messages),
+                    Err(e) => format!("❌ **Failed to attach resource** {}: {}", uri, e),
+                }
+            }
+            MCPAction::Prompt { server, name, arguments } => {
+                match self.app_state.attach_prompt(conversation_id, &server, &name, arguments).await {
+                    Ok(messages) => format_attached(&format!("prompt **{}/{}**", server, name), &messages),
+                    Err(e) => format!("❌ **Failed to run prompt** {}/{}: {}", server, name, e),
+                }
+            }
+            MCPAction::Call { server, tool, arguments } => {
+                // Runs in the background so the approval dialog can be answered while the call waits
+                let app_state = self.app_state.clone();
+                let event_sender = self.event_sender.clone();
+                let conversation_id = conversation_id.clone();
+                let label = format!("{}/{}", server, tool);
+                let message = format!("🔧 **Calling tool** {}...", label);
+                tokio::spawn(async move {
+                    let summary = match app_state.execute_mcp_tool(&conversation_id, &server, &tool, arguments).await {
+                        Ok(result) if result.is_error == Some(true) => format!("Tool {} reported an error", label),
+                        Ok(_) => format!("Tool {} completed", label),
+                        Err(e) => format!("Tool {} failed: {}", label, e),
+                    };
+                    let _ = event_sender.send(Event::ToolCallFinished(conversation_id, summary));
+                });
+                message
+            }
+            MCPAction::Pin(uri) => {
+                let on_update = pinned_resource_listener(self.event_sender.clone());
+                match self.app_state.pin_resource(conversation_id, &uri, on_update).await {
+                    Ok(_) => format!("📌 **Pinned resource**: `{}`\n\nIts current contents are included with every message and refresh when the server reports changes.", uri),
+                    Err(e) => format!("❌ **Failed to pin resource** {}: {}", uri, e),
+                }
+            }
+            MCPAction::Unpin(uri) => {
+                match self.app_state.unpin_resource(conversation_id, &uri).await {
+                    Ok(true) => format!("✅ **Unpinned resource**: `{}`", uri),
+                    Ok(false) => format!("❌ Resource `{}` is not pinned to this conversation", uri),
+                    Err(e) => format!("❌ **Failed to unpin resource** {}: {}", uri, e),
+                }
+            }
+            _ => {
+                let pinned = match self.app_state.get_pinned_resources(conversation_id).await {
+                    Ok(pinned) => pinned,
+                    Err(e) => return format!("❌ **Failed to load pinned resources**: {}", e),
+                };
+                if pinned.is_empty() {
+                    return "📌 **No pinned resources** in this conversation\n\nUse `/mcp pin <uri>` to add one.".to_string();
+                }
+                
+                let mut output = String::from("📌 **Pinned Resources**\n\n");
+                for uri in pinned {
+                    output.push_str(&format!("  • `{}`\n", uri));
+                }
+                output
+            }
+        }
+    }
+}
+
+/// Forwards changes to pinned resources to the interface as events
+pub fn pinned_resource_listener(event_sender: mpsc::UnboundedSender<Event>) -> Arc<dyn Fn(ResourceUpdateEvent) + Send + Sync> {
+    Arc::new(move |event: ResourceUpdateEvent| {
+        let event = match event {
+            ResourceUpdateEvent::Updated(resource) => Event::ResourceUpdated(resource.uri),
+            ResourceUpdateEvent::Created(resource) => Event::ResourceUpdated(resource.uri),
+            ResourceUpdateEvent::Deleted(uri) => Event::Error(format!("Pinned resource removed: {}", uri)),
+            ResourceUpdateEvent::Error(message) => Event::Error(message),
+        };
+        let _ = event_sender.send(event);
+    })
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(max_chars).collect();
+    format!("{}…", truncated)
+}
+
+/// Summarize messages inserted from MCP content
+fn format_attached(source: &str, messages: &[valechat::chat::types::ChatMessage]) -> String {
+    let mut output = format!("📎 **Attached** {} as {} message(s)\n", source, messages.len());
+    
+    for message in messages {
+        let text = message.content.get_text().unwrap_or("[non-text content]");
+        let preview: String = text.chars().take(120).collect();
+        let ellipsis = if text.chars().count() > 120 { "…" } else { "" };
+        let role = match message.role {
+            valechat::chat::types::MessageRole::User => "",
+            valechat::chat::types::MessageRole::Assistant => "(assistant) ",
+            valechat::chat::types::MessageRole::System => "(system) ",
+            valechat::chat::types::MessageRole::Tool => "(tool) ",
+        };
+        output.push_str(&format!("  • {}{}{}\n", role, preview.replace('\n', " "), ellipsis));
+    }
+    
+    output
+}
@@ -0,0 +1,525 @@
+use chrono::{Datelike, NaiveDate, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Sparkline, Table, TableState, Tabs},
+    Frame,
+};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::tui::Theme;
+use valechat::storage::dashboard::{AlertSeverity, TrendDirection};
+use valechat::billing::CurrencyFormatter;
+use valechat::storage::{ConversationSpend, DashboardData, ExportFormat, PeriodReport};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DashboardTab {
+    Overview,
+    Providers,
+    Models,
+    Conversations,
+    Alerts,
+}
+
+impl DashboardTab {
+    const ALL: [DashboardTab; 5] = [
+        DashboardTab::Overview,
+        DashboardTab::Providers,
+        DashboardTab::Models,
+        DashboardTab::Conversations,
+        DashboardTab::Alerts,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            DashboardTab::Overview => "Overview",
+            DashboardTab::Providers => "Providers",
+            DashboardTab::Models => "Models",
+            DashboardTab::Conversations => "Conversations",
+            DashboardTab::Alerts => "Alerts",
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|t| t == self).unwrap_or(0)
+    }
+}
+
+/// What the app should do after a key press in the dashboard
+#[derive(Debug, Clone)]
+pub enum DashboardAction {
+    None,
+    Close,
+    /// Reload data for the current period
+    Reload,
+    /// Load the conversations that spent the most on a model
+    DrillDown(String),
+    OpenConversation(String),
+    Export(ExportFormat),
+}
+
+/// Full-screen billing dashboard
+pub struct BillingDashboardView {
+    visible: bool,
+    tab: DashboardTab,
+    /// Selected billing period (YYYY-MM)
+    period: String,
+    data: Option<DashboardData>,
+    report: Option<PeriodReport>,
+    /// Model being drilled into and its top conversations
+    drill_down: Option<(String, Vec<ConversationSpend>)>,
+    table_state: TableState,
+    message: Option<String>,
+    currency: CurrencyFormatter,
+}
+
+impl BillingDashboardView {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            tab: DashboardTab::Overview,
+            period: current_period(),
+            data: None,
+            report: None,
+            drill_down: None,
+            table_state: TableState::default(),
+            message: None,
+            currency: CurrencyFormatter::usd(),
+        }
+    }
+
+    /// Show amounts in another display currency; exports stay in USD
+    pub fn set_currency(&mut self, currency: CurrencyFormatter) {
+        self.currency = currency;
+    }
+
+    pub fn show(&mut self) {
+        self.visible = true;
+        self.message = None;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn period(&self) -> &str {
+        &self.period
+    }
+
+    pub fn drill_down_model(&self) -> Option<&str> {
+        self.drill_down.as_ref().map(|(model, _)| model.as_str())
+    }
+
+    pub fn set_data(&mut self, data: DashboardData, report: PeriodReport) {
+        self.data = Some(data);
+        self.report = Some(report);
+        self.clamp_selection();
+    }
+
+    pub fn set_drill_down(&mut self, model: String, conversations: Vec<ConversationSpend>) {
+        self.drill_down = Some((model, conversations));
+        self.tab = DashboardTab::Conversations;
+        self.table_state.select(Some(0));
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> DashboardAction {
+        match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) if self.drill_down.is_some() => {
+                self.drill_down = None;
+                self.table_state.select(Some(0));
+                DashboardAction::None
+            }
+            (KeyCode::Esc, _) | (KeyCode::Char('q'), _) | (KeyCode::F(2), _) => DashboardAction::Close,
+            (KeyCode::Tab, _) => {
+                self.select_tab((self.tab.index() + 1) % DashboardTab::ALL.len());
+                DashboardAction::None
+            }
+            (KeyCode::BackTab, _) => {
+                let len = DashboardTab::ALL.len();
+                self.select_tab((self.tab.index() + len - 1) % len);
+                DashboardAction::None
+            }
+            (KeyCode::Char(c @ '1'..='5'), KeyModifiers::NONE) => {
+                self.select_tab(c as usize - '1' as usize);
+                DashboardAction::None
+            }
+            (KeyCode::Left, _) | (KeyCode::Char('['), _) => self.shift_period(-1),
+            (KeyCode::Right, _) | (KeyCode::Char(']'), _) => self.shift_period(1),
+            (KeyCode::Down, _) | (KeyCode::Char('j'), _) => {
+                self.move_selection(1);
+                DashboardAction::None
+            }
+            (KeyCode::Up, _) | (KeyCode::Char('k'), _) => {
+                self.move_selection(-1);
+                DashboardAction::None
+            }
+            (KeyCode::Enter, _) => self.activate_selection(),
+            (KeyCode::Char('r'), _) => DashboardAction::Reload,
+            (KeyCode::Char('e'), _) => DashboardAction::Export(ExportFormat::Csv),
+            (KeyCode::Char('E'), _) => DashboardAction::Export(ExportFormat::Json),
+            _ => DashboardAction::None,
+        }
+    }
+
+    fn select_tab(&mut self, index: usize) {
+        if let Some(tab) = DashboardTab::ALL.get(index) {
+            self.tab = *tab;
+            self.table_state.select(Some(0));
+            self.clamp_selection();
+        }
+    }
+
+    fn shift_period(&mut self, months: i32) -> DashboardAction {
+        let Ok(first) = NaiveDate::parse_from_str(&format!("{}-01", self.period), "%Y-%m-%d") else {
+            self.period = current_period();
+            return DashboardAction::Reload;
+        };
+
+        let total = first.year() * 12 + first.month0() as i32 + months;
+        let (year, month) = (total.div_euclid(12), total.rem_euclid(12) + 1);
+        let next = format!("{:04}-{:02}", year, month);
+        if next > current_period() {
+            return DashboardAction::None;
+        }
+
+        self.period = next;
+        self.drill_down = None;
+        DashboardAction::Reload
+    }
+
+    fn row_count(&self) -> usize {
+        match self.tab {
+            DashboardTab::Providers => self.report.as_ref().map_or(0, |r| r.providers.len()),
+            DashboardTab::Models => self.report.as_ref().map_or(0, |r| r.models.len()),
+            DashboardTab::Conversations => self.conversations().len(),
+            DashboardTab::Alerts => self.data.as_ref().map_or(0, |d| d.alerts.len()),
+            DashboardTab::Overview => 0,
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let count = self.row_count();
+        if count == 0 {
+            self.table_state.select(None);
+            return;
+        }
+        let current = self.table_state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, count as isize - 1);
+        self.table_state.select(Some(next as usize));
+    }
+
+    fn clamp_selection(&mut self) {
+        let count = self.row_count();
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state.select(if count == 0 { None } else { Some(selected.min(count - 1)) });
+    }
+
+    fn activate_selection(&mut self) -> DashboardAction {
+        let Some(index) = self.table_state.selected() else {
+            return DashboardAction::None;
+        };
+
+        match self.tab {
+            DashboardTab::Models => self.report.as_ref()
+                .and_then(|r| r.models.get(index))
+                .map(|m| DashboardAction::DrillDown(m.model.clone()))
+                .unwrap_or(DashboardAction::None),
+            DashboardTab::Conversations => self.conversations()
+                .get(index)
+                .filter(|c| !c.conversation_id.is_empty())
+                .map(|c| DashboardAction::OpenConversation(c.conversation_id.clone()))
+                .unwrap_or(DashboardAction::None),
+            _ => DashboardAction::None,
+        }
+    }
+
+    fn conversations(&self) -> &[ConversationSpend] {
+        match (&self.drill_down, &self.report) {
+            (Some((_, conversations)), _) => conversations,
+            (None, Some(report)) => &report.conversations,
+            (None, None) => &[],
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if !self.visible {
+            return;
+        }
+
+        frame.render_widget(Clear, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Tabs
+                Constraint::Min(1),    // Content
+                Constraint::Length(1), // Key hints / messages
+            ])
+            .split(area);
+
+        let titles: Vec<Line> = DashboardTab::ALL.iter()
+            .enumerate()
+            .map(|(i, tab)| Line::from(format!("{} {}", i + 1, tab.title())))
+            .collect();
+        let tabs = Tabs::new(titles)
+            .select(self.tab.index())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.accent())
+                    .title(format!(" Billing Dashboard - {} ", self.period)),
+            )
+            .style(theme.normal())
+            .highlight_style(theme.highlight().add_modifier(Modifier::BOLD));
+        frame.render_widget(tabs, chunks[0]);
+
+        match self.tab {
+            DashboardTab::Overview => self.render_overview(frame, chunks[1], theme),
+            DashboardTab::Providers => self.render_providers(frame, chunks[1], theme),
+            DashboardTab::Models => self.render_models(frame, chunks[1], theme),
+            DashboardTab::Conversations => self.render_conversations(frame, chunks[1], theme),
+            DashboardTab::Alerts => self.render_alerts(frame, chunks[1], theme),
+        }
+
+        let footer = match &self.message {
+            Some(message) => Line::from(Span::styled(message.clone(), theme.accent())),
+            None => Line::from(Span::styled(
+                "Tab/1-5: tabs  ←/→: period  ↑/↓: select  Enter: drill down/open  r: reload  e/E: export CSV/JSON  Esc: back",
+                theme.secondary(),
+            )),
+        };
+        frame.render_widget(Paragraph::new(footer), chunks[2]);
+    }
+
+    fn render_overview(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let (Some(data), Some(report)) = (&self.data, &self.report) else {
+            frame.render_widget(placeholder("Loading...", theme), area);
+            return;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(9), Constraint::Min(5)])
+            .split(area);
+        let top = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[0]);
+
+        let overview = &data.overview;
+        let trend = match data.spending_trends.trend_direction {
+            TrendDirection::Increasing => format!("↑ {:.1}%", data.spending_trends.trend_percentage),
+            TrendDirection::Decreasing => format!("↓ {:.1}%", data.spending_trends.trend_percentage),
+            TrendDirection::Stable => "→ stable".to_string(),
+        };
+        let most_expensive = report.daily_breakdown.iter()
+            .max_by(|a, b| a.amount.cmp(&b.amount))
+            .map(|d| format!("{} ({})", d.date.format("%b %d"), self.currency.format(d.amount)))
+            .unwrap_or_else(|| "-".to_string());
+
+        let period_lines = vec![
+            stat_line("Spend", self.currency.format(report.total_spend)),
+            stat_line("Requests", report.total_requests.to_string()),
+            stat_line("Avg / request", if report.total_requests > 0 {
+                self.currency.format(report.total_spend / Decimal::from(report.total_requests))
+            } else {
+                "-".to_string()
+            }),
+            stat_line("Most expensive day", most_expensive),
+            stat_line("Providers", report.providers.len().to_string()),
+            stat_line("Models", report.models.len().to_string()),
+        ];
+        frame.render_widget(
+            Paragraph::new(period_lines).block(bordered(&format!("Period {}", report.period), theme)),
+            top[0],
+        );
+
+        let month_lines = vec![
+            stat_line("This month", self.currency.format(overview.current_month_spend)),
+            stat_line("Previous month", self.currency.format(overview.previous_month_spend)),
+            stat_line("Month over month", format!("{:+.1}%", overview.month_over_month_change)),
+            stat_line("Projected", self.currency.format(overview.projected_monthly_spend)),
+            stat_line("30-day trend", trend),
+            stat_line("Verified", format!("{:.1}%", data.current_month.verified_percentage)),
+            stat_line("Efficiency", format!("{:.0}/100", data.spending_trends.usage_patterns.efficiency_score)),
+        ];
+        frame.render_widget(
+            Paragraph::new(month_lines).block(bordered("Current Month", theme)),
+            top[1],
+        );
+
+        let daily: Vec<u64> = report.daily_breakdown.iter().map(|d| to_cents(d.amount)).collect();
+        let sparkline = Sparkline::default()
+            .block(bordered("Daily Spend (cents)", theme))
+            .data(&daily)
+            .style(theme.accent());
+        frame.render_widget(sparkline, chunks[1]);
+    }
+
+    fn render_providers(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let rows: Vec<Row> = self.report.iter()
+            .flat_map(|r| r.providers.iter())
+            .map(|p| Row::new(vec![
+                Cell::from(p.provider.clone()),
+                Cell::from(self.currency.format(p.amount)),
+                Cell::from(p.requests.to_string()),
+                Cell::from(format!("{:.1}%", p.percentage_of_total)),
+                Cell::from(self.currency.format_precise(p.average_cost_per_request)),
+            ]))
+            .collect();
+
+        let table = Table::new(rows, [
+            Constraint::Percentage(30),
+            Constraint::Percentage(20),
+            Constraint::Percentage(15),
+            Constraint::Percentage(15),
+            Constraint::Percentage(20),
+        ])
+        .header(header(&["Provider", "Spend", "Requests", "Share", "Avg / request"], theme))
+        .block(bordered("Spend by Provider", theme))
+        .highlight_style(theme.selected());
+
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    fn render_models(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let rows: Vec<Row> = self.report.iter()
+            .flat_map(|r| r.models.iter())
+            .map(|m| Row::new(vec![
+                Cell::from(m.model.clone()),
+                Cell::from(m.provider.clone()),
+                Cell::from(self.currency.format(m.amount)),
+                Cell::from(m.requests.to_string()),
+                Cell::from(m.tokens_used.to_string()),
+                Cell::from(format!("{:.1}%", m.percentage_of_total)),
+            ]))
+            .collect();
+
+        let table = Table::new(rows, [
+            Constraint::Percentage(30),
+            Constraint::Percentage(15),
+            Constraint::Percentage(15),
+            Constraint::Percentage(12),
+            Constraint::Percentage(16),
+            Constraint::Percentage(12),
+        ])
+        .header(header(&["Model", "Provider", "Spend", "Requests", "Tokens", "Share"], theme))
+        .block(bordered("Spend by Model (Enter: top conversations)", theme))
+        .highlight_style(theme.selected());
+
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    fn render_conversations(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let title = match self.drill_down_model() {
+            Some(model) => format!("Top Conversations for {} (Esc: all models, Enter: open)", model),
+            None => "Top Conversations (Enter: open)".to_string(),
+        };
+
+        let rows: Vec<Row> = self.conversations().iter()
+            .map(|c| Row::new(vec![
+                Cell::from(c.title.clone()),
+                Cell::from(self.currency.format(c.amount)),
+                Cell::from(c.requests.to_string()),
+                Cell::from(format!("{:.1}%", c.percentage_of_total)),
+            ]))
+            .collect();
+
+        let table = Table::new(rows, [
+            Constraint::Percentage(55),
+            Constraint::Percentage(15),
+            Constraint::Percentage(15),
+            Constraint::Percentage(15),
+        ])
+        .header(header(&["Conversation", "Spend", "Requests", "Share"], theme))
+        .block(bordered(&title, theme))
+        .highlight_style(theme.selected());
+
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    fn render_alerts(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let alerts = self.data.as_ref().map(|d| d.alerts.as_slice()).unwrap_or(&[]);
+        if alerts.is_empty() {
+            frame.render_widget(placeholder("✅ No billing alerts", theme), area);
+            return;
+        }
+
+        let rows: Vec<Row> = alerts.iter()
+            .map(|alert| {
+                let style = match alert.severity {
+                    AlertSeverity::Critical | AlertSeverity::High => theme.error(),
+                    AlertSeverity::Medium => theme.warning(),
+                    AlertSeverity::Low => theme.normal(),
+                };
+                Row::new(vec![
+                    Cell::from(format!("{:?}", alert.severity)),
+                    Cell::from(alert.message.clone()),
+                    Cell::from(alert.details.clone().unwrap_or_default()),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let table = Table::new(rows, [
+            Constraint::Length(10),
+            Constraint::Percentage(45),
+            Constraint::Percentage(45),
+        ])
+        .header(header(&["Severity", "Alert", "Details"], theme))
+        .block(bordered("Alerts", theme))
+        .highlight_style(theme.selected());
+
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+}
+
+impl Default for BillingDashboardView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn current_period() -> String {
+    Utc::now().format("%Y-%m").to_string()
+}
+
+fn to_cents(amount: Decimal) -> u64 {
+    (amount.to_f64().unwrap_or(0.0) * 100.0).round().max(0.0) as u64
+}
+
+fn stat_line(label: &str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{}: ", label), Style::default().add_modifier(Modifier::BOLD)),
+        Span::from(value),
+    ])
+}
+
+fn bordered<'a>(title: &str, theme: &Theme) -> Block<'a> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border())
+        .title(format!(" {} ", title))
+}
+
+fn header<'a>(columns: &[&'a str], theme: &Theme) -> Row<'a> {
+    Row::new(columns.iter().map(|c| Cell::from(*c)).collect::<Vec<_>>())
+        .style(theme.accent().add_modifier(Modifier::BOLD))
+}
+
+fn placeholder<'a>(text: &'a str, theme: &Theme) -> Paragraph<'a> {
+    Paragraph::new(text)
+        .style(theme.secondary())
+        .block(Block::default().borders(Borders::ALL).border_style(theme.border()))
+}
@@ -1,4 +1,5 @@
 use crossterm::event::{KeyCode, KeyEvent};
+use std::collections::HashSet;
 use ratatui::{
     layout::{Alignment, Rect},
     style::Style,
@@ -8,6 +9,8 @@ use ratatui::{
 };
 
 use crate::tui::{components::Component, Event, Theme};
+use valechat::billing::CurrencyFormatter;
+use valechat::chat::types::ToolInvocation;
 
 #[derive(Clone, Debug)]
 pub struct ChatMessage {
@@ -22,6 +25,7 @@ pub struct ChatMessage {
     #[allow(dead_code)]
     pub output_tokens: Option<i32>,
     pub cost: Option<String>,
+    pub tool: Option<ToolBlock>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -30,6 +34,79 @@ pub enum MessageRole {
     Assistant,
     #[allow(dead_code)]
     System,
+    Tool,
+}
+
+/// A recorded tool call shown as a collapsible block
+#[derive(Clone, Debug)]
+pub struct ToolBlock {
+    pub server_name: String,
+    pub tool_name: String,
+    pub arguments: String,
+    pub output: String,
+    pub status: ToolStatus,
+    pub duration_ms: Option<u64>,
+    pub decision: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToolStatus {
+    Succeeded,
+    Failed,
+    Denied,
+}
+
+impl ToolStatus {
+    fn icon(&self) -> &'static str {
+        match self {
+            ToolStatus::Succeeded => "✅",
+            ToolStatus::Failed => "❌",
+            ToolStatus::Denied => "🚫",
+        }
+    }
+}
+
+impl From<&ToolInvocation> for ToolBlock {
+    fn from(invocation: &ToolInvocation) -> Self {
+        let status = match (&invocation.decision, &invocation.error) {
+            (Some(decision), _) if !decision.is_allowed() => ToolStatus::Denied,
+            (_, Some(_)) => ToolStatus::Failed,
+            _ => ToolStatus::Succeeded,
+        };
+
+        let output = match (&invocation.error, &invocation.result) {
+            (Some(error), _) => error.clone(),
+            (None, Some(result)) => tool_output_text(result),
+            (None, None) => String::new(),
+        };
+
+        Self {
+            server_name: invocation.server_name.clone(),
+            tool_name: invocation.tool_name.clone(),
+            arguments: serde_json::to_string_pretty(&invocation.arguments)
+                .unwrap_or_else(|_| invocation.arguments.to_string()),
+            output,
+            status,
+            duration_ms: invocation.duration_ms,
+            decision: invocation.decision.map(|d| d.to_string()),
+        }
+    }
+}
+
+/// Render an MCP tool result as text, falling back to pretty-printed JSON
+fn tool_output_text(result: &serde_json::Value) -> String {
+    let Some(items) = result.get("content").and_then(|c| c.as_array()) else {
+        return serde_json::to_string_pretty(result).unwrap_or_else(|_| result.to_string());
+    };
+
+    items.iter()
+        .map(|item| match item.get("type").and_then(|t| t.as_str()) {
+            Some("text") => item.get("text").and_then(|t| t.as_str()).unwrap_or_default().to_string(),
+            Some("image") => format!("[image {}]", item.get("mimeType").and_then(|m| m.as_str()).unwrap_or("")),
+            _ => item.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 pub struct ChatView {
@@ -38,6 +115,9 @@ pub struct ChatView {
     auto_scroll: bool,
     conversation_title: String,
     scroll_offset: usize,
+    expanded_tools: HashSet<String>, // message ids of expanded tool blocks
+    selected_tool: Option<usize>,    // index into messages
+    currency: CurrencyFormatter,
 }
 
 impl ChatView {
@@ -48,9 +128,17 @@ impl ChatView {
             auto_scroll: true,
             conversation_title: "No conversation selected".to_string(),
             scroll_offset: 0,
+            expanded_tools: HashSet::new(),
+            selected_tool: None,
+            currency: CurrencyFormatter::usd(),
         }
     }
 
+    /// Show message costs in another display currency
+    pub fn set_currency(&mut self, currency: CurrencyFormatter) {
+        self.currency = currency;
+    }
+
     pub fn focus(&mut self) {
         self.is_focused = true;
     }
@@ -84,6 +172,101 @@ impl ChatView {
     pub fn clear_messages(&mut self) {
         self.messages.clear();
         self.scroll_offset = 0;
+        self.expanded_tools.clear();
+        self.selected_tool = None;
+    }
+
+    /// Move the tool block selection forwards or backwards, wrapping around
+    fn select_tool(&mut self, forward: bool) {
+        let tool_indices: Vec<usize> = self.messages.iter()
+            .enumerate()
+            .filter(|(_, m)| m.tool.is_some())
+            .map(|(i, _)| i)
+            .collect();
+
+        if tool_indices.is_empty() {
+            self.selected_tool = None;
+            return;
+        }
+
+        let position = self.selected_tool.and_then(|s| tool_indices.iter().position(|i| *i == s));
+        let next = match (position, forward) {
+            (None, true) => 0,
+            (None, false) => tool_indices.len() - 1,
+            (Some(p), true) => (p + 1) % tool_indices.len(),
+            (Some(p), false) => (p + tool_indices.len() - 1) % tool_indices.len(),
+        };
+        self.selected_tool = Some(tool_indices[next]);
+    }
+
+    fn toggle_selected_tool(&mut self) -> bool {
+        let Some(message) = self.selected_tool.and_then(|i| self.messages.get(i)) else {
+            return false;
+        };
+
+        if !self.expanded_tools.remove(&message.id) {
+            self.expanded_tools.insert(message.id.clone());
+        }
+        true
+    }
+
+    fn toggle_all_tools(&mut self) {
+        let tool_ids: Vec<String> = self.messages.iter()
+            .filter(|m| m.tool.is_some())
+            .map(|m| m.id.clone())
+            .collect();
+
+        if tool_ids.iter().all(|id| self.expanded_tools.contains(id)) {
+            self.expanded_tools.clear();
+        } else {
+            self.expanded_tools.extend(tool_ids);
+        }
+    }
+
+    fn tool_block_lines(&self, index: usize, message: &ChatMessage, block: &ToolBlock, width: usize, theme: &Theme) -> Vec<Line<'static>> {
+        let expanded = self.expanded_tools.contains(&message.id);
+        let selected = self.selected_tool == Some(index);
+
+        let mut header = vec![
+            Span::styled(if expanded { "▼ " } else { "▶ " }, theme.secondary()),
+            Span::styled("🔧 ", theme.warning()),
+            Span::styled(Self::format_timestamp(message.timestamp), theme.secondary()),
+            Span::raw(" "),
+            Span::styled(
+                format!("{}/{}", block.server_name, block.tool_name),
+                if selected { theme.accent() } else { theme.normal() },
+            ),
+            Span::raw(" "),
+            Span::raw(block.status.icon()),
+        ];
+        if let Some(duration) = block.duration_ms {
+            header.push(Span::styled(format!(" {}ms", duration), theme.secondary()));
+        }
+        if let Some(decision) = &block.decision {
+            header.push(Span::styled(format!(" [{}]", decision), theme.secondary()));
+        }
+
+        let mut lines = vec![Line::from(header)];
+        if !expanded {
+            return lines;
+        }
+
+        let mut section = |title: &str, text: &str, style: Style| {
+            lines.push(Line::from(vec![Span::raw("  "), Span::styled(title.to_string(), theme.secondary())]));
+            for line in Self::wrap_text(text, width.saturating_sub(4)) {
+                lines.push(Line::from(vec![Span::raw("    "), Span::styled(line, style)]));
+            }
+        };
+
+        section("Arguments:", &block.arguments, theme.normal());
+        if !block.output.is_empty() {
+            match block.status {
+                ToolStatus::Succeeded => section("Result:", &block.output, theme.normal()),
+                _ => section("Error:", &block.output, theme.error()),
+            }
+        }
+
+        lines
     }
 
     pub fn scroll_up(&mut self) {
@@ -121,6 +304,7 @@ impl ChatView {
             MessageRole::User => ("👤".to_string(), theme.accent()),
             MessageRole::Assistant => ("🤖".to_string(), theme.success()),
             MessageRole::System => ("⚙️".to_string(), theme.warning()),
+            MessageRole::Tool => ("🔧".to_string(), theme.warning()),
         }
     }
 
@@ -191,7 +375,13 @@ impl Component for ChatView {
         // Generate all display lines
         let mut all_lines: Vec<Line> = Vec::new();
         
-        for message in &self.messages {
+        for (index, message) in self.messages.iter().enumerate() {
+            if let Some(block) = &message.tool {
+                all_lines.extend(self.tool_block_lines(index, message, block, content_width, theme));
+                all_lines.push(Line::from(""));
+                continue;
+            }
+
             let (role_icon, role_style) = Self::get_role_indicator(&message.role, theme);
             let timestamp = Self::format_timestamp(message.timestamp);
             
@@ -210,10 +400,10 @@ impl Component for ChatView {
                 }
                 
                 if let Some(cost) = &message.cost {
-                    if let Ok(cost_float) = cost.parse::<f64>() {
-                        if cost_float > 0.0 {
+                    if let Ok(cost) = cost.parse::<rust_decimal::Decimal>() {
+                        if cost > rust_decimal::Decimal::ZERO {
                             header_spans.push(Span::raw(" | "));
-                            header_spans.push(Span::styled(format!("${}", cost), theme.warning()));
+                            header_spans.push(Span::styled(self.currency.format_precise(cost), theme.warning()));
                         }
                     }
                 }
@@ -311,6 +501,19 @@ impl Component for ChatView {
                         }
                         true
                     }
+                    KeyCode::Char('t') => {
+                        self.select_tool(true);
+                        true
+                    }
+                    KeyCode::Char('T') => {
+                        self.select_tool(false);
+                        true
+                    }
+                    KeyCode::Enter | KeyCode::Char(' ') => self.toggle_selected_tool(),
+                    KeyCode::Char('e') => {
+                        self.toggle_all_tools();
+                        true
+                    }
                     _ => false,
                 }
             }
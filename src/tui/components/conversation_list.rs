@@ -5,6 +5,8 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, ListState},
     Frame,
 };
+use rust_decimal::Decimal;
+use valechat::billing::CurrencyFormatter;
 
 use crate::tui::{components::Component, Event, Theme};
 
@@ -21,6 +23,7 @@ pub struct ConversationList {
     conversations: Vec<ConversationItem>,
     state: ListState,
     is_focused: bool,
+    currency: CurrencyFormatter,
 }
 
 impl ConversationList {
@@ -32,6 +35,7 @@ impl ConversationList {
             conversations: Vec::new(),
             state,
             is_focused: false,
+            currency: CurrencyFormatter::usd(),
         }
     }
 
@@ -121,14 +125,21 @@ impl ConversationList {
         }
     }
 
-    fn format_cost(cost: f64) -> String {
-        if cost < 0.01 {
-            "<$0.01".to_string()
+    fn format_cost(&self, cost: f64) -> String {
+        let cost = Decimal::try_from(cost).unwrap_or_default();
+        let cent = self.currency.to_usd(Decimal::new(1, 2));
+        if cost < cent {
+            format!("<{}", self.currency.format(cent))
         } else {
-            format!("${:.2}", cost)
+            self.currency.format(cost)
         }
     }
 
+    /// Show conversation costs in another display currency
+    pub fn set_currency(&mut self, currency: CurrencyFormatter) {
+        self.currency = currency;
+    }
+
     pub fn add_conversation(&mut self, conversation: ConversationItem) {
         self.conversations.insert(0, conversation); // Add at beginning
         if self.conversations.len() == 1 {
@@ -155,7 +166,7 @@ impl Component for ConversationList {
             .iter()
             .map(|conv| {
                 let time_ago = Self::format_time_ago(conv.updated_at);
-                let cost = Self::format_cost(conv.total_cost);
+                let cost = self.format_cost(conv.total_cost);
                 
                 // Truncate title to fit
                 let max_title_len = area.width.saturating_sub(15) as usize; // Leave space for metadata
@@ -2,14 +2,16 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Sparkline},
+    widgets::{BarChart, Block, Borders, Gauge, List, ListItem, Paragraph, Sparkline},
     Frame,
 };
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 
-use valechat::storage::{CostAlert, AlertSeverity, UsageStatistics};
+use valechat::billing::CurrencyFormatter;
+use valechat::storage::{CostAlert, AlertSeverity, DailySpend, SpendForecast, UsagePatterns, UsageStatistics};
+use valechat::storage::dashboard::TrendDirection;
 
 /// Cost tracking display component
 pub struct CostTracker {
@@ -29,6 +31,14 @@ pub struct CostTracker {
     monthly_limit: Option<Decimal>,
     /// Provider breakdown
     provider_breakdown: HashMap<String, Decimal>,
+    /// Per-day spend for the analytics window, oldest first
+    daily_spend: Vec<DailySpend>,
+    /// Hour, weekday and model usage patterns
+    usage_patterns: Option<UsagePatterns>,
+    /// Month-end and budget exhaustion forecast
+    forecast: Option<SpendForecast>,
+    /// Display currency for all amounts
+    currency: CurrencyFormatter,
     /// Show detailed view
     pub show_details: bool,
 }
@@ -44,6 +54,10 @@ impl CostTracker {
             monthly_spending: Decimal::ZERO,
             monthly_limit: None,
             provider_breakdown: HashMap::new(),
+            daily_spend: Vec::new(),
+            usage_patterns: None,
+            forecast: None,
+            currency: CurrencyFormatter::usd(),
             show_details: false,
         }
     }
@@ -60,6 +74,11 @@ impl CostTracker {
         self.daily_trend = trend;
     }
 
+    /// Show amounts in another display currency
+    pub fn set_currency(&mut self, currency: CurrencyFormatter) {
+        self.currency = currency;
+    }
+
     /// Update spending limits
     pub fn update_limits(&mut self, daily: Option<Decimal>, monthly: Option<Decimal>) {
         self.daily_limit = daily;
@@ -71,6 +90,17 @@ impl CostTracker {
         self.provider_breakdown = breakdown;
     }
 
+    /// Update dashboard analytics shown in the detailed view
+    pub fn update_analytics(&mut self, daily_spend: Vec<DailySpend>, patterns: UsagePatterns) {
+        self.daily_spend = daily_spend;
+        self.usage_patterns = Some(patterns);
+    }
+
+    /// Update the spend forecast
+    pub fn update_forecast(&mut self, forecast: SpendForecast) {
+        self.forecast = Some(forecast);
+    }
+
     /// Add new alert
     pub fn add_alert(&mut self, alert: CostAlert) {
         // Keep only recent alerts
@@ -80,6 +110,14 @@ impl CostTracker {
         }
     }
 
+    /// Drop an acknowledged alert, or all of them for `None`
+    pub fn acknowledge_alert(&mut self, alert_id: Option<&str>) {
+        match alert_id {
+            Some(id) => self.alerts.retain(|alert| alert.id != id),
+            None => self.alerts.clear(),
+        }
+    }
+
     /// Toggle detailed view
     pub fn toggle_details(&mut self) {
         self.show_details = !self.show_details;
@@ -120,21 +158,34 @@ impl CostTracker {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(8),  // Spending overview
-                Constraint::Length(8),  // Provider breakdown
-                Constraint::Length(6),  // Trend chart
-                Constraint::Min(6),     // Alerts
+                Constraint::Length(6),  // Spending overview
+                Constraint::Length(8),  // Daily spend and hour-of-day
+                Constraint::Length(8),  // Weekday, models, providers
+                Constraint::Min(4),     // Alerts
             ])
             .split(area);
 
         // Spending overview
         self.render_spending_overview(f, chunks[0]);
 
-        // Provider breakdown
-        self.render_provider_breakdown(f, chunks[1]);
+        let trend_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[1]);
+        self.render_cost_trend(f, trend_chunks[0]);
+        self.render_hourly_heatmap(f, trend_chunks[1]);
 
-        // Cost trend
-        self.render_cost_trend(f, chunks[2]);
+        let pattern_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(40),
+                Constraint::Percentage(30),
+                Constraint::Percentage(30),
+            ])
+            .split(chunks[2]);
+        self.render_weekday_heatmap(f, pattern_chunks[0]);
+        self.render_top_models(f, pattern_chunks[1]);
+        self.render_provider_breakdown(f, pattern_chunks[2]);
 
         // Detailed alerts
         self.render_alerts_detailed(f, chunks[3]);
@@ -163,7 +214,7 @@ impl CostTracker {
             .block(Block::default().borders(Borders::ALL).title("Daily"))
             .gauge_style(Style::default().fg(color))
             .ratio(ratio)
-            .label(format!("${:.2}", self.today_spending.to_f64().unwrap_or(0.0)));
+            .label(self.currency.format(self.today_spending));
 
         f.render_widget(gauge, area);
     }
@@ -191,7 +242,14 @@ impl CostTracker {
             .block(Block::default().borders(Borders::ALL).title("Monthly"))
             .gauge_style(Style::default().fg(color))
             .ratio(ratio)
-            .label(format!("${:.2}", self.monthly_spending.to_f64().unwrap_or(0.0)));
+            .label(match &self.forecast {
+                Some(forecast) => format!(
+                    "{} → {}",
+                    self.currency.format(self.monthly_spending),
+                    self.currency.format(forecast.projected_month_end)
+                ),
+                None => self.currency.format(self.monthly_spending),
+            });
 
         f.render_widget(gauge, area);
     }
@@ -233,12 +291,12 @@ impl CostTracker {
         let daily_text = vec![
             Line::from(vec![
                 Span::styled("Today: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::from(format!("${:.2}", self.today_spending.to_f64().unwrap_or(0.0))),
+                Span::from(self.currency.format(self.today_spending)),
             ]),
             Line::from(vec![
                 Span::styled("Limit: ", Style::default().add_modifier(Modifier::BOLD)),
                 Span::from(match self.daily_limit {
-                    Some(limit) => format!("${:.2}", limit.to_f64().unwrap_or(0.0)),
+                    Some(limit) => self.currency.format(limit),
                     None => "Not set".to_string(),
                 }),
             ]),
@@ -246,16 +304,25 @@ impl CostTracker {
                 Span::styled("Remaining: ", Style::default().add_modifier(Modifier::BOLD)),
                 Span::from(match self.daily_limit {
                     Some(limit) => {
-                        let remaining = limit - self.today_spending;
-                        if remaining >= Decimal::ZERO {
-                            format!("${:.2}", remaining.to_f64().unwrap_or(0.0))
-                        } else {
-                            format!("-${:.2}", (-remaining).to_f64().unwrap_or(0.0))
-                        }
+                        self.currency.format(limit - self.today_spending)
                     }
                     None => "∞".to_string(),
                 }),
             ]),
+            Line::from(vec![
+                Span::styled("Runs out: ", Style::default().add_modifier(Modifier::BOLD)),
+                match self.forecast.as_ref().and_then(|forecast| forecast.earliest_exhaustion()) {
+                    Some(budget) => Span::styled(
+                        format!(
+                            "{} on {}",
+                            budget.description,
+                            budget.exhaustion_date.map(|date| date.format("%b %d").to_string()).unwrap_or_default()
+                        ),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    None => Span::from("no budget at risk"),
+                },
+            ]),
         ];
 
         let daily_paragraph = Paragraph::new(daily_text)
@@ -266,12 +333,12 @@ impl CostTracker {
         let monthly_text = vec![
             Line::from(vec![
                 Span::styled("This month: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::from(format!("${:.2}", self.monthly_spending.to_f64().unwrap_or(0.0))),
+                Span::from(self.currency.format(self.monthly_spending)),
             ]),
             Line::from(vec![
                 Span::styled("Limit: ", Style::default().add_modifier(Modifier::BOLD)),
                 Span::from(match self.monthly_limit {
-                    Some(limit) => format!("${:.2}", limit.to_f64().unwrap_or(0.0)),
+                    Some(limit) => self.currency.format(limit),
                     None => "Not set".to_string(),
                 }),
             ]),
@@ -279,16 +346,26 @@ impl CostTracker {
                 Span::styled("Remaining: ", Style::default().add_modifier(Modifier::BOLD)),
                 Span::from(match self.monthly_limit {
                     Some(limit) => {
-                        let remaining = limit - self.monthly_spending;
-                        if remaining >= Decimal::ZERO {
-                            format!("${:.2}", remaining.to_f64().unwrap_or(0.0))
-                        } else {
-                            format!("-${:.2}", (-remaining).to_f64().unwrap_or(0.0))
-                        }
+                        self.currency.format(limit - self.monthly_spending)
                     }
                     None => "∞".to_string(),
                 }),
             ]),
+            Line::from(vec![
+                Span::styled("Projected: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::from(match &self.forecast {
+                    Some(forecast) => format!(
+                        "{} {}",
+                        self.currency.format(forecast.projected_month_end),
+                        match forecast.trend {
+                            TrendDirection::Increasing => "↑",
+                            TrendDirection::Decreasing => "↓",
+                            TrendDirection::Stable => "→",
+                        }
+                    ),
+                    None => "-".to_string(),
+                }),
+            ]),
         ];
 
         let monthly_paragraph = Paragraph::new(monthly_text)
@@ -309,7 +386,7 @@ impl CostTracker {
                         format!("{:10}", provider),
                         Style::default().add_modifier(Modifier::BOLD)
                     ),
-                    Span::from(format!(" ${:8.2} ({:5.1}%)", cost_f64, percentage)),
+                    Span::from(format!(" {:>9} ({:5.1}%)", self.currency.format(*cost), percentage)),
                 ]))
             })
             .collect();
@@ -321,14 +398,110 @@ impl CostTracker {
     }
 
     fn render_cost_trend(&self, f: &mut Frame, area: Rect) {
+        // Prefer the analytics series; fall back to the short trend before it is loaded
+        let (data, title) = if self.daily_spend.is_empty() {
+            (self.daily_trend.clone(), "7-Day Trend".to_string())
+        } else {
+            let data = self.daily_spend.iter().map(|d| to_cents(d.amount)).collect();
+            let title = match self.daily_spend.iter().max_by(|a, b| a.amount.cmp(&b.amount)) {
+                Some(peak) if peak.amount > Decimal::ZERO => format!(
+                    "{}-Day Spend (peak {} on {})",
+                    self.daily_spend.len(),
+                    self.currency.format(peak.amount),
+                    peak.date.format("%b %d")
+                ),
+                _ => format!("{}-Day Spend", self.daily_spend.len()),
+            };
+            (data, title)
+        };
+
         let sparkline = Sparkline::default()
-            .block(Block::default().borders(Borders::ALL).title("7-Day Trend"))
-            .data(&self.daily_trend)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .data(&data)
             .style(Style::default().fg(Color::Cyan));
 
         f.render_widget(sparkline, area);
     }
 
+    fn render_hourly_heatmap(&self, f: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title("Spend by Hour (UTC)");
+        let Some(patterns) = &self.usage_patterns else {
+            f.render_widget(Paragraph::new("No data").block(block), area);
+            return;
+        };
+
+        let labels: Vec<String> = patterns.hourly_heatmap.iter()
+            .map(|h| if h.hour % 6 == 0 { h.hour.to_string() } else { String::new() })
+            .collect();
+        let data: Vec<(&str, u64)> = patterns.hourly_heatmap.iter()
+            .zip(labels.iter())
+            .map(|(h, label)| (label.as_str(), to_cents(h.cost)))
+            .collect();
+        let bar_width = (area.width.saturating_sub(2) / 24).max(1);
+
+        let chart = BarChart::default()
+            .block(block)
+            .data(&data)
+            .bar_width(bar_width)
+            .bar_gap(0)
+            .bar_style(Style::default().fg(Color::Magenta))
+            .value_style(Style::default().fg(Color::Magenta));
+
+        f.render_widget(chart, area);
+    }
+
+    fn render_weekday_heatmap(&self, f: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title("Spend by Weekday");
+        let Some(patterns) = &self.usage_patterns else {
+            f.render_widget(Paragraph::new("No data").block(block), area);
+            return;
+        };
+
+        let data: Vec<(&str, u64)> = patterns.weekday_heatmap.iter()
+            .map(|d| (d.weekday.get(..2).unwrap_or(&d.weekday), to_cents(d.cost)))
+            .collect();
+        let bar_width = (area.width.saturating_sub(2) / 7).saturating_sub(1).max(1);
+
+        let chart = BarChart::default()
+            .block(block)
+            .data(&data)
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(Color::Yellow))
+            .value_style(Style::default().fg(Color::Black).bg(Color::Yellow));
+
+        f.render_widget(chart, area);
+    }
+
+    fn render_top_models(&self, f: &mut Frame, area: Rect) {
+        let mut lines = Vec::new();
+        if let Some(patterns) = &self.usage_patterns {
+            lines.push(Line::from(vec![
+                Span::styled("Avg session: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::from(self.currency.format_precise(patterns.average_session_cost)),
+            ]));
+            lines.push(Line::from(vec![
+                Span::styled("Efficiency: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::from(format!("{:.0}/100", patterns.efficiency_score)),
+            ]));
+            if !patterns.peak_days.is_empty() {
+                lines.push(Line::from(vec![
+                    Span::styled("Peak days: ", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::from(patterns.peak_days.join(", ")),
+                ]));
+            }
+            for (rank, model) in patterns.most_active_models.iter().take(3).enumerate() {
+                lines.push(Line::from(format!("{}. {}", rank + 1, model)));
+            }
+        } else {
+            lines.push(Line::from("No data"));
+        }
+
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Most Active Models"));
+        f.render_widget(paragraph, area);
+    }
+
     fn render_alerts_detailed(&self, f: &mut Frame, area: Rect) {
         let items: Vec<ListItem> = self.alerts
             .iter()
@@ -365,18 +538,23 @@ impl CostTracker {
 
     /// Get summary for status bar
     pub fn get_status_summary(&self) -> String {
-        let daily = self.today_spending.to_f64().unwrap_or(0.0);
-        let monthly = self.monthly_spending.to_f64().unwrap_or(0.0);
+        let daily = self.currency.format(self.today_spending);
+        let monthly = self.currency.format(self.monthly_spending);
         let alert_count = self.alerts.len();
 
         if alert_count > 0 {
-            format!("💰 ${:.2}/day ${:.2}/mo 🚨{}", daily, monthly, alert_count)
+            format!("💰 {}/day {}/mo 🚨{}", daily, monthly, alert_count)
         } else {
-            format!("💰 ${:.2}/day ${:.2}/mo", daily, monthly)
+            format!("💰 {}/day {}/mo", daily, monthly)
         }
     }
 }
 
+/// Bar and sparkline widgets take integers; chart spend in cents
+fn to_cents(amount: Decimal) -> u64 {
+    (amount.to_f64().unwrap_or(0.0) * 100.0).round().max(0.0) as u64
+}
+
 impl Default for CostTracker {
     fn default() -> Self {
         Self::new()
@@ -48,6 +48,9 @@ impl HelpPopup {
             ("  Arrow keys / hjkl", "Navigate lists and messages"),
             ("  Page Up/Down", "Scroll messages quickly"),
             ("  Home/End (g/G)", "Go to top/bottom of messages"),
+            ("  t / T", "Select next/previous tool call"),
+            ("  Enter / Space", "Expand or collapse selected tool call"),
+            ("  e", "Expand or collapse all tool calls"),
             ("", ""),
             ("Conversations", ""),
             ("  n", "New conversation"),
@@ -65,8 +68,10 @@ impl HelpPopup {
             ("General", ""),
             ("  F1 / Ctrl+/", "Show/hide this help"),
             ("  Ctrl+M", "Toggle cost tracking details"),
+            ("  F2 / Ctrl+B", "Open billing dashboard"),
             ("  Ctrl+C / Ctrl+Q", "Quit application"),
-            ("  Ctrl+S", "Settings"),
+            ("  F3 / Ctrl+S", "Edit settings"),
+            ("  F4", "Review API key audit log"),
             ("  Ctrl+E", "Export conversation"),
         ]
     }
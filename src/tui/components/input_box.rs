@@ -1,8 +1,8 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
-    layout::Rect,
+    layout::{Alignment, Rect},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{block::Title, Block, Borders, Paragraph, Wrap},
     Frame,
 };
 use tui_input::{backend::crossterm::EventHandler, Input};
@@ -16,6 +16,8 @@ pub struct InputBox {
     placeholder: String,
     is_multiline_mode: bool,
     lines: Vec<String>,
+    /// Formatted cost estimate for the draft and whether it needs confirmation
+    cost_estimate: Option<(String, bool)>,
 }
 
 impl InputBox {
@@ -26,9 +28,15 @@ impl InputBox {
             placeholder: "Type your message... (Enter: Send, Shift+Enter: New line)".to_string(),
             is_multiline_mode: false,
             lines: Vec::new(),
+            cost_estimate: None,
         }
     }
 
+    /// Show an estimated cost in the border, highlighted when it needs confirmation
+    pub fn set_cost_estimate(&mut self, estimate: Option<(String, bool)>) {
+        self.cost_estimate = estimate;
+    }
+
     pub fn focus(&mut self) {
         self.is_focused = true;
     }
@@ -148,11 +156,18 @@ impl Component for InputBox {
             " Message "
         };
 
-        let block = Block::default()
+        let mut block = Block::default()
             .borders(Borders::ALL)
             .border_style(border_style)
             .title(title);
 
+        if let Some((estimate, over_threshold)) = &self.cost_estimate {
+            let style = if *over_threshold { theme.error() } else { theme.secondary() };
+            block = block.title(
+                Title::from(Span::styled(format!(" ~{} ", estimate), style)).alignment(Alignment::Right),
+            );
+        }
+
         if self.is_multiline_mode {
             let content = if self.lines.is_empty() {
                 vec![Line::from(Span::styled(&self.placeholder, theme.secondary()))]
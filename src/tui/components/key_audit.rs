@@ -0,0 +1,278 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Tabs},
+    Frame,
+};
+
+use crate::tui::Theme;
+use valechat::storage::{KeyAuditAnomaly, KeyAuditEntry, KeyAuditRules};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuditTab {
+    Log,
+    Anomalies,
+}
+
+/// What the app should do after a key press in the key audit view
+#[derive(Debug, Clone)]
+pub enum KeyAuditAction {
+    None,
+    Close,
+    Reload,
+}
+
+/// Full-screen viewer for the API key audit log
+pub struct KeyAuditView {
+    visible: bool,
+    tab: AuditTab,
+    entries: Vec<KeyAuditEntry>,
+    anomalies: Vec<KeyAuditAnomaly>,
+    rules: KeyAuditRules,
+    failures_only: bool,
+    table_state: TableState,
+    message: Option<String>,
+}
+
+impl KeyAuditView {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            tab: AuditTab::Log,
+            entries: Vec::new(),
+            anomalies: Vec::new(),
+            rules: KeyAuditRules::default(),
+            failures_only: false,
+            table_state: TableState::default(),
+            message: None,
+        }
+    }
+
+    pub fn show(&mut self) {
+        self.visible = true;
+        self.message = None;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Entries most recent first, and the anomalies found among them
+    pub fn set_data(&mut self, entries: Vec<KeyAuditEntry>, anomalies: Vec<KeyAuditAnomaly>) {
+        self.entries = entries;
+        self.anomalies = anomalies;
+        self.clamp_selection();
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> KeyAuditAction {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => KeyAuditAction::Close,
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.tab = match self.tab {
+                    AuditTab::Log => AuditTab::Anomalies,
+                    AuditTab::Anomalies => AuditTab::Log,
+                };
+                self.table_state.select(Some(0));
+                self.clamp_selection();
+                KeyAuditAction::None
+            }
+            KeyCode::Char('f') => {
+                self.failures_only = !self.failures_only;
+                self.table_state.select(Some(0));
+                self.clamp_selection();
+                KeyAuditAction::None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.move_selection(1);
+                KeyAuditAction::None
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.move_selection(-1);
+                KeyAuditAction::None
+            }
+            KeyCode::Char('r') => KeyAuditAction::Reload,
+            _ => KeyAuditAction::None,
+        }
+    }
+
+    fn listed(&self) -> impl Iterator<Item = &KeyAuditEntry> {
+        self.entries.iter().filter(|entry| !self.failures_only || !entry.success)
+    }
+
+    fn row_count(&self) -> usize {
+        match self.tab {
+            AuditTab::Log => self.listed().count(),
+            AuditTab::Anomalies => self.anomalies.len(),
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let count = self.row_count();
+        if count == 0 {
+            self.table_state.select(None);
+            return;
+        }
+        let current = self.table_state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, count as isize - 1);
+        self.table_state.select(Some(next as usize));
+    }
+
+    fn clamp_selection(&mut self) {
+        let count = self.row_count();
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state.select(if count == 0 { None } else { Some(selected.min(count - 1)) });
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if !self.visible {
+            return;
+        }
+
+        frame.render_widget(Clear, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Tabs
+                Constraint::Min(1),    // Content
+                Constraint::Length(1), // Key hints / messages
+            ])
+            .split(area);
+
+        let titles = vec![
+            Line::from("Log"),
+            Line::from(format!("Anomalies ({})", self.anomalies.len())),
+        ];
+        let tabs = Tabs::new(titles)
+            .select(if self.tab == AuditTab::Log { 0 } else { 1 })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.accent())
+                    .title(" API Key Audit "),
+            )
+            .style(theme.normal())
+            .highlight_style(theme.highlight().add_modifier(Modifier::BOLD));
+        frame.render_widget(tabs, chunks[0]);
+
+        match self.tab {
+            AuditTab::Log => self.render_log(frame, chunks[1], theme),
+            AuditTab::Anomalies => self.render_anomalies(frame, chunks[1], theme),
+        }
+
+        let footer = match &self.message {
+            Some(message) => Line::from(Span::styled(message.clone(), theme.accent())),
+            None => Line::from(Span::styled(
+                "Tab: log/anomalies  ↑/↓: select  f: failures only  r: reload  Esc: back",
+                theme.secondary(),
+            )),
+        };
+        frame.render_widget(Paragraph::new(footer), chunks[2]);
+    }
+
+    fn render_log(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let rows: Vec<Row> = self.listed()
+            .map(|entry| {
+                let unexpected = self.rules.is_unexpected(entry);
+                let style = if !entry.success {
+                    theme.error()
+                } else if unexpected {
+                    theme.warning()
+                } else {
+                    theme.normal()
+                };
+                let command = entry.command.clone().unwrap_or_else(|| "-".to_string());
+                Row::new(vec![
+                    Cell::from(entry.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string()),
+                    Cell::from(entry.provider.clone()),
+                    Cell::from(entry.operation.clone()),
+                    Cell::from(if entry.success { "ok" } else { "FAILED" }),
+                    Cell::from(entry.source.clone().unwrap_or_else(|| "-".to_string())),
+                    Cell::from(if unexpected { format!("⚠ {}", command) } else { command }),
+                    Cell::from(entry.error_message.clone().unwrap_or_default()),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        if rows.is_empty() {
+            let text = if self.failures_only { "✅ No failed key operations" } else { "No API key operations recorded" };
+            frame.render_widget(placeholder(text, theme), area);
+            return;
+        }
+
+        let title = if self.failures_only { "Failed operations" } else { "Operations" };
+        let table = Table::new(rows, [
+            Constraint::Length(16),
+            Constraint::Length(12),
+            Constraint::Length(9),
+            Constraint::Length(6),
+            Constraint::Length(22),
+            Constraint::Length(11),
+            Constraint::Min(10),
+        ])
+        .header(header(&["Time", "Provider", "Operation", "Result", "Source", "Command", "Error"], theme))
+        .block(bordered(title, theme))
+        .highlight_style(theme.selected());
+
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    fn render_anomalies(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if self.anomalies.is_empty() {
+            frame.render_widget(placeholder("✅ No anomalies", theme), area);
+            return;
+        }
+
+        let rows: Vec<Row> = self.anomalies.iter()
+            .map(|anomaly| {
+                let (kind, style) = match anomaly {
+                    KeyAuditAnomaly::UnexpectedCommand { .. } => ("Unexpected command", theme.warning()),
+                    KeyAuditAnomaly::FailureBurst { .. } => ("Failure burst", theme.error()),
+                };
+                Row::new(vec![Cell::from(kind), Cell::from(anomaly.to_string())]).style(style)
+            })
+            .collect();
+
+        let table = Table::new(rows, [Constraint::Length(20), Constraint::Min(20)])
+            .header(header(&["Kind", "Details"], theme))
+            .block(bordered("Anomalies", theme))
+            .highlight_style(theme.selected());
+
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+}
+
+impl Default for KeyAuditView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn bordered<'a>(title: &str, theme: &Theme) -> Block<'a> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border())
+        .title(format!(" {} ", title))
+}
+
+fn header<'a>(columns: &[&'a str], theme: &Theme) -> Row<'a> {
+    Row::new(columns.iter().map(|c| Cell::from(*c)).collect::<Vec<_>>())
+        .style(theme.accent().add_modifier(Modifier::BOLD))
+}
+
+fn placeholder<'a>(text: &'a str, theme: &Theme) -> Paragraph<'a> {
+    Paragraph::new(text)
+        .style(theme.secondary())
+        .block(Block::default().borders(Borders::ALL).border_style(theme.border()))
+}
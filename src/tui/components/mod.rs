@@ -4,6 +4,9 @@ pub mod input_box;
 pub mod status_bar;
 pub mod help_popup;
 pub mod cost_tracker;
+pub mod billing_dashboard;
+pub mod settings_editor;
+pub mod key_audit;
 
 use ratatui::{layout::Rect, Frame};
 use crate::tui::{Event, Theme};
@@ -12,6 +15,9 @@ pub use input_box::InputBox;
 pub use status_bar::StatusBar;
 pub use help_popup::HelpPopup;
 pub use cost_tracker::CostTracker;
+pub use billing_dashboard::{BillingDashboardView, DashboardAction};
+pub use settings_editor::{SettingsAction, SettingsEditorView};
+pub use key_audit::{KeyAuditAction, KeyAuditView};
 
 /// Base trait for all TUI components
 pub trait Component {
@@ -0,0 +1,962 @@
+use std::collections::BTreeSet;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Tabs},
+    Frame,
+};
+use toml::Value;
+
+use crate::tui::Theme;
+use valechat::app::{apply_changes, AppConfig, MCPServerConfig, ModelConfig, SettingChange};
+use valechat::app::config::TransportType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingsTab {
+    Models,
+    McpServers,
+    Billing,
+    Ui,
+    Fallback,
+    RateLimiting,
+    Capabilities,
+}
+
+impl SettingsTab {
+    const ALL: [SettingsTab; 7] = [
+        SettingsTab::Models,
+        SettingsTab::McpServers,
+        SettingsTab::Billing,
+        SettingsTab::Ui,
+        SettingsTab::Fallback,
+        SettingsTab::RateLimiting,
+        SettingsTab::Capabilities,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            SettingsTab::Models => "Models",
+            SettingsTab::McpServers => "MCP Servers",
+            SettingsTab::Billing => "Billing",
+            SettingsTab::Ui => "UI",
+            SettingsTab::Fallback => "Fallback",
+            SettingsTab::RateLimiting => "Rate Limiting",
+            SettingsTab::Capabilities => "Capabilities",
+        }
+    }
+
+    /// Configuration section the tab edits
+    fn section(&self) -> &'static str {
+        match self {
+            SettingsTab::Models => "models",
+            SettingsTab::McpServers => "mcp_servers",
+            SettingsTab::Billing => "billing",
+            SettingsTab::Ui => "ui",
+            SettingsTab::Fallback => "fallback",
+            SettingsTab::RateLimiting => "rate_limiting",
+            SettingsTab::Capabilities => "capability_detection",
+        }
+    }
+
+    fn fields(&self) -> &'static [FieldSpec] {
+        match self {
+            SettingsTab::Models => MODEL_FIELDS,
+            SettingsTab::McpServers => MCP_SERVER_FIELDS,
+            SettingsTab::Billing => BILLING_FIELDS,
+            SettingsTab::Ui => UI_FIELDS,
+            SettingsTab::Fallback => FALLBACK_FIELDS,
+            SettingsTab::RateLimiting => RATE_LIMITING_FIELDS,
+            SettingsTab::Capabilities => CAPABILITY_FIELDS,
+        }
+    }
+
+    /// Sections holding named entries, edited one entry at a time
+    fn has_entries(&self) -> bool {
+        matches!(self, SettingsTab::Models | SettingsTab::McpServers)
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|t| t == self).unwrap_or(0)
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        let name = name.to_lowercase().replace(['-', '_'], "");
+        Self::ALL.into_iter().find(|tab| {
+            tab.section().replace('_', "").starts_with(&name) || tab.title().to_lowercase().replace(' ', "").starts_with(&name)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Text,
+    Bool,
+    Integer,
+    Number,
+    Choice(&'static [&'static str]),
+    /// Comma-separated text values
+    List,
+    /// Comma-separated `key=value` pairs with text values
+    TextMap,
+    /// Comma-separated `key=value` pairs with numeric values
+    NumberMap,
+    /// `stdio` or a WebSocket URL
+    Transport,
+}
+
+#[derive(Debug)]
+struct FieldSpec {
+    /// Dotted key below the section or entry
+    key: &'static str,
+    label: &'static str,
+    kind: FieldKind,
+    /// Empty input removes the setting
+    optional: bool,
+}
+
+const fn required(key: &'static str, label: &'static str, kind: FieldKind) -> FieldSpec {
+    FieldSpec { key, label, kind, optional: false }
+}
+
+const fn optional(key: &'static str, label: &'static str, kind: FieldKind) -> FieldSpec {
+    FieldSpec { key, label, kind, optional: true }
+}
+
+const MODEL_FIELDS: &[FieldSpec] = &[
+    required("provider", "Provider", FieldKind::Text),
+    required("default_model", "Default model", FieldKind::Text),
+    required("enabled", "Enabled", FieldKind::Bool),
+    required("priority", "Fallback priority", FieldKind::Integer),
+    optional("api_endpoint", "API endpoint", FieldKind::Text),
+    optional("api_key_command", "API key command", FieldKind::Text),
+    optional("timeout_seconds", "Timeout (seconds)", FieldKind::Integer),
+    optional("max_retries", "Max retries", FieldKind::Integer),
+    optional("rate_limits.requests_per_minute", "Requests per minute", FieldKind::Integer),
+    optional("rate_limits.tokens_per_minute", "Tokens per minute", FieldKind::Integer),
+    optional("rate_limits.requests_per_day", "Requests per day", FieldKind::Integer),
+    optional("rate_limits.concurrent_requests", "Concurrent requests", FieldKind::Integer),
+    optional("cost_limits.max_cost_per_request", "Max cost per request", FieldKind::Text),
+    optional("cost_limits.daily_cost_limit", "Daily cost limit", FieldKind::Text),
+    optional("cost_limits.monthly_cost_limit", "Monthly cost limit", FieldKind::Text),
+];
+
+const MCP_SERVER_FIELDS: &[FieldSpec] = &[
+    required("command", "Command", FieldKind::Text),
+    required("args", "Arguments", FieldKind::List),
+    required("transport_type", "Transport", FieldKind::Transport),
+    required("env_vars", "Environment", FieldKind::TextMap),
+    required("enabled", "Enabled", FieldKind::Bool),
+    required("auto_start", "Start automatically", FieldKind::Bool),
+    required("timeout_seconds", "Timeout (seconds)", FieldKind::Integer),
+];
+
+const BILLING_FIELDS: &[FieldSpec] = &[
+    optional("daily_limit", "Daily limit", FieldKind::Number),
+    optional("monthly_limit", "Monthly limit", FieldKind::Number),
+    required("per_model_limits", "Per-model limits", FieldKind::NumberMap),
+    required("alert_threshold_percent", "Alert threshold (%)", FieldKind::Number),
+    required("track_usage", "Track usage", FieldKind::Bool),
+    required("confirm_request_cost", "Confirm requests above", FieldKind::Number),
+    required("currency.display", "Display currency", FieldKind::Text),
+    required("currency.limits", "Limit currency", FieldKind::Text),
+    required("emergency_stop_on_anomaly", "Stop on runaway spend", FieldKind::Bool),
+];
+
+const UI_FIELDS: &[FieldSpec] = &[
+    required("theme", "Theme", FieldKind::Choice(&["dark", "light", "matrix"])),
+    required("language", "Language", FieldKind::Text),
+    required("font_size", "Font size", FieldKind::Integer),
+    required("window_width", "Window width", FieldKind::Integer),
+    required("window_height", "Window height", FieldKind::Integer),
+    required("show_token_counts", "Show token counts", FieldKind::Bool),
+    required("show_cost_estimates", "Show cost estimates", FieldKind::Bool),
+    required("auto_save", "Auto save", FieldKind::Bool),
+    required("streaming", "Stream responses", FieldKind::Bool),
+];
+
+const FALLBACK_FIELDS: &[FieldSpec] = &[
+    required("enabled", "Enabled", FieldKind::Bool),
+    required("max_retries", "Max retries", FieldKind::Integer),
+    required("retry_delay_ms", "Retry delay (ms)", FieldKind::Integer),
+    required("timeout_ms", "Timeout (ms)", FieldKind::Integer),
+    required("fallback_on_rate_limit", "On rate limit", FieldKind::Bool),
+    required("fallback_on_error", "On error", FieldKind::Bool),
+    required("fallback_on_timeout", "On timeout", FieldKind::Bool),
+    required("quality_degradation_allowed", "Allow lower quality", FieldKind::Bool),
+];
+
+const RATE_LIMITING_FIELDS: &[FieldSpec] = &[
+    required("enabled", "Enabled", FieldKind::Bool),
+    required("token_bucket_refill_rate", "Refill rate", FieldKind::Number),
+    required("burst_allowance_multiplier", "Burst multiplier", FieldKind::Number),
+    required("backoff_base_delay_ms", "Backoff base delay (ms)", FieldKind::Integer),
+    required("backoff_max_delay_ms", "Backoff max delay (ms)", FieldKind::Integer),
+    required("backoff_multiplier", "Backoff multiplier", FieldKind::Number),
+];
+
+const CAPABILITY_FIELDS: &[FieldSpec] = &[
+    required("enabled", "Enabled", FieldKind::Bool),
+    required("default_quality_priority", "Quality priority", FieldKind::Choice(&["Speed", "Cost", "Quality", "Balanced"])),
+    required("task_type_inference", "Infer task type", FieldKind::Bool),
+    required("cost_optimization", "Optimize cost", FieldKind::Bool),
+    required("performance_tracking", "Track performance", FieldKind::Bool),
+];
+
+struct FormField {
+    spec: &'static FieldSpec,
+    value: String,
+    original: String,
+}
+
+impl FormField {
+    fn is_modified(&self) -> bool {
+        self.value != self.original
+    }
+}
+
+/// One section, or one entry of the models or MCP servers sections
+struct Form {
+    tab: SettingsTab,
+    /// Entry name; `None` for sections without entries
+    entry: Option<String>,
+    /// The entry does not exist yet and `entry` is still being typed
+    is_new: bool,
+    /// Stored values the fields start from
+    base: Value,
+    fields: Vec<FormField>,
+}
+
+impl Form {
+    fn open(tab: SettingsTab, config: &AppConfig, entry: Option<&str>) -> Self {
+        let section = Value::try_from(config).ok()
+            .and_then(|value| value.get(tab.section()).cloned())
+            .unwrap_or_else(|| Value::Table(Default::default()));
+        let (base, is_new) = match entry {
+            Some(name) => match section.get(name) {
+                Some(value) => (value.clone(), false),
+                None => (new_entry(tab), true),
+            },
+            None if tab.has_entries() => (new_entry(tab), true),
+            None => (section, false),
+        };
+
+        let fields = tab.fields().iter()
+            .map(|spec| {
+                let value = display(spec.kind, lookup(&base, spec.key));
+                FormField { spec, original: if is_new { String::new() } else { value.clone() }, value }
+            })
+            .collect();
+        Self { tab, entry: entry.map(String::from), is_new, base, fields }
+    }
+
+    fn rows(&self) -> usize {
+        self.fields.len() + usize::from(self.is_new)
+    }
+
+    /// Field shown in `row`; the first row of a new entry is its name
+    fn field_index(&self, row: usize) -> Option<usize> {
+        match (self.is_new, row) {
+            (true, 0) => None,
+            (true, row) => Some(row - 1),
+            (false, row) => Some(row),
+        }
+    }
+
+    fn is_modified(&self) -> bool {
+        self.is_new || self.fields.iter().any(FormField::is_modified)
+    }
+
+    /// Path of the section or entry in the configuration
+    fn path(&self, name: &str) -> Vec<String> {
+        let mut path = vec![self.tab.section().to_string()];
+        if self.tab.has_entries() {
+            path.push(name.to_string());
+        }
+        path
+    }
+
+    /// The entry as it would be stored, with every field applied
+    fn entry_value(&self, name: &str) -> Result<Value, String> {
+        let mut value = self.base.clone();
+        for field in &self.fields {
+            let key: Vec<&str> = field.spec.key.split('.').collect();
+            set_path(&mut value, &key, parse(field.spec, &field.value)?);
+        }
+        if self.tab == SettingsTab::McpServers {
+            set_path(&mut value, &["name"], Some(Value::String(name.to_string())));
+        }
+        Ok(value)
+    }
+
+    fn changes(&self, config: &AppConfig) -> Result<Vec<SettingChange>, String> {
+        let name = self.entry.clone().unwrap_or_default();
+        let changes = if self.is_new {
+            validate_entry_name(&name)?;
+            let exists = match self.tab {
+                SettingsTab::Models => config.models.contains_key(&name),
+                _ => config.mcp_servers.contains_key(&name),
+            };
+            if exists {
+                return Err(format!("{} already exists", name));
+            }
+            vec![SettingChange::set(self.path(&name), self.entry_value(&name)?)]
+        } else {
+            let mut changes = Vec::new();
+            for field in self.fields.iter().filter(|f| f.is_modified()) {
+                let mut path = self.path(&name);
+                path.extend(field.spec.key.split('.').map(String::from));
+                changes.push(SettingChange { path, value: parse(field.spec, &field.value)? });
+            }
+            changes
+        };
+
+        // Catch values the configuration types reject before anything is saved
+        apply_changes(config, &changes).map_err(|e| e.to_string())?;
+        Ok(changes)
+    }
+
+    fn mcp_server(&self) -> Result<(String, MCPServerConfig), String> {
+        let name = self.entry.clone().unwrap_or_default();
+        validate_entry_name(&name)?;
+        let config = self.entry_value(&name)?.try_into()
+            .map_err(|e: toml::de::Error| e.message().to_string())?;
+        Ok((name, config))
+    }
+}
+
+fn new_entry(tab: SettingsTab) -> Value {
+    let value = match tab {
+        SettingsTab::McpServers => Value::try_from(MCPServerConfig {
+            name: String::new(),
+            command: String::new(),
+            args: Vec::new(),
+            transport_type: TransportType::Stdio,
+            env_vars: Default::default(),
+            enabled: true,
+            auto_start: false,
+            timeout_seconds: 30,
+        }),
+        _ => Value::try_from(ModelConfig {
+            provider: String::new(),
+            default_model: String::new(),
+            enabled: true,
+            api_endpoint: None,
+            api_key_command: None,
+            timeout_seconds: None,
+            max_retries: None,
+            rate_limits: None,
+            cost_limits: None,
+            priority: 0,
+        }),
+    };
+    value.unwrap_or_else(|_| Value::Table(Default::default()))
+}
+
+fn validate_entry_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Enter a name first".to_string());
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err("Names may only contain letters, digits, '-' and '_'".to_string());
+    }
+    Ok(())
+}
+
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(value, |value, segment| value.get(segment))
+}
+
+fn set_path(value: &mut Value, path: &[&str], new: Option<Value>) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut current = value;
+    for segment in parents {
+        let Some(table) = current.as_table_mut() else {
+            return;
+        };
+        current = table.entry(segment.to_string()).or_insert_with(|| Value::Table(Default::default()));
+    }
+    if let Some(table) = current.as_table_mut() {
+        match new {
+            Some(new) => {
+                table.insert(last.to_string(), new);
+            }
+            None => {
+                table.remove(*last);
+            }
+        }
+    }
+}
+
+fn text(value: &Value) -> String {
+    value.as_str().map(String::from).unwrap_or_else(|| value.to_string())
+}
+
+fn display(kind: FieldKind, value: Option<&Value>) -> String {
+    match (kind, value) {
+        (_, None) => String::new(),
+        (FieldKind::Transport, Some(Value::Table(table))) => table.get("WebSocket")
+            .and_then(|ws| ws.get("url"))
+            .map(text)
+            .unwrap_or_default(),
+        (FieldKind::Transport, Some(value)) => text(value).to_lowercase(),
+        (_, Some(Value::Array(items))) => items.iter().map(text).collect::<Vec<_>>().join(", "),
+        (_, Some(Value::Table(table))) => table.iter()
+            .map(|(key, value)| format!("{}={}", key, text(value)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        (_, Some(value)) => text(value),
+    }
+}
+
+fn split_list(input: &str) -> impl Iterator<Item = &str> {
+    input.split(',').map(str::trim).filter(|item| !item.is_empty())
+}
+
+/// Read form input as the value stored for `spec`; `None` when an optional field is cleared
+fn parse(spec: &FieldSpec, input: &str) -> Result<Option<Value>, String> {
+    let input = input.trim();
+    if input.is_empty() && spec.optional {
+        return Ok(None);
+    }
+
+    let value = match spec.kind {
+        FieldKind::Text => Value::String(input.to_string()),
+        FieldKind::Bool => match input.to_lowercase().as_str() {
+            "true" | "yes" | "on" => Value::Boolean(true),
+            "false" | "no" | "off" => Value::Boolean(false),
+            _ => return Err(format!("{} must be yes or no", spec.label)),
+        },
+        FieldKind::Integer => input.parse::<i64>()
+            .map(Value::Integer)
+            .map_err(|_| format!("{} must be a whole number", spec.label))?,
+        FieldKind::Number => input.parse::<f64>()
+            .map(Value::Float)
+            .map_err(|_| format!("{} must be a number", spec.label))?,
+        FieldKind::Choice(options) => options.iter()
+            .find(|option| option.eq_ignore_ascii_case(input))
+            .map(|option| Value::String(option.to_string()))
+            .ok_or_else(|| format!("{} must be one of {}", spec.label, options.join(", ")))?,
+        FieldKind::List => Value::Array(split_list(input).map(|item| Value::String(item.to_string())).collect()),
+        FieldKind::TextMap | FieldKind::NumberMap => {
+            let mut table = toml::Table::new();
+            for pair in split_list(input) {
+                let (key, value) = pair.split_once('=')
+                    .ok_or_else(|| format!("{} takes key=value pairs separated by commas", spec.label))?;
+                let value = if spec.kind == FieldKind::NumberMap {
+                    Value::Float(value.trim().parse().map_err(|_| format!("{} needs a number for {}", spec.label, key.trim()))?)
+                } else {
+                    Value::String(value.trim().to_string())
+                };
+                table.insert(key.trim().to_string(), value);
+            }
+            Value::Table(table)
+        }
+        FieldKind::Transport => {
+            if input.is_empty() || input.eq_ignore_ascii_case("stdio") {
+                Value::String("Stdio".to_string())
+            } else if input.starts_with("ws://") || input.starts_with("wss://") {
+                let url = toml::Table::from_iter([("url".to_string(), Value::String(input.to_string()))]);
+                Value::Table(toml::Table::from_iter([("WebSocket".to_string(), Value::Table(url))]))
+            } else {
+                return Err("Transport must be stdio or a ws:// or wss:// URL".to_string());
+            }
+        }
+    };
+    Ok(Some(value))
+}
+
+/// What the app should do after a key press in the settings editor
+#[derive(Debug, Clone)]
+pub enum SettingsAction {
+    None,
+    Close,
+    /// Validate the changed configuration and save it
+    Save(Vec<SettingChange>),
+    /// Start an MCP server from the form's values to see whether it answers
+    TestMcpServer(String, MCPServerConfig),
+}
+
+/// Full-screen editor for the configuration sections
+pub struct SettingsEditorView {
+    visible: bool,
+    tab: SettingsTab,
+    config: AppConfig,
+    /// Entries that come with the application; they can be disabled but not removed
+    builtin_models: BTreeSet<String>,
+    form: Option<Form>,
+    entry_state: TableState,
+    form_state: TableState,
+    /// Text being typed into the selected row
+    editing: Option<String>,
+    confirm_delete: Option<String>,
+    message: Option<(String, bool)>,
+}
+
+impl SettingsEditorView {
+    pub fn new() -> Self {
+        let config = AppConfig::default();
+        Self {
+            visible: false,
+            tab: SettingsTab::Models,
+            builtin_models: config.models.keys().cloned().collect(),
+            config,
+            form: None,
+            entry_state: TableState::default(),
+            form_state: TableState::default(),
+            editing: None,
+            confirm_delete: None,
+            message: None,
+        }
+    }
+
+    /// Open on the current configuration, at the named section if there is one
+    pub fn show(&mut self, config: AppConfig, section: Option<&str>) {
+        self.config = config;
+        self.visible = true;
+        self.message = None;
+        if let Some(name) = section {
+            match SettingsTab::parse(name) {
+                Some(tab) => self.tab = tab,
+                None => self.set_error(format!("No settings section named {}", name)),
+            }
+        }
+        self.select_tab(self.tab.index());
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.form = None;
+        self.editing = None;
+        self.confirm_delete = None;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some((message, false));
+    }
+
+    pub fn set_error(&mut self, message: String) {
+        self.message = Some((message, true));
+    }
+
+    /// The last save succeeded; continue from the configuration now in effect
+    pub fn saved(&mut self, config: AppConfig) {
+        self.config = config;
+        let entry = self.form.as_ref().and_then(|form| form.entry.clone());
+        if self.tab.has_entries() {
+            // Back to the list, which now shows the saved entry
+            self.form = None;
+            self.clamp_entries();
+            if let Some(index) = entry.and_then(|name| self.entry_names().iter().position(|n| *n == name)) {
+                self.entry_state.select(Some(index));
+            }
+        } else {
+            self.form = Some(Form::open(self.tab, &self.config, None));
+        }
+        self.set_message("Settings saved".to_string());
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> SettingsAction {
+        if self.editing.is_some() {
+            self.handle_editing_key(key);
+            return SettingsAction::None;
+        }
+
+        if let Some(name) = self.confirm_delete.take() {
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                return SettingsAction::Save(vec![SettingChange::remove(vec![self.tab.section().to_string(), name])]);
+            }
+            self.set_message("Not deleted".to_string());
+            return SettingsAction::None;
+        }
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) if self.tab.has_entries() && self.form.is_some() => {
+                self.form = None;
+                self.message = None;
+                SettingsAction::None
+            }
+            (KeyCode::Esc, _) | (KeyCode::F(3), _) => SettingsAction::Close,
+            (KeyCode::Tab, _) => {
+                self.select_tab((self.tab.index() + 1) % SettingsTab::ALL.len());
+                SettingsAction::None
+            }
+            (KeyCode::BackTab, _) => {
+                let len = SettingsTab::ALL.len();
+                self.select_tab((self.tab.index() + len - 1) % len);
+                SettingsAction::None
+            }
+            (KeyCode::Down, _) | (KeyCode::Char('j'), KeyModifiers::NONE) => {
+                self.move_selection(1);
+                SettingsAction::None
+            }
+            (KeyCode::Up, _) | (KeyCode::Char('k'), KeyModifiers::NONE) => {
+                self.move_selection(-1);
+                SettingsAction::None
+            }
+            (KeyCode::Char('s'), KeyModifiers::CONTROL) | (KeyCode::Char('s'), KeyModifiers::NONE) => self.save(),
+            (KeyCode::Char('t'), KeyModifiers::NONE) if self.tab == SettingsTab::McpServers => self.test_server(),
+            _ if self.form.is_some() => {
+                self.handle_form_key(key);
+                SettingsAction::None
+            }
+            _ => {
+                self.handle_list_key(key);
+                SettingsAction::None
+            }
+        }
+    }
+
+    fn handle_list_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {
+                if let Some(name) = self.selected_entry() {
+                    self.open_form(Some(&name));
+                }
+            }
+            KeyCode::Char('a') => {
+                self.open_form(None);
+                self.editing = Some(String::new());
+            }
+            KeyCode::Char('d') => {
+                let Some(name) = self.selected_entry() else {
+                    return;
+                };
+                if self.tab == SettingsTab::Models && self.builtin_models.contains(&name) {
+                    self.set_error(format!("{} is built in; disable it instead of deleting it", name));
+                } else {
+                    self.set_message(format!("Delete {}? (y/n)", name));
+                    self.confirm_delete = Some(name);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_form_key(&mut self, key: KeyEvent) {
+        let Some(form) = &mut self.form else {
+            return;
+        };
+        let row = self.form_state.selected().unwrap_or(0);
+        let Some(index) = form.field_index(row) else {
+            if key.code == KeyCode::Enter {
+                self.editing = Some(form.entry.clone().unwrap_or_default());
+            }
+            return;
+        };
+        let field = &mut form.fields[index];
+
+        match (key.code, field.spec.kind) {
+            (KeyCode::Enter | KeyCode::Char(' '), FieldKind::Bool) => {
+                let on = matches!(field.value.as_str(), "true" | "yes" | "on");
+                field.value = (!on).to_string();
+            }
+            (KeyCode::Enter | KeyCode::Char(' '), FieldKind::Choice(options)) => {
+                let current = options.iter().position(|o| o.eq_ignore_ascii_case(&field.value));
+                field.value = options[current.map_or(0, |i| (i + 1) % options.len())].to_string();
+            }
+            (KeyCode::Enter, _) => self.editing = Some(field.value.clone()),
+            (KeyCode::Delete | KeyCode::Backspace, _) if field.spec.optional => field.value.clear(),
+            (KeyCode::Char('u'), _) => field.value = field.original.clone(),
+            _ => {}
+        }
+    }
+
+    fn handle_editing_key(&mut self, key: KeyEvent) {
+        let Some(buffer) = &mut self.editing else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => buffer.push(c),
+            KeyCode::Backspace => {
+                buffer.pop();
+            }
+            KeyCode::Esc => {
+                self.editing = None;
+                if self.form.as_ref().is_some_and(|form| form.is_new && form.entry.is_none()) {
+                    self.form = None;
+                }
+            }
+            KeyCode::Enter => {
+                let input = buffer.trim().to_string();
+                let Some(form) = &mut self.form else {
+                    self.editing = None;
+                    return;
+                };
+                let row = self.form_state.selected().unwrap_or(0);
+                match form.field_index(row) {
+                    None => match validate_entry_name(&input) {
+                        Ok(()) => {
+                            // A new provider entry is usually named after its provider
+                            for field in &mut form.fields {
+                                if field.spec.key == "provider" && field.value.is_empty() {
+                                    field.value = input.clone();
+                                }
+                            }
+                            form.entry = Some(input);
+                            self.editing = None;
+                            self.message = None;
+                            self.form_state.select(Some(1));
+                        }
+                        Err(e) => self.set_error(e),
+                    },
+                    Some(index) => {
+                        let field = &mut form.fields[index];
+                        match parse(field.spec, &input) {
+                            Ok(_) => {
+                                field.value = input;
+                                self.editing = None;
+                                self.message = None;
+                            }
+                            Err(e) => self.set_error(e),
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn save(&mut self) -> SettingsAction {
+        let Some(form) = &self.form else {
+            return SettingsAction::None;
+        };
+        if !form.is_modified() {
+            self.set_message("Nothing to save".to_string());
+            return SettingsAction::None;
+        }
+        match form.changes(&self.config) {
+            Ok(changes) => SettingsAction::Save(changes),
+            Err(e) => {
+                self.set_error(e);
+                SettingsAction::None
+            }
+        }
+    }
+
+    fn test_server(&mut self) -> SettingsAction {
+        let server = match &self.form {
+            Some(form) => form.mcp_server(),
+            None => match self.selected_entry().and_then(|name| self.config.mcp_servers.get(&name).map(|c| (name, c.clone()))) {
+                Some(server) => Ok(server),
+                None => return SettingsAction::None,
+            },
+        };
+        match server {
+            Ok((name, config)) => {
+                self.set_message(format!("Testing {}...", name));
+                SettingsAction::TestMcpServer(name, config)
+            }
+            Err(e) => {
+                self.set_error(e);
+                SettingsAction::None
+            }
+        }
+    }
+
+    fn open_form(&mut self, entry: Option<&str>) {
+        self.form = Some(Form::open(self.tab, &self.config, entry));
+        self.form_state.select(Some(0));
+        self.message = None;
+    }
+
+    fn select_tab(&mut self, index: usize) {
+        let Some(tab) = SettingsTab::ALL.get(index) else {
+            return;
+        };
+        self.tab = *tab;
+        self.editing = None;
+        self.confirm_delete = None;
+        if tab.has_entries() {
+            self.form = None;
+            self.clamp_entries();
+        } else {
+            self.open_form(None);
+        }
+    }
+
+    fn entry_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = match self.tab {
+            SettingsTab::Models => self.config.models.keys().cloned().collect(),
+            SettingsTab::McpServers => self.config.mcp_servers.keys().cloned().collect(),
+            _ => Vec::new(),
+        };
+        names.sort();
+        names
+    }
+
+    fn selected_entry(&self) -> Option<String> {
+        self.entry_state.selected().and_then(|i| self.entry_names().get(i).cloned())
+    }
+
+    fn clamp_entries(&mut self) {
+        let count = self.entry_names().len();
+        let selected = self.entry_state.selected().unwrap_or(0);
+        self.entry_state.select(if count == 0 { None } else { Some(selected.min(count - 1)) });
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let (count, state) = match &self.form {
+            Some(form) => (form.rows(), &mut self.form_state),
+            None => (self.entry_names().len(), &mut self.entry_state),
+        };
+        if count == 0 {
+            state.select(None);
+            return;
+        }
+        let current = state.selected().unwrap_or(0) as isize;
+        state.select(Some((current + delta).clamp(0, count as isize - 1) as usize));
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if !self.visible {
+            return;
+        }
+
+        frame.render_widget(Clear, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Tabs
+                Constraint::Min(1),    // Content
+                Constraint::Length(1), // Key hints / messages
+            ])
+            .split(area);
+
+        let titles: Vec<Line> = SettingsTab::ALL.iter().map(|tab| Line::from(tab.title())).collect();
+        let tabs = Tabs::new(titles)
+            .select(self.tab.index())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.accent())
+                    .title(" Settings "),
+            )
+            .style(theme.normal())
+            .highlight_style(theme.highlight().add_modifier(Modifier::BOLD));
+        frame.render_widget(tabs, chunks[0]);
+
+        if self.form.is_some() {
+            self.render_form(frame, chunks[1], theme);
+        } else {
+            self.render_entries(frame, chunks[1], theme);
+        }
+
+        let footer = match &self.message {
+            Some((message, true)) => Line::from(Span::styled(message.clone(), theme.error())),
+            Some((message, false)) => Line::from(Span::styled(message.clone(), theme.accent())),
+            None => Line::from(Span::styled(self.key_hints(), theme.secondary())),
+        };
+        frame.render_widget(Paragraph::new(footer), chunks[2]);
+    }
+
+    fn key_hints(&self) -> &'static str {
+        match (&self.form, self.editing.is_some(), self.tab) {
+            (_, true, _) => "Type a value  Enter: accept  Esc: cancel",
+            (None, _, SettingsTab::McpServers) => "↑/↓: select  Enter: edit  a: add  d: delete  t: test connection  Tab: sections  Esc: close",
+            (None, _, _) => "↑/↓: select  Enter: edit  a: add  d: delete  Tab: sections  Esc: close",
+            (Some(_), _, SettingsTab::McpServers) => "Enter: edit/toggle  u: undo field  s: save  t: test connection  Esc: back",
+            (Some(_), _, tab) if tab.has_entries() => "Enter: edit/toggle  u: undo field  Del: clear  s: save  Esc: back",
+            (Some(_), _, _) => "Enter: edit/toggle  u: undo field  Del: clear  s: save  Tab: sections  Esc: close",
+        }
+    }
+
+    fn render_entries(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let rows: Vec<Row> = self.entry_names().into_iter()
+            .map(|name| {
+                let (detail, enabled, extra) = match self.tab {
+                    SettingsTab::Models => {
+                        let model = &self.config.models[&name];
+                        (model.default_model.clone(), model.enabled, format!("priority {}", model.priority))
+                    }
+                    _ => {
+                        let server = &self.config.mcp_servers[&name];
+                        let command = std::iter::once(server.command.as_str())
+                            .chain(server.args.iter().map(String::as_str))
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        let start = if server.auto_start { "auto start" } else { "manual start" };
+                        (command, server.enabled, start.to_string())
+                    }
+                };
+                Row::new(vec![
+                    Cell::from(name),
+                    Cell::from(detail),
+                    Cell::from(if enabled { "enabled" } else { "disabled" }),
+                    Cell::from(extra),
+                ])
+            })
+            .collect();
+
+        let (title, detail) = match self.tab {
+            SettingsTab::Models => ("Model Providers", "Default model"),
+            _ => ("MCP Servers", "Command"),
+        };
+        let table = Table::new(rows, [
+            Constraint::Percentage(20),
+            Constraint::Percentage(50),
+            Constraint::Percentage(12),
+            Constraint::Percentage(18),
+        ])
+        .header(Row::new(vec!["Name", detail, "State", ""]).style(theme.highlight()))
+        .block(Block::default().borders(Borders::ALL).border_style(theme.border()).title(title))
+        .highlight_style(theme.selected());
+
+        frame.render_stateful_widget(table, area, &mut self.entry_state);
+    }
+
+    fn render_form(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let Some(form) = &self.form else {
+            return;
+        };
+        let selected = self.form_state.selected();
+        let value_cell = |row: usize, value: &str| match (&self.editing, selected == Some(row)) {
+            (Some(buffer), true) => Cell::from(format!("{}▏", buffer)).style(theme.highlight()),
+            _ => Cell::from(value.to_string()),
+        };
+
+        let mut rows = Vec::new();
+        if form.is_new {
+            rows.push(Row::new(vec![
+                Cell::from("Name"),
+                value_cell(0, form.entry.as_deref().unwrap_or_default()),
+            ]));
+        }
+        for (index, field) in form.fields.iter().enumerate() {
+            let row = index + usize::from(form.is_new);
+            let label = if field.is_modified() && !form.is_new {
+                format!("{} *", field.spec.label)
+            } else {
+                field.spec.label.to_string()
+            };
+            let value = if field.value.is_empty() && field.spec.optional { "(not set)" } else { field.value.as_str() };
+            rows.push(Row::new(vec![Cell::from(label), value_cell(row, value)]));
+        }
+
+        let title = match (&form.entry, form.is_new) {
+            (_, true) => format!("New {} entry", self.tab.title()),
+            (Some(name), false) => format!("{}.{}", self.tab.section(), name),
+            (None, false) => self.tab.section().to_string(),
+        };
+        let table = Table::new(rows, [Constraint::Percentage(30), Constraint::Percentage(70)])
+            .block(Block::default().borders(Borders::ALL).border_style(theme.border()).title(title))
+            .highlight_style(theme.selected());
+
+        frame.render_stateful_widget(table, area, &mut self.form_state);
+    }
+}
+
+impl Default for SettingsEditorView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -6,11 +6,12 @@ use ratatui::{
     Frame,
 };
 
+use valechat::billing::CurrencyFormatter;
+
 use crate::tui::{components::Component, Event, Theme};
 
 // Constants for repeated strings
 const SEPARATOR: &str = " │ ";
-const DEFAULT_COST: &str = "$0.00";
 const STATUS_MESSAGE_WIDTH: usize = 40;
 
 // Connection status text constants
@@ -41,6 +42,7 @@ pub struct StatusBar {
     key_hints: Vec<KeyHint>,
     conversation_cost: f64,
     session_cost: f64,
+    currency: CurrencyFormatter,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -59,7 +61,7 @@ impl StatusBar {
         Self {
             status_message: "Ready".to_string(),
             model_info: "No model selected".to_string(),
-            cost_info: DEFAULT_COST.to_string(),
+            cost_info: CurrencyFormatter::usd().format_f64(0.0, 2),
             connection_status: ConnectionStatus::Disconnected,
             key_hints: vec![
                 KeyHint::new("F1", "Help"),
@@ -68,9 +70,16 @@ impl StatusBar {
             ],
             conversation_cost: 0.0,
             session_cost: 0.0,
+            currency: CurrencyFormatter::usd(),
         }
     }
 
+    /// Show costs in another display currency
+    pub fn set_currency(&mut self, currency: CurrencyFormatter) {
+        self.currency = currency;
+        self.update_cost_display();
+    }
+
     pub fn set_status(&mut self, message: String) {
         self.status_message = message;
     }
@@ -82,7 +91,7 @@ impl StatusBar {
 
     #[allow(dead_code)]
     pub fn set_cost_info(&mut self, cost: f64) {
-        self.cost_info = format!("${:.4}", cost);
+        self.cost_info = self.currency.format_f64(cost, 4);
     }
 
     pub fn set_cost_info_string(&mut self, cost_info: String) {
@@ -112,11 +121,15 @@ impl StatusBar {
 
     fn update_cost_display(&mut self) {
         if self.session_cost > 0.0 {
-            self.cost_info = format!("Conv: ${:.4} | Total: ${:.4}", self.conversation_cost, self.session_cost);
+            self.cost_info = format!(
+                "Conv: {} | Total: {}",
+                self.currency.format_f64(self.conversation_cost, 4),
+                self.currency.format_f64(self.session_cost, 4)
+            );
         } else if self.conversation_cost > 0.0 {
-            self.cost_info = format!("${:.4}", self.conversation_cost);
+            self.cost_info = self.currency.format_f64(self.conversation_cost, 4);
         } else {
-            self.cost_info = DEFAULT_COST.to_string();
+            self.cost_info = self.currency.format_f64(0.0, 2);
         }
     }
 
@@ -153,7 +166,7 @@ impl Component for StatusBar {
         }
         
         // Cost info (medium priority)
-        if available_width > 80 && self.cost_info != DEFAULT_COST {
+        if available_width > 80 && self.cost_info != self.currency.format_f64(0.0, 2) {
             spans.push(Span::raw(SEPARATOR));
             spans.push(Span::styled(&self.cost_info, theme.warning()));
         }
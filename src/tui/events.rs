@@ -2,6 +2,8 @@ use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio::time::timeout;
+use valechat::app::ConfigApplied;
+use valechat::storage::CostAlert;
 
 #[derive(Clone, Debug)]
 pub enum Event {
@@ -35,6 +37,21 @@ pub enum Event {
     Error(String),
     #[allow(dead_code)]
     StatusUpdate(String),
+    ResourceUpdated(String), // resource URI
+    ToolCallFinished(String, String), // conversation_id, summary
+    RefreshCostTracker,
+    OpenBillingDashboard,
+    /// Open the settings editor, optionally at a section
+    OpenSettings(Option<String>),
+    OpenKeyAudit,
+    /// Configuration files changed and were reloaded
+    ConfigReloaded(ConfigApplied),
+    /// Result of testing an MCP server from the settings editor: its tool names or the error
+    McpServerTested(String, Result<Vec<String>, String>),
+    /// Cost alert delivered by the alert dispatcher
+    CostAlert(CostAlert),
+    /// Alert acknowledged by the user; `None` acknowledges all of them
+    AlertsAcknowledged(Option<String>),
     #[allow(dead_code)]
     Quit,
 }